@@ -0,0 +1,26 @@
+-- Add migration script here
+-- Entities mentioned in each stream, for per-MP pages and bill tracking
+CREATE TABLE IF NOT EXISTS stream_members (
+    video_id TEXT NOT NULL REFERENCES streams(video_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    constituency TEXT,
+    party TEXT,
+    PRIMARY KEY (video_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stream_members_name ON stream_members(name);
+
+CREATE TABLE IF NOT EXISTS stream_bills (
+    video_id TEXT NOT NULL REFERENCES streams(video_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    number TEXT,
+    PRIMARY KEY (video_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stream_bills_name ON stream_bills(name);
+
+CREATE TABLE IF NOT EXISTS stream_committees (
+    video_id TEXT NOT NULL REFERENCES streams(video_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    PRIMARY KEY (video_id, name)
+);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS summary_tldr TEXT;
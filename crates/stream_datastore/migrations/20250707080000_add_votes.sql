@@ -0,0 +1,12 @@
+-- Add migration script here
+-- Divisions (recorded votes) held during each stream
+CREATE TABLE IF NOT EXISTS votes (
+    video_id TEXT NOT NULL REFERENCES streams(video_id) ON DELETE CASCADE,
+    division_index INTEGER NOT NULL,
+    motion TEXT NOT NULL,
+    ayes INTEGER,
+    noes INTEGER,
+    abstentions INTEGER,
+    outcome TEXT NOT NULL CHECK (outcome IN ('agreed', 'negatived', 'unknown')),
+    PRIMARY KEY (video_id, division_index)
+);
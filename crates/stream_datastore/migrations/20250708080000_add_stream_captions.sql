@@ -0,0 +1,8 @@
+-- Add migration script here
+-- Caption files generated from each stream's transcript segments
+CREATE TABLE IF NOT EXISTS stream_captions (
+    video_id TEXT NOT NULL REFERENCES streams(video_id) ON DELETE CASCADE,
+    format TEXT NOT NULL CHECK (format IN ('srt', 'vtt')),
+    content TEXT NOT NULL,
+    PRIMARY KEY (video_id, format)
+);
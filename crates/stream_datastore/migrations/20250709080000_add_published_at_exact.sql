@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Exact start of the stream, resolved from the watch page or the Data API.
+-- NULL when only YouTube's "time ago" text was available
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS published_at_exact TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- Add migration script here
+-- House or committee label, from the stream's channel, its title, or a model for ambiguous titles.
+-- NULL for streams stored before classification was added
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS category TEXT
+    CHECK (category IN ('national_assembly', 'senate', 'committee', 'other'));
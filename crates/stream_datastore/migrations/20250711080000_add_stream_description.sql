@@ -0,0 +1,5 @@
+-- Add migration script here
+-- Video description and the chapter markers listed in it, as `[{"start_seconds", "title"}]`.
+-- NULL when the channel listing and watch page had neither
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS description TEXT;
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS chapters JSONB;
@@ -0,0 +1,7 @@
+-- Add migration script here
+-- Streams are recorded while still live, with no summary, so the site can show the sitting
+-- as in progress. Their rows are completed, and archived, once they have been processed
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'archived'
+    CHECK (status IN ('live', 'archived'));
+
+CREATE INDEX IF NOT EXISTS streams_live_idx ON streams (status) WHERE status = 'live';
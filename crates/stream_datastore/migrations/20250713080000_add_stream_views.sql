@@ -0,0 +1,8 @@
+-- Add migration script here
+-- view_count is kept as YouTube displays it, e.g. "12,345 views", which can't be sorted
+-- or charted. Existing rows are backfilled from unabbreviated counts
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS views BIGINT;
+
+UPDATE streams
+SET views = replace(substring(view_count FROM '^([0-9,]+)'), ',', '')::BIGINT
+WHERE views IS NULL AND view_count ~ '^[0-9,]+( views?)?$';
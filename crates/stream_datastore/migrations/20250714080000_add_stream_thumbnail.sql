@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Thumbnail URLs as YouTube lists them, since not every video has one at the
+-- conventional i.ytimg.com path
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS thumbnail_url TEXT;
@@ -0,0 +1,8 @@
+-- Add migration script here
+-- Upcoming streams are recorded with their scheduled start, so the site can show when a
+-- sitting is due to begin. Their rows go live, then archived, like any other stream's
+ALTER TABLE streams DROP CONSTRAINT IF EXISTS streams_status_check;
+ALTER TABLE streams ADD CONSTRAINT streams_status_check
+    CHECK (status IN ('scheduled', 'live', 'archived'));
+
+CREATE INDEX IF NOT EXISTS streams_scheduled_idx ON streams (status) WHERE status = 'scheduled';
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- The sitting's order paper from parliament.go.ke, as {"url": ..., "agenda": [...]}
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS order_paper JSONB;
@@ -0,0 +1,9 @@
+-- Add migration script here
+-- Streams whose processing failed, counted so that runs can retry them with backoff
+-- until they are stored or run out of attempts
+CREATE TABLE IF NOT EXISTS failed_streams (
+    video_id TEXT PRIMARY KEY,
+    attempts INT NOT NULL DEFAULT 1,
+    last_error TEXT NOT NULL,
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,9 @@
+-- Add migration script here
+-- Transcripts stored as soon as each stream is transcribed, ahead of its summary, so that
+-- a stream whose summary failed is summarized again without transcribing it again.
+-- Streams are only inserted once summarized, so there is no reference to them.
+CREATE TABLE IF NOT EXISTS stream_transcripts (
+    video_id TEXT PRIMARY KEY,
+    transcript TEXT NOT NULL,
+    transcribed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
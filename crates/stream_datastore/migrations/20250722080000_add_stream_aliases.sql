@@ -0,0 +1,8 @@
+-- Add migration script here
+-- Video IDs of sittings that were uploaded again, pointing at the stored stream of the
+-- same sitting, so that neither upload is summarized twice
+CREATE TABLE IF NOT EXISTS stream_aliases (
+    video_id TEXT PRIMARY KEY,
+    canonical_video_id TEXT NOT NULL REFERENCES streams(video_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
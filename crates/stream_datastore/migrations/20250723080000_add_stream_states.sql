@@ -0,0 +1,12 @@
+-- Add migration script here
+-- How far each stream got through the pipeline, updated as it moves from one stage to
+-- the next, so that a stream that is not stored yet shows where it stopped. Like
+-- transcripts, states are recorded before the stream is inserted, so there is no
+-- reference to streams.
+CREATE TABLE IF NOT EXISTS stream_states (
+    video_id TEXT PRIMARY KEY,
+    state TEXT NOT NULL,
+    -- the stage a failed stream failed in
+    failed_stage TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Keys authorizing requests to stream-pulse's endpoints, stored as SHA-256 hashes so
+-- that the keys themselves are only ever seen by whoever created them. Public keys
+-- can read; admin keys can also queue runs.
+CREATE TABLE IF NOT EXISTS api_keys (
+    key_hash TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    role TEXT NOT NULL,
+    -- requests the key may make each minute, unlimited when NULL
+    requests_per_minute INT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
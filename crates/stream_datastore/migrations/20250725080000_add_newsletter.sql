@@ -0,0 +1,17 @@
+-- Add migration script here
+-- Subscribers to the email digest of newly summarized streams. A subscriber is sent
+-- digests once they follow the link in their confirmation email, and until they
+-- follow the unsubscribe link in a digest; both links carry the subscriber's token.
+CREATE TABLE IF NOT EXISTS newsletter_subscribers (
+    email TEXT PRIMARY KEY,
+    token TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    confirmed_at TIMESTAMPTZ,
+    unsubscribed_at TIMESTAMPTZ
+);
+
+-- Streams sent in a digest, so that each is sent once
+CREATE TABLE IF NOT EXISTS newsletter_streams (
+    video_id TEXT PRIMARY KEY REFERENCES streams(video_id) ON DELETE CASCADE,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Phone numbers on the broadcast lists stream summaries are sent to over WhatsApp.
+-- WhatsApp only allows messaging numbers that opted in, so numbers that opted out are
+-- kept, to record that they did, until they are removed.
+CREATE TABLE IF NOT EXISTS whatsapp_subscribers (
+    list TEXT NOT NULL,
+    -- digits only, with the country code, e.g. 254712345678
+    phone_number TEXT NOT NULL,
+    opted_in BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (list, phone_number)
+);
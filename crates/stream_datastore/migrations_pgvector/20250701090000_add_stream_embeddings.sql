@@ -0,0 +1,10 @@
+-- Add migration script here
+-- Purpose: Store summary embeddings for semantic search over streams
+-- NOTE: Only applied when `stream_datastore` is built with the `pgvector` feature.
+-- Requires the `vector` extension to be available on the database server.
+
+CREATE EXTENSION IF NOT EXISTS vector;
+
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS embedding vector(1536);
+
+CREATE INDEX IF NOT EXISTS streams_embedding_idx ON streams USING hnsw (embedding vector_cosine_ops);
@@ -0,0 +1,15 @@
+-- Add migration script here
+-- Purpose: Store transcript passage embeddings, so search can point into a sitting
+-- NOTE: Only applied when `stream_datastore` is built with the `pgvector` feature.
+
+CREATE TABLE IF NOT EXISTS stream_chunk_embeddings (
+    video_id TEXT NOT NULL REFERENCES streams(video_id) ON DELETE CASCADE,
+    chunk_index INTEGER NOT NULL,
+    start_seconds DOUBLE PRECISION,
+    end_seconds DOUBLE PRECISION,
+    content TEXT NOT NULL,
+    embedding vector(1536) NOT NULL,
+    PRIMARY KEY (video_id, chunk_index)
+);
+
+CREATE INDEX IF NOT EXISTS stream_chunk_embeddings_embedding_idx ON stream_chunk_embeddings USING hnsw (embedding vector_cosine_ops);
@@ -1,29 +1,699 @@
-use std::{collections::HashSet, fmt::Debug, future::Future};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    future::Future,
+};
 
-use crate::Stream;
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "pgvector")]
+use crate::StreamEmbeddings;
+use crate::{
+    ApiKey, DataStoreError, Division, EntityKind, FailedStream, SearchMatch, StoredStream, Stream,
+    StreamDetail, StreamEntities, StreamPage, StreamQuery, StreamState, Subscriber, WebhookEvent,
+    WebhookSubscription, WebhookTarget, WhatsAppSubscriber,
+};
 
 pub mod postgres;
 
+/// Where processed streams are stored. What else a store can do, e.g. track failed
+/// streams with [`FailedStreamStore`], is a trait of its own, so that a store lacking a
+/// capability it is used for fails to compile rather than at runtime.
 pub trait DataStore {
     fn get_existing_stream_ids(
         &self,
         video_ids: &[&str],
-    ) -> impl Future<Output = anyhow::Result<HashSet<String>>> + Send;
+    ) -> impl Future<Output = Result<HashSet<String>, DataStoreError>> + Send;
+
+    /// Stores a processed stream, completing its row if it was recorded while live
+    fn insert_stream(&self, stream: &Stream) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Records a stream that is being broadcast now, so the site can show the sitting as
+    /// in progress. Its row is completed by [`DataStore::insert_stream`] once it has
+    /// ended and been processed; until then it is not among the existing stream IDs.
+    fn insert_live_stream(
+        &self,
+        stream: &Stream,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Records an upcoming stream at its scheduled start, so the site can show when the
+    /// sitting is due. Like a live stream's, its row is completed by
+    /// [`DataStore::insert_stream`].
+    fn insert_scheduled_stream(
+        &self,
+        stream: &Stream,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// IDs among `video_ids` of streams recorded while scheduled or live, and not yet
+    /// processed
+    fn get_tracked_stream_ids(
+        &self,
+        video_ids: &[&str],
+    ) -> impl Future<Output = Result<HashSet<String>, DataStoreError>> + Send;
+
+    /// Stores entities mentioned in the stream `video_id`, which must already be inserted
+    fn insert_stream_entities(
+        &self,
+        video_id: &str,
+        entities: &StreamEntities,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Stores the divisions held during the stream `video_id`, which must already be
+    /// inserted, in the order they were held
+    fn insert_stream_divisions(
+        &self,
+        video_id: &str,
+        divisions: &[Division],
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Stores a caption file for the stream `video_id`, which must already be inserted.
+    /// `format` is the file extension, "srt" or "vtt"; an existing file in the same
+    /// format is replaced.
+    fn insert_stream_captions(
+        &self,
+        video_id: &str,
+        format: &str,
+        content: &str,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+}
+
+/// Stores embeddings of stream summaries and transcripts, for semantic search
+#[cfg(feature = "pgvector")]
+pub trait EmbeddingStore {
+    /// Embedding dimension expected by the `streams.embedding` column.
+    const EMBEDDING_DIMENSIONS: usize = 1536;
+
+    /// Stores embeddings of the summary and transcript of the stream `video_id`, which
+    /// must already be inserted, returning a [`DataStoreError::Constraint`] otherwise
+    fn store_stream_embeddings(
+        &self,
+        video_id: &str,
+        embeddings: &StreamEmbeddings,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+}
+
+/// Counts failed attempts at processing streams, for retrying them a limited number of
+/// times
+pub trait FailedStreamStore {
+    /// Records a failed attempt at processing the stream `video_id`, counted with its
+    /// earlier failed attempts
+    fn record_stream_failure(
+        &self,
+        video_id: &str,
+        error: &str,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Streams with a recorded failed attempt that have not been stored since
+    fn get_failed_streams(
+        &self,
+    ) -> impl Future<Output = Result<Vec<FailedStream>, DataStoreError>> + Send;
+
+    /// Forgets the failed attempts at processing the stream `video_id`, once it is stored
+    fn clear_stream_failure(
+        &self,
+        video_id: &str,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+}
+
+/// Keeps transcripts apart from the streams they belong to
+pub trait TranscriptStore {
+    /// Stores the transcript of the stream `video_id`, serialized by the caller, ahead of
+    /// the stream itself so that a failed summary doesn't cost the transcription. An
+    /// existing transcript is replaced.
+    fn insert_stream_transcript(
+        &self,
+        video_id: &str,
+        transcript: &str,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Stored transcripts of the streams among `video_ids`, by video ID
+    fn get_stream_transcripts(
+        &self,
+        video_ids: &[&str],
+    ) -> impl Future<Output = Result<HashMap<String, String>, DataStoreError>> + Send;
+}
+
+/// Tracks streams uploaded more than once, so that a re-upload isn't processed again
+pub trait ReuploadStore {
+    /// Processed streams that started between `from` and `to`, for matching newly listed
+    /// streams against
+    fn get_streams_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<StoredStream>, DataStoreError>> + Send;
+
+    /// Records `video_id` as another upload of the stored stream `canonical_video_id`,
+    /// after which it is among the existing stream IDs
+    fn insert_stream_alias(
+        &self,
+        video_id: &str,
+        canonical_video_id: &str,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Deletes the stored stream `video_id`, along with what was stored for it, in
+    /// favour of `replacement_video_id`, which must already be inserted. `video_id` is
+    /// recorded as an alias of its replacement.
+    fn replace_stream(
+        &self,
+        video_id: &str,
+        replacement_video_id: &str,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+}
+
+/// Tracks how far each stream has come through the pipeline
+pub trait StreamStateStore {
+    /// Records the state the stream `video_id` has reached in the pipeline, replacing
+    /// the one recorded before
+    fn set_stream_state(
+        &self,
+        video_id: &str,
+        state: StreamState,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// Recorded states of the streams among `video_ids`, by video ID, e.g. for showing
+    /// where the streams that are not stored yet stopped
+    fn get_stream_states(
+        &self,
+        video_ids: &[&str],
+    ) -> impl Future<Output = Result<HashMap<String, StreamState>, DataStoreError>> + Send;
+}
+
+/// Serves the published streams, for the site and bots to browse
+pub trait StreamReader {
+    /// A page of the published streams matching `query`, for the site to browse
+    fn list_streams(
+        &self,
+        query: &StreamQuery,
+    ) -> impl Future<Output = Result<StreamPage, DataStoreError>> + Send;
+
+    /// The published stream `video_id` with its summaries, `None` if there is none. A
+    /// re-upload's ID finds the stream it is an alias of. Fetch its transcript with
+    /// [`TranscriptStore::get_stream_transcripts`].
+    fn get_published_stream(
+        &self,
+        video_id: &str,
+    ) -> impl Future<Output = Result<Option<StreamDetail>, DataStoreError>> + Send;
+
+    /// The published streams matching `query` whose title or summary matches `terms`,
+    /// best match first and paginated by `query`. `terms` takes web search syntax, e.g.
+    /// `"housing levy" -senate`.
+    fn search_streams(
+        &self,
+        terms: &str,
+        query: &StreamQuery,
+    ) -> impl Future<Output = Result<Vec<SearchMatch>, DataStoreError>> + Send;
+
+    /// The entities stored for the stream `video_id`, empty if none were
+    fn get_stream_entities(
+        &self,
+        video_id: &str,
+    ) -> impl Future<Output = Result<StreamEntities, DataStoreError>> + Send;
+
+    /// A page of the published streams matching `query` that mention the `kind` of
+    /// entity named `name`, as it was stored, e.g. every sitting a bill was discussed in
+    fn list_streams_mentioning(
+        &self,
+        kind: EntityKind,
+        name: &str,
+        query: &StreamQuery,
+    ) -> impl Future<Output = Result<StreamPage, DataStoreError>> + Send;
+}
+
+/// Keeps the API keys issued, by the hashes of the keys
+pub trait ApiKeyStore {
+    /// Stores `key` under `key_hash`, the hash of the key it was issued as
+    fn insert_api_key(
+        &self,
+        key_hash: &str,
+        key: &ApiKey,
+    ) -> impl Future<Output = Result<(), DataStoreError>>;
+
+    /// The API key stored under `key_hash`, `None` if there is none or it was revoked
+    fn get_api_key(
+        &self,
+        key_hash: &str,
+    ) -> impl Future<Output = Result<Option<ApiKey>, DataStoreError>> + Send;
+
+    /// Revokes the API key issued to `name`, returning whether there was one to revoke
+    fn revoke_api_key(&self, name: &str) -> impl Future<Output = Result<bool, DataStoreError>>;
+}
+
+/// Keeps newsletter subscribers, and which streams digests were sent with
+pub trait SubscriberStore {
+    /// Stores `email` as a subscriber awaiting confirmation of `token`, returning whether it
+    /// was, and so whether to send it a confirmation. A confirmed subscriber is left as it
+    /// is.
+    fn add_subscriber(
+        &self,
+        email: &str,
+        token: &str,
+    ) -> impl Future<Output = Result<bool, DataStoreError>> + Send;
+
+    /// Confirms the subscriber `token` was sent to, returning whether there is one that
+    /// hasn't unsubscribed
+    fn confirm_subscriber(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<bool, DataStoreError>> + Send;
+
+    /// Unsubscribes the subscriber `token` was sent to, returning whether there is one
+    fn unsubscribe(&self, token: &str)
+        -> impl Future<Output = Result<bool, DataStoreError>> + Send;
+
+    /// The subscribers to send digests to
+    fn list_subscribers(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Subscriber>, DataStoreError>> + Send;
+
+    /// IDs of the published, summarized streams from `since` on that no digest was sent
+    /// with yet, oldest first
+    fn list_unsent_streams(
+        &self,
+        since: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<String>, DataStoreError>> + Send;
+
+    /// Records the streams `video_ids` as sent with a digest
+    fn mark_streams_sent(
+        &self,
+        video_ids: &[&str],
+    ) -> impl Future<Output = Result<(), DataStoreError>> + Send;
+}
 
-    fn insert_stream(&self, stream: &Stream) -> impl Future<Output = Result<(), anyhow::Error>>;
+/// Keeps the numbers on WhatsApp broadcast lists
+pub trait WhatsAppStore {
+    /// Adds `phone_number` to the WhatsApp broadcast list `list`, or updates it, as opted
+    /// in or out
+    fn set_whatsapp_opt_in(
+        &self,
+        list: &str,
+        phone_number: &str,
+        opted_in: bool,
+    ) -> impl Future<Output = Result<(), DataStoreError>> + Send;
+
+    /// Removes `phone_number` from `list`, returning whether it was on it
+    fn remove_whatsapp_subscriber(
+        &self,
+        list: &str,
+        phone_number: &str,
+    ) -> impl Future<Output = Result<bool, DataStoreError>> + Send;
+
+    /// The numbers on `list`, whether or not they opted in
+    fn list_whatsapp_subscribers(
+        &self,
+        list: &str,
+    ) -> impl Future<Output = Result<Vec<WhatsAppSubscriber>, DataStoreError>> + Send;
+}
+
+/// Keeps webhook subscriptions, and how deliveries to them went
+pub trait WebhookSubscriptionStore {
+    /// Registers `url` for `owner`, the name of an API key, to be delivered `events`
+    /// signed with `secret`
+    fn insert_webhook_subscription(
+        &self,
+        owner: &str,
+        url: &str,
+        secret: &str,
+        events: &[WebhookEvent],
+    ) -> impl Future<Output = Result<WebhookSubscription, DataStoreError>> + Send;
+
+    /// The subscriptions `owner` registered, oldest first
+    fn list_webhook_subscriptions(
+        &self,
+        owner: &str,
+    ) -> impl Future<Output = Result<Vec<WebhookSubscription>, DataStoreError>> + Send;
+
+    /// Changes what is given of `owner`'s subscription `id`, returning it as changed, or
+    /// `None` if `owner` has no such subscription. Enabling it forgets its failures.
+    fn update_webhook_subscription(
+        &self,
+        owner: &str,
+        id: i64,
+        url: Option<&str>,
+        events: Option<&[WebhookEvent]>,
+        enabled: Option<bool>,
+    ) -> impl Future<Output = Result<Option<WebhookSubscription>, DataStoreError>> + Send;
+
+    /// Deletes `owner`'s subscription `id`, returning whether there was one
+    fn delete_webhook_subscription(
+        &self,
+        owner: &str,
+        id: i64,
+    ) -> impl Future<Output = Result<bool, DataStoreError>> + Send;
+
+    /// Where to deliver `event`: the enabled subscriptions to it
+    fn list_webhook_targets(
+        &self,
+        event: WebhookEvent,
+    ) -> impl Future<Output = Result<Vec<WebhookTarget>, DataStoreError>> + Send;
+
+    /// Records whether a delivery to subscription `id` succeeded, disabling it once
+    /// `max_failures` in a row failed. Returns whether this delivery disabled it.
+    fn record_webhook_delivery(
+        &self,
+        id: i64,
+        delivered: bool,
+        max_failures: u32,
+    ) -> impl Future<Output = Result<bool, DataStoreError>> + Send;
 }
 
 impl<T: DataStore + Send + Sync> DataStore for &T {
     async fn get_existing_stream_ids(
         &self,
         video_ids: &[&str],
-    ) -> anyhow::Result<std::collections::HashSet<String>> {
+    ) -> Result<std::collections::HashSet<String>, DataStoreError> {
         (**self).get_existing_stream_ids(video_ids).await
     }
 
-    async fn insert_stream(&self, stream: &Stream) -> Result<(), anyhow::Error> {
+    async fn insert_stream(&self, stream: &Stream) -> Result<(), DataStoreError> {
         (**self).insert_stream(stream).await
     }
+
+    async fn insert_live_stream(&self, stream: &Stream) -> Result<(), DataStoreError> {
+        (**self).insert_live_stream(stream).await
+    }
+
+    async fn insert_scheduled_stream(&self, stream: &Stream) -> Result<(), DataStoreError> {
+        (**self).insert_scheduled_stream(stream).await
+    }
+
+    async fn get_tracked_stream_ids(
+        &self,
+        video_ids: &[&str],
+    ) -> Result<std::collections::HashSet<String>, DataStoreError> {
+        (**self).get_tracked_stream_ids(video_ids).await
+    }
+
+    async fn insert_stream_entities(
+        &self,
+        video_id: &str,
+        entities: &StreamEntities,
+    ) -> Result<(), DataStoreError> {
+        (**self).insert_stream_entities(video_id, entities).await
+    }
+
+    async fn insert_stream_divisions(
+        &self,
+        video_id: &str,
+        divisions: &[Division],
+    ) -> Result<(), DataStoreError> {
+        (**self).insert_stream_divisions(video_id, divisions).await
+    }
+
+    async fn insert_stream_captions(
+        &self,
+        video_id: &str,
+        format: &str,
+        content: &str,
+    ) -> Result<(), DataStoreError> {
+        (**self)
+            .insert_stream_captions(video_id, format, content)
+            .await
+    }
+}
+
+#[cfg(feature = "pgvector")]
+impl<T: EmbeddingStore + Send + Sync> EmbeddingStore for &T {
+    async fn store_stream_embeddings(
+        &self,
+        video_id: &str,
+        embeddings: &StreamEmbeddings,
+    ) -> Result<(), DataStoreError> {
+        (**self).store_stream_embeddings(video_id, embeddings).await
+    }
+}
+
+impl<T: FailedStreamStore + Send + Sync> FailedStreamStore for &T {
+    async fn record_stream_failure(
+        &self,
+        video_id: &str,
+        error: &str,
+    ) -> Result<(), DataStoreError> {
+        (**self).record_stream_failure(video_id, error).await
+    }
+
+    async fn get_failed_streams(&self) -> Result<Vec<FailedStream>, DataStoreError> {
+        (**self).get_failed_streams().await
+    }
+
+    async fn clear_stream_failure(&self, video_id: &str) -> Result<(), DataStoreError> {
+        (**self).clear_stream_failure(video_id).await
+    }
+}
+
+impl<T: TranscriptStore + Send + Sync> TranscriptStore for &T {
+    async fn insert_stream_transcript(
+        &self,
+        video_id: &str,
+        transcript: &str,
+    ) -> Result<(), DataStoreError> {
+        (**self)
+            .insert_stream_transcript(video_id, transcript)
+            .await
+    }
+
+    async fn get_stream_transcripts(
+        &self,
+        video_ids: &[&str],
+    ) -> Result<HashMap<String, String>, DataStoreError> {
+        (**self).get_stream_transcripts(video_ids).await
+    }
+}
+
+impl<T: ReuploadStore + Send + Sync> ReuploadStore for &T {
+    async fn get_streams_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<StoredStream>, DataStoreError> {
+        (**self).get_streams_between(from, to).await
+    }
+
+    async fn insert_stream_alias(
+        &self,
+        video_id: &str,
+        canonical_video_id: &str,
+    ) -> Result<(), DataStoreError> {
+        (**self)
+            .insert_stream_alias(video_id, canonical_video_id)
+            .await
+    }
+
+    async fn replace_stream(
+        &self,
+        video_id: &str,
+        replacement_video_id: &str,
+    ) -> Result<(), DataStoreError> {
+        (**self)
+            .replace_stream(video_id, replacement_video_id)
+            .await
+    }
+}
+
+impl<T: StreamStateStore + Send + Sync> StreamStateStore for &T {
+    async fn set_stream_state(
+        &self,
+        video_id: &str,
+        state: StreamState,
+    ) -> Result<(), DataStoreError> {
+        (**self).set_stream_state(video_id, state).await
+    }
+
+    async fn get_stream_states(
+        &self,
+        video_ids: &[&str],
+    ) -> Result<HashMap<String, StreamState>, DataStoreError> {
+        (**self).get_stream_states(video_ids).await
+    }
+}
+
+impl<T: StreamReader + Send + Sync> StreamReader for &T {
+    async fn list_streams(&self, query: &StreamQuery) -> Result<StreamPage, DataStoreError> {
+        (**self).list_streams(query).await
+    }
+
+    async fn get_published_stream(
+        &self,
+        video_id: &str,
+    ) -> Result<Option<StreamDetail>, DataStoreError> {
+        (**self).get_published_stream(video_id).await
+    }
+
+    async fn search_streams(
+        &self,
+        terms: &str,
+        query: &StreamQuery,
+    ) -> Result<Vec<SearchMatch>, DataStoreError> {
+        (**self).search_streams(terms, query).await
+    }
+
+    async fn get_stream_entities(&self, video_id: &str) -> Result<StreamEntities, DataStoreError> {
+        (**self).get_stream_entities(video_id).await
+    }
+
+    async fn list_streams_mentioning(
+        &self,
+        kind: EntityKind,
+        name: &str,
+        query: &StreamQuery,
+    ) -> Result<StreamPage, DataStoreError> {
+        (**self).list_streams_mentioning(kind, name, query).await
+    }
+}
+
+impl<T: ApiKeyStore + Send + Sync> ApiKeyStore for &T {
+    async fn insert_api_key(&self, key_hash: &str, key: &ApiKey) -> Result<(), DataStoreError> {
+        (**self).insert_api_key(key_hash, key).await
+    }
+
+    async fn get_api_key(&self, key_hash: &str) -> Result<Option<ApiKey>, DataStoreError> {
+        (**self).get_api_key(key_hash).await
+    }
+
+    async fn revoke_api_key(&self, name: &str) -> Result<bool, DataStoreError> {
+        (**self).revoke_api_key(name).await
+    }
+}
+
+impl<T: SubscriberStore + Send + Sync> SubscriberStore for &T {
+    async fn add_subscriber(&self, email: &str, token: &str) -> Result<bool, DataStoreError> {
+        (**self).add_subscriber(email, token).await
+    }
+
+    async fn confirm_subscriber(&self, token: &str) -> Result<bool, DataStoreError> {
+        (**self).confirm_subscriber(token).await
+    }
+
+    async fn unsubscribe(&self, token: &str) -> Result<bool, DataStoreError> {
+        (**self).unsubscribe(token).await
+    }
+
+    async fn list_subscribers(&self) -> Result<Vec<Subscriber>, DataStoreError> {
+        (**self).list_subscribers().await
+    }
+
+    async fn list_unsent_streams(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<String>, DataStoreError> {
+        (**self).list_unsent_streams(since).await
+    }
+
+    async fn mark_streams_sent(&self, video_ids: &[&str]) -> Result<(), DataStoreError> {
+        (**self).mark_streams_sent(video_ids).await
+    }
+}
+
+impl<T: WhatsAppStore + Send + Sync> WhatsAppStore for &T {
+    async fn set_whatsapp_opt_in(
+        &self,
+        list: &str,
+        phone_number: &str,
+        opted_in: bool,
+    ) -> Result<(), DataStoreError> {
+        (**self)
+            .set_whatsapp_opt_in(list, phone_number, opted_in)
+            .await
+    }
+
+    async fn remove_whatsapp_subscriber(
+        &self,
+        list: &str,
+        phone_number: &str,
+    ) -> Result<bool, DataStoreError> {
+        (**self)
+            .remove_whatsapp_subscriber(list, phone_number)
+            .await
+    }
+
+    async fn list_whatsapp_subscribers(
+        &self,
+        list: &str,
+    ) -> Result<Vec<WhatsAppSubscriber>, DataStoreError> {
+        (**self).list_whatsapp_subscribers(list).await
+    }
+}
+
+impl<T: WebhookSubscriptionStore + Send + Sync> WebhookSubscriptionStore for &T {
+    async fn insert_webhook_subscription(
+        &self,
+        owner: &str,
+        url: &str,
+        secret: &str,
+        events: &[WebhookEvent],
+    ) -> Result<WebhookSubscription, DataStoreError> {
+        (**self)
+            .insert_webhook_subscription(owner, url, secret, events)
+            .await
+    }
+
+    async fn list_webhook_subscriptions(
+        &self,
+        owner: &str,
+    ) -> Result<Vec<WebhookSubscription>, DataStoreError> {
+        (**self).list_webhook_subscriptions(owner).await
+    }
+
+    async fn update_webhook_subscription(
+        &self,
+        owner: &str,
+        id: i64,
+        url: Option<&str>,
+        events: Option<&[WebhookEvent]>,
+        enabled: Option<bool>,
+    ) -> Result<Option<WebhookSubscription>, DataStoreError> {
+        (**self)
+            .update_webhook_subscription(owner, id, url, events, enabled)
+            .await
+    }
+
+    async fn delete_webhook_subscription(
+        &self,
+        owner: &str,
+        id: i64,
+    ) -> Result<bool, DataStoreError> {
+        (**self).delete_webhook_subscription(owner, id).await
+    }
+
+    async fn list_webhook_targets(
+        &self,
+        event: WebhookEvent,
+    ) -> Result<Vec<WebhookTarget>, DataStoreError> {
+        (**self).list_webhook_targets(event).await
+    }
+
+    async fn record_webhook_delivery(
+        &self,
+        id: i64,
+        delivered: bool,
+        max_failures: u32,
+    ) -> Result<bool, DataStoreError> {
+        (**self)
+            .record_webhook_delivery(id, delivered, max_failures)
+            .await
+    }
+}
+
+/// Semantic search over the stream summary embeddings stored with
+/// [`EmbeddingStore::store_stream_embeddings`].
+#[cfg(feature = "pgvector")]
+pub trait SimilaritySearch: EmbeddingStore {
+    fn find_similar_streams(
+        &self,
+        embedding: &[f32],
+        k: usize,
+    ) -> impl Future<Output = Result<Vec<SimilarStream>, DataStoreError>> + Send;
+}
+
+/// A stream returned from a similarity query, ordered by ascending cosine distance.
+#[cfg(feature = "pgvector")]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SimilarStream {
+    pub video_id: String,
+    pub title: String,
+    pub stream_timestamp: chrono::DateTime<chrono::Utc>,
+    pub summary_md: Option<String>,
+    pub distance: f64,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,54 @@
+use std::{fmt::Display, str::FromStr};
+
+/// What requests authenticated with an API key may do, each role allowing what the
+/// ones before it do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiKeyRole {
+    /// Reading what the site shows
+    Public,
+    /// Queueing runs and reprocessing streams, besides reading
+    Admin,
+}
+
+impl ApiKeyRole {
+    /// Label the role is stored with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyRole::Public => "public",
+            ApiKeyRole::Admin => "admin",
+        }
+    }
+
+    /// Whether the role allows what `required` does
+    pub fn allows(&self, required: ApiKeyRole) -> bool {
+        *self >= required
+    }
+}
+
+impl Display for ApiKeyRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiKeyRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "public" => Ok(ApiKeyRole::Public),
+            "admin" => Ok(ApiKeyRole::Admin),
+            other => Err(format!("Unsupported API key role: {other}")),
+        }
+    }
+}
+
+/// An API key that has not been revoked, stored by the hash of the key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey {
+    /// Who the key was issued to, unique among keys
+    pub name: String,
+    pub role: ApiKeyRole,
+    /// Requests the key may make each minute, unlimited when `None`
+    pub requests_per_minute: Option<u32>,
+}
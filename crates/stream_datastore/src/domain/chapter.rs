@@ -0,0 +1,94 @@
+use std::{fmt::Display, sync::LazyLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Description lines YouTube turns into chapters, e.g. "1:02:15 Statements"
+static CHAPTER_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:(\d{1,2}):)?(\d{1,2}):(\d{2})\s*[-–—|:]?\s+(\S.*?)\s*$").unwrap()
+});
+
+/// A chapter marker of a stream, e.g. an order-paper item the sitting moved on to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chapter {
+    /// Offset into the stream the chapter starts at
+    pub start_seconds: u32,
+    pub title: String,
+}
+
+impl Chapter {
+    /// Reads the chapters listed in a video description the way YouTube does: one
+    /// timestamped line per chapter, in ascending order, the first at 0:00, and at
+    /// least three of them. Descriptions that don't qualify have no chapters.
+    pub fn from_description(description: &str) -> Vec<Chapter> {
+        let chapters = CHAPTER_LINE_RE
+            .captures_iter(description)
+            .filter_map(|cap| {
+                let hours = cap
+                    .get(1)
+                    .map_or(Some(0), |h| h.as_str().parse::<u32>().ok())?;
+                let minutes = cap[2].parse::<u32>().ok()?;
+                let seconds = cap[3].parse::<u32>().ok()?;
+                Some(Chapter {
+                    start_seconds: hours * 3600 + minutes * 60 + seconds,
+                    title: cap[4].to_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let ascending = chapters
+            .windows(2)
+            .all(|pair| pair[0].start_seconds < pair[1].start_seconds);
+        match chapters.first() {
+            Some(first) if first.start_seconds == 0 && chapters.len() >= 3 && ascending => chapters,
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Display for Chapter {
+    /// Formats the chapter as it's listed in descriptions, e.g. "1:02:15 Statements"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (hours, minutes, seconds) = (
+            self.start_seconds / 3600,
+            self.start_seconds % 3600 / 60,
+            self.start_seconds % 60,
+        );
+        match hours {
+            0 => write!(f, "{minutes}:{seconds:02} {}", self.title),
+            _ => write!(f, "{hours}:{minutes:02}:{seconds:02} {}", self.title),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chapters_from_description() {
+        let description = "Order of business\n\
+            0:00 Prayers\n\
+            4:30 - Communication from the Chair\n\
+            1:02:15 The Finance Bill (National Assembly Bill No. 12 of 2025)\n\
+            Follow us on X";
+
+        let chapters = Chapter::from_description(description);
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[1].start_seconds, 270);
+        assert_eq!(chapters[1].title, "Communication from the Chair");
+        assert_eq!(
+            chapters[2].to_string(),
+            "1:02:15 The Finance Bill (National Assembly Bill No. 12 of 2025)"
+        );
+
+        assert!(
+            Chapter::from_description("0:00 Prayers\n4:30 Statements").is_empty(),
+            "fewer than three chapters"
+        );
+        assert!(
+            Chapter::from_description("0:10 Prayers\n4:30 Statements\n9:00 Bills").is_empty(),
+            "first chapter must start at 0:00"
+        );
+    }
+}
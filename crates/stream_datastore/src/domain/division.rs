@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// A division (recorded vote) held during a sitting, as extracted by an LLM.
+///
+/// Counts are only present when the result was announced in the chamber; voice votes
+/// that were not challenged are not divisions and are not recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Division {
+    /// The question put, e.g. "That the Finance Bill be now read a Second Time"
+    pub motion: String,
+    pub ayes: Option<u32>,
+    pub noes: Option<u32>,
+    pub abstentions: Option<u32>,
+    pub outcome: DivisionOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivisionOutcome {
+    /// The question was agreed to
+    Agreed,
+    /// The question was negatived
+    Negatived,
+    /// The result was not announced in the transcript, or could not be read
+    #[serde(other)]
+    Unknown,
+}
+
+impl DivisionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DivisionOutcome::Agreed => "agreed",
+            DivisionOutcome::Negatived => "negatived",
+            DivisionOutcome::Unknown => "unknown",
+        }
+    }
+}
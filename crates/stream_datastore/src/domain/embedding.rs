@@ -0,0 +1,18 @@
+/// Embeddings of a stream's summary and transcript, for semantic search over sittings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamEmbeddings {
+    /// Embedding of the markdown summary
+    pub summary: Vec<f32>,
+    /// Embeddings of consecutive transcript passages, in playback order
+    pub chunks: Vec<ChunkEmbedding>,
+}
+
+/// A passage of the transcript and its embedding
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkEmbedding {
+    /// Offset of the passage into the stream, when the transcript has segment timestamps
+    pub start_seconds: Option<f64>,
+    pub end_seconds: Option<f64>,
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
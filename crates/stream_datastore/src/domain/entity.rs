@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// People, bills and committees mentioned during a sitting, as extracted by an LLM.
+///
+/// Names are stored as extracted, so the same MP may appear under slightly
+/// different spellings across streams.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamEntities {
+    #[serde(default)]
+    pub members: Vec<MemberMention>,
+    #[serde(default)]
+    pub bills: Vec<BillMention>,
+    #[serde(default)]
+    pub committees: Vec<CommitteeMention>,
+}
+
+/// A Member of Parliament or Senator who spoke or was referred to by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberMention {
+    pub name: String,
+    /// Constituency for MPs, county for Senators
+    pub constituency: Option<String>,
+    pub party: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BillMention {
+    pub name: String,
+    /// e.g. "National Assembly Bill No. 12 of 2025"
+    pub number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitteeMention {
+    pub name: String,
+}
+
+/// The kinds of entities streams are searched by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Member,
+    Bill,
+    Committee,
+}
+
+impl StreamEntities {
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty() && self.bills.is_empty() && self.committees.is_empty()
+    }
+}
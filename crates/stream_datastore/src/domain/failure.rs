@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A stream whose processing failed and that has not been stored since
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct FailedStream {
+    pub video_id: String,
+    /// Failed attempts at processing the stream, the first included
+    #[sqlx(try_from = "i32")]
+    pub attempts: u32,
+    pub last_error: String,
+    pub last_failed_at: DateTime<Utc>,
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, FromRow};
+
+use crate::{Chapter, OrderPaper, StreamCategory, StructuredSummary};
+
+/// What a stored stream was listed with, for telling whether a newly listed stream is
+/// the same sitting uploaded again
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct StoredStream {
+    pub video_id: String,
+    pub title: String,
+    pub duration: String,
+    pub stream_timestamp: DateTime<Utc>,
+}
+
+/// Which published streams [`StreamReader::list_streams`](crate::StreamReader::list_streams)
+/// returns, newest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamQuery {
+    /// Only streams stored with this category. Streams not yet classified are left out.
+    pub category: Option<StreamCategory>,
+    /// Only streams that started at or after this
+    pub from: Option<DateTime<Utc>>,
+    /// Only streams that started before this
+    pub to: Option<DateTime<Utc>>,
+    /// 1-based page of the results
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl StreamQuery {
+    /// Largest page size a query is served with
+    pub const MAX_PER_PAGE: u32 = 100;
+
+    /// Streams to skip before the page starts
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page.max(1) - 1) * u64::from(self.limit())
+    }
+
+    /// Streams on a page, capped at [`StreamQuery::MAX_PER_PAGE`]
+    pub fn limit(&self) -> u32 {
+        self.per_page.clamp(1, Self::MAX_PER_PAGE)
+    }
+}
+
+impl Default for StreamQuery {
+    fn default() -> Self {
+        Self {
+            category: None,
+            from: None,
+            to: None,
+            page: 1,
+            per_page: 20,
+        }
+    }
+}
+
+/// A published stream as it is listed, without its summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedStream {
+    pub video_id: String,
+    pub title: String,
+    pub stream_timestamp: DateTime<Utc>,
+    pub duration: String,
+    pub category: Option<StreamCategory>,
+    pub thumbnail_url: Option<String>,
+    pub has_summary: bool,
+}
+
+/// A published stream with its summaries, as it is shown on its own page
+#[derive(Debug, Clone)]
+pub struct StreamDetail {
+    pub stream: ListedStream,
+    pub description: Option<String>,
+    pub chapters: Option<Json<Vec<Chapter>>>,
+    pub order_paper: Option<Json<OrderPaper>>,
+    pub summary_md: Option<String>,
+    pub summary_tldr: Option<String>,
+    pub timestamp_md: Option<String>,
+    pub structured_summary: Option<Json<StructuredSummary>>,
+}
+
+/// A published stream whose title or summary matched a search, ranked against the
+/// other matches
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    pub stream: ListedStream,
+    /// How closely the stream matched, higher first
+    pub rank: f32,
+    /// Excerpt of the summary around the matched words, which are wrapped in `<mark>`
+    pub snippet: String,
+}
+
+/// A page of [`ListedStream`]s, with how many streams match the query across all pages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamPage {
+    pub streams: Vec<ListedStream>,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_are_capped_and_one_based() {
+        let query = StreamQuery {
+            page: 3,
+            per_page: 500,
+            ..Default::default()
+        };
+        assert_eq!(query.limit(), StreamQuery::MAX_PER_PAGE);
+        assert_eq!(query.offset(), 200);
+
+        let first = StreamQuery {
+            page: 0,
+            ..Default::default()
+        };
+        assert_eq!(first.offset(), 0);
+    }
+}
@@ -1,3 +1,29 @@
+mod api_key;
+mod chapter;
+mod division;
+mod embedding;
+mod entity;
+mod failure;
+mod listing;
+mod order_paper;
+mod state;
 mod stream;
+mod subscriber;
+mod summary;
+mod verification;
+mod webhook;
 
-pub use stream::{Stream, StreamCategory, TIME_AGO_REGEX};
+pub use api_key::{ApiKey, ApiKeyRole};
+pub use chapter::Chapter;
+pub use division::{Division, DivisionOutcome};
+pub use embedding::{ChunkEmbedding, StreamEmbeddings};
+pub use entity::{BillMention, CommitteeMention, EntityKind, MemberMention, StreamEntities};
+pub use failure::FailedStream;
+pub use listing::{ListedStream, SearchMatch, StoredStream, StreamDetail, StreamPage, StreamQuery};
+pub use order_paper::OrderPaper;
+pub use state::{IllegalTransition, PipelineStage, StreamState};
+pub use stream::{Stream, StreamCategory, StreamStatus, TIME_AGO_REGEX};
+pub use subscriber::{Subscriber, WhatsAppSubscriber};
+pub use summary::{BillDiscussed, KeySpeaker, Motion, StructuredSummary, Vote};
+pub use verification::{SummaryVerification, VerificationIssue, VerificationIssueKind};
+pub use webhook::{WebhookEvent, WebhookSubscription, WebhookTarget};
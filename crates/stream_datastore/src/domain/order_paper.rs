@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// The order paper published on parliament.go.ke for the sitting a stream recorded
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct OrderPaper {
+    /// The order paper document, usually a PDF
+    pub url: String,
+    /// Headings of the items of business, in order, e.g. "PRAYERS" or
+    /// "THE FINANCE BILL (NATIONAL ASSEMBLY BILLS NO. 30 OF 2025)"
+    pub agenda: Vec<String>,
+}
@@ -0,0 +1,216 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Stages of processing a stream, each moving it to the next [`StreamState`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Download,
+    Clean,
+    Transcribe,
+    Summarize,
+    Store,
+}
+
+impl PipelineStage {
+    /// Label the stage is stored with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PipelineStage::Download => "download",
+            PipelineStage::Clean => "clean",
+            PipelineStage::Transcribe => "transcribe",
+            PipelineStage::Summarize => "summarize",
+            PipelineStage::Store => "store",
+        }
+    }
+}
+
+impl Display for PipelineStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PipelineStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "download" => Ok(PipelineStage::Download),
+            "clean" => Ok(PipelineStage::Clean),
+            "transcribe" => Ok(PipelineStage::Transcribe),
+            "summarize" => Ok(PipelineStage::Summarize),
+            "store" => Ok(PipelineStage::Store),
+            other => Err(format!("Unsupported pipeline stage: {other}")),
+        }
+    }
+}
+
+/// How far a stream got through the pipeline
+///
+/// Streams move forward through the states in order, skipping those a stream doesn't go
+/// through, e.g. straight from `Discovered` to `Transcribed` when transcribed from its
+/// captions or resumed from a checkpoint. Any state before `Stored` can fail, and a
+/// failed stream is discovered again by a later run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamState {
+    #[default]
+    Discovered,
+    Downloaded,
+    Cleaned,
+    Transcribed,
+    Summarized,
+    Stored,
+    Failed {
+        stage: PipelineStage,
+    },
+}
+
+/// A change of state [`StreamState::transition`] rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("A stream can't go from {from} to {to}")]
+pub struct IllegalTransition {
+    pub from: StreamState,
+    pub to: StreamState,
+}
+
+impl StreamState {
+    /// Label the state is stored with, the failed stage being stored on its own
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamState::Discovered => "discovered",
+            StreamState::Downloaded => "downloaded",
+            StreamState::Cleaned => "cleaned",
+            StreamState::Transcribed => "transcribed",
+            StreamState::Summarized => "summarized",
+            StreamState::Stored => "stored",
+            StreamState::Failed { .. } => "failed",
+        }
+    }
+
+    /// The state stored as `state`, with the stage it failed in if it is "failed"
+    pub fn from_parts(state: &str, failed_stage: Option<&str>) -> Result<Self, String> {
+        match state {
+            "discovered" => Ok(StreamState::Discovered),
+            "downloaded" => Ok(StreamState::Downloaded),
+            "cleaned" => Ok(StreamState::Cleaned),
+            "transcribed" => Ok(StreamState::Transcribed),
+            "summarized" => Ok(StreamState::Summarized),
+            "stored" => Ok(StreamState::Stored),
+            "failed" => Ok(StreamState::Failed {
+                stage: failed_stage
+                    .ok_or("Failed stream state without a stage")?
+                    .parse()?,
+            }),
+            other => Err(format!("Unsupported stream state: {other}")),
+        }
+    }
+
+    /// Position of the state in the pipeline, `None` when failed
+    fn position(&self) -> Option<u8> {
+        match self {
+            StreamState::Discovered => Some(0),
+            StreamState::Downloaded => Some(1),
+            StreamState::Cleaned => Some(2),
+            StreamState::Transcribed => Some(3),
+            StreamState::Summarized => Some(4),
+            StreamState::Stored => Some(5),
+            StreamState::Failed { .. } => None,
+        }
+    }
+
+    /// The stage that moves a stream on from this state, `None` once stored or failed
+    pub fn next_stage(&self) -> Option<PipelineStage> {
+        match self {
+            StreamState::Discovered => Some(PipelineStage::Download),
+            StreamState::Downloaded => Some(PipelineStage::Clean),
+            StreamState::Cleaned => Some(PipelineStage::Transcribe),
+            StreamState::Transcribed => Some(PipelineStage::Summarize),
+            StreamState::Summarized => Some(PipelineStage::Store),
+            StreamState::Stored | StreamState::Failed { .. } => None,
+        }
+    }
+
+    /// Whether the stream has got at least as far as `state`
+    pub fn reached(&self, state: StreamState) -> bool {
+        match (self.position(), state.position()) {
+            (Some(current), Some(target)) => current >= target,
+            _ => *self == state,
+        }
+    }
+
+    pub fn can_transition_to(&self, next: StreamState) -> bool {
+        match (self, next) {
+            (StreamState::Failed { .. }, StreamState::Discovered) => true,
+            (StreamState::Failed { .. }, _) | (StreamState::Stored, _) => false,
+            // only the stages still ahead of the stream can fail it
+            (current, StreamState::Failed { stage }) => {
+                current.next_stage().is_some_and(|next| stage >= next)
+            }
+            (current, next) => current.position() < next.position(),
+        }
+    }
+
+    /// The state after moving to `next`, if that is a legal transition
+    pub fn transition(self, next: StreamState) -> Result<StreamState, IllegalTransition> {
+        match self.can_transition_to(next) {
+            true => Ok(next),
+            false => Err(IllegalTransition {
+                from: self,
+                to: next,
+            }),
+        }
+    }
+
+    /// The state of a stream that failed in its next stage, or the state itself once
+    /// there is none
+    pub fn failed(self) -> StreamState {
+        match self.next_stage() {
+            Some(stage) => StreamState::Failed { stage },
+            None => self,
+        }
+    }
+}
+
+impl Display for StreamState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamState::Failed { stage } => write!(f, "failed to {stage}"),
+            state => f.write_str(state.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streams_only_move_forward_until_they_fail() {
+        let captioned = StreamState::Discovered.transition(StreamState::Transcribed);
+        assert_eq!(captioned, Ok(StreamState::Transcribed));
+        assert!(!StreamState::Summarized.can_transition_to(StreamState::Cleaned));
+        assert!(!StreamState::Stored.can_transition_to(StreamState::Discovered));
+
+        let failed = StreamState::Transcribed.failed();
+        assert_eq!(
+            failed,
+            StreamState::Failed {
+                stage: PipelineStage::Summarize
+            }
+        );
+        assert!(
+            !StreamState::Transcribed.can_transition_to(StreamState::Failed {
+                stage: PipelineStage::Download
+            })
+        );
+        assert!(failed.can_transition_to(StreamState::Discovered));
+        assert!(!failed.can_transition_to(StreamState::Summarized));
+        assert_eq!(
+            StreamState::from_parts(failed.as_str(), Some("summarize")),
+            Ok(failed)
+        );
+    }
+}
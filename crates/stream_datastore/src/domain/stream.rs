@@ -1,9 +1,12 @@
 use chrono::{DateTime, Duration, Utc};
 use regex::Regex;
-use sqlx::FromRow;
+use sqlx::{types::Json, FromRow};
 use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::LazyLock;
 
+use crate::domain::{Chapter, OrderPaper, StructuredSummary, SummaryVerification};
+
 pub static TIME_AGO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(\d+)\s+(second|minute|hour|day|week|month|year)s?\s+ago").unwrap()
 });
@@ -12,13 +15,44 @@ pub static TIME_AGO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 pub struct Stream {
     pub video_id: String,
     pub title: String,
+    /// View count as YouTube displays it, e.g. "12,345 views" or "1.2K views".
+    /// See [`Stream::views`] for the number.
     pub view_count: String,
     /// Initially fetched stream date from youtube in "time ago" format. This is easily expired when persisted, hence
-    /// the need to infer a timestamp using the `timestamp_from_time_ago` function
+    /// the need to infer a timestamp using the `timestamp_from_time_ago` function. Streams listed through the
+    /// YouTube Data API carry their exact RFC 3339 publish date instead
     pub streamed_date: String,
     pub duration: String,
+    /// Exact start of the stream, when it could be resolved
+    pub published_at_exact: Option<DateTime<Utc>>,
+    /// Video description, or the snippet of it shown on the channel page until the full
+    /// one is read from the watch page. Often lists the order paper's items.
+    pub description: Option<String>,
+    /// Chapter markers listed in the description
+    pub chapters: Option<Json<Vec<Chapter>>>,
+    /// Largest thumbnail YouTube lists the video with, or where it was mirrored to
+    pub thumbnail_url: Option<String>,
+    /// Order paper of the sitting, when one was found for the stream's date and house
+    pub order_paper: Option<Json<OrderPaper>>,
     pub summary_md: Option<String>,
+    /// One-paragraph summary for social media posts
+    pub summary_tldr: Option<String>,
     pub timestamp_md: Option<String>,
+    pub structured_summary: Option<Json<StructuredSummary>>,
+    pub summary_verification: Option<Json<SummaryVerification>>,
+    /// Category from the stream's channel or its title, or from a model when the title is
+    /// ambiguous. `None` until classified. Stored as [`StreamCategory::as_str`].
+    #[sqlx(skip)]
+    pub category: Option<StreamCategory>,
+    /// Whether the video was streamed live, rather than uploaded as a clip or an edited
+    /// recording. Sources that can't tell take every video to have been streamed.
+    #[sqlx(skip)]
+    pub is_live_recording: bool,
+    /// Whether the stream is scheduled, still being broadcast, or over. Scheduled and live
+    /// streams are recorded with no summary, and processed once they end. Stored as
+    /// [`StreamStatus::as_str`].
+    #[sqlx(skip)]
+    pub status: StreamStatus,
 }
 
 impl Stream {
@@ -31,11 +65,45 @@ impl Stream {
         format!("https://www.youtube.com/watch?v={}", self.video_id)
     }
 
+    /// Sets the stream's description, and the chapters listed in it, if any
+    pub fn set_description(&mut self, description: impl Into<String>) {
+        let description = description.into();
+        let chapters = Chapter::from_description(&description);
+        self.chapters = (!chapters.is_empty()).then_some(Json(chapters));
+        self.description = Some(description).filter(|d| !d.trim().is_empty());
+    }
+
+    /// Number of views in `view_count`, e.g. 12345 for "12,345 views" or 1200 for
+    /// "1.2K views". Abbreviated counts are approximate, as YouTube rounds them.
+    pub fn views(&self) -> Option<u64> {
+        let count = self.view_count.split_whitespace().next()?.replace(',', "");
+        if count.eq_ignore_ascii_case("no") {
+            return Some(0);
+        }
+
+        let (number, multiplier) = match count.chars().last()? {
+            'K' | 'k' => (&count[..count.len() - 1], 1e3),
+            'M' | 'm' => (&count[..count.len() - 1], 1e6),
+            'B' | 'b' => (&count[..count.len() - 1], 1e9),
+            _ => return count.parse().ok(),
+        };
+        let number = number.parse::<f64>().ok()?;
+        (number.is_finite() && number >= 0.0).then(|| (number * multiplier).round() as u64)
+    }
+
+    /// When the stream started: `published_at_exact` if it was resolved,
+    /// otherwise the approximation from [`Stream::timestamp_from_time_ago`]
+    pub fn published_at(&self) -> Option<DateTime<Utc>> {
+        self.published_at_exact
+            .or_else(|| self.timestamp_from_time_ago())
+    }
+
     /// Attempts to parse the `streamed_date` field and convert it to a `DateTime<Utc>`.
     ///
     /// This method interprets strings in the format "X units ago" where units can be
     /// seconds, minutes, hours, days, weeks, months, or years. It then calculates
-    /// an approximate timestamp based on the current time.
+    /// an approximate timestamp based on the current time. RFC 3339 dates, as returned
+    /// by the YouTube Data API, are used as they are.
     ///
     /// # Returns
     ///
@@ -47,6 +115,10 @@ impl Stream {
     /// The calculated timestamp is an approximation and may not be exact, especially
     /// for longer time periods like months or years due to varying month lengths and leap years.
     pub fn timestamp_from_time_ago(&self) -> Option<DateTime<Utc>> {
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(&self.streamed_date) {
+            return Some(timestamp.with_timezone(&Utc));
+        }
+
         let now = Utc::now();
 
         if let Some(captures) = TIME_AGO_REGEX.captures(&self.streamed_date) {
@@ -72,32 +144,150 @@ impl Stream {
 
     /// Attempts to determine the StreamCategory from a given title.
     ///
-    /// This function searches for specific keywords in the title to identify
-    /// the appropriate StreamCategory.
+    /// Uses the stream's `category` if it has been classified, and otherwise searches
+    /// the title for keywords with [`StreamCategory::from_title`].
     pub fn category(&self) -> StreamCategory {
-        if self.title.to_lowercase().contains("national assembly") {
-            return StreamCategory::NationalAssembly;
-        }
-        if self.title.to_lowercase().contains("senate") {
-            return StreamCategory::Senate;
+        self.category
+            .or_else(|| StreamCategory::from_title(&self.title))
+            .unwrap_or(StreamCategory::Other)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamStatus {
+    /// Announced with a scheduled start, which [`Stream::published_at_exact`] holds
+    /// until the broadcast starts
+    Scheduled,
+    /// Being broadcast now, too early to process
+    Live,
+    /// The broadcast has ended and the stream can be processed
+    #[default]
+    Archived,
+}
+
+impl StreamStatus {
+    /// Label the status is stored with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamStatus::Scheduled => "scheduled",
+            StreamStatus::Live => "live",
+            StreamStatus::Archived => "archived",
         }
-        StreamCategory::Other
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StreamCategory {
     NationalAssembly,
     Senate,
+    Committee,
     Other,
 }
 
+impl StreamCategory {
+    /// Classifies a stream by the house or committee named in its title, e.g.
+    /// "National Assembly | Committee of the Whole House". `None` when the title
+    /// names neither house, or both.
+    pub fn from_title(title: &str) -> Option<Self> {
+        let title = title.to_lowercase();
+        // the committee of the whole house is a sitting of the house itself
+        if title.contains("committee") && !title.contains("committee of the whole") {
+            return Some(StreamCategory::Committee);
+        }
+
+        match (
+            title.contains("national assembly"),
+            title.contains("senate"),
+        ) {
+            (true, false) => Some(StreamCategory::NationalAssembly),
+            (false, true) => Some(StreamCategory::Senate),
+            _ => None,
+        }
+    }
+
+    /// Label the category is stored with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamCategory::NationalAssembly => "national_assembly",
+            StreamCategory::Senate => "senate",
+            StreamCategory::Committee => "committee",
+            StreamCategory::Other => "other",
+        }
+    }
+}
+
 impl Display for StreamCategory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             StreamCategory::NationalAssembly => write!(f, "National Assembly"),
             StreamCategory::Senate => write!(f, "Senate"),
+            StreamCategory::Committee => write!(f, "Committee"),
             StreamCategory::Other => write!(f, "Other"),
         }
     }
 }
+
+impl FromStr for StreamCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['-', '_', ' '], "")
+            .as_str()
+        {
+            "nationalassembly" | "na" => Ok(StreamCategory::NationalAssembly),
+            "senate" => Ok(StreamCategory::Senate),
+            "committee" => Ok(StreamCategory::Committee),
+            "other" => Ok(StreamCategory::Other),
+            other => Err(format!("Unsupported stream category: {other}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_from_title() {
+        let from_title = StreamCategory::from_title;
+        assert_eq!(
+            from_title("National Assembly | Committee of the Whole House | Tuesday 4th March 2025"),
+            Some(StreamCategory::NationalAssembly)
+        );
+        assert_eq!(
+            from_title("Senate Plenary | Afternoon Sitting"),
+            Some(StreamCategory::Senate)
+        );
+        assert_eq!(
+            from_title("Senate Standing Committee on Health"),
+            Some(StreamCategory::Committee)
+        );
+        assert_eq!(
+            from_title("Joint Sitting of the National Assembly and the Senate"),
+            None
+        );
+        assert_eq!(from_title("Presidential Address"), None);
+    }
+
+    #[test]
+    fn test_views() {
+        let views = |view_count: &str| {
+            Stream {
+                view_count: view_count.to_string(),
+                ..Default::default()
+            }
+            .views()
+        };
+        assert_eq!(views("12,345 views"), Some(12_345));
+        assert_eq!(views("1 view"), Some(1));
+        assert_eq!(views("No views"), Some(0));
+        assert_eq!(views("1.2K views"), Some(1_200));
+        assert_eq!(views("3M views"), Some(3_000_000));
+        assert_eq!(views("8200"), Some(8_200));
+        assert_eq!(views(""), None);
+        assert_eq!(views("many views"), None);
+    }
+}
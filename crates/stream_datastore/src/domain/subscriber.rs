@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+/// A newsletter subscriber who confirmed their address and hasn't unsubscribed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subscriber {
+    pub email: String,
+    /// Token of the subscriber's confirmation and unsubscribe links
+    pub token: String,
+}
+
+/// A phone number on a WhatsApp broadcast list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WhatsAppSubscriber {
+    pub list: String,
+    /// Digits only, with the country code, e.g. "254712345678"
+    pub phone_number: String,
+    /// Whether the number may be sent summaries. WhatsApp only allows messaging numbers
+    /// that opted in
+    pub opted_in: bool,
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable breakdown of a sitting, generated alongside the markdown summary.
+///
+/// Fields are intentionally loose (`Option`s and free-form strings) since the
+/// content is extracted by an LLM from noisy transcripts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructuredSummary {
+    #[serde(default)]
+    pub bills: Vec<BillDiscussed>,
+    #[serde(default)]
+    pub motions: Vec<Motion>,
+    #[serde(default)]
+    pub votes: Vec<Vote>,
+    #[serde(default)]
+    pub key_speakers: Vec<KeySpeaker>,
+    #[serde(default)]
+    pub action_items: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BillDiscussed {
+    pub name: String,
+    /// Bill number as referenced in the sitting, e.g. "National Assembly Bill No. 12 of 2025"
+    pub number: Option<String>,
+    /// Legislative stage, e.g. "Second Reading"
+    pub stage: Option<String>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Motion {
+    pub title: String,
+    pub mover: Option<String>,
+    pub outcome: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vote {
+    pub subject: String,
+    pub result: String,
+    pub ayes: Option<u32>,
+    pub noes: Option<u32>,
+    pub abstentions: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeySpeaker {
+    pub name: String,
+    /// e.g. "Speaker", "Majority Leader", "MP for Kibra"
+    pub role: Option<String>,
+    pub summary: String,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of checking a generated summary against its transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryVerification {
+    /// The verifier's confidence, from 0 to 1, that the summary is faithful to the transcript
+    pub confidence: f32,
+    #[serde(default)]
+    pub issues: Vec<VerificationIssue>,
+    /// Set when the summary stayed below the confidence threshold after all regenerations
+    #[serde(default)]
+    pub flagged: bool,
+    /// Number of summaries generated, including the first
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// A claim in the summary that the transcript does not support
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationIssue {
+    pub kind: VerificationIssueKind,
+    /// The claim as it appears in the summary
+    pub claim: String,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationIssueKind {
+    Name,
+    Figure,
+    BillNumber,
+    Other,
+}
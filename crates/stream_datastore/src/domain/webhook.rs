@@ -0,0 +1,64 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// What happened that a webhook subscription is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A stream was summarized and stored
+    NewSummary,
+    /// A stream whose summary records votes was stored
+    NewVoteRecord,
+}
+
+impl WebhookEvent {
+    /// Label the event is stored and delivered with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::NewSummary => "new_summary",
+            WebhookEvent::NewVoteRecord => "new_vote_record",
+        }
+    }
+}
+
+impl Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "new_summary" => Ok(WebhookEvent::NewSummary),
+            "new_vote_record" => Ok(WebhookEvent::NewVoteRecord),
+            other => Err(format!("Unsupported webhook event: {other}")),
+        }
+    }
+}
+
+/// A callback URL registered with an API key, without the secret its deliveries are
+/// signed with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WebhookSubscription {
+    pub id: i64,
+    /// Name of the API key that registered it, the only key that may change it
+    pub owner: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Whether it is delivered events. Disabled after repeated failures
+    pub enabled: bool,
+    /// Deliveries failed since the last one that succeeded
+    pub consecutive_failures: u32,
+}
+
+/// Where an event is delivered, and the secret to sign it with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookTarget {
+    pub subscription_id: i64,
+    pub url: String,
+    pub secret: String,
+}
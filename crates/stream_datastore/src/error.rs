@@ -0,0 +1,74 @@
+/// Errors returned by [`DataStore`](crate::DataStore) implementations.
+///
+/// The variants are coarse on purpose so that callers can decide how to react
+/// (e.g. retry on `Connection`, skip on `Constraint`) without depending on the
+/// underlying database driver.
+#[derive(thiserror::Error, Debug)]
+pub enum DataStoreError {
+    #[error("Connection error: {0}")]
+    Connection(#[source] sqlx::Error),
+    #[error("Migration error: {0}")]
+    Migration(#[from] sqlx::migrate::MigrateError),
+    #[error("Constraint violation: {0}")]
+    Constraint(String),
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+    #[error(transparent)]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<sqlx::Error> for DataStoreError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => DataStoreError::Connection(err),
+            sqlx::Error::Database(ref db_err)
+                if db_err.is_unique_violation()
+                    || db_err.is_foreign_key_violation()
+                    || db_err.is_check_violation() =>
+            {
+                DataStoreError::Constraint(db_err.message().to_string())
+            }
+            sqlx::Error::Encode(_)
+            | sqlx::Error::Decode(_)
+            | sqlx::Error::ColumnDecode { .. }
+            | sqlx::Error::TypeNotFound { .. } => DataStoreError::Serialization(err.to_string()),
+            sqlx::Error::Migrate(migrate_err) => DataStoreError::Migration(*migrate_err),
+            err => DataStoreError::Other(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_errors_map_to_connection() {
+        assert!(matches!(
+            DataStoreError::from(sqlx::Error::PoolTimedOut),
+            DataStoreError::Connection(_)
+        ));
+        assert!(matches!(
+            DataStoreError::from(sqlx::Error::PoolClosed),
+            DataStoreError::Connection(_)
+        ));
+    }
+
+    #[test]
+    fn test_decode_errors_map_to_serialization() {
+        let err = DataStoreError::from(sqlx::Error::Decode("bad value".into()));
+        assert!(matches!(err, DataStoreError::Serialization(_)));
+    }
+
+    #[test]
+    fn test_unclassified_errors_map_to_other() {
+        assert!(matches!(
+            DataStoreError::from(sqlx::Error::RowNotFound),
+            DataStoreError::Other(_)
+        ));
+    }
+}
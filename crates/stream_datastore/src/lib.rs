@@ -8,8 +8,25 @@
 
 mod datastore;
 mod domain;
+mod error;
 
 // pub use datastore::DataStore;
 pub use datastore::postgres::PgDataStore;
-pub use datastore::{BulkInsertResult, DataStore};
-pub use domain::{Stream, StreamCategory};
+pub use datastore::{
+    ApiKeyStore, BulkInsertResult, DataStore, FailedStreamStore, ReuploadStore, StreamReader,
+    StreamStateStore, SubscriberStore, TranscriptStore, WebhookSubscriptionStore, WhatsAppStore,
+};
+#[cfg(feature = "pgvector")]
+pub use datastore::{EmbeddingStore, SimilarStream, SimilaritySearch};
+pub use domain::{
+    ApiKey, ApiKeyRole, BillDiscussed, BillMention, Chapter, ChunkEmbedding, CommitteeMention,
+    Division, DivisionOutcome, EntityKind, FailedStream, IllegalTransition, KeySpeaker,
+    ListedStream, MemberMention, Motion, OrderPaper, PipelineStage, SearchMatch, StoredStream,
+    Stream, StreamCategory, StreamDetail, StreamEmbeddings, StreamEntities, StreamPage,
+    StreamQuery, StreamState, StreamStatus, StructuredSummary, Subscriber, SummaryVerification,
+    VerificationIssue, VerificationIssueKind, Vote, WebhookEvent, WebhookSubscription,
+    WebhookTarget, WhatsAppSubscriber,
+};
+pub use error::DataStoreError;
+// re-exported so consumers can set `Stream::structured_summary` without depending on sqlx
+pub use sqlx::types::Json;
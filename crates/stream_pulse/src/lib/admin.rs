@@ -0,0 +1,412 @@
+//! # Admin
+//!
+//! Endpoints for operators to start runs without exec-ing into the container, served
+//! by the [server](crate::server) and authorized by an admin API key
+//! sent as a bearer token:
+//!
+//! - `POST /admin/run` queues a run, with an optional JSON body overriding the run's
+//!   `max_streams`, or setting `dry_run` to list the streams it would process without
+//!   processing them, e.g. `{"max_streams": 1, "dry_run": true}`
+//! - `POST /admin/reprocess/{video_id}` queues a run processing the video, whether or not
+//!   it is stored
+//!
+//! Both answer 202 once the run is queued. It starts once the runs before it finish.
+//! The [WhatsApp broadcast lists](crate::whatsapp) are managed under `/admin/whatsapp/`
+//! when they are enabled, and [webhook subscriptions](crate::webhooks) under
+//! `/api/webhooks`, the GraphQL API at `/api/graphql` when built with the `graphql`
+//! feature, and the [streams API](crate::api) under the rest of `/api/`, the only
+//! endpoints public keys are authorized for.
+//!
+//! Keys are stored by their SHA-256 [hash](hash_api_key), with a role and an optional
+//! per-minute request budget. Requests with an unknown key are answered 401, with a
+//! key whose role doesn't allow them 403, and over the key's budget 429.
+
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+};
+
+use futures::future::BoxFuture;
+use governor::{DefaultDirectRateLimiter, Quota};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use stream_datastore::{ApiKey, ApiKeyRole};
+
+#[cfg(feature = "graphql")]
+use crate::graphql::{self, GraphQlSchema};
+use crate::{
+    api::{self, StreamSource},
+    server::{Request, Response},
+    webhooks::{self, WebhookStore},
+    whatsapp::{self, BroadcastLists},
+};
+
+/// Prefix of the keys [`generate_api_key`] issues, to tell them apart in secret scanners
+const API_KEY_PREFIX: &str = "sp_";
+
+/// Overrides of a run requested through the admin endpoints
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunRequest {
+    pub max_streams: Option<usize>,
+    pub dry_run: bool,
+    /// Video to process instead of the streams listed on the channels, from the path of
+    /// `/admin/reprocess`
+    #[serde(skip)]
+    pub video_id: Option<String>,
+}
+
+/// Queues a requested run, failing with why it couldn't be, e.g. while shutting down
+pub type RunTrigger = Arc<dyn Fn(RunRequest) -> Result<(), String> + Send + Sync>;
+
+/// Looks up the API key stored under a key's [hash](hash_api_key), `None` if there is
+/// none or it was revoked
+pub type ApiKeyLookup =
+    Arc<dyn Fn(String) -> BoxFuture<'static, Result<Option<ApiKey>, String>> + Send + Sync>;
+
+/// The admin endpoints, handing the runs of requests authorized by an admin key to
+/// `trigger`
+#[derive(Clone)]
+pub struct AdminApi {
+    trigger: RunTrigger,
+    api_keys: Option<ApiKeyLookup>,
+    whatsapp: Option<BroadcastLists>,
+    webhooks: Option<Arc<dyn WebhookStore>>,
+    streams: Option<Arc<dyn StreamSource>>,
+    #[cfg(feature = "graphql")]
+    graphql: Option<GraphQlSchema>,
+    /// A key authorized as admin without a lookup, and without a budget
+    token: Option<Arc<str>>,
+    budgets: Budgets,
+}
+
+/// Budgets of the keys that have one, by key name, with the requests per minute each
+/// limiter was created for
+type Budgets = Arc<Mutex<HashMap<String, (u32, Arc<DefaultDirectRateLimiter>)>>>;
+
+impl AdminApi {
+    /// Endpoints authorizing no one until given keys or a token
+    pub fn new(trigger: RunTrigger) -> Self {
+        Self {
+            trigger,
+            api_keys: None,
+            whatsapp: None,
+            webhooks: None,
+            streams: None,
+            #[cfg(feature = "graphql")]
+            graphql: None,
+            token: None,
+            budgets: Default::default(),
+        }
+    }
+
+    /// Authorize the keys `api_keys` finds by their role
+    pub fn with_api_keys(mut self, api_keys: ApiKeyLookup) -> Self {
+        self.api_keys = Some(api_keys);
+        self
+    }
+
+    /// Manage the WhatsApp broadcast lists with `lists`
+    pub fn with_broadcast_lists(mut self, lists: BroadcastLists) -> Self {
+        self.whatsapp = Some(lists);
+        self
+    }
+
+    /// Manage the webhook subscriptions kept in `store`
+    pub fn with_webhook_store(mut self, store: Arc<dyn WebhookStore>) -> Self {
+        self.webhooks = Some(store);
+        self
+    }
+
+    /// Answer the streams API from `source`
+    pub fn with_stream_source(mut self, source: Arc<dyn StreamSource>) -> Self {
+        self.streams = Some(source);
+        self
+    }
+
+    /// Answer GraphQL queries at `/api/graphql` with `schema`
+    #[cfg(feature = "graphql")]
+    pub fn with_graphql(mut self, schema: GraphQlSchema) -> Self {
+        self.graphql = Some(schema);
+        self
+    }
+
+    /// Also authorize `token` as an admin key, e.g. before any key is stored
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into().into());
+        self
+    }
+
+    pub(crate) async fn route(&self, request: &Request) -> Response {
+        let key = match self.authenticate(request).await {
+            Ok(Some(key)) => key,
+            Ok(None) => return ("401 Unauthorized", "text/plain", "unauthorized".into()),
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to look up API key");
+                return (
+                    "503 Service Unavailable",
+                    "text/plain",
+                    "API keys unavailable".into(),
+                );
+            }
+        };
+        let api = request.path.starts_with("/api/");
+        let required = if api {
+            ApiKeyRole::Public
+        } else {
+            ApiKeyRole::Admin
+        };
+        if !key.role.allows(required) {
+            return ("403 Forbidden", "text/plain", "forbidden".into());
+        }
+        if !self.within_budget(&key) {
+            return (
+                "429 Too Many Requests",
+                "text/plain",
+                "too many requests".into(),
+            );
+        }
+        if request.path == "/api/webhooks" || request.path.starts_with("/api/webhooks/") {
+            return match &self.webhooks {
+                Some(store) => webhooks::route(store.as_ref(), &key, request).await,
+                None => ("404 Not Found", "text/plain", "not found".into()),
+            };
+        }
+        #[cfg(feature = "graphql")]
+        if request.path == "/api/graphql" {
+            return match &self.graphql {
+                Some(schema) => graphql::route(schema, request).await,
+                None => ("404 Not Found", "text/plain", "not found".into()),
+            };
+        }
+        if api {
+            return match &self.streams {
+                Some(source) => api::route(source.as_ref(), request).await,
+                None => ("404 Not Found", "text/plain", "not found".into()),
+            };
+        }
+        if request.path.starts_with("/admin/whatsapp/") {
+            return match &self.whatsapp {
+                Some(lists) => whatsapp::route(lists, request).await,
+                None => ("404 Not Found", "text/plain", "not found".into()),
+            };
+        }
+        if request.method != "POST" {
+            return (
+                "405 Method Not Allowed",
+                "text/plain",
+                "method not allowed".into(),
+            );
+        }
+
+        let run = match request.path.as_str() {
+            "/admin/run" if request.body.is_empty() => RunRequest::default(),
+            "/admin/run" => match serde_json::from_slice(&request.body) {
+                Ok(run) => run,
+                Err(e) => return ("400 Bad Request", "text/plain", e.to_string()),
+            },
+            path => match path.strip_prefix("/admin/reprocess/") {
+                Some(video_id) if is_video_id(video_id) => RunRequest {
+                    video_id: Some(video_id.to_string()),
+                    ..Default::default()
+                },
+                Some(_) => return ("400 Bad Request", "text/plain", "invalid video ID".into()),
+                None => return ("404 Not Found", "text/plain", "not found".into()),
+            },
+        };
+
+        tracing::info!(
+            ?run,
+            key = key.name,
+            "Run requested through the admin endpoints"
+        );
+        match (self.trigger)(run) {
+            Ok(()) => ("202 Accepted", "text/plain", "queued".into()),
+            Err(reason) => ("503 Service Unavailable", "text/plain", reason),
+        }
+    }
+
+    /// The key the request's bearer token was issued as, `None` if there is none
+    async fn authenticate(&self, request: &Request) -> Result<Option<ApiKey>, String> {
+        let Some(token) = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            return Ok(None);
+        };
+        if self
+            .token
+            .as_deref()
+            .is_some_and(|admin| equal(token, admin))
+        {
+            return Ok(Some(ApiKey {
+                name: "ADMIN_TOKEN".into(),
+                role: ApiKeyRole::Admin,
+                requests_per_minute: None,
+            }));
+        }
+        match &self.api_keys {
+            Some(lookup) => lookup(hash_api_key(token)).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Takes a request from the key's budget, if it has one, returning whether it fit.
+    /// A budget changed since the key was last seen starts over.
+    fn within_budget(&self, key: &ApiKey) -> bool {
+        let Some(rpm) = key.requests_per_minute.and_then(NonZeroU32::new) else {
+            return true;
+        };
+        let mut budgets = self.budgets.lock().unwrap();
+        let (budgeted, limiter) = budgets.entry(key.name.clone()).or_insert_with(|| {
+            (
+                rpm.get(),
+                Arc::new(governor::RateLimiter::direct(Quota::per_minute(rpm))),
+            )
+        });
+        if *budgeted != rpm.get() {
+            *budgeted = rpm.get();
+            *limiter = Arc::new(governor::RateLimiter::direct(Quota::per_minute(rpm)));
+        }
+        limiter.check().is_ok()
+    }
+}
+
+/// The hash an API key is stored by, hex encoded. Unsalted, as keys are random rather
+/// than chosen, and have to be found by their hash.
+pub fn hash_api_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// A new random API key, to be shown once and stored by its [hash](hash_api_key)
+pub fn generate_api_key() -> String {
+    let random = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect::<String>();
+    format!("{API_KEY_PREFIX}{random}")
+}
+
+/// Compares `a` and `b` in constant time for strings of the same length
+fn equal(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Whether `id` looks like a YouTube video ID, so that nothing else reaches yt-dlp
+fn is_video_id(id: &str) -> bool {
+    id.len() == 11
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn request(path: &str, token: &str, body: &str) -> Request {
+        Request {
+            method: "POST".into(),
+            path: path.into(),
+            query: String::new(),
+            headers: vec![("authorization".into(), format!("Bearer {token}"))],
+            body: body.as_bytes().to_vec(),
+            peer: None,
+        }
+    }
+
+    fn queueing(queued: &Arc<Mutex<Vec<RunRequest>>>) -> RunTrigger {
+        let queued = queued.clone();
+        Arc::new(move |run| {
+            queued.lock().unwrap().push(run);
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_authorized_requests_queue_runs() {
+        let queued = Arc::new(Mutex::new(Vec::new()));
+        let admin = AdminApi::new(queueing(&queued)).with_token("secret");
+
+        let unauthorized = admin.route(&request("/admin/run", "guess", "")).await;
+        assert_eq!(unauthorized.0, "401 Unauthorized");
+        let invalid = admin
+            .route(&request("/admin/reprocess/../etc", "secret", ""))
+            .await;
+        assert_eq!(invalid.0, "400 Bad Request");
+
+        let run = admin
+            .route(&request("/admin/run", "secret", r#"{"dry_run": true}"#))
+            .await;
+        assert_eq!(run.0, "202 Accepted");
+        let reprocess = admin
+            .route(&request("/admin/reprocess/dQw4w9WgXcQ", "secret", ""))
+            .await;
+        assert_eq!(reprocess.0, "202 Accepted");
+
+        assert_eq!(
+            *queued.lock().unwrap(),
+            vec![
+                RunRequest {
+                    dry_run: true,
+                    ..Default::default()
+                },
+                RunRequest {
+                    video_id: Some("dQw4w9WgXcQ".into()),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_authorized_by_role_and_budget() {
+        let keys = HashMap::from([
+            (
+                hash_api_key("sp_admin"),
+                ApiKey {
+                    name: "ops".into(),
+                    role: ApiKeyRole::Admin,
+                    requests_per_minute: Some(1),
+                },
+            ),
+            (
+                hash_api_key("sp_public"),
+                ApiKey {
+                    name: "site".into(),
+                    role: ApiKeyRole::Public,
+                    requests_per_minute: None,
+                },
+            ),
+        ]);
+        let lookup: ApiKeyLookup = Arc::new(move |hash| {
+            let key = keys.get(&hash).cloned();
+            Box::pin(async move { Ok(key) })
+        });
+        let admin = AdminApi::new(queueing(&Default::default())).with_api_keys(lookup);
+
+        let public = admin.route(&request("/admin/run", "sp_public", "")).await;
+        assert_eq!(public.0, "403 Forbidden");
+        let webhooks = admin
+            .route(&request("/api/webhooks", "sp_public", ""))
+            .await;
+        assert_eq!(webhooks.0, "404 Not Found");
+        let first = admin.route(&request("/admin/run", "sp_admin", "")).await;
+        assert_eq!(first.0, "202 Accepted");
+        let second = admin.route(&request("/admin/run", "sp_admin", "")).await;
+        assert_eq!(second.0, "429 Too Many Requests");
+        assert!(generate_api_key().starts_with(API_KEY_PREFIX));
+    }
+}
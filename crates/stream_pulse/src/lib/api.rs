@@ -0,0 +1,553 @@
+//! # Streams API
+//!
+//! Read-only endpoints over the published streams, for the site and other clients,
+//! served through the [admin endpoints](crate::admin) and authorized by any API key:
+//!
+//! - `GET /api/streams` lists them newest first, filtered by any of `category`, `from`
+//!   and `to`, a page of `per_page` at a time, e.g.
+//!   `/api/streams?category=senate&from=2025-07-01&page=2`
+//! - `GET /api/streams/{video_id}` answers one with its summaries, and its transcript's
+//!   text and timed segments with `?transcript=true`
+//! - `GET /api/search?q=` answers the streams whose title or summary matches `q`, best
+//!   match first, with an excerpt of the summary the matched words are marked in. `q`
+//!   takes web search syntax, e.g. `q="housing levy" -senate`, and the listing's other
+//!   parameters filter and page the matches
+//!
+//! Dates are given as `YYYY-MM-DD` or RFC 3339 timestamps. `to` is exclusive for
+//! timestamps and inclusive for dates. Answers may be cached for
+//! [`LISTING_MAX_AGE`] or, for a single stream, [`STREAM_MAX_AGE`] seconds.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use stream_datastore::{
+    Chapter, ListedStream, OrderPaper, SearchMatch, StreamDetail, StreamPage, StreamQuery,
+    StructuredSummary,
+};
+
+use crate::{
+    server::{Request, Response},
+    TranscribeResponse,
+};
+
+/// Seconds listings and searches may be cached for
+pub const LISTING_MAX_AGE: u32 = 60;
+
+/// Seconds a single stream may be cached for, longer than listings as it only changes
+/// when reprocessed
+pub const STREAM_MAX_AGE: u32 = 300;
+
+/// Where published streams are read from, e.g. the datastore, failing with why it
+/// couldn't be reached
+pub trait StreamSource: Send + Sync {
+    fn list<'a>(&'a self, query: &'a StreamQuery) -> BoxFuture<'a, Result<StreamPage, String>>;
+
+    /// The published streams matching `query` whose title or summary matches `terms`,
+    /// best match first
+    fn search<'a>(
+        &'a self,
+        terms: &'a str,
+        query: &'a StreamQuery,
+    ) -> BoxFuture<'a, Result<Vec<SearchMatch>, String>>;
+
+    /// The published stream `video_id`, `None` if there is none
+    fn stream<'a>(
+        &'a self,
+        video_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<StreamDetail>, String>>;
+
+    /// The stored transcript of `video_id`, `None` if none was stored
+    fn transcript<'a>(
+        &'a self,
+        video_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<TranscribeResponse>, String>>;
+}
+
+impl<T: StreamSource + ?Sized> StreamSource for Arc<T> {
+    fn list<'a>(&'a self, query: &'a StreamQuery) -> BoxFuture<'a, Result<StreamPage, String>> {
+        (**self).list(query)
+    }
+
+    fn search<'a>(
+        &'a self,
+        terms: &'a str,
+        query: &'a StreamQuery,
+    ) -> BoxFuture<'a, Result<Vec<SearchMatch>, String>> {
+        (**self).search(terms, query)
+    }
+
+    fn stream<'a>(
+        &'a self,
+        video_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<StreamDetail>, String>> {
+        (**self).stream(video_id)
+    }
+
+    fn transcript<'a>(
+        &'a self,
+        video_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<TranscribeResponse>, String>> {
+        (**self).transcript(video_id)
+    }
+}
+
+/// A listed stream as it is answered
+#[derive(Debug, Serialize, PartialEq)]
+struct StreamListing<'a> {
+    video_id: &'a str,
+    title: &'a str,
+    /// RFC 3339
+    date: String,
+    duration: &'a str,
+    category: Option<&'a str>,
+    thumbnail_url: Option<&'a str>,
+    has_summary: bool,
+}
+
+impl<'a> From<&'a ListedStream> for StreamListing<'a> {
+    fn from(stream: &'a ListedStream) -> Self {
+        Self {
+            video_id: &stream.video_id,
+            title: &stream.title,
+            date: stream.stream_timestamp.to_rfc3339(),
+            duration: &stream.duration,
+            category: stream.category.map(|c| c.as_str()),
+            thumbnail_url: stream.thumbnail_url.as_deref(),
+            has_summary: stream.has_summary,
+        }
+    }
+}
+
+/// A stream with its summaries as it is answered
+#[derive(Debug, Serialize)]
+struct StreamPageView<'a> {
+    #[serde(flatten)]
+    stream: StreamListing<'a>,
+    description: Option<&'a str>,
+    chapters: Option<&'a [Chapter]>,
+    order_paper: Option<&'a OrderPaper>,
+    summary_md: Option<&'a str>,
+    summary_tldr: Option<&'a str>,
+    timestamp_md: Option<&'a str>,
+    structured_summary: Option<&'a StructuredSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcript: Option<Transcript<'a>>,
+}
+
+impl<'a> StreamPageView<'a> {
+    fn new(detail: &'a StreamDetail, transcript: Option<&'a TranscribeResponse>) -> Self {
+        Self {
+            stream: StreamListing::from(&detail.stream),
+            description: detail.description.as_deref(),
+            chapters: detail.chapters.as_ref().map(|c| c.0.as_slice()),
+            order_paper: detail.order_paper.as_ref().map(|o| &o.0),
+            summary_md: detail.summary_md.as_deref(),
+            summary_tldr: detail.summary_tldr.as_deref(),
+            timestamp_md: detail.timestamp_md.as_deref(),
+            structured_summary: detail.structured_summary.as_ref().map(|s| &s.0),
+            transcript: transcript.map(Transcript::from),
+        }
+    }
+}
+
+/// A stream's transcript as it is answered, without the transcriber's confidence scores
+#[derive(Debug, Serialize)]
+struct Transcript<'a> {
+    text: &'a str,
+    /// Empty if the transcript wasn't stored with its timing
+    segments: Vec<Segment<'a>>,
+}
+
+/// Seconds into the stream a transcript segment starts and ends at
+#[derive(Debug, Serialize)]
+struct Segment<'a> {
+    start: f64,
+    end: f64,
+    text: &'a str,
+}
+
+impl<'a> From<&'a TranscribeResponse> for Transcript<'a> {
+    fn from(transcript: &'a TranscribeResponse) -> Self {
+        Self {
+            text: &transcript.text,
+            segments: transcript
+                .segments
+                .iter()
+                .flatten()
+                .map(|segment| Segment {
+                    start: segment.start,
+                    end: segment.end,
+                    text: segment.text.trim(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A search match as it is answered
+#[derive(Debug, Serialize)]
+struct SearchResult<'a> {
+    #[serde(flatten)]
+    stream: StreamListing<'a>,
+    rank: f32,
+    /// Summary excerpt, the matched words wrapped in `<mark>`
+    snippet: &'a str,
+}
+
+/// A page of search matches as it is answered
+#[derive(Debug, Serialize)]
+struct SearchResults<'a> {
+    results: Vec<SearchResult<'a>>,
+    page: u32,
+    per_page: u32,
+}
+
+/// A page of listed streams as it is answered
+#[derive(Debug, Serialize, PartialEq)]
+struct Page<'a> {
+    streams: Vec<StreamListing<'a>>,
+    page: u32,
+    per_page: u32,
+    total: u64,
+}
+
+pub(crate) async fn route(source: &dyn StreamSource, request: &Request) -> Response {
+    if request.method != "GET" {
+        return (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed".into(),
+        );
+    }
+
+    match request.path.trim_end_matches('/') {
+        "/api/streams" => {
+            let query = match stream_query(request) {
+                Ok(query) => query,
+                Err(reason) => return ("400 Bad Request", "text/plain", reason),
+            };
+            answer(source.list(&query).await.map(|page| {
+                json(&Page {
+                    streams: page.streams.iter().map(StreamListing::from).collect(),
+                    page: page.page,
+                    per_page: page.per_page,
+                    total: page.total,
+                })
+            }))
+        }
+        "/api/search" => {
+            let terms = match request.query_param("q").filter(|q| !q.trim().is_empty()) {
+                Some(terms) => terms,
+                None => return ("400 Bad Request", "text/plain", "missing q".into()),
+            };
+            let query = match stream_query(request) {
+                Ok(query) => query,
+                Err(reason) => return ("400 Bad Request", "text/plain", reason),
+            };
+            answer(source.search(&terms, &query).await.map(|matches| {
+                json(&SearchResults {
+                    results: matches
+                        .iter()
+                        .map(|m| SearchResult {
+                            stream: StreamListing::from(&m.stream),
+                            rank: m.rank,
+                            snippet: &m.snippet,
+                        })
+                        .collect(),
+                    page: query.page.max(1),
+                    per_page: query.limit(),
+                })
+            }))
+        }
+        path => match path.strip_prefix("/api/streams/") {
+            Some(video_id) if !video_id.is_empty() && !video_id.contains('/') => {
+                answer(stream_page(source, video_id, request).await)
+            }
+            _ => ("404 Not Found", "text/plain", "not found".into()),
+        },
+    }
+}
+
+/// The `Cache-Control` the answer to `request` is sent with: successful reads may be
+/// cached, by shared caches too as streams are public, anything else isn't
+pub(crate) fn cache_control(request: &Request, status: &str) -> String {
+    let path = request.path.trim_end_matches('/');
+    if request.method != "GET" || !status.starts_with("200") {
+        return "no-store".into();
+    }
+    match path.strip_prefix("/api/streams/") {
+        Some(_) => format!("public, max-age={STREAM_MAX_AGE}"),
+        None if path == "/api/streams" || path == "/api/search" => {
+            format!("public, max-age={LISTING_MAX_AGE}")
+        }
+        None => "no-store".into(),
+    }
+}
+
+async fn stream_page(
+    source: &dyn StreamSource,
+    video_id: &str,
+    request: &Request,
+) -> Result<Response, String> {
+    let Some(detail) = source.stream(video_id).await? else {
+        return Ok(("404 Not Found", "text/plain", "stream not found".into()));
+    };
+    let transcript = match request.query_param("transcript").as_deref() {
+        Some("true") => source.transcript(&detail.stream.video_id).await?,
+        _ => None,
+    };
+    Ok(json(&StreamPageView::new(&detail, transcript.as_ref())))
+}
+
+fn json(body: &impl Serialize) -> Response {
+    (
+        "200 OK",
+        "application/json",
+        serde_json::to_string(body).unwrap_or_default(),
+    )
+}
+
+fn answer(response: Result<Response, String>) -> Response {
+    response.unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Failed to read streams");
+        (
+            "503 Service Unavailable",
+            "text/plain",
+            "streams unavailable".into(),
+        )
+    })
+}
+
+/// The query the request's `category`, `from`, `to`, `page` and `per_page` make
+fn stream_query(request: &Request) -> Result<StreamQuery, String> {
+    let mut query = StreamQuery::default();
+    if let Some(category) = request.query_param("category").filter(|c| !c.is_empty()) {
+        query.category = Some(category.parse()?);
+    }
+    if let Some(from) = request.query_param("from").filter(|d| !d.is_empty()) {
+        query.from = Some(parse_date(&from, false)?);
+    }
+    if let Some(to) = request.query_param("to").filter(|d| !d.is_empty()) {
+        query.to = Some(parse_date(&to, true)?);
+    }
+    if let Some(page) = request.query_param("page") {
+        query.page = page.parse().map_err(|_| format!("invalid page: {page}"))?;
+    }
+    if let Some(per_page) = request.query_param("per_page") {
+        query.per_page = per_page
+            .parse()
+            .map_err(|_| format!("invalid per_page: {per_page}"))?;
+    }
+    Ok(query)
+}
+
+/// `date` as a timestamp, a date standing for its start or, as an exclusive `end`, the
+/// start of the day after
+pub(crate) fn parse_date(date: &str, end: bool) -> Result<DateTime<Utc>, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(date) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    let day =
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("invalid date: {date}"))?;
+    let day = if end {
+        day.succ_opt().unwrap_or(day)
+    } else {
+        day
+    };
+    Ok(day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use stream_datastore::{Json, StreamCategory};
+
+    use super::*;
+    use crate::TranscribeSegment;
+
+    /// Answers with `streams`, recording the queries it was asked
+    #[derive(Default)]
+    struct Streams {
+        streams: Vec<ListedStream>,
+        queries: Mutex<Vec<StreamQuery>>,
+    }
+
+    impl StreamSource for Streams {
+        fn list<'a>(&'a self, query: &'a StreamQuery) -> BoxFuture<'a, Result<StreamPage, String>> {
+            self.queries.lock().unwrap().push(query.clone());
+            Box::pin(async move {
+                Ok(StreamPage {
+                    streams: self.streams.clone(),
+                    page: query.page,
+                    per_page: query.limit(),
+                    total: self.streams.len() as u64,
+                })
+            })
+        }
+
+        fn search<'a>(
+            &'a self,
+            terms: &'a str,
+            _query: &'a StreamQuery,
+        ) -> BoxFuture<'a, Result<Vec<SearchMatch>, String>> {
+            let matches = self
+                .streams
+                .iter()
+                .map(|stream| SearchMatch {
+                    stream: stream.clone(),
+                    rank: 0.5,
+                    snippet: format!("the <mark>{terms}</mark> was debated"),
+                })
+                .collect();
+            Box::pin(async move { Ok(matches) })
+        }
+
+        fn stream<'a>(
+            &'a self,
+            video_id: &'a str,
+        ) -> BoxFuture<'a, Result<Option<StreamDetail>, String>> {
+            let detail = self
+                .streams
+                .iter()
+                .find(|s| s.video_id == video_id)
+                .map(|stream| StreamDetail {
+                    stream: stream.clone(),
+                    description: None,
+                    chapters: Some(Json(Vec::new())),
+                    order_paper: None,
+                    summary_md: Some("## Summary".into()),
+                    summary_tldr: None,
+                    timestamp_md: None,
+                    structured_summary: None,
+                });
+            Box::pin(async move { Ok(detail) })
+        }
+
+        fn transcript<'a>(
+            &'a self,
+            _video_id: &'a str,
+        ) -> BoxFuture<'a, Result<Option<TranscribeResponse>, String>> {
+            Box::pin(async {
+                Ok(Some(TranscribeResponse {
+                    duration: 4.0,
+                    text: "Order, order".into(),
+                    segments: Some(vec![TranscribeSegment {
+                        start: 0.0,
+                        end: 4.0,
+                        text: " Order, order".into(),
+                        ..Default::default()
+                    }]),
+                }))
+            })
+        }
+    }
+
+    fn listed(video_id: &str) -> ListedStream {
+        ListedStream {
+            video_id: video_id.into(),
+            title: "Senate Plenary | Tuesday 22nd July 2025".into(),
+            stream_timestamp: "2025-07-22T14:30:00Z".parse().unwrap(),
+            duration: "3:12:05".into(),
+            category: Some(StreamCategory::Senate),
+            thumbnail_url: None,
+            has_summary: true,
+        }
+    }
+
+    fn get(path: &str, query: &str) -> Request {
+        Request {
+            method: "GET".into(),
+            path: path.into(),
+            query: query.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            peer: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streams_are_listed_by_the_query_given() {
+        let source = Streams {
+            streams: vec![listed("dQw4w9WgXcQ")],
+            ..Default::default()
+        };
+
+        let request = get(
+            "/api/streams",
+            "category=senate&from=2025-07-01&to=2025-07-31&page=2",
+        );
+        let (status, content_type, body) = route(&source, &request).await;
+        assert_eq!((status, content_type), ("200 OK", "application/json"));
+        let page: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(page["page"], 2);
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["streams"][0]["video_id"], "dQw4w9WgXcQ");
+        assert_eq!(page["streams"][0]["category"], "senate");
+        assert_eq!(page["streams"][0]["has_summary"], true);
+
+        let query = source.queries.lock().unwrap()[0].clone();
+        assert_eq!(query.category, Some(StreamCategory::Senate));
+        assert_eq!(query.from, Some("2025-07-01T00:00:00Z".parse().unwrap()));
+        assert_eq!(query.to, Some("2025-08-01T00:00:00Z".parse().unwrap()));
+
+        let invalid = get("/api/streams", "from=last+week");
+        assert_eq!(route(&source, &invalid).await.0, "400 Bad Request");
+    }
+
+    #[tokio::test]
+    async fn test_a_stream_is_answered_with_its_transcript_when_asked() {
+        let source = Streams {
+            streams: vec![listed("dQw4w9WgXcQ")],
+            ..Default::default()
+        };
+
+        let request = get("/api/streams/dQw4w9WgXcQ", "");
+        let (status, _, body) = route(&source, &request).await;
+        assert_eq!(status, "200 OK");
+        assert_eq!(cache_control(&request, status), "public, max-age=300");
+        let stream: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(stream["video_id"], "dQw4w9WgXcQ");
+        assert_eq!(stream["summary_md"], "## Summary");
+        assert!(stream.get("transcript").is_none());
+
+        let request = get("/api/streams/dQw4w9WgXcQ", "transcript=true");
+        let stream: serde_json::Value =
+            serde_json::from_str(&route(&source, &request).await.2).unwrap();
+        assert_eq!(stream["transcript"]["text"], "Order, order");
+        assert_eq!(stream["transcript"]["segments"][0]["text"], "Order, order");
+        assert_eq!(stream["transcript"]["segments"][0]["end"], 4.0);
+
+        let missing = get("/api/streams/missing", "");
+        let (status, _, _) = route(&source, &missing).await;
+        assert_eq!(status, "404 Not Found");
+        assert_eq!(cache_control(&missing, status), "no-store");
+        let listing = get("/api/streams", "");
+        assert_eq!(cache_control(&listing, "200 OK"), "public, max-age=60");
+    }
+
+    #[tokio::test]
+    async fn test_searches_answer_ranked_highlighted_matches() {
+        let source = Streams {
+            streams: vec![listed("dQw4w9WgXcQ")],
+            ..Default::default()
+        };
+
+        let request = get("/api/search", "q=housing+levy&category=senate");
+        let (status, _, body) = route(&source, &request).await;
+        assert_eq!(status, "200 OK");
+        assert_eq!(cache_control(&request, status), "public, max-age=60");
+        let results: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(results["results"][0]["video_id"], "dQw4w9WgXcQ");
+        assert_eq!(results["results"][0]["rank"], 0.5);
+        assert_eq!(
+            results["results"][0]["snippet"],
+            "the <mark>housing levy</mark> was debated"
+        );
+
+        let blank = get("/api/search", "q=+");
+        assert_eq!(route(&source, &blank).await.0, "400 Bad Request");
+    }
+}
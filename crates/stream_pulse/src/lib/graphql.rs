@@ -0,0 +1,698 @@
+//! # GraphQL API
+//!
+//! One endpoint over the published streams, their summaries and the MPs, bills and
+//! committees mentioned in them, so that clients can ask for just what a page shows
+//! instead of stitching several [streams API](crate::api) answers together. It is
+//! served through the [admin endpoints](crate::admin) and authorized by any API key:
+//!
+//! - `POST /api/graphql` answers a `{"query": "...", "variables": {...}}` request, e.g.
+//!   `{ streams(filter: {category: "senate"}) { streams { title entities { bills { name } } } } }`
+//!
+//! Streams link to the entities mentioned in them and entities back to the streams
+//! that mention them, so queries are limited to a depth of [`MAX_DEPTH`] and a
+//! complexity of [`MAX_COMPLEXITY`].
+
+use std::sync::Arc;
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, Error, InputObject, Json, Object, Schema,
+};
+use futures::future::BoxFuture;
+use stream_datastore::{
+    BillMention, Chapter, CommitteeMention, EntityKind, ListedStream, MemberMention, OrderPaper,
+    SearchMatch, StreamCategory, StreamDetail, StreamEntities, StreamPage, StreamQuery,
+    StructuredSummary,
+};
+use tokio::sync::OnceCell;
+
+use crate::{
+    api::{parse_date, StreamSource},
+    server::{Request, Response},
+};
+
+/// Deepest a query may nest its selections
+pub const MAX_DEPTH: usize = 8;
+
+/// Most fields a query may select, each list's items counted once
+pub const MAX_COMPLEXITY: usize = 500;
+
+/// Where the entities mentioned in published streams are read from, e.g. the
+/// datastore, failing with why it couldn't be reached
+pub trait EntitySource: Send + Sync {
+    /// The entities stored for the stream `video_id`, empty if none were
+    fn entities<'a>(&'a self, video_id: &'a str) -> BoxFuture<'a, Result<StreamEntities, String>>;
+
+    /// A page of the published streams matching `query` that mention the `kind` of
+    /// entity named `name`
+    fn mentioning<'a>(
+        &'a self,
+        kind: EntityKind,
+        name: &'a str,
+        query: &'a StreamQuery,
+    ) -> BoxFuture<'a, Result<StreamPage, String>>;
+}
+
+impl<T: EntitySource + ?Sized> EntitySource for Arc<T> {
+    fn entities<'a>(&'a self, video_id: &'a str) -> BoxFuture<'a, Result<StreamEntities, String>> {
+        (**self).entities(video_id)
+    }
+
+    fn mentioning<'a>(
+        &'a self,
+        kind: EntityKind,
+        name: &'a str,
+        query: &'a StreamQuery,
+    ) -> BoxFuture<'a, Result<StreamPage, String>> {
+        (**self).mentioning(kind, name, query)
+    }
+}
+
+/// The schema queries are answered with
+pub type GraphQlSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The schema answering queries from `streams` and `entities`
+pub fn schema(streams: Arc<dyn StreamSource>, entities: Arc<dyn EntitySource>) -> GraphQlSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(streams)
+        .data(entities)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+pub(crate) async fn route(schema: &GraphQlSchema, request: &Request) -> Response {
+    if request.method != "POST" {
+        return (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed".into(),
+        );
+    }
+    let query = match serde_json::from_slice::<async_graphql::Request>(&request.body) {
+        Ok(query) => query,
+        Err(e) => return ("400 Bad Request", "text/plain", e.to_string()),
+    };
+    let response = schema.execute(query).await;
+    (
+        "200 OK",
+        "application/json",
+        serde_json::to_string(&response).unwrap_or_default(),
+    )
+}
+
+/// Which published streams a query asks for, as the streams API's parameters of the
+/// same names
+#[derive(Debug, Default, InputObject)]
+pub struct StreamFilter {
+    /// e.g. "senate"
+    category: Option<String>,
+    /// `YYYY-MM-DD` or an RFC 3339 timestamp
+    from: Option<String>,
+    /// `YYYY-MM-DD`, inclusive, or an RFC 3339 timestamp, exclusive
+    to: Option<String>,
+}
+
+/// The query `filter`, `page` and `per_page` make
+fn stream_query(
+    filter: Option<StreamFilter>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> Result<StreamQuery, Error> {
+    let filter = filter.unwrap_or_default();
+    let mut query = StreamQuery::default();
+    if let Some(category) = filter.category {
+        query.category = Some(category.parse::<StreamCategory>().map_err(Error::new)?);
+    }
+    if let Some(from) = filter.from {
+        query.from = Some(parse_date(&from, false).map_err(Error::new)?);
+    }
+    if let Some(to) = filter.to {
+        query.to = Some(parse_date(&to, true).map_err(Error::new)?);
+    }
+    query.page = page.unwrap_or(query.page);
+    query.per_page = per_page.unwrap_or(query.per_page);
+    Ok(query)
+}
+
+/// Logs why the store couldn't be read, answering without the reason
+fn unavailable(e: String) -> Error {
+    tracing::error!(error = %e, "Failed to read streams");
+    Error::new("streams unavailable")
+}
+
+/// The kinds of entities streams are found by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Entity {
+    Member,
+    Bill,
+    Committee,
+}
+
+impl From<Entity> for EntityKind {
+    fn from(entity: Entity) -> Self {
+        match entity {
+            Entity::Member => EntityKind::Member,
+            Entity::Bill => EntityKind::Bill,
+            Entity::Committee => EntityKind::Committee,
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The published streams, newest first
+    async fn streams(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<StreamFilter>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    ) -> Result<Page, Error> {
+        let query = stream_query(filter, page, per_page)?;
+        let source = ctx.data::<Arc<dyn StreamSource>>()?;
+        source.list(&query).await.map(Page).map_err(unavailable)
+    }
+
+    /// The published streams whose title or summary matches `q`, best match first
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        q: String,
+        filter: Option<StreamFilter>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    ) -> Result<Vec<SearchResult>, Error> {
+        if q.trim().is_empty() {
+            return Err(Error::new("missing q"));
+        }
+        let query = stream_query(filter, page, per_page)?;
+        let source = ctx.data::<Arc<dyn StreamSource>>()?;
+        let matches = source.search(&q, &query).await.map_err(unavailable)?;
+        Ok(matches.into_iter().map(SearchResult).collect())
+    }
+
+    /// The published stream `video_id`, `null` if there is none
+    async fn stream(&self, ctx: &Context<'_>, video_id: String) -> Result<Option<Stream>, Error> {
+        let source = ctx.data::<Arc<dyn StreamSource>>()?;
+        let detail = source.stream(&video_id).await.map_err(unavailable)?;
+        Ok(detail.map(Stream::from))
+    }
+
+    /// The published streams mentioning the `kind` of entity named `name`, as it was
+    /// extracted, newest first
+    async fn streams_mentioning(
+        &self,
+        ctx: &Context<'_>,
+        kind: Entity,
+        name: String,
+        filter: Option<StreamFilter>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    ) -> Result<Page, Error> {
+        mentioning(ctx, kind, &name, stream_query(filter, page, per_page)?).await
+    }
+}
+
+async fn mentioning(
+    ctx: &Context<'_>,
+    kind: Entity,
+    name: &str,
+    query: StreamQuery,
+) -> Result<Page, Error> {
+    let source = ctx.data::<Arc<dyn EntitySource>>()?;
+    source
+        .mentioning(kind.into(), name, &query)
+        .await
+        .map(Page)
+        .map_err(unavailable)
+}
+
+/// A page of streams, with how many match across all pages
+pub struct Page(StreamPage);
+
+#[Object]
+impl Page {
+    async fn streams(&self) -> Vec<Stream> {
+        self.0.streams.iter().cloned().map(Stream::from).collect()
+    }
+
+    async fn page(&self) -> u32 {
+        self.0.page
+    }
+
+    async fn per_page(&self) -> u32 {
+        self.0.per_page
+    }
+
+    async fn total(&self) -> u64 {
+        self.0.total
+    }
+}
+
+pub struct SearchResult(SearchMatch);
+
+#[Object]
+impl SearchResult {
+    async fn stream(&self) -> Stream {
+        Stream::from(self.0.stream.clone())
+    }
+
+    async fn rank(&self) -> f32 {
+        self.0.rank
+    }
+
+    /// Summary excerpt, the matched words wrapped in `<mark>`
+    async fn snippet(&self) -> &str {
+        &self.0.snippet
+    }
+}
+
+/// A published stream. Its summaries are read once asked for, unless it was
+/// asked for by ID
+pub struct Stream {
+    stream: ListedStream,
+    detail: OnceCell<Option<StreamDetail>>,
+}
+
+impl From<ListedStream> for Stream {
+    fn from(stream: ListedStream) -> Self {
+        Self {
+            stream,
+            detail: OnceCell::new(),
+        }
+    }
+}
+
+impl From<StreamDetail> for Stream {
+    fn from(detail: StreamDetail) -> Self {
+        Self {
+            stream: detail.stream.clone(),
+            detail: OnceCell::new_with(Some(Some(detail))),
+        }
+    }
+}
+
+impl Stream {
+    async fn detail(&self, ctx: &Context<'_>) -> Result<Option<&StreamDetail>, Error> {
+        let detail = self
+            .detail
+            .get_or_try_init(|| async {
+                let source = ctx.data::<Arc<dyn StreamSource>>()?;
+                source
+                    .stream(&self.stream.video_id)
+                    .await
+                    .map_err(unavailable)
+            })
+            .await?;
+        Ok(detail.as_ref())
+    }
+}
+
+#[Object]
+impl Stream {
+    async fn video_id(&self) -> &str {
+        &self.stream.video_id
+    }
+
+    async fn title(&self) -> &str {
+        &self.stream.title
+    }
+
+    /// RFC 3339
+    async fn date(&self) -> String {
+        self.stream.stream_timestamp.to_rfc3339()
+    }
+
+    async fn duration(&self) -> &str {
+        &self.stream.duration
+    }
+
+    async fn category(&self) -> Option<&str> {
+        self.stream.category.map(|c| c.as_str())
+    }
+
+    async fn thumbnail_url(&self) -> Option<&str> {
+        self.stream.thumbnail_url.as_deref()
+    }
+
+    async fn has_summary(&self) -> bool {
+        self.stream.has_summary
+    }
+
+    async fn description(&self, ctx: &Context<'_>) -> Result<Option<String>, Error> {
+        let detail = self.detail(ctx).await?;
+        Ok(detail.and_then(|d| d.description.clone()))
+    }
+
+    async fn chapters(&self, ctx: &Context<'_>) -> Result<Option<Json<Vec<Chapter>>>, Error> {
+        let detail = self.detail(ctx).await?;
+        Ok(detail.and_then(|d| d.chapters.as_ref().map(|c| Json(c.0.clone()))))
+    }
+
+    async fn order_paper(&self, ctx: &Context<'_>) -> Result<Option<Json<OrderPaper>>, Error> {
+        let detail = self.detail(ctx).await?;
+        Ok(detail.and_then(|d| d.order_paper.as_ref().map(|o| Json(o.0.clone()))))
+    }
+
+    /// `null` until the stream is summarized
+    async fn summary(&self, ctx: &Context<'_>) -> Result<Option<Summary>, Error> {
+        let detail = self.detail(ctx).await?;
+        Ok(detail.filter(|d| d.summary_md.is_some()).map(|d| Summary {
+            markdown: d.summary_md.clone().unwrap_or_default(),
+            tldr: d.summary_tldr.clone(),
+            timestamped: d.timestamp_md.clone(),
+            structured: d.structured_summary.as_ref().map(|s| s.0.clone()),
+        }))
+    }
+
+    /// The MPs, bills and committees mentioned, empty if none were extracted
+    async fn entities(&self, ctx: &Context<'_>) -> Result<Entities, Error> {
+        let source = ctx.data::<Arc<dyn EntitySource>>()?;
+        source
+            .entities(&self.stream.video_id)
+            .await
+            .map(Entities)
+            .map_err(unavailable)
+    }
+}
+
+/// A stream's summaries
+pub struct Summary {
+    markdown: String,
+    tldr: Option<String>,
+    timestamped: Option<String>,
+    structured: Option<StructuredSummary>,
+}
+
+#[Object]
+impl Summary {
+    async fn markdown(&self) -> &str {
+        &self.markdown
+    }
+
+    /// One paragraph, for social media
+    async fn tldr(&self) -> Option<&str> {
+        self.tldr.as_deref()
+    }
+
+    /// The summary citing transcript timestamps, linked to that moment of the stream
+    async fn timestamped(&self) -> Option<&str> {
+        self.timestamped.as_deref()
+    }
+
+    /// The bills, motions, votes, speakers and action items of the sitting
+    async fn structured(&self) -> Option<Json<StructuredSummary>> {
+        self.structured.clone().map(Json)
+    }
+}
+
+pub struct Entities(StreamEntities);
+
+#[Object]
+impl Entities {
+    async fn members(&self) -> Vec<Member> {
+        self.0.members.iter().cloned().map(Member).collect()
+    }
+
+    async fn bills(&self) -> Vec<Bill> {
+        self.0.bills.iter().cloned().map(Bill).collect()
+    }
+
+    async fn committees(&self) -> Vec<Committee> {
+        self.0.committees.iter().cloned().map(Committee).collect()
+    }
+}
+
+/// A Member of Parliament or Senator mentioned in a stream
+pub struct Member(MemberMention);
+
+#[Object]
+impl Member {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Constituency for MPs, county for Senators
+    async fn constituency(&self) -> Option<&str> {
+        self.0.constituency.as_deref()
+    }
+
+    async fn party(&self) -> Option<&str> {
+        self.0.party.as_deref()
+    }
+
+    /// The published streams mentioning the member by the same name, newest first
+    async fn streams(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<StreamFilter>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    ) -> Result<Page, Error> {
+        let query = stream_query(filter, page, per_page)?;
+        mentioning(ctx, Entity::Member, &self.0.name, query).await
+    }
+}
+
+pub struct Bill(BillMention);
+
+#[Object]
+impl Bill {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// e.g. "National Assembly Bill No. 12 of 2025"
+    async fn number(&self) -> Option<&str> {
+        self.0.number.as_deref()
+    }
+
+    /// The published streams mentioning the bill by the same name, newest first
+    async fn streams(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<StreamFilter>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    ) -> Result<Page, Error> {
+        let query = stream_query(filter, page, per_page)?;
+        mentioning(ctx, Entity::Bill, &self.0.name, query).await
+    }
+}
+
+pub struct Committee(CommitteeMention);
+
+#[Object]
+impl Committee {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// The published streams mentioning the committee by the same name, newest first
+    async fn streams(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<StreamFilter>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    ) -> Result<Page, Error> {
+        let query = stream_query(filter, page, per_page)?;
+        mentioning(ctx, Entity::Committee, &self.0.name, query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::{json, Value};
+    use stream_datastore::Json as Stored;
+
+    use super::*;
+    use crate::TranscribeResponse;
+
+    /// Answers with one stream, mentioning one MP and one bill
+    struct Store;
+
+    fn listed() -> ListedStream {
+        ListedStream {
+            video_id: "dQw4w9WgXcQ".into(),
+            title: "Senate Plenary, Tuesday 1st July 2025".into(),
+            stream_timestamp: Utc.with_ymd_and_hms(2025, 7, 1, 14, 30, 0).unwrap(),
+            duration: "3:02:11".into(),
+            category: Some(StreamCategory::Senate),
+            thumbnail_url: None,
+            has_summary: true,
+        }
+    }
+
+    fn page(query: &StreamQuery) -> StreamPage {
+        StreamPage {
+            streams: vec![listed()],
+            page: query.page,
+            per_page: query.limit(),
+            total: 1,
+        }
+    }
+
+    impl StreamSource for Store {
+        fn list<'a>(&'a self, query: &'a StreamQuery) -> BoxFuture<'a, Result<StreamPage, String>> {
+            Box::pin(async move { Ok(page(query)) })
+        }
+
+        fn search<'a>(
+            &'a self,
+            _terms: &'a str,
+            _query: &'a StreamQuery,
+        ) -> BoxFuture<'a, Result<Vec<SearchMatch>, String>> {
+            Box::pin(async { Err("connection refused".to_string()) })
+        }
+
+        fn stream<'a>(
+            &'a self,
+            video_id: &'a str,
+        ) -> BoxFuture<'a, Result<Option<StreamDetail>, String>> {
+            let detail = (video_id == "dQw4w9WgXcQ").then(|| StreamDetail {
+                stream: listed(),
+                description: None,
+                chapters: Some(Stored(Vec::new())),
+                order_paper: None,
+                summary_md: Some("## Housing Levy".into()),
+                summary_tldr: Some("The Senate debated the Housing Levy.".into()),
+                timestamp_md: None,
+                structured_summary: None,
+            });
+            Box::pin(async move { Ok(detail) })
+        }
+
+        fn transcript<'a>(
+            &'a self,
+            _video_id: &'a str,
+        ) -> BoxFuture<'a, Result<Option<TranscribeResponse>, String>> {
+            Box::pin(async { Ok(None) })
+        }
+    }
+
+    impl EntitySource for Store {
+        fn entities<'a>(
+            &'a self,
+            _video_id: &'a str,
+        ) -> BoxFuture<'a, Result<StreamEntities, String>> {
+            Box::pin(async {
+                Ok(StreamEntities {
+                    members: vec![MemberMention {
+                        name: "Hon. Jane Wanjiru".into(),
+                        constituency: Some("Nyeri".into()),
+                        party: None,
+                    }],
+                    bills: vec![BillMention {
+                        name: "Affordable Housing Bill".into(),
+                        number: None,
+                    }],
+                    committees: Vec::new(),
+                })
+            })
+        }
+
+        fn mentioning<'a>(
+            &'a self,
+            kind: EntityKind,
+            name: &'a str,
+            query: &'a StreamQuery,
+        ) -> BoxFuture<'a, Result<StreamPage, String>> {
+            let found = kind == EntityKind::Bill && name == "Affordable Housing Bill";
+            Box::pin(async move {
+                Ok(match found {
+                    true => page(query),
+                    false => StreamPage {
+                        total: 0,
+                        streams: Vec::new(),
+                        ..page(query)
+                    },
+                })
+            })
+        }
+    }
+
+    async fn execute(query: &str) -> Value {
+        let schema = schema(Arc::new(Store), Arc::new(Store));
+        serde_json::to_value(schema.execute(query).await).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_streams_link_to_their_entities_and_back() {
+        let response = execute(
+            r#"{
+                streams(filter: {category: "senate"}) {
+                    total
+                    streams {
+                        videoId
+                        summary { tldr }
+                        entities {
+                            members { name constituency }
+                            bills { name streams { total } }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .await;
+        assert_eq!(response["errors"], Value::Null);
+        let stream = &response["data"]["streams"]["streams"][0];
+        assert_eq!(stream["videoId"], "dQw4w9WgXcQ");
+        assert_eq!(
+            stream["summary"]["tldr"],
+            "The Senate debated the Housing Levy."
+        );
+        assert_eq!(
+            stream["entities"]["members"],
+            json!([{"name": "Hon. Jane Wanjiru", "constituency": "Nyeri"}])
+        );
+        assert_eq!(stream["entities"]["bills"][0]["streams"]["total"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_filters_and_unreachable_stores_are_errors() {
+        let response = execute(r#"{ streams(filter: {from: "July"}) { total } }"#).await;
+        assert_eq!(response["errors"][0]["message"], "invalid date: July");
+
+        let response = execute(r#"{ search(q: "housing") { snippet } }"#).await;
+        assert_eq!(response["errors"][0]["message"], "streams unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_deep_queries_are_rejected() {
+        let nested = "entities { bills { streams { streams { ".repeat(2);
+        let closing = "} } } } ".repeat(2);
+        let query = format!("{{ streams {{ streams {{ {nested} title {closing} }} }} }}");
+        let response = execute(&query).await;
+        assert!(response["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("nested too deep"));
+    }
+
+    #[tokio::test]
+    async fn test_only_posts_are_answered() {
+        let schema = schema(Arc::new(Store), Arc::new(Store));
+        let mut request = Request {
+            method: "GET".into(),
+            path: "/api/graphql".into(),
+            query: String::new(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            peer: None,
+        };
+        assert_eq!(route(&schema, &request).await.0, "405 Method Not Allowed");
+
+        request.method = "POST".into();
+        request.body = br#"{"query": "{ stream(videoId: \"missing\") { title } }"}"#.to_vec();
+        let (status, _, body) = route(&schema, &request).await;
+        assert_eq!(status, "200 OK");
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap()["data"],
+            json!({"stream": null})
+        );
+    }
+}
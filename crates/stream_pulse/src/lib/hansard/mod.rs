@@ -0,0 +1,95 @@
+//! # Hansard
+//!
+//! Cross-references streams with the order papers the Clerks publish for each sitting,
+//! so that summaries name the business before the house the way the order paper does,
+//! and the site can link the document itself.
+
+#[cfg(feature = "hansard")]
+pub mod parliament;
+
+use std::{fmt::Debug, future::Future, sync::LazyLock};
+
+use regex::Regex;
+use stream_datastore::{OrderPaper, Stream};
+
+/// Numbered lines of an order paper, e.g. "7. NOTICES OF MOTION"
+static AGENDA_ITEM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(\d{1,2})\s*\.\s+(\S.*?)\s*$").unwrap());
+
+/// Finds the order paper of the sitting a stream recorded
+pub trait OrderPaperSource {
+    type Error: Debug;
+
+    /// The order paper of `stream`'s sitting, or `None` if none is published for it
+    fn find_order_paper(
+        &self,
+        stream: &Stream,
+    ) -> impl Future<Output = Result<Option<OrderPaper>, Self::Error>> + Send;
+}
+
+/// Placeholder for processors built without an [`OrderPaperSource`]. It has no values,
+/// so it can never actually be called.
+#[derive(Debug, Clone, Copy)]
+pub enum NoOrderPaperSource {}
+
+impl OrderPaperSource for NoOrderPaperSource {
+    type Error = std::convert::Infallible;
+
+    async fn find_order_paper(&self, _stream: &Stream) -> Result<Option<OrderPaper>, Self::Error> {
+        match *self {}
+    }
+}
+
+/// Reads the headings of the items of business from the text of an order paper. Items
+/// are numbered from 1 and headed in capitals; numbered lines out of sequence, or in
+/// sentence case, are paragraphs of a motion's text and are skipped.
+pub fn parse_agenda(text: &str) -> Vec<String> {
+    let mut agenda = Vec::new();
+    for cap in AGENDA_ITEM_RE.captures_iter(text) {
+        let Ok(number) = cap[1].parse::<usize>() else {
+            continue;
+        };
+        let heading = cap[2].split_whitespace().collect::<Vec<_>>().join(" ");
+        let is_heading = heading.chars().filter(|c| c.is_alphabetic()).count() >= 2
+            && !heading.chars().any(char::is_lowercase);
+        if number == agenda.len() + 1 && is_heading {
+            agenda.push(heading);
+        }
+    }
+    agenda
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_agenda() {
+        let text = "
+            NATIONAL ASSEMBLY
+            ORDER PAPER
+            Tuesday, 11th March 2025 at 2.30 p.m.
+            1. PRAYERS
+            2. COMMUNICATION FROM THE CHAIR
+            3.   PAPERS
+            4. MOTION - ADOPTION OF THE REPORT ON THE
+               (The Chairperson, Departmental Committee on Finance)
+               THAT, this House adopts the Report, and
+            1. notes that the Committee held public hearings;
+            5. THE FINANCE BILL (NATIONAL ASSEMBLY BILLS NO. 30 OF 2025)
+            7. QUESTIONS
+        ";
+
+        assert_eq!(
+            parse_agenda(text),
+            vec![
+                "PRAYERS",
+                "COMMUNICATION FROM THE CHAIR",
+                "PAPERS",
+                "MOTION - ADOPTION OF THE REPORT ON THE",
+                "THE FINANCE BILL (NATIONAL ASSEMBLY BILLS NO. 30 OF 2025)",
+            ]
+        );
+        assert!(parse_agenda("1. Prayers\n2. Papers").is_empty());
+    }
+}
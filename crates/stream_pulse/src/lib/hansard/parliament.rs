@@ -0,0 +1,219 @@
+//! # parliament.go.ke order papers
+//!
+//! Each house lists its order papers on parliament.go.ke as PDFs, newest first, linked
+//! with titles like "Order Paper for Tuesday, 11th March 2025 (Afternoon Sitting)".
+//! A stream's order paper is the one titled with its date, and with its sitting when
+//! the house sat more than once that day.
+
+use std::sync::LazyLock;
+
+use chrono::{Datelike, NaiveDate};
+use chrono_tz::Africa::Nairobi;
+use regex::Regex;
+use stream_datastore::{OrderPaper, Stream, StreamCategory};
+
+use crate::hansard::{parse_agenda, OrderPaperSource};
+
+const BASE_URL: &str = "https://www.parliament.go.ke";
+const NATIONAL_ASSEMBLY_LISTING: &str = "/the-national-assembly/house-business/order-paper";
+const SENATE_LISTING: &str = "/the-senate/house-business/order-paper";
+
+/// Words telling apart the sittings of one day, in stream and order paper titles
+const SITTINGS: [&str; 4] = ["morning", "afternoon", "evening", "special"];
+
+static PDF_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)<a[^>]+href="([^"]+\.pdf)"[^>]*>(.*?)</a>"#).unwrap());
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+#[derive(Debug, Clone)]
+pub struct ParliamentOrderPapers {
+    client: reqwest::Client,
+    base_url: String,
+    max_pages: usize,
+}
+
+impl Default for ParliamentOrderPapers {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            base_url: BASE_URL.to_string(),
+            max_pages: 3,
+        }
+    }
+}
+
+impl ParliamentOrderPapers {
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Pages of each house's listing to look through, 3 by default. Each lists about
+    /// 20 order papers, so backfilling older streams needs more.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Finds the link to the order paper for `date` on the listing at `path`
+    async fn find_link(
+        &self,
+        path: &str,
+        date: NaiveDate,
+        title: &str,
+    ) -> anyhow::Result<Option<String>> {
+        for page in 0..self.max_pages {
+            let listing = self
+                .client
+                .get(format!("{}{path}", self.base_url))
+                .query(&[("page", page)])
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await?;
+
+            let links = pdf_links(&listing);
+            if links.is_empty() {
+                break;
+            }
+            if let Some(href) = matching_link(&links, date, title) {
+                return Ok(Some(match href.starts_with("http") {
+                    true => href.to_string(),
+                    false => format!("{}{href}", self.base_url),
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl OrderPaperSource for ParliamentOrderPapers {
+    type Error = anyhow::Error;
+
+    #[tracing::instrument(skip_all, fields(video_id = %stream.video_id))]
+    async fn find_order_paper(&self, stream: &Stream) -> anyhow::Result<Option<OrderPaper>> {
+        let path = match stream.category() {
+            StreamCategory::NationalAssembly => NATIONAL_ASSEMBLY_LISTING,
+            StreamCategory::Senate => SENATE_LISTING,
+            // committees sit without an order paper
+            StreamCategory::Committee | StreamCategory::Other => return Ok(None),
+        };
+        let Some(date) = stream
+            .published_at()
+            .map(|date| date.with_timezone(&Nairobi).date_naive())
+        else {
+            return Ok(None);
+        };
+        let Some(url) = self.find_link(path, date, &stream.title).await? else {
+            return Ok(None);
+        };
+
+        let pdf = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&pdf))
+            .await?
+            .map_err(|e| anyhow::anyhow!("Failed to read order paper {url}: {e}"))?;
+
+        Ok(Some(OrderPaper {
+            url,
+            agenda: parse_agenda(&text),
+        }))
+    }
+}
+
+/// The PDF links of a listing page, with their text lowercased and its punctuation
+/// and markup stripped
+fn pdf_links(html: &str) -> Vec<(&str, String)> {
+    PDF_LINK_RE
+        .captures_iter(html)
+        .map(|cap| {
+            let text = TAG_RE.replace_all(&cap[2], " ").to_lowercase();
+            let text = text
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            (cap.get(1).unwrap().as_str(), text)
+        })
+        .collect()
+}
+
+/// The link titled with `date`, preferring the one titled with the same sitting as the
+/// stream's `title`
+fn matching_link<'a>(links: &[(&'a str, String)], date: NaiveDate, title: &str) -> Option<&'a str> {
+    let suffix = match date.day() {
+        11..=13 => "th",
+        day if day % 10 == 1 => "st",
+        day if day % 10 == 2 => "nd",
+        day if day % 10 == 3 => "rd",
+        _ => "th",
+    };
+    let month = date.format("%B").to_string().to_lowercase();
+    // led by a space so that the 1st doesn't match the 21st
+    let dates = [
+        format!(" {}{suffix} {month} {}", date.day(), date.year()),
+        format!(" {} {month} {}", date.day(), date.year()),
+    ];
+
+    let on_date = links
+        .iter()
+        .filter(|(_, text)| {
+            let text = format!(" {text}");
+            dates.iter().any(|date| text.contains(date.as_str()))
+        })
+        .collect::<Vec<_>>();
+    let title = title.to_lowercase();
+    let sitting = SITTINGS.iter().find(|sitting| title.contains(*sitting));
+
+    on_date
+        .iter()
+        .find(|(_, text)| sitting.is_some_and(|sitting| text.contains(sitting)))
+        .or_else(|| on_date.first())
+        .map(|(href, _)| *href)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = r#"
+        <div class="view-content">
+          <a href="/sites/default/files/2025-03/Order%20Paper%2012.03.2025%20(A).pdf">Order Paper for Wednesday, 12th March, 2025 (Afternoon Sitting)</a>
+          <a href="/sites/default/files/2025-03/Order%20Paper%2011.03.2025%20(A).pdf"><span>Order Paper for Tuesday, 11th March 2025 (Afternoon Sitting)</span></a>
+          <a href="/sites/default/files/2025-03/Order%20Paper%2011.03.2025%20(M).pdf">Order Paper for Tuesday, 11th March 2025 (Morning Sitting)</a>
+          <a href="https://www.parliament.go.ke/sites/default/files/2025-03/Order%20Paper%2001.03.2025.pdf">Order Paper for Saturday, 1st March 2025</a>
+          <a href="/the-national-assembly/house-business/order-paper?page=1">Next</a>
+        </div>
+    "#;
+
+    #[test]
+    fn test_matching_link() {
+        let links = pdf_links(LISTING);
+        assert_eq!(links.len(), 4);
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+
+        assert_eq!(
+            matching_link(&links, date(11), "National Assembly | Morning Sitting"),
+            Some("/sites/default/files/2025-03/Order%20Paper%2011.03.2025%20(M).pdf")
+        );
+        assert_eq!(
+            matching_link(&links, date(11), "National Assembly Proceedings"),
+            Some("/sites/default/files/2025-03/Order%20Paper%2011.03.2025%20(A).pdf")
+        );
+        assert_eq!(
+            matching_link(&links, date(12), "National Assembly | Morning Sitting"),
+            Some("/sites/default/files/2025-03/Order%20Paper%2012.03.2025%20(A).pdf")
+        );
+        assert!(matching_link(&links, date(1), "Senate Plenary").is_some());
+        assert_eq!(matching_link(&links, date(21), "Senate Plenary"), None);
+        assert_eq!(matching_link(&links, date(13), "Senate Plenary"), None);
+    }
+}
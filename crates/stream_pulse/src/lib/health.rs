@@ -0,0 +1,84 @@
+//! # Health
+//!
+//! Endpoints for orchestrators to probe the process with:
+//!
+//! - `/healthz` answers 200 for as long as the process is up
+//! - `/readyz` answers 200 once the readiness check passes, and 503 otherwise, e.g. to
+//!   restart a deployment wedged on a run that never finishes
+//! - `/version` answers with the running version, as JSON
+//!
+//! The reason a readiness check failed is logged rather than answered, as probes are
+//! unauthenticated. They are served by the [server](crate::server).
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+
+use crate::server::Response;
+
+/// Checks what the process needs to get its work done, failing with what is missing
+pub type ReadinessCheck = Arc<dyn Fn() -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+/// When a run last succeeded, shared by the runs and the readiness check
+#[derive(Debug, Clone)]
+pub struct RunHeartbeat {
+    last_success: Arc<Mutex<Instant>>,
+}
+
+impl RunHeartbeat {
+    /// A heartbeat counting from now, so that a new deployment is given time for its
+    /// first run
+    pub fn new() -> Self {
+        Self {
+            last_success: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    pub fn succeeded(&self) {
+        *self.last_success.lock().unwrap() = Instant::now();
+    }
+
+    /// Time since the last successful run, or since the heartbeat was created before
+    /// the first
+    pub fn since_success(&self) -> Duration {
+        self.last_success.lock().unwrap().elapsed()
+    }
+}
+
+impl Default for RunHeartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) async fn route(
+    method: &str,
+    path: &str,
+    version: &str,
+    ready: &ReadinessCheck,
+) -> Response {
+    match (method, path) {
+        ("GET", "/healthz") => ("200 OK", "text/plain", "ok".into()),
+        ("GET", "/readyz") => match ready().await {
+            Ok(()) => ("200 OK", "text/plain", "ready".into()),
+            Err(reason) => {
+                tracing::warn!(reason, "Readiness check failed");
+                ("503 Service Unavailable", "text/plain", "not ready".into())
+            }
+        },
+        ("GET", "/version") => (
+            "200 OK",
+            "application/json",
+            serde_json::json!({ "version": version }).to_string(),
+        ),
+        ("GET", _) => ("404 Not Found", "text/plain", "not found".into()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed".into(),
+        ),
+    }
+}
@@ -1,14 +1,68 @@
+pub mod admin;
+pub mod api;
 mod error;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod hansard;
+pub mod health;
 mod llm;
+pub mod metrics;
+pub mod newsletter;
 pub mod parser;
 mod processor;
+pub mod server;
+pub mod store;
+#[cfg(feature = "telegram")]
+pub mod telegram;
 pub mod tracing;
 pub mod types;
+pub mod webhooks;
+pub mod whatsapp;
+pub mod x;
 pub mod yt;
 
-pub use llm::openai;
+#[cfg(feature = "bedrock")]
+pub use llm::bedrock;
+pub use llm::captions::CaptionFormat;
+#[cfg(feature = "cassette")]
+pub use llm::cassette::{Cassette, CassetteMode};
+pub use llm::classifier::{CategoryClassifier, NoCategoryClassifier};
+pub use llm::divisions::{DivisionExtractor, NoDivisionExtractor};
+pub use llm::embedder::{Embedder, NoEmbedder};
+pub use llm::entities::{EntityExtractor, NoEntityExtractor};
+pub use llm::fallback::{FallbackError, FallbackSummarizer, ShouldFallback};
+pub use llm::glossary::Glossary;
+pub use llm::prompt::{PromptError, PromptTemplate, PromptVariables};
+pub use llm::rate_limit::{RateLimitConfig, RateLimiter};
+pub use llm::registry;
+pub use llm::retry::RetryPolicy;
+pub use llm::usage::{CompletionUsage, ModelUsage, UsageReport, UsageTracker};
+pub use llm::verification::{SummaryVerdict, SummaryVerifier, VerifiedSummarizer};
+pub use llm::{anthropic, groq, openai, openrouter};
 pub use llm::{
-    summarizer::{Summarizer, SummaryResponse},
-    transcriber::{AudioInput, TranscribeResponse, Transcriber},
+    summarizer::{
+        CompletionOptions, SearchContextSize, Summarizer, SummaryContext, SummaryResponse,
+        UserLocation, WebSearchOptions,
+    },
+    transcriber::{
+        AudioInput, SegmentFilter, TranscribeResponse, TranscribeSegment, Transcriber,
+        TranscriptionOptions, TranscriptionResponseFormat,
+    },
+};
+pub use processor::{
+    builder::{
+        BuilderError, CaptionDestination, ChunkingConfig, LiveStreamProcessorBuilder,
+        ThumbnailMirror, CHUNK_DURATION_SECONDS,
+    },
+    cleanup::CleanupConfig,
+    events::{ProcessorEvent, ProcessorEvents},
+    hooks::ProcessorHook,
+    priority::PrioritizationStrategy,
+    report::RunReport,
+    retention::RetentionPolicy,
+    retries::StreamRetryPolicy,
+    reuploads::ReuploadPolicy,
+    timeouts::{StageTimeout, StageTimeouts},
+    webhook::WebhookNotifier,
+    LiveStreamProcessor, PipelineStore,
 };
-pub use processor::{builder::LiveStreamProcessorBuilder, LiveStreamProcessor};
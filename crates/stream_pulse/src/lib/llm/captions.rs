@@ -0,0 +1,137 @@
+//! # Captions
+//!
+//! Renders transcript segments as SRT or WebVTT caption files, so corrected captions
+//! can be uploaded back to YouTube.
+
+use std::{fmt::Write, str::FromStr};
+
+use crate::TranscribeResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionFormat {
+    /// SubRip, `00:00:01,000 --> 00:00:04,500` cues numbered from 1
+    Srt,
+    /// WebVTT, `00:00:01.000 --> 00:00:04.500` cues after a `WEBVTT` header
+    WebVtt,
+}
+
+impl CaptionFormat {
+    /// File extension, also used to identify the format in the datastore
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CaptionFormat::Srt => "srt",
+            CaptionFormat::WebVtt => "vtt",
+        }
+    }
+}
+
+impl FromStr for CaptionFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "srt" => Ok(CaptionFormat::Srt),
+            "vtt" | "webvtt" => Ok(CaptionFormat::WebVtt),
+            other => Err(format!("Unsupported caption format: {other}")),
+        }
+    }
+}
+
+impl TranscribeResponse {
+    /// Renders the transcript's segments as a caption file, one cue per segment.
+    /// `None` when the transcript has no segment timestamps.
+    pub fn to_captions(&self, format: CaptionFormat) -> Option<String> {
+        let segments = self.segments.as_ref().filter(|s| !s.is_empty())?;
+
+        let mut captions = match format {
+            CaptionFormat::Srt => String::new(),
+            CaptionFormat::WebVtt => String::from("WEBVTT\n\n"),
+        };
+        let mut cue = 0;
+        for segment in segments {
+            // blank lines end a cue, and "-->" is reserved for cue timings
+            let text = segment
+                .text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n")
+                .replace("-->", "->");
+            if text.is_empty() {
+                continue;
+            }
+
+            cue += 1;
+            if format == CaptionFormat::Srt {
+                let _ = writeln!(captions, "{cue}");
+            }
+            let _ = writeln!(
+                captions,
+                "{} --> {}\n{text}\n",
+                format_cue_time(segment.start, format),
+                format_cue_time(segment.end.max(segment.start), format)
+            );
+        }
+
+        Some(captions)
+    }
+}
+
+fn format_cue_time(seconds: f64, format: CaptionFormat) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    let separator = match format {
+        CaptionFormat::Srt => ',',
+        CaptionFormat::WebVtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::transcriber::TranscribeSegment;
+
+    fn transcript() -> TranscribeResponse {
+        let segment = |start: f64, end: f64, text: &str| TranscribeSegment {
+            start,
+            end,
+            text: text.into(),
+            ..Default::default()
+        };
+        TranscribeResponse {
+            duration: 3_700.0,
+            text: String::new(),
+            segments: Some(vec![
+                segment(0.0, 4.5, " Order, order."),
+                segment(4.5, 5.0, "  "),
+                segment(3_661.25, 3_663.0, "The House is adjourned."),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_srt_cues_are_numbered() {
+        assert_eq!(
+            transcript().to_captions(CaptionFormat::Srt).unwrap(),
+            "1\n00:00:00,000 --> 00:00:04,500\nOrder, order.\n\n\
+             2\n01:01:01,250 --> 01:01:03,000\nThe House is adjourned.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_vtt_has_header() {
+        assert_eq!(
+            transcript().to_captions(CaptionFormat::WebVtt).unwrap(),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:04.500\nOrder, order.\n\n\
+             01:01:01.250 --> 01:01:03.000\nThe House is adjourned.\n\n"
+        );
+    }
+}
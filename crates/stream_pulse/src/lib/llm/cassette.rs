@@ -0,0 +1,267 @@
+//! # Cassette
+//!
+//! Records provider requests and responses to a JSON file, and replays them later,
+//! so tests can exercise real request and response shapes without API keys or network
+//! access. Only the method, URL path and JSON body of requests are kept, never their
+//! headers, so recordings do not contain credentials.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::llm::transport::Transport;
+
+/// Whether requests are forwarded and recorded, or answered from the recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests as usual, writing each interaction to the cassette file
+    Record,
+    /// Answer requests from the cassette file, failing on requests it has no entry for
+    Replay,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    /// URL path and query, without the host, so recordings replay against any base URL
+    path: String,
+    /// JSON bodies only. Multipart uploads are matched on method and path alone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+    /// Set once an interaction has been replayed, so repeated requests replay in order
+    #[serde(skip)]
+    played: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Recording {
+    interactions: Vec<Interaction>,
+}
+
+/// A recording of provider interactions, shared by clones of the clients using it
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl Cassette {
+    /// Records into `path`, replacing any earlier recording once the first
+    /// interaction is written
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+            recording: Arc::default(),
+        }
+    }
+
+    /// Replays the recording at `path`
+    pub fn replay(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let recording = serde_json::from_slice(&fs::read(&path)?)?;
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            recording: Arc::new(Mutex::new(recording)),
+        })
+    }
+
+    /// Records into `path` in [`CassetteMode::Record`], and replays it otherwise
+    pub fn open(path: impl Into<PathBuf>, mode: CassetteMode) -> io::Result<Self> {
+        match mode {
+            CassetteMode::Record => Ok(Self::record(path)),
+            CassetteMode::Replay => Self::replay(path),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Transport that records or replays the requests `inner` would have sent
+    pub(crate) fn transport(&self, inner: Arc<dyn Transport>) -> CassetteTransport {
+        CassetteTransport {
+            cassette: self.clone(),
+            inner,
+        }
+    }
+
+    /// The first unplayed interaction recorded for `request`
+    fn play(&self, request: &RecordedRequest) -> Option<RecordedResponse> {
+        let mut recording = self.recording.lock().unwrap();
+        let interaction = recording
+            .interactions
+            .iter_mut()
+            .find(|i| !i.played && i.request == *request)?;
+        interaction.played = true;
+        Some(interaction.response.clone())
+    }
+
+    fn append(&self, request: RecordedRequest, response: RecordedResponse) -> io::Result<()> {
+        let mut recording = self.recording.lock().unwrap();
+        recording.interactions.push(Interaction {
+            request,
+            response,
+            played: false,
+        });
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_vec_pretty(&*recording)?)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CassetteTransport {
+    cassette: Cassette,
+    inner: Arc<dyn Transport>,
+}
+
+impl Transport for CassetteTransport {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, reqwest_middleware::Error>> {
+        Box::pin(async move {
+            let recorded = RecordedRequest::from(&request);
+
+            match self.cassette.mode {
+                CassetteMode::Replay => {
+                    let response = self.cassette.play(&recorded).ok_or_else(|| {
+                        middleware_error(anyhow::anyhow!(
+                            "No recorded response for {} {} in {}",
+                            recorded.method,
+                            recorded.path,
+                            self.cassette.path.display()
+                        ))
+                    })?;
+                    response.into_response().map_err(middleware_error)
+                }
+                CassetteMode::Record => {
+                    let response = self.inner.send(request).await?;
+                    let status = response.status().as_u16();
+                    let content_type = response
+                        .headers()
+                        .get(reqwest::header::CONTENT_TYPE)
+                        .and_then(|v| v.to_str().ok())
+                        .map(String::from);
+                    // the body is buffered, so streamed responses replay as a single chunk
+                    let body = String::from_utf8_lossy(&response.bytes().await?).into_owned();
+
+                    let response = RecordedResponse {
+                        status,
+                        content_type,
+                        body,
+                    };
+                    self.cassette
+                        .append(recorded, response.clone())
+                        .map_err(middleware_error)?;
+                    response.into_response().map_err(middleware_error)
+                }
+            }
+        })
+    }
+}
+
+impl From<&reqwest::Request> for RecordedRequest {
+    fn from(request: &reqwest::Request) -> Self {
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .and_then(|bytes| serde_json::from_slice(bytes).ok());
+
+        Self {
+            method: request.method().to_string(),
+            path,
+            body,
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn into_response(self) -> Result<reqwest::Response, http::Error> {
+        let mut response = http::Response::builder().status(self.status);
+        if let Some(content_type) = &self.content_type {
+            response = response.header(http::header::CONTENT_TYPE, content_type);
+        }
+        Ok(response.body(self.body)?.into())
+    }
+}
+
+fn middleware_error(err: impl Into<anyhow::Error>) -> reqwest_middleware::Error {
+    reqwest_middleware::Error::Middleware(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(body: serde_json::Value) -> RecordedRequest {
+        RecordedRequest {
+            method: "POST".into(),
+            path: "/v1/chat/completions".into(),
+            body: Some(body),
+        }
+    }
+
+    fn response(body: &str) -> RecordedResponse {
+        RecordedResponse {
+            status: 200,
+            content_type: Some("application/json".into()),
+            body: body.into(),
+        }
+    }
+
+    #[test]
+    fn test_interactions_replay_in_order() {
+        let path = std::env::temp_dir().join("stream-pulse-cassette-test.json");
+        let cassette = Cassette::record(&path);
+        let body = serde_json::json!({ "model": "gpt-4o-mini" });
+        cassette
+            .append(request(body.clone()), response("first"))
+            .unwrap();
+        cassette
+            .append(request(body.clone()), response("second"))
+            .unwrap();
+
+        let cassette = Cassette::replay(&path).unwrap();
+        let play = |body: &serde_json::Value| cassette.play(&request(body.clone())).map(|r| r.body);
+        assert_eq!(play(&serde_json::json!({ "model": "gpt-4o" })), None);
+        assert_eq!(play(&body).as_deref(), Some("first"));
+        assert_eq!(play(&body).as_deref(), Some("second"));
+        assert_eq!(play(&body), None);
+
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,88 @@
+//! # Category Classification
+//!
+//! Classifies streams whose titles don't name a single house, such as joint sittings or
+//! special sessions, so every stored stream gets a house or committee label. Titles that
+//! do are classified by the parser with [`StreamCategory::from_title`].
+
+use std::{fmt::Debug, future::Future};
+
+use serde::Deserialize;
+use stream_datastore::StreamCategory;
+
+use crate::llm::entities::strip_code_fence;
+
+pub(crate) const CLASSIFICATION_PROMPT: &str = include_str!("prompts/category_0.txt");
+
+pub trait CategoryClassifier {
+    /// Model used for classification unless overridden, usually the same cheaper model as
+    /// [`EntityExtractor::EXTRACTION_MODEL`](crate::EntityExtractor::EXTRACTION_MODEL)
+    const CLASSIFICATION_MODEL: &'static str;
+
+    type Error: Debug;
+
+    fn classify_title(
+        &self,
+        title: &str,
+    ) -> impl Future<Output = Result<StreamCategory, Self::Error>> + Send;
+}
+
+/// Placeholder for processors built without a [`CategoryClassifier`]. It has no values,
+/// so it can never actually be called.
+#[derive(Debug, Clone, Copy)]
+pub enum NoCategoryClassifier {}
+
+impl CategoryClassifier for NoCategoryClassifier {
+    const CLASSIFICATION_MODEL: &'static str = "";
+
+    type Error = std::convert::Infallible;
+
+    async fn classify_title(&self, _title: &str) -> Result<StreamCategory, Self::Error> {
+        match *self {}
+    }
+}
+
+/// The JSON object the model responds with
+#[derive(Debug, Deserialize)]
+struct Classification {
+    category: Label,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Label {
+    NationalAssembly,
+    Senate,
+    Committee,
+    Other,
+}
+
+/// Parses the model's response, tolerating a surrounding markdown code fence
+/// from providers that can't be constrained to a JSON schema
+pub(crate) fn parse_category(content: &str) -> Result<StreamCategory, serde_json::Error> {
+    let category = match serde_json::from_str::<Classification>(strip_code_fence(content))?.category
+    {
+        Label::NationalAssembly => StreamCategory::NationalAssembly,
+        Label::Senate => StreamCategory::Senate,
+        Label::Committee => StreamCategory::Committee,
+        Label::Other => StreamCategory::Other,
+    };
+    Ok(category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_category() {
+        assert_eq!(
+            parse_category(r#"{"category": "committee"}"#).unwrap(),
+            StreamCategory::Committee
+        );
+        assert_eq!(
+            parse_category("```json\n{\"category\": \"national_assembly\"}\n```").unwrap(),
+            StreamCategory::NationalAssembly
+        );
+        assert!(parse_category(r#"{"category": "county_assembly"}"#).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! # Division Extraction
+//!
+//! Detects the divisions (recorded votes) held during a sitting, with the question put,
+//! the announced counts and the outcome, so voting records can be published per stream.
+
+use std::{fmt::Debug, future::Future, sync::LazyLock};
+
+use serde::Deserialize;
+use stream_datastore::Division;
+
+use crate::{
+    llm::{entities::strip_code_fence, summarizer::SummaryContext},
+    PromptTemplate,
+};
+
+static DIVISION_PROMPT: LazyLock<PromptTemplate> = LazyLock::new(|| {
+    PromptTemplate::new(include_str!("prompts/divisions_0.txt"))
+        .expect("bundled division prompt is not empty")
+});
+
+pub trait DivisionExtractor {
+    /// Model used for extraction unless overridden, usually the same cheaper model as
+    /// [`EntityExtractor::EXTRACTION_MODEL`](crate::EntityExtractor::EXTRACTION_MODEL)
+    const DIVISION_EXTRACTION_MODEL: &'static str;
+
+    type Error: Debug;
+
+    fn extract_divisions(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> impl Future<Output = Result<Vec<Division>, Self::Error>> + Send;
+}
+
+/// Placeholder for processors built without a [`DivisionExtractor`]. It has no values,
+/// so it can never actually be called.
+#[derive(Debug, Clone, Copy)]
+pub enum NoDivisionExtractor {}
+
+impl DivisionExtractor for NoDivisionExtractor {
+    const DIVISION_EXTRACTION_MODEL: &'static str = "";
+
+    type Error = std::convert::Infallible;
+
+    async fn extract_divisions(
+        &self,
+        _transcript: &str,
+        _context: &SummaryContext,
+    ) -> Result<Vec<Division>, Self::Error> {
+        match *self {}
+    }
+}
+
+/// The JSON object the model responds with
+#[derive(Debug, Deserialize)]
+struct Divisions {
+    #[serde(default)]
+    divisions: Vec<Division>,
+}
+
+/// System prompt for extraction, grounded with the sitting's metadata
+pub(crate) fn division_system_prompt(context: &SummaryContext) -> String {
+    DIVISION_PROMPT.render(&context.prompt_variables())
+}
+
+/// Parses the model's response, tolerating a surrounding markdown code fence
+/// from providers that can't be constrained to a JSON schema
+pub(crate) fn parse_divisions(content: &str) -> Result<Vec<Division>, serde_json::Error> {
+    let mut divisions = serde_json::from_str::<Divisions>(strip_code_fence(content))?.divisions;
+    divisions.retain(|d| !d.motion.trim().is_empty());
+    Ok(divisions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stream_datastore::DivisionOutcome;
+
+    #[test]
+    fn test_parse_divisions() {
+        let content = r#"```json
+{
+  "divisions": [
+    {
+      "motion": "That the Finance Bill be now read a Second Time",
+      "ayes": 195,
+      "noes": 106,
+      "abstentions": null,
+      "outcome": "agreed"
+    },
+    { "motion": " ", "ayes": null, "noes": null, "abstentions": null, "outcome": "unknown" }
+  ]
+}
+```"#;
+
+        let divisions = parse_divisions(content).unwrap();
+        assert_eq!(divisions.len(), 1);
+        assert_eq!(divisions[0].ayes, Some(195));
+        assert_eq!(divisions[0].outcome, DivisionOutcome::Agreed);
+    }
+
+    #[test]
+    fn test_unrecognised_outcome_is_unknown() {
+        let content = r#"{ "divisions": [{ "motion": "That the House do now adjourn", "ayes": null, "noes": null, "abstentions": null, "outcome": "carried" }] }"#;
+        let divisions = parse_divisions(content).unwrap();
+        assert_eq!(divisions[0].outcome, DivisionOutcome::Unknown);
+    }
+}
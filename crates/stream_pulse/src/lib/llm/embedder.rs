@@ -0,0 +1,205 @@
+//! # Embeddings
+//!
+//! Embeds each stream's summary and transcript passages, so sittings can be searched
+//! semantically and search results can point to the moment in the stream they match.
+
+use std::{fmt::Debug, future::Future};
+
+use stream_datastore::{ChunkEmbedding, StreamEmbeddings};
+
+use crate::TranscribeResponse;
+
+/// Transcript passages are kept well under the embedding models' input limit, so that
+/// each embedding stays specific to one stretch of debate
+const MAX_CHUNK_CHARS: usize = 2_000;
+
+pub trait Embedder {
+    const EMBEDDING_MODEL: &'static str;
+    /// Inputs sent per request
+    const BATCH_SIZE: usize = 64;
+
+    type Error: Debug;
+
+    /// Embeds each of `inputs`, returning the embeddings in the same order
+    fn embed(
+        &self,
+        inputs: &[String],
+    ) -> impl Future<Output = Result<Vec<Vec<f32>>, Self::Error>> + Send;
+}
+
+/// Placeholder for processors built without an [`Embedder`]. It has no values,
+/// so it can never actually be called.
+#[derive(Debug, Clone, Copy)]
+pub enum NoEmbedder {}
+
+impl Embedder for NoEmbedder {
+    const EMBEDDING_MODEL: &'static str = "";
+
+    type Error = std::convert::Infallible;
+
+    async fn embed(&self, _inputs: &[String]) -> Result<Vec<Vec<f32>>, Self::Error> {
+        match *self {}
+    }
+}
+
+/// A passage of the transcript to be embedded
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TranscriptChunk {
+    pub start_seconds: Option<f64>,
+    pub end_seconds: Option<f64>,
+    pub content: String,
+}
+
+/// Embeds `summary` and the passages of `transcript`, batching requests by
+/// [`Embedder::BATCH_SIZE`]
+#[cfg_attr(not(feature = "pgvector"), allow(dead_code))]
+pub(crate) async fn embed_stream<M: Embedder + Sync>(
+    embedder: &M,
+    summary: &str,
+    transcript: &TranscribeResponse,
+) -> Result<StreamEmbeddings, M::Error> {
+    let chunks = chunk_transcript(transcript, MAX_CHUNK_CHARS);
+    let inputs = std::iter::once(summary.to_string())
+        .chain(chunks.iter().map(|chunk| chunk.content.clone()))
+        .collect::<Vec<_>>();
+
+    let mut embeddings = Vec::with_capacity(inputs.len());
+    for batch in inputs.chunks(M::BATCH_SIZE.max(1)) {
+        embeddings.extend(embedder.embed(batch).await?);
+    }
+
+    let mut embeddings = embeddings.into_iter();
+    let summary = embeddings.next().unwrap_or_default();
+    let chunks = chunks
+        .into_iter()
+        .zip(embeddings)
+        .map(|(chunk, embedding)| ChunkEmbedding {
+            start_seconds: chunk.start_seconds,
+            end_seconds: chunk.end_seconds,
+            content: chunk.content,
+            embedding,
+        })
+        .collect();
+
+    Ok(StreamEmbeddings { summary, chunks })
+}
+
+/// Groups consecutive transcript segments into passages of at most `max_chars`, keeping
+/// their timestamps. Transcripts without segments are split on word boundaries instead.
+pub(crate) fn chunk_transcript(
+    transcript: &TranscribeResponse,
+    max_chars: usize,
+) -> Vec<TranscriptChunk> {
+    let Some(segments) = transcript.segments.as_ref().filter(|s| !s.is_empty()) else {
+        return chunk_words(&transcript.text, max_chars)
+            .into_iter()
+            .map(|content| TranscriptChunk {
+                start_seconds: None,
+                end_seconds: None,
+                content,
+            })
+            .collect();
+    };
+
+    let mut chunks = Vec::new();
+    let mut current: Option<TranscriptChunk> = None;
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let fits = |chunk: &&mut TranscriptChunk| chunk.content.len() + 1 + text.len() <= max_chars;
+        if let Some(chunk) = current.as_mut().filter(fits) {
+            chunk.content.push(' ');
+            chunk.content.push_str(text);
+            chunk.end_seconds = Some(segment.end);
+            continue;
+        }
+
+        chunks.extend(current.take());
+        current = Some(TranscriptChunk {
+            start_seconds: Some(segment.start),
+            end_seconds: Some(segment.end),
+            content: text.to_string(),
+        });
+    }
+    chunks.extend(current);
+
+    chunks
+}
+
+fn chunk_words(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::transcriber::TranscribeSegment;
+
+    fn segment(start: f64, end: f64, text: &str) -> TranscribeSegment {
+        TranscribeSegment {
+            start,
+            end,
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_segments_are_grouped_with_timestamps() {
+        let transcript = TranscribeResponse {
+            duration: 30.0,
+            text: String::new(),
+            segments: Some(vec![
+                segment(0.0, 10.0, "Order, order."),
+                segment(10.0, 20.0, "Next order."),
+                segment(20.0, 30.0, "The Finance Bill, second reading."),
+            ]),
+        };
+
+        let chunks = chunk_transcript(&transcript, 30);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].content, "Order, order. Next order.");
+        assert_eq!(chunks[0].start_seconds, Some(0.0));
+        assert_eq!(chunks[0].end_seconds, Some(20.0));
+        assert_eq!(chunks[1].start_seconds, Some(20.0));
+    }
+
+    #[test]
+    fn test_text_without_segments_is_split_on_words() {
+        let transcript = TranscribeResponse {
+            duration: 0.0,
+            text: "Hon. Members, the House is adjourned".into(),
+            segments: None,
+        };
+
+        let chunks = chunk_transcript(&transcript, 16);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.start_seconds.is_none()));
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|c| c.content.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            transcript.text
+        );
+    }
+}
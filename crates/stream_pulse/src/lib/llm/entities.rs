@@ -0,0 +1,101 @@
+//! # Entity Extraction
+//!
+//! Pulls the MPs, bills and committees mentioned in a sitting out of its transcript,
+//! so they can be stored alongside the stream for per-MP pages and bill tracking.
+
+use std::{fmt::Debug, future::Future, sync::LazyLock};
+
+use stream_datastore::StreamEntities;
+
+use crate::{llm::summarizer::SummaryContext, PromptTemplate};
+
+static ENTITY_PROMPT: LazyLock<PromptTemplate> = LazyLock::new(|| {
+    PromptTemplate::new(include_str!("prompts/entities_0.txt"))
+        .expect("bundled entity prompt is not empty")
+});
+
+pub trait EntityExtractor {
+    /// Model used for extraction unless overridden. Extraction does not need the
+    /// reasoning of the summarizer model, so this is usually a cheaper one.
+    const EXTRACTION_MODEL: &'static str;
+
+    type Error: Debug;
+
+    fn extract_entities(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> impl Future<Output = Result<StreamEntities, Self::Error>> + Send;
+}
+
+/// Placeholder for processors built without an [`EntityExtractor`]. It has no values,
+/// so it can never actually be called.
+#[derive(Debug, Clone, Copy)]
+pub enum NoEntityExtractor {}
+
+impl EntityExtractor for NoEntityExtractor {
+    const EXTRACTION_MODEL: &'static str = "";
+
+    type Error = std::convert::Infallible;
+
+    async fn extract_entities(
+        &self,
+        _transcript: &str,
+        _context: &SummaryContext,
+    ) -> Result<StreamEntities, Self::Error> {
+        match *self {}
+    }
+}
+
+/// System prompt for extraction, grounded with the sitting's metadata
+pub(crate) fn entity_system_prompt(context: &SummaryContext) -> String {
+    ENTITY_PROMPT.render(&context.prompt_variables())
+}
+
+/// Parses the model's response, tolerating a surrounding markdown code fence
+/// from providers that can't be constrained to a JSON schema
+pub(crate) fn parse_entities(content: &str) -> Result<StreamEntities, serde_json::Error> {
+    let mut entities = serde_json::from_str::<StreamEntities>(strip_code_fence(content))?;
+    entities.members.retain(|m| !m.name.trim().is_empty());
+    entities.bills.retain(|b| !b.name.trim().is_empty());
+    entities.committees.retain(|c| !c.name.trim().is_empty());
+    Ok(entities)
+}
+
+/// Strips a markdown code fence around a JSON response
+pub(crate) fn strip_code_fence(content: &str) -> &str {
+    let content = content.trim();
+    content
+        .strip_prefix("```json")
+        .or_else(|| content.strip_prefix("```"))
+        .and_then(|fenced| fenced.strip_suffix("```"))
+        .unwrap_or(content)
+        .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entities_strips_code_fence() {
+        let content = r#"```json
+{
+  "members": [{ "name": "Kimani Ichung'wah", "constituency": "Kikuyu", "party": null }],
+  "bills": [{ "name": "Finance Bill", "number": "National Assembly Bill No. 30 of 2025" }],
+  "committees": [{ "name": "" }]
+}
+```"#;
+
+        let entities = parse_entities(content).unwrap();
+        assert_eq!(entities.members[0].constituency.as_deref(), Some("Kikuyu"));
+        assert_eq!(entities.bills.len(), 1);
+        assert!(entities.committees.is_empty());
+    }
+
+    #[test]
+    fn test_parse_entities_defaults_missing_lists() {
+        let entities = parse_entities(r#"{ "members": [] }"#).unwrap();
+        assert!(entities.is_empty());
+    }
+}
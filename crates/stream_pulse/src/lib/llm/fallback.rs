@@ -0,0 +1,235 @@
+//! # Fallback
+//!
+//! A [`Summarizer`] combinator that retries with a secondary summarizer when the primary
+//! fails for reasons outside our control, such as rate limits or a model outage.
+
+#[cfg(feature = "bedrock")]
+use crate::bedrock::BedrockError;
+use crate::{
+    anthropic::AnthropicError,
+    llm::summarizer::{SummaryContext, SummaryResponse},
+    openai::OpenAIError,
+    openrouter::OpenRouterError,
+    registry::ProviderError,
+    Summarizer,
+};
+
+/// Errors that may succeed against a different model or provider
+pub trait ShouldFallback {
+    fn should_fallback(&self) -> bool;
+}
+
+#[derive(Debug)]
+pub enum FallbackError<EA, EB> {
+    /// The primary failed with an error that does not warrant a fallback
+    Primary(EA),
+    /// Token counting with the secondary failed
+    Secondary(EB),
+    /// Both summarizers failed
+    Both { primary: EA, secondary: EB },
+}
+
+/// Summarizes with `A`, falling back to `B` when `A` fails with an error for which
+/// [`ShouldFallback::should_fallback`] holds.
+#[derive(Debug, Clone)]
+pub struct FallbackSummarizer<A, B> {
+    primary: A,
+    secondary: B,
+}
+
+impl<A, B> FallbackSummarizer<A, B> {
+    pub fn new(primary: A, secondary: B) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+impl<A, B> Summarizer for FallbackSummarizer<A, B>
+where
+    A: Summarizer + Sync,
+    A::Error: ShouldFallback + Send,
+    B: Summarizer + Sync,
+{
+    // content must fit either summarizer, since we can't know in advance which one is used
+    const CONTEXT_WINDOW_LIMIT: usize = if A::CONTEXT_WINDOW_LIMIT < B::CONTEXT_WINDOW_LIMIT {
+        A::CONTEXT_WINDOW_LIMIT
+    } else {
+        B::CONTEXT_WINDOW_LIMIT
+    };
+    const SUMMARIZER_MODEL: &'static str = A::SUMMARIZER_MODEL;
+
+    type Error = FallbackError<A::Error, B::Error>;
+
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
+        let primary = match self.primary.summarize(content, context).await {
+            Ok(response) => return Ok(response),
+            Err(e) if e.should_fallback() => e,
+            Err(e) => return Err(FallbackError::Primary(e)),
+        };
+
+        tracing::warn!(
+            error = ?primary,
+            primary = A::SUMMARIZER_MODEL,
+            secondary = B::SUMMARIZER_MODEL,
+            "Primary summarizer failed, falling back"
+        );
+
+        self.secondary
+            .summarize(content, context)
+            .await
+            .map_err(|secondary| FallbackError::Both { primary, secondary })
+    }
+
+    /// Tokenizers differ between providers, so the larger of the two counts is used
+    fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
+        let primary = self
+            .primary
+            .count_tokens(content)
+            .map_err(FallbackError::Primary)?;
+        let secondary = self
+            .secondary
+            .count_tokens(content)
+            .map_err(FallbackError::Secondary)?;
+        Ok(primary.max(secondary))
+    }
+}
+
+/// 429s, server errors and transport failures
+fn is_transient(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
+
+impl ShouldFallback for OpenAIError {
+    fn should_fallback(&self) -> bool {
+        match self {
+            OpenAIError::Request(_) | OpenAIError::RequestMiddleware(_) => true,
+            OpenAIError::Api { status, .. } => is_transient(*status),
+            OpenAIError::ChunkFailed { source, .. } => source.should_fallback(),
+            _ => false,
+        }
+    }
+}
+
+impl ShouldFallback for AnthropicError {
+    fn should_fallback(&self) -> bool {
+        match self {
+            AnthropicError::Request(_) | AnthropicError::RequestMiddleware(_) => true,
+            AnthropicError::Api { status, .. } => is_transient(*status),
+            AnthropicError::MaxTokensReached(_) | AnthropicError::MalformedResponse(_) => false,
+        }
+    }
+}
+
+impl ShouldFallback for OpenRouterError {
+    fn should_fallback(&self) -> bool {
+        match self {
+            OpenRouterError::Request(_) | OpenRouterError::RequestMiddleware(_) => true,
+            OpenRouterError::Api { status, .. } => is_transient(*status),
+            OpenRouterError::MalformedResponse(_) => false,
+        }
+    }
+}
+
+#[cfg(feature = "bedrock")]
+impl ShouldFallback for BedrockError {
+    fn should_fallback(&self) -> bool {
+        match self {
+            BedrockError::Request(_) | BedrockError::RequestMiddleware(_) => true,
+            BedrockError::Api { status, .. } => is_transient(*status),
+            _ => false,
+        }
+    }
+}
+
+impl ShouldFallback for ProviderError {
+    fn should_fallback(&self) -> bool {
+        match self {
+            ProviderError::OpenAI(e) => e.should_fallback(),
+            ProviderError::Anthropic(e) => e.should_fallback(),
+            ProviderError::OpenRouter(e) => e.should_fallback(),
+            #[cfg(feature = "bedrock")]
+            ProviderError::Bedrock(e) => e.should_fallback(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Fixed {
+        status: Option<u16>,
+        calls: AtomicUsize,
+    }
+
+    impl Fixed {
+        fn new(status: Option<u16>) -> Self {
+            Self {
+                status,
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl Summarizer for Fixed {
+        const CONTEXT_WINDOW_LIMIT: usize = 100;
+        const SUMMARIZER_MODEL: &'static str = "fixed";
+
+        type Error = OpenAIError;
+
+        async fn summarize(
+            &self,
+            _content: &str,
+            _context: &SummaryContext,
+        ) -> Result<SummaryResponse, Self::Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.status {
+                Some(status) => Err(OpenAIError::Api {
+                    status,
+                    message: "failed".into(),
+                }),
+                None => Ok(SummaryResponse {
+                    summary: "summary".into(),
+                    structured: None,
+                    tldr: None,
+                    verification: None,
+                }),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_on_rate_limit() {
+        let summarizer = FallbackSummarizer::new(Fixed::new(Some(429)), Fixed::new(None));
+        assert!(summarizer
+            .summarize("transcript", &SummaryContext::default())
+            .await
+            .is_ok());
+        assert_eq!(summarizer.secondary.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_fall_back_on_client_error() {
+        let summarizer = FallbackSummarizer::new(Fixed::new(Some(400)), Fixed::new(None));
+        assert!(matches!(
+            summarizer
+                .summarize("transcript", &SummaryContext::default())
+                .await,
+            Err(FallbackError::Primary(_))
+        ));
+        assert_eq!(summarizer.secondary.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_does_not_fall_back_on_malformed_response() {
+        let malformed = || "entity extraction response: expected value".to_string();
+        assert!(!OpenAIError::MalformedResponse(malformed()).should_fallback());
+        assert!(!AnthropicError::MalformedResponse(malformed()).should_fallback());
+        assert!(!OpenRouterError::MalformedResponse(malformed()).should_fallback());
+    }
+}
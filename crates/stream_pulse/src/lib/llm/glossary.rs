@@ -0,0 +1,93 @@
+//! # Glossary
+//!
+//! Names and terms given to the transcription model as its prompt for the first audio
+//! chunk, so MPs, constituencies and Kiswahili phrases are spelled correctly instead of
+//! being mangled into the summaries.
+
+use std::path::Path;
+
+const DEFAULT_GLOSSARY: &str = include_str!("prompts/glossary_0.txt");
+
+/// Whisper only considers the last 224 tokens of its prompt, so the glossary is cut
+/// short well before that
+const MAX_PROMPT_CHARS: usize = 800;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glossary {
+    terms: Vec<String>,
+}
+
+impl Default for Glossary {
+    /// The bundled glossary of Kenyan parliamentary terms
+    fn default() -> Self {
+        Self::parse(DEFAULT_GLOSSARY)
+    }
+}
+
+impl Glossary {
+    pub fn new(terms: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            terms: terms.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Parses one term per line, skipping blank lines and `#` comments
+    pub fn parse(content: &str) -> Self {
+        Self::new(
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        )
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// The terms as a transcription prompt, keeping as many of the leading terms as fit.
+    /// `None` when the glossary is empty.
+    pub fn prompt(&self) -> Option<String> {
+        let mut prompt = String::new();
+        for term in &self.terms {
+            if !prompt.is_empty() && prompt.len() + 2 + term.len() > MAX_PROMPT_CHARS {
+                break;
+            }
+            if !prompt.is_empty() {
+                prompt.push_str(", ");
+            }
+            prompt.push_str(term);
+        }
+
+        (!prompt.is_empty()).then(|| format!("{prompt}."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_and_blank_lines_are_skipped() {
+        let glossary = Glossary::parse("# MPs\nKimani Ichung'wah\n\n  Kathiani \n");
+        assert_eq!(glossary.terms(), ["Kimani Ichung'wah", "Kathiani"]);
+        assert_eq!(
+            glossary.prompt().as_deref(),
+            Some("Kimani Ichung'wah, Kathiani.")
+        );
+    }
+
+    #[test]
+    fn test_prompt_is_cut_short() {
+        let glossary = Glossary::new(std::iter::repeat_n("Mheshimiwa Spika", 100));
+        let prompt = glossary.prompt().unwrap();
+        assert!(prompt.len() <= MAX_PROMPT_CHARS + 1);
+        assert!(prompt.starts_with("Mheshimiwa Spika, "));
+        assert!(Glossary::new(Vec::<String>::new()).prompt().is_none());
+        assert!(!Glossary::default().terms().is_empty());
+    }
+}
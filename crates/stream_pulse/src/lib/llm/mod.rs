@@ -1,5 +1,45 @@
+pub mod captions;
+#[cfg(feature = "cassette")]
+pub mod cassette;
+pub mod classifier;
+pub mod divisions;
+pub mod embedder;
+pub mod entities;
+pub mod fallback;
+pub mod glossary;
+pub mod prompt;
 mod providers;
+pub mod rate_limit;
+pub mod registry;
+pub mod retry;
+#[cfg(feature = "bedrock")]
+mod sigv4;
+mod sse;
 pub mod summarizer;
+pub(crate) mod timestamps;
 pub mod transcriber;
+mod transport;
+pub mod usage;
+pub mod verification;
 
-pub use providers::openai;
+#[cfg(feature = "bedrock")]
+pub use providers::bedrock;
+pub use providers::{anthropic, groq, openai, openrouter};
+
+/// A conservative estimate of `content`'s token count, at roughly four characters per
+/// token, for providers without a local tokenizer
+pub(crate) fn estimate_tokens(content: &str) -> usize {
+    content.chars().count().div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_estimates_round_up() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("Bunge"), 2);
+        assert_eq!(estimate_tokens("Hoja"), 1);
+    }
+}
@@ -0,0 +1,137 @@
+//! # Prompt Templates
+//!
+//! System prompts loaded at runtime from a file or environment variable, so that prompt
+//! iteration does not require rebuilding the binary. Templates may reference
+//! `{{title}}`, `{{date}}`, `{{house}}`, `{{duration}}`, `{{description}}`, `{{chapters}}`
+//! and `{{agenda}}`, which are filled in from [`PromptVariables`].
+
+use std::{fmt::Display, path::Path};
+
+const DEFAULT_SYSTEM_PROMPT: &str = include_str!("prompts/system_0.txt");
+
+/// Substituted for variables that have no value
+const UNKNOWN: &str = "unknown";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PromptError {
+    #[error("Failed to read prompt template {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Prompt template is empty")]
+    Empty,
+}
+
+/// A system prompt with `{{variable}}` placeholders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    template: String,
+}
+
+/// Values substituted into a [`PromptTemplate`]
+#[derive(Debug, Clone, Default)]
+pub struct PromptVariables {
+    /// Title of the stream, e.g. "National Assembly | Afternoon Sitting"
+    pub title: Option<String>,
+    /// Date of the sitting
+    pub date: Option<String>,
+    /// "National Assembly", "Senate" etc.
+    pub house: Option<String>,
+    /// Length of the stream, e.g. "3:12:45"
+    pub duration: Option<String>,
+    /// Video description
+    pub description: Option<String>,
+    /// Chapter markers, e.g. "0:00 Prayers; 1:02:15 The Finance Bill"
+    pub chapters: Option<String>,
+    /// Order paper items, e.g. "PRAYERS; PAPERS; THE FINANCE BILL, 2025"
+    pub agenda: Option<String>,
+}
+
+impl Default for PromptTemplate {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_SYSTEM_PROMPT.to_string(),
+        }
+    }
+}
+
+impl PromptTemplate {
+    pub fn new(template: impl Into<String>) -> Result<Self, PromptError> {
+        let template = template.into();
+        if template.trim().is_empty() {
+            return Err(PromptError::Empty);
+        }
+        Ok(Self { template })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PromptError> {
+        let path = path.as_ref();
+        let template = std::fs::read_to_string(path).map_err(|source| PromptError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::new(template)
+    }
+
+    /// Substitutes known variables. Unknown placeholders are left untouched so that
+    /// templates can contain literal braces.
+    pub fn render(&self, variables: &PromptVariables) -> String {
+        let value = |v: &Option<String>| v.as_deref().unwrap_or(UNKNOWN).to_string();
+
+        self.template
+            .replace("{{title}}", &value(&variables.title))
+            .replace("{{date}}", &value(&variables.date))
+            .replace("{{house}}", &value(&variables.house))
+            .replace("{{duration}}", &value(&variables.duration))
+            .replace("{{description}}", &value(&variables.description))
+            .replace("{{chapters}}", &value(&variables.chapters))
+            .replace("{{agenda}}", &value(&variables.agenda))
+    }
+}
+
+impl Display for PromptTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let template =
+            PromptTemplate::new("{{house}} sitting of {{date}}: {{title}} {{other}}").unwrap();
+        let rendered = template.render(&PromptVariables {
+            title: Some("Afternoon Sitting".into()),
+            date: Some("12 March 2025".into()),
+            house: None,
+            ..Default::default()
+        });
+        assert_eq!(
+            rendered,
+            "unknown sitting of 12 March 2025: Afternoon Sitting {{other}}"
+        );
+    }
+
+    #[test]
+    fn test_empty_template_is_rejected() {
+        assert!(matches!(
+            PromptTemplate::new("  \n"),
+            Err(PromptError::Empty)
+        ));
+    }
+
+    #[test]
+    fn test_default_template_is_bundled_prompt() {
+        let rendered = PromptTemplate::default().render(&PromptVariables {
+            title: Some("Senate | Morning Sitting".into()),
+            ..Default::default()
+        });
+        assert!(rendered.contains("Senate | Morning Sitting"));
+        assert!(!rendered.contains("{{"));
+    }
+}
@@ -0,0 +1,448 @@
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use stream_datastore::{Division, StreamCategory, StreamEntities};
+
+use crate::{
+    llm::{
+        classifier::{parse_category, CategoryClassifier, CLASSIFICATION_PROMPT},
+        divisions::{division_system_prompt, parse_divisions, DivisionExtractor},
+        entities::{entity_system_prompt, parse_entities, EntityExtractor},
+        estimate_tokens,
+        prompt::PromptTemplate,
+        retry::retrying_client,
+        summarizer::{CompletionOptions, SummaryContext, SummaryResponse},
+        usage::{CompletionUsage, UsageTracker},
+        verification::{
+            parse_verdict, verification_content, verification_system_prompt, SummaryVerdict,
+            SummaryVerifier,
+        },
+    },
+    Summarizer,
+};
+
+#[derive(Debug, Clone)]
+pub struct AnthropicClient {
+    client: ClientWithMiddleware,
+    api_key: String,
+    base_url: String,
+    summarizer_model: Option<String>,
+    extraction_model: Option<String>,
+    verification_model: Option<String>,
+    completion_options: CompletionOptions,
+    max_tokens: u32,
+    system_prompt: PromptTemplate,
+    usage_tracker: UsageTracker,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnthropicError {
+    #[error("HTTP error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("HTTP middleare error: {0}")]
+    RequestMiddleware(#[from] reqwest_middleware::Error),
+    #[error("API error: {status} - {message}")]
+    Api { status: u16, message: String },
+    #[error("Malformed {0}")]
+    MalformedResponse(String),
+    #[error("Response was truncated after reaching max_tokens ({0})")]
+    MaxTokensReached(u32),
+}
+
+impl AnthropicClient {
+    const API_VERSION: &str = "2023-06-01";
+    const DEFAULT_MAX_TOKENS: u32 = 8_192;
+
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: retrying_client(reqwest::Client::new()),
+            api_key: api_key.into(),
+            base_url: "https://api.anthropic.com/v1".into(),
+            summarizer_model: None,
+            extraction_model: None,
+            verification_model: None,
+            completion_options: CompletionOptions::default(),
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+            system_prompt: PromptTemplate::default(),
+            usage_tracker: UsageTracker::default(),
+        }
+    }
+
+    /// Send requests through `client`, e.g. one shared with the rest of the run for its
+    /// proxy and timeout settings
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = retrying_client(client);
+        self
+    }
+
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Override the model used for summarization, which otherwise
+    /// defaults to [`Summarizer::SUMMARIZER_MODEL`]
+    pub fn with_summarizer_model(mut self, model: impl Into<String>) -> Self {
+        self.summarizer_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for entity and division extraction and title classification,
+    /// which otherwise defaults to [`EntityExtractor::EXTRACTION_MODEL`],
+    /// [`DivisionExtractor::DIVISION_EXTRACTION_MODEL`] and
+    /// [`CategoryClassifier::CLASSIFICATION_MODEL`]
+    pub fn with_extraction_model(mut self, model: impl Into<String>) -> Self {
+        self.extraction_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for summary verification, which otherwise
+    /// defaults to [`SummaryVerifier::VERIFICATION_MODEL`]
+    pub fn with_verification_model(mut self, model: impl Into<String>) -> Self {
+        self.verification_model = Some(model.into());
+        self
+    }
+
+    /// Replace the bundled system prompt used for summarization
+    pub fn with_system_prompt(mut self, system_prompt: PromptTemplate) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Configure sampling and the web search tool, which is enabled by default. The seed
+    /// and frequency penalty are not supported by the messages API
+    pub fn with_completion_options(mut self, options: CompletionOptions) -> Self {
+        self.completion_options = options;
+        self
+    }
+
+    /// Set the maximum number of tokens the model may generate per summary
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Record token usage into `usage_tracker`
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = usage_tracker;
+        self
+    }
+
+    /// [`CompletionOptions::max_tokens`] when set, [`Self::with_max_tokens`] otherwise
+    fn max_tokens(&self) -> u32 {
+        self.completion_options
+            .max_tokens
+            .unwrap_or(self.max_tokens)
+    }
+
+    pub async fn send_messages_request(
+        &self,
+        model_name: impl Into<String>,
+        system_prompt: &str,
+        user_content: impl Into<String>,
+    ) -> Result<MessagesResponse, AnthropicError> {
+        let model_name = model_name.into();
+        let mut body = serde_json::json!({
+            "model": model_name,
+            "max_tokens": self.max_tokens(),
+            "system": system_prompt,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": user_content.into()
+                }
+            ]
+        });
+        if let Some(web_search) = &self.completion_options.web_search {
+            let mut tool = serde_json::json!({
+                "type": "web_search_20250305",
+                "name": "web_search",
+                "max_uses": web_search.max_results
+            });
+            if let Some(location) = &web_search.user_location {
+                let mut user_location = serde_json::json!(location);
+                user_location["type"] = "approximate".into();
+                tool["user_location"] = user_location;
+            }
+            body["tools"] = serde_json::json!([tool]);
+        }
+        if let Some(temperature) = self.completion_options.temperature {
+            body["temperature"] = temperature.into();
+        }
+        if let Some(top_p) = self.completion_options.top_p {
+            body["top_p"] = top_p.into();
+        }
+
+        let resp = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", Self::API_VERSION)
+            .json(&body)
+            .send()
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let message = resp.text().await.unwrap_or_default();
+            return Err(AnthropicError::Api { status, message });
+        }
+
+        let response = resp.json::<MessagesResponse>().await?;
+        if let Some(usage) = &response.usage {
+            self.usage_tracker.record_completion(
+                &model_name,
+                CompletionUsage {
+                    prompt_tokens: usage.input_tokens,
+                    completion_tokens: usage.output_tokens,
+                },
+            );
+        }
+
+        Ok(response)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessagesResponse {
+    pub id: String,
+    pub content: Vec<ContentBlock>,
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub usage: Option<MessagesUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessagesUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContentBlock {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub text: Option<String>,
+}
+
+impl MessagesResponse {
+    /// Concatenates all text blocks, skipping tool use and tool result blocks
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter(|block| block.kind == "text")
+            .filter_map(|block| block.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("")
+    }
+}
+
+impl Summarizer for AnthropicClient {
+    const SUMMARIZER_MODEL: &'static str = "claude-sonnet-4-20250514";
+    const CONTEXT_WINDOW_LIMIT: usize = 200_000 - Self::DEFAULT_MAX_TOKENS as usize;
+
+    type Error = AnthropicError;
+
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
+        let token_count = self.count_tokens(content)?;
+
+        if token_count > Self::CONTEXT_WINDOW_LIMIT {
+            return Err(AnthropicError::Api {
+                status: 0,
+                message: "Token limit exceeded".into(),
+            });
+        }
+
+        let model = self
+            .summarizer_model
+            .as_deref()
+            .unwrap_or(Self::SUMMARIZER_MODEL);
+
+        let system_prompt = self.system_prompt.render(&context.prompt_variables());
+
+        let response = self
+            .send_messages_request(model, &system_prompt, content)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to summarize content"))?;
+
+        if response.stop_reason.as_deref() == Some("max_tokens") {
+            tracing::error!(
+                max_tokens = self.max_tokens(),
+                "Summary truncated by max_tokens"
+            );
+            return Err(AnthropicError::MaxTokensReached(self.max_tokens()));
+        }
+
+        let summary = response.text();
+        if summary.trim().is_empty() {
+            return Err(AnthropicError::Api {
+                status: 0,
+                message: "No content in response".into(),
+            });
+        }
+
+        Ok(SummaryResponse {
+            summary,
+            structured: None,
+            tldr: None,
+            verification: None,
+        })
+    }
+
+    /// Anthropic does not publish a local tokenizer, so this is an estimate
+    fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
+        Ok(estimate_tokens(content))
+    }
+}
+
+impl EntityExtractor for AnthropicClient {
+    const EXTRACTION_MODEL: &'static str = "claude-3-5-haiku-20241022";
+
+    type Error = AnthropicError;
+
+    async fn extract_entities(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<StreamEntities, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::EXTRACTION_MODEL);
+
+        let response = self
+            .send_messages_request(model, &entity_system_prompt(context), transcript)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract entities"))?;
+
+        if response.stop_reason.as_deref() == Some("max_tokens") {
+            return Err(AnthropicError::MaxTokensReached(self.max_tokens()));
+        }
+
+        // the messages API has no JSON schema mode, so the prompt asks for bare JSON
+        parse_entities(&response.text())
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed entity extraction response"))
+            .map_err(|e| {
+                AnthropicError::MalformedResponse(format!("entity extraction response: {e}"))
+            })
+    }
+}
+
+impl DivisionExtractor for AnthropicClient {
+    const DIVISION_EXTRACTION_MODEL: &'static str = "claude-3-5-haiku-20241022";
+
+    type Error = AnthropicError;
+
+    async fn extract_divisions(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<Vec<Division>, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::DIVISION_EXTRACTION_MODEL);
+
+        let response = self
+            .send_messages_request(model, &division_system_prompt(context), transcript)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract divisions"))?;
+
+        if response.stop_reason.as_deref() == Some("max_tokens") {
+            return Err(AnthropicError::MaxTokensReached(self.max_tokens()));
+        }
+
+        parse_divisions(&response.text())
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed division extraction response"))
+            .map_err(|e| {
+                AnthropicError::MalformedResponse(format!("division extraction response: {e}"))
+            })
+    }
+}
+
+impl SummaryVerifier for AnthropicClient {
+    const VERIFICATION_MODEL: &'static str = "claude-3-5-haiku-20241022";
+
+    type Error = AnthropicError;
+
+    async fn verify_summary(
+        &self,
+        transcript: &str,
+        summary: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryVerdict, Self::Error> {
+        let model = self
+            .verification_model
+            .as_deref()
+            .unwrap_or(Self::VERIFICATION_MODEL);
+
+        let response = self
+            .send_messages_request(
+                model,
+                &verification_system_prompt(context),
+                verification_content(transcript, summary),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to verify summary"))?;
+
+        if response.stop_reason.as_deref() == Some("max_tokens") {
+            return Err(AnthropicError::MaxTokensReached(self.max_tokens()));
+        }
+
+        parse_verdict(&response.text())
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed verification response"))
+            .map_err(|e| AnthropicError::MalformedResponse(format!("verification response: {e}")))
+    }
+}
+
+impl CategoryClassifier for AnthropicClient {
+    const CLASSIFICATION_MODEL: &'static str = "claude-3-5-haiku-20241022";
+
+    type Error = AnthropicError;
+
+    async fn classify_title(&self, title: &str) -> Result<StreamCategory, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::CLASSIFICATION_MODEL);
+
+        let response = self
+            .send_messages_request(model, CLASSIFICATION_PROMPT, title)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to classify stream title"))?;
+
+        if response.stop_reason.as_deref() == Some("max_tokens") {
+            return Err(AnthropicError::MaxTokensReached(self.max_tokens()));
+        }
+
+        parse_category(&response.text())
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed classification response"))
+            .map_err(|e| AnthropicError::MalformedResponse(format!("classification response: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_skips_non_text_blocks() {
+        let response: MessagesResponse = serde_json::from_value(serde_json::json!({
+            "id": "msg_1",
+            "stop_reason": "end_turn",
+            "content": [
+                { "type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search" },
+                { "type": "web_search_tool_result", "tool_use_id": "srvtoolu_1", "content": [] },
+                { "type": "text", "text": "# National Assembly" },
+                { "type": "text", "text": " Sitting" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(response.text(), "# National Assembly Sitting");
+    }
+}
@@ -0,0 +1,1045 @@
+//! # Bedrock
+//!
+//! Summarization through the Bedrock Converse API and transcription with Amazon
+//! Transcribe, so deployments running in AWS keep model traffic and billing inside
+//! their own account. Requests are signed with SigV4.
+
+use std::{
+    fmt,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::header::CONTENT_LENGTH;
+use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use serde::Deserialize;
+use stream_datastore::{Division, StreamCategory, StreamEntities};
+use tokio_util::io::ReaderStream;
+use ytdlp_bindings::AudioProcessor;
+
+use crate::{
+    llm::{
+        classifier::{parse_category, CategoryClassifier, CLASSIFICATION_PROMPT},
+        divisions::{division_system_prompt, parse_divisions, DivisionExtractor},
+        entities::{entity_system_prompt, parse_entities, EntityExtractor},
+        estimate_tokens,
+        prompt::PromptTemplate,
+        retry::{retry, retrying_client, RetryPolicy},
+        sigv4::{sign, uri_encode},
+        summarizer::{CompletionOptions, SummaryContext, SummaryResponse},
+        transcriber::{
+            chunk_duration, prepare_chunks, transcribe_chunks, ChunkFailure, ChunkingError,
+            TranscribeResponse, TranscribeSegment,
+        },
+        usage::{CompletionUsage, UsageTracker},
+        verification::{
+            parse_verdict, verification_content, verification_system_prompt, SummaryVerdict,
+            SummaryVerifier,
+        },
+    },
+    AudioInput, ProcessorEvents, Summarizer, Transcriber,
+};
+
+/// Static or temporary IAM credentials
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Set for temporary credentials, e.g. from an assumed role
+    pub session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    pub fn with_session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.session_token = Some(session_token.into());
+        self
+    }
+
+    /// Reads `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`,
+    /// as set by the AWS CLI, ECS task roles and Lambda
+    pub fn from_env() -> Option<Self> {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let mut credentials = Self::new(var("AWS_ACCESS_KEY_ID")?, var("AWS_SECRET_ACCESS_KEY")?);
+        credentials.session_token = var("AWS_SESSION_TOKEN");
+        Some(credentials)
+    }
+}
+
+// the secret access key and session token are kept out of logs
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BedrockError {
+    #[error("HTTP error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("HTTP middleare error: {0}")]
+    RequestMiddleware(#[from] reqwest_middleware::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("API error: {status} - {message}")]
+    Api { status: u16, message: String },
+    #[error("Malformed {0}")]
+    MalformedResponse(String),
+    #[error("Response was truncated after reaching max_tokens ({0})")]
+    MaxTokensReached(u32),
+    #[error("Transcription job {job} failed: {reason}")]
+    TranscriptionFailed { job: String, reason: String },
+    #[error("Transcription job {job} still running after {waited:?}")]
+    TranscriptionTimedOut { job: String, waited: Duration },
+    #[error("FFmpeg error: {0}")]
+    Ffmpeg(String),
+    #[error("Chunk {chunk} of {chunks} failed to transcribe: {source}")]
+    ChunkFailed {
+        chunk: usize,
+        chunks: usize,
+        source: Box<BedrockError>,
+    },
+}
+
+impl From<ChunkFailure<BedrockError>> for BedrockError {
+    fn from(failure: ChunkFailure<BedrockError>) -> Self {
+        BedrockError::ChunkFailed {
+            chunk: failure.chunk,
+            chunks: failure.chunks,
+            source: Box::new(failure.error),
+        }
+    }
+}
+
+impl From<ChunkingError> for BedrockError {
+    fn from(err: ChunkingError) -> Self {
+        match err {
+            ChunkingError::Io(e) => BedrockError::Io(e),
+            ChunkingError::Ffmpeg(msg) => BedrockError::Ffmpeg(msg),
+        }
+    }
+}
+
+/// Signs and sends `request`. Retries resend the signed request, which stays valid
+/// for five minutes.
+async fn send_signed(
+    client: &ClientWithMiddleware,
+    request: RequestBuilder,
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+) -> Result<reqwest::Response, BedrockError> {
+    let mut request = request.build()?;
+    sign(
+        &mut request,
+        credentials,
+        region,
+        service,
+        SystemTime::now().into(),
+    );
+
+    let resp = client
+        .execute(request)
+        .await
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+    successful(resp).await
+}
+
+async fn successful(resp: reqwest::Response) -> Result<reqwest::Response, BedrockError> {
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let message = resp.text().await.unwrap_or_default();
+        return Err(BedrockError::Api { status, message });
+    }
+
+    Ok(resp)
+}
+
+/// Summarizer backed by the Bedrock Converse API, which takes the same request shape
+/// for every model family. Model IDs look like `anthropic.claude-3-5-sonnet-20240620-v1:0`;
+/// cross-region inference profiles such as `us.anthropic...` work too.
+#[derive(Debug, Clone)]
+pub struct BedrockClient {
+    client: ClientWithMiddleware,
+    credentials: AwsCredentials,
+    region: String,
+    base_url: String,
+    summarizer_model: Option<String>,
+    extraction_model: Option<String>,
+    verification_model: Option<String>,
+    completion_options: CompletionOptions,
+    max_tokens: u32,
+    system_prompt: PromptTemplate,
+    usage_tracker: UsageTracker,
+}
+
+impl BedrockClient {
+    const DEFAULT_MAX_TOKENS: u32 = 8_192;
+
+    pub fn new(credentials: AwsCredentials, region: impl Into<String>) -> Self {
+        let region = region.into();
+        Self {
+            client: retrying_client(reqwest::Client::new()),
+            credentials,
+            base_url: format!("https://bedrock-runtime.{region}.amazonaws.com"),
+            region,
+            summarizer_model: None,
+            extraction_model: None,
+            verification_model: None,
+            completion_options: CompletionOptions::without_web_search(),
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+            system_prompt: PromptTemplate::default(),
+            usage_tracker: UsageTracker::default(),
+        }
+    }
+
+    /// Send requests through `client`, e.g. one shared with the rest of the run for its
+    /// proxy and timeout settings
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = retrying_client(client);
+        self
+    }
+
+    /// Override the regional endpoint, e.g. with a VPC endpoint
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Override the model used for summarization, which otherwise
+    /// defaults to [`Summarizer::SUMMARIZER_MODEL`]
+    pub fn with_summarizer_model(mut self, model: impl Into<String>) -> Self {
+        self.summarizer_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for entity and division extraction and title classification,
+    /// which otherwise defaults to [`EntityExtractor::EXTRACTION_MODEL`],
+    /// [`DivisionExtractor::DIVISION_EXTRACTION_MODEL`] and
+    /// [`CategoryClassifier::CLASSIFICATION_MODEL`]
+    pub fn with_extraction_model(mut self, model: impl Into<String>) -> Self {
+        self.extraction_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for summary verification, which otherwise
+    /// defaults to [`SummaryVerifier::VERIFICATION_MODEL`]
+    pub fn with_verification_model(mut self, model: impl Into<String>) -> Self {
+        self.verification_model = Some(model.into());
+        self
+    }
+
+    /// Replace the bundled system prompt used for summarization
+    pub fn with_system_prompt(mut self, system_prompt: PromptTemplate) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Configure sampling. Web search, the seed and the frequency penalty are not
+    /// supported by the Converse API
+    pub fn with_completion_options(mut self, options: CompletionOptions) -> Self {
+        self.completion_options = options;
+        self
+    }
+
+    /// Set the maximum number of tokens the model may generate per summary
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Record token usage into `usage_tracker`
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = usage_tracker;
+        self
+    }
+
+    /// [`CompletionOptions::max_tokens`] when set, [`Self::with_max_tokens`] otherwise
+    fn max_tokens(&self) -> u32 {
+        self.completion_options
+            .max_tokens
+            .unwrap_or(self.max_tokens)
+    }
+
+    pub async fn send_converse_request(
+        &self,
+        model_id: impl Into<String>,
+        system_prompt: &str,
+        user_content: impl Into<String>,
+    ) -> Result<ConverseResponse, BedrockError> {
+        let model_id = model_id.into();
+        let mut body = serde_json::json!({
+            "system": [{ "text": system_prompt }],
+            "messages": [
+                {
+                    "role": "user",
+                    "content": [{ "text": user_content.into() }]
+                }
+            ],
+            "inferenceConfig": { "maxTokens": self.max_tokens() }
+        });
+        if let Some(temperature) = self.completion_options.temperature {
+            body["inferenceConfig"]["temperature"] = temperature.into();
+        }
+        if let Some(top_p) = self.completion_options.top_p {
+            body["inferenceConfig"]["topP"] = top_p.into();
+        }
+
+        let request = self
+            .client
+            .post(format!(
+                "{}/model/{}/converse",
+                self.base_url,
+                uri_encode(&model_id, true)
+            ))
+            .json(&body);
+        let resp = send_signed(
+            &self.client,
+            request,
+            &self.credentials,
+            &self.region,
+            "bedrock",
+        )
+        .await?;
+
+        let response = resp.json::<ConverseResponse>().await?;
+        if let Some(usage) = &response.usage {
+            self.usage_tracker.record_completion(
+                &model_id,
+                CompletionUsage {
+                    prompt_tokens: usage.input_tokens,
+                    completion_tokens: usage.output_tokens,
+                },
+            );
+        }
+
+        Ok(response)
+    }
+
+    /// Sends a request whose response is only usable in full
+    async fn converse(
+        &self,
+        model_id: &str,
+        system_prompt: &str,
+        user_content: impl Into<String>,
+    ) -> Result<String, BedrockError> {
+        let response = self
+            .send_converse_request(model_id, system_prompt, user_content)
+            .await?;
+
+        if response.stop_reason.as_deref() == Some("max_tokens") {
+            tracing::error!(
+                max_tokens = self.max_tokens(),
+                "Response truncated by max_tokens"
+            );
+            return Err(BedrockError::MaxTokensReached(self.max_tokens()));
+        }
+
+        Ok(response.text())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConverseResponse {
+    pub output: ConverseOutput,
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub usage: Option<ConverseUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConverseOutput {
+    pub message: Option<ConverseMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConverseMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Vec<ConverseContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConverseContentBlock {
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConverseUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl ConverseResponse {
+    /// Concatenates all text blocks of the output message
+    pub fn text(&self) -> String {
+        self.output
+            .message
+            .iter()
+            .flat_map(|message| &message.content)
+            .filter_map(|block| block.text.as_deref())
+            .collect()
+    }
+}
+
+impl Summarizer for BedrockClient {
+    const SUMMARIZER_MODEL: &'static str = "anthropic.claude-3-5-sonnet-20240620-v1:0";
+    const CONTEXT_WINDOW_LIMIT: usize = 200_000 - Self::DEFAULT_MAX_TOKENS as usize;
+
+    type Error = BedrockError;
+
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
+        let token_count = self.count_tokens(content)?;
+
+        if token_count > Self::CONTEXT_WINDOW_LIMIT {
+            return Err(BedrockError::Api {
+                status: 0,
+                message: "Token limit exceeded".into(),
+            });
+        }
+
+        let model = self
+            .summarizer_model
+            .as_deref()
+            .unwrap_or(Self::SUMMARIZER_MODEL);
+
+        let system_prompt = self.system_prompt.render(&context.prompt_variables());
+
+        let summary = self
+            .converse(model, &system_prompt, content)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to summarize content"))?;
+
+        if summary.trim().is_empty() {
+            return Err(BedrockError::Api {
+                status: 0,
+                message: "No content in response".into(),
+            });
+        }
+
+        Ok(SummaryResponse {
+            summary,
+            structured: None,
+            tldr: None,
+            verification: None,
+        })
+    }
+
+    /// Bedrock has no tokenizer endpoint, so this is an estimate
+    fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
+        Ok(estimate_tokens(content))
+    }
+}
+
+impl EntityExtractor for BedrockClient {
+    const EXTRACTION_MODEL: &'static str = "anthropic.claude-3-5-haiku-20241022-v1:0";
+
+    type Error = BedrockError;
+
+    async fn extract_entities(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<StreamEntities, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::EXTRACTION_MODEL);
+
+        let content = self
+            .converse(model, &entity_system_prompt(context), transcript)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract entities"))?;
+
+        parse_entities(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed entity extraction response"))
+            .map_err(|e| {
+                BedrockError::MalformedResponse(format!("entity extraction response: {e}"))
+            })
+    }
+}
+
+impl DivisionExtractor for BedrockClient {
+    const DIVISION_EXTRACTION_MODEL: &'static str = "anthropic.claude-3-5-haiku-20241022-v1:0";
+
+    type Error = BedrockError;
+
+    async fn extract_divisions(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<Vec<Division>, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::DIVISION_EXTRACTION_MODEL);
+
+        let content = self
+            .converse(model, &division_system_prompt(context), transcript)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract divisions"))?;
+
+        parse_divisions(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed division extraction response"))
+            .map_err(|e| {
+                BedrockError::MalformedResponse(format!("division extraction response: {e}"))
+            })
+    }
+}
+
+impl SummaryVerifier for BedrockClient {
+    const VERIFICATION_MODEL: &'static str = "anthropic.claude-3-5-haiku-20241022-v1:0";
+
+    type Error = BedrockError;
+
+    async fn verify_summary(
+        &self,
+        transcript: &str,
+        summary: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryVerdict, Self::Error> {
+        let model = self
+            .verification_model
+            .as_deref()
+            .unwrap_or(Self::VERIFICATION_MODEL);
+
+        let content = self
+            .converse(
+                model,
+                &verification_system_prompt(context),
+                verification_content(transcript, summary),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to verify summary"))?;
+
+        parse_verdict(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed verification response"))
+            .map_err(|e| BedrockError::MalformedResponse(format!("verification response: {e}")))
+    }
+}
+
+impl CategoryClassifier for BedrockClient {
+    const CLASSIFICATION_MODEL: &'static str = "anthropic.claude-3-5-haiku-20241022-v1:0";
+
+    type Error = BedrockError;
+
+    async fn classify_title(&self, title: &str) -> Result<StreamCategory, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::CLASSIFICATION_MODEL);
+
+        let content = self
+            .converse(model, CLASSIFICATION_PROMPT, title)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to classify stream title"))?;
+
+        parse_category(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed classification response"))
+            .map_err(|e| BedrockError::MalformedResponse(format!("classification response: {e}")))
+    }
+}
+
+/// Transcriber backed by Amazon Transcribe batch jobs. The audio is staged in an S3
+/// bucket the credentials can write to, and removed once the job has finished.
+#[derive(Debug, Clone)]
+pub struct AmazonTranscriber<F: AudioProcessor> {
+    client: ClientWithMiddleware,
+    /// Sends uploads, whose streamed bodies the retrying `client` can't resend
+    upload_client: reqwest::Client,
+    credentials: AwsCredentials,
+    ffmpeg: F,
+    region: String,
+    bucket: String,
+    key_prefix: String,
+    language_code: Option<String>,
+    poll_interval: Duration,
+    max_wait: Duration,
+    usage_tracker: UsageTracker,
+    events: ProcessorEvents,
+}
+
+impl<F: AudioProcessor> AmazonTranscriber<F> {
+    /// Longest audio a job accepts
+    const MAX_JOB_SECONDS: u64 = 4 * 60 * 60;
+    /// Largest file a job accepts
+    const MAX_JOB_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+    pub fn new(
+        credentials: AwsCredentials,
+        region: impl Into<String>,
+        bucket: impl Into<String>,
+        ffmpeg: F,
+    ) -> Self {
+        let client = reqwest::Client::new();
+        Self {
+            client: retrying_client(client.clone()),
+            upload_client: client,
+            credentials,
+            ffmpeg,
+            region: region.into(),
+            bucket: bucket.into(),
+            key_prefix: "stream-pulse/".into(),
+            language_code: None,
+            poll_interval: Duration::from_secs(15),
+            max_wait: Duration::from_secs(Self::MAX_JOB_SECONDS),
+            usage_tracker: UsageTracker::default(),
+            events: ProcessorEvents::default(),
+        }
+    }
+
+    /// Send requests through `client`, e.g. one shared with the rest of the run for its
+    /// proxy and timeout settings
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = retrying_client(client.clone());
+        self.upload_client = client;
+        self
+    }
+
+    /// Prefix of the S3 keys audio is staged under
+    pub fn with_key_prefix(mut self, key_prefix: impl Into<String>) -> Self {
+        self.key_prefix = key_prefix.into();
+        self
+    }
+
+    /// Language of the audio, e.g. `en-US` or `sw-KE`. Identified automatically when unset
+    pub fn with_language_code(mut self, language_code: impl Into<String>) -> Self {
+        self.language_code = Some(language_code.into());
+        self
+    }
+
+    /// How often the status of a running job is checked
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Give up on a job still running after `max_wait`, instead of after four hours
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Record audio transcribed into `usage_tracker`
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = usage_tracker;
+        self
+    }
+
+    /// Report each transcribed chunk to `events`
+    pub fn with_events(mut self, events: ProcessorEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        format!(
+            "https://{}.s3.{}.amazonaws.com/{}",
+            self.bucket,
+            self.region,
+            uri_encode(key, false)
+        )
+    }
+
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        service: &str,
+    ) -> Result<reqwest::Response, BedrockError> {
+        send_signed(
+            &self.client,
+            request,
+            &self.credentials,
+            &self.region,
+            service,
+        )
+        .await
+    }
+
+    /// Calls a Transcribe API `action`, e.g. `StartTranscriptionJob`
+    async fn call(
+        &self,
+        action: &str,
+        body: serde_json::Value,
+    ) -> Result<TranscriptionJobResponse, BedrockError> {
+        let request = self
+            .client
+            .post(format!("https://transcribe.{}.amazonaws.com/", self.region))
+            .header("x-amz-target", format!("Transcribe.{action}"))
+            .header("content-type", "application/x-amz-json-1.1")
+            .body(body.to_string());
+        Ok(self.send(request, "transcribe").await?.json().await?)
+    }
+
+    /// Runs a job for the audio staged at `key`, returning the URL of its transcript
+    async fn run_job(
+        &self,
+        job_name: &str,
+        key: &str,
+        media_format: &str,
+    ) -> Result<String, BedrockError> {
+        let mut body = serde_json::json!({
+            "TranscriptionJobName": job_name,
+            "Media": { "MediaFileUri": format!("s3://{}/{key}", self.bucket) },
+            "MediaFormat": media_format
+        });
+        match &self.language_code {
+            Some(language_code) => body["LanguageCode"] = language_code.as_str().into(),
+            None => body["IdentifyLanguage"] = true.into(),
+        }
+        self.call("StartTranscriptionJob", body).await?;
+        tracing::info!(job = job_name, "Started transcription job");
+
+        let started = tokio::time::Instant::now();
+        loop {
+            let job = self
+                .call(
+                    "GetTranscriptionJob",
+                    serde_json::json!({ "TranscriptionJobName": job_name }),
+                )
+                .await?
+                .transcription_job;
+
+            match job.transcription_job_status.as_str() {
+                "COMPLETED" => {
+                    return job
+                        .transcript
+                        .and_then(|t| t.transcript_file_uri)
+                        .ok_or_else(|| BedrockError::TranscriptionFailed {
+                            job: job_name.to_string(),
+                            reason: "No transcript in completed job".into(),
+                        })
+                }
+                "FAILED" => {
+                    return Err(BedrockError::TranscriptionFailed {
+                        job: job_name.to_string(),
+                        reason: job.failure_reason.unwrap_or_default(),
+                    })
+                }
+                _ if started.elapsed() >= self.max_wait => {
+                    return Err(BedrockError::TranscriptionTimedOut {
+                        job: job_name.to_string(),
+                        waited: started.elapsed(),
+                    })
+                }
+                _ => tokio::time::sleep(self.poll_interval).await,
+            }
+        }
+    }
+
+    /// Stages the audio at `file_path` under `key`, streamed from disk rather than read
+    /// into memory. Each attempt reopens the file, a streamed body not being resendable.
+    async fn upload(&self, file_path: &Path, key: &str) -> Result<(), BedrockError> {
+        let size = tokio::fs::metadata(file_path).await?.len();
+        let resp = retry(&RetryPolicy::default(), || async {
+            let file = tokio::fs::File::open(file_path)
+                .await
+                .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+            let mut request = self
+                .upload_client
+                .put(self.object_url(key))
+                .header(CONTENT_LENGTH, size)
+                .body(reqwest::Body::wrap_stream(ReaderStream::new(file)))
+                .build()?;
+            sign(
+                &mut request,
+                &self.credentials,
+                &self.region,
+                "s3",
+                SystemTime::now().into(),
+            );
+            Ok(self.upload_client.execute(request).await?)
+        })
+        .await
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+        successful(resp).await?;
+        Ok(())
+    }
+
+    /// Whether the audio at `file_path` is too long or too large for one job, its length
+    /// going by its bit rate
+    fn exceeds_job_limits(&self, file_path: &Path) -> Result<bool, BedrockError> {
+        let size = std::fs::metadata(file_path)?.len();
+        if size > Self::MAX_JOB_BYTES {
+            return Ok(true);
+        }
+        let bit_rate = self
+            .ffmpeg
+            .bit_rate(file_path)
+            .map_err(|e| BedrockError::Ffmpeg(e.to_string()))?;
+        Ok(bit_rate > 0 && size.saturating_mul(8) / bit_rate > Self::MAX_JOB_SECONDS)
+    }
+
+    /// Transcribes the audio at `file_path` in one job, staging it for the job's length
+    async fn transcribe_file(&self, file_path: &Path) -> Result<TranscribeResponse, BedrockError> {
+        let media_format = file_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp3")
+            .to_ascii_lowercase();
+        let unix_seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let job_name = job_name(file_path, unix_seconds);
+        let key = format!("{}{job_name}.{media_format}", self.key_prefix);
+
+        self.upload(file_path, &key)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to stage audio in S3"))?;
+
+        let result = self
+            .transcribe_staged(&job_name, &key, &media_format)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to transcribe audio"));
+
+        // the staged audio is only needed while the job runs
+        if let Err(e) = self
+            .send(self.client.delete(self.object_url(&key)), "s3")
+            .await
+        {
+            tracing::warn!(error = %e, key = %key, "Failed to remove staged audio from S3");
+        }
+
+        result
+    }
+
+    async fn transcribe_staged(
+        &self,
+        job_name: &str,
+        key: &str,
+        media_format: &str,
+    ) -> Result<TranscribeResponse, BedrockError> {
+        let transcript_url = self.run_job(job_name, key, media_format).await?;
+
+        // a presigned URL, so the request is not signed
+        let resp = self
+            .client
+            .get(transcript_url)
+            .send()
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let message = resp.text().await.unwrap_or_default();
+            return Err(BedrockError::Api { status, message });
+        }
+
+        Ok(resp.json::<TranscriptDocument>().await?.into())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TranscriptionJobResponse {
+    #[serde(default)]
+    transcription_job: TranscriptionJob,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TranscriptionJob {
+    #[serde(default)]
+    transcription_job_status: String,
+    failure_reason: Option<String>,
+    transcript: Option<JobTranscript>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JobTranscript {
+    transcript_file_uri: Option<String>,
+}
+
+/// The transcript file a completed job writes
+#[derive(Debug, Deserialize)]
+struct TranscriptDocument {
+    results: TranscriptResults,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptResults {
+    transcripts: Vec<TranscriptText>,
+    #[serde(default)]
+    audio_segments: Vec<AudioSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptText {
+    transcript: String,
+}
+
+/// Times are given as strings of seconds, e.g. `"4.53"`
+#[derive(Debug, Deserialize)]
+struct AudioSegment {
+    start_time: String,
+    end_time: String,
+    transcript: String,
+}
+
+impl From<TranscriptDocument> for TranscribeResponse {
+    fn from(document: TranscriptDocument) -> Self {
+        let results = document.results;
+        let segments = results
+            .audio_segments
+            .into_iter()
+            .map(|segment| TranscribeSegment {
+                start: segment.start_time.parse().unwrap_or_default(),
+                end: segment.end_time.parse().unwrap_or_default(),
+                text: segment.transcript,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        TranscribeResponse {
+            duration: segments.last().map(|s| s.end).unwrap_or_default(),
+            text: results
+                .transcripts
+                .into_iter()
+                .map(|t| t.transcript)
+                .collect::<Vec<_>>()
+                .join(" "),
+            segments: Some(segments),
+        }
+    }
+}
+
+/// Job names must be unique and may only contain letters, digits, `.`, `_` and `-`
+fn job_name(file_path: &Path, unix_seconds: u64) -> String {
+    let stem = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("audio")
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '-',
+        })
+        .take(150)
+        .collect::<String>();
+    format!("stream-pulse-{stem}-{unix_seconds}")
+}
+
+impl<F: AudioProcessor + Send + Sync> Transcriber for AmazonTranscriber<F> {
+    const TRANSCRIBER_MODEL: &'static str = "amazon-transcribe";
+
+    type Error = BedrockError;
+
+    /// Transcribe accepts audio of up to four hours and 2 GB, so files are transcribed
+    /// whole in one job, and [`AudioInput::Chunked`] inputs are only split, one job per
+    /// chunk, when they run past that
+    async fn transcribe(&self, input: AudioInput) -> Result<TranscribeResponse, Self::Error> {
+        let response = match input {
+            AudioInput::Chunked {
+                file_path,
+                chunks_dir_path,
+                chunk_duration_seconds,
+                max_chunk_bytes,
+            } if self.exceeds_job_limits(&file_path)? => {
+                tracing::info!(
+                    file = ?file_path,
+                    "Audio exceeds a transcription job's limits, transcribing in chunks"
+                );
+                let chunk_duration_seconds = chunk_duration(
+                    &self.ffmpeg,
+                    &file_path,
+                    chunk_duration_seconds,
+                    max_chunk_bytes,
+                )?;
+                let chunks = prepare_chunks(
+                    &self.ffmpeg,
+                    &file_path,
+                    &chunks_dir_path,
+                    chunk_duration_seconds,
+                )?;
+                // jobs can't be primed with the text before them
+                transcribe_chunks(
+                    &chunks,
+                    chunk_duration_seconds,
+                    None,
+                    0,
+                    |chunk, _| self.transcribe_file(chunk),
+                    |response| response,
+                    &self.events,
+                )
+                .await?
+            }
+            AudioInput::File(file_path) | AudioInput::Chunked { file_path, .. } => {
+                self.transcribe_file(&file_path).await?
+            }
+        };
+        self.usage_tracker
+            .record_transcription(Self::TRANSCRIBER_MODEL, response.duration);
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converse_response_text() {
+        let response: ConverseResponse = serde_json::from_value(serde_json::json!({
+            "output": {
+                "message": {
+                    "role": "assistant",
+                    "content": [{ "text": "# National Assembly" }, { "text": " Sitting" }]
+                }
+            },
+            "stopReason": "end_turn",
+            "usage": { "inputTokens": 120, "outputTokens": 8, "totalTokens": 128 }
+        }))
+        .unwrap();
+
+        assert_eq!(response.text(), "# National Assembly Sitting");
+        assert_eq!(response.usage.unwrap().output_tokens, 8);
+    }
+
+    #[test]
+    fn test_transcript_document_keeps_segments() {
+        let document: TranscriptDocument = serde_json::from_value(serde_json::json!({
+            "jobName": "stream-pulse-abc-1",
+            "results": {
+                "transcripts": [{ "transcript": "Order, order. The House is adjourned." }],
+                "items": [],
+                "audio_segments": [
+                    { "id": 0, "start_time": "0.0", "end_time": "4.5", "transcript": "Order, order.", "items": [0, 1] },
+                    { "id": 1, "start_time": "61.25", "end_time": "63.0", "transcript": "The House is adjourned.", "items": [2] }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let response = TranscribeResponse::from(document);
+        assert_eq!(response.text, "Order, order. The House is adjourned.");
+        assert_eq!(response.duration, 63.0);
+        let segments = response.segments.unwrap();
+        assert_eq!(segments[1].start, 61.25);
+        assert_eq!(segments[1].text, "The House is adjourned.");
+    }
+
+    #[test]
+    fn test_job_name_is_sanitised() {
+        assert_eq!(
+            job_name(Path::new("/tmp/Bunge Sitting (am).mp3"), 1),
+            "stream-pulse-Bunge-Sitting--am--1"
+        );
+    }
+}
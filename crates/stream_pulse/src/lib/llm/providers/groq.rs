@@ -0,0 +1,398 @@
+use std::{path::Path, time::Duration};
+
+use reqwest::header::HeaderMap;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use ytdlp_bindings::AudioProcessor;
+
+use crate::{
+    llm::{
+        glossary::Glossary,
+        retry::retrying_client,
+        transcriber::{
+            chunk_duration, prepare_chunks, transcribe_chunks, within_chunk_timeout, ChunkFailure,
+            ChunkingError, SegmentFilter, TranscribeResponse, TranscribeSegment,
+            TranscriptionOptions,
+        },
+        usage::UsageTracker,
+    },
+    AudioInput, ProcessorEvents, Transcriber,
+};
+
+/// Transcriber backed by Groq's OpenAI-compatible audio transcription endpoint.
+#[derive(Debug, Clone)]
+pub struct GroqTranscriber<F: AudioProcessor> {
+    client: ClientWithMiddleware,
+    api_key: String,
+    ffmpeg: F,
+    base_url: String,
+    transcriber_model: Option<String>,
+    max_file_size_bytes: u64,
+    glossary: Option<Glossary>,
+    context_words: usize,
+    segment_filter: Option<SegmentFilter>,
+    chunk_timeout: Option<Duration>,
+    usage_tracker: UsageTracker,
+    events: ProcessorEvents,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GroqError {
+    #[error("HTTP error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("HTTP middleare error: {0}")]
+    RequestMiddleware(#[from] reqwest_middleware::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("API error: {status} - {message}")]
+    Api { status: u16, message: String },
+    #[error("FFmpeg error: {0}")]
+    Ffmpeg(String),
+    #[error("Audio chunk {path} is {size} bytes, exceeding the {limit} byte upload limit")]
+    FileTooLarge { path: String, size: u64, limit: u64 },
+    #[error("Unsupported input: Groq transcriber only supports chunked input")]
+    UnsupportedInput,
+    #[error("Transcription timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("Chunk {chunk} of {chunks} failed to transcribe: {source}")]
+    ChunkFailed {
+        chunk: usize,
+        chunks: usize,
+        source: Box<GroqError>,
+    },
+}
+
+impl From<ChunkFailure<GroqError>> for GroqError {
+    fn from(failure: ChunkFailure<GroqError>) -> Self {
+        GroqError::ChunkFailed {
+            chunk: failure.chunk,
+            chunks: failure.chunks,
+            source: Box::new(failure.error),
+        }
+    }
+}
+
+impl From<ChunkingError> for GroqError {
+    fn from(err: ChunkingError) -> Self {
+        match err {
+            ChunkingError::Io(e) => GroqError::Io(e),
+            ChunkingError::Ffmpeg(msg) => GroqError::Ffmpeg(msg),
+        }
+    }
+}
+
+/// Groq's verbose_json response does not always include `duration`
+#[derive(Debug, Deserialize)]
+struct GroqTranscribeResponse {
+    #[serde(default)]
+    duration: Option<f64>,
+    text: String,
+    segments: Option<Vec<TranscribeSegment>>,
+}
+
+impl From<GroqTranscribeResponse> for TranscribeResponse {
+    fn from(resp: GroqTranscribeResponse) -> Self {
+        let duration = resp.duration.unwrap_or_else(|| {
+            resp.segments
+                .as_ref()
+                .and_then(|segments| segments.last())
+                .map(|seg| seg.end)
+                .unwrap_or_default()
+        });
+        TranscribeResponse {
+            duration,
+            text: resp.text,
+            segments: resp.segments,
+        }
+    }
+}
+
+impl<F: AudioProcessor> GroqTranscriber<F> {
+    /// Upload limit for the free tier. Paid tiers allow larger files.
+    const DEFAULT_MAX_FILE_SIZE_BYTES: u64 = 25 * 1024 * 1024;
+
+    pub fn new(api_key: impl Into<String>, ffmpeg: F) -> Self {
+        Self {
+            client: retrying_client(reqwest::Client::new()),
+            api_key: api_key.into(),
+            ffmpeg,
+            base_url: "https://api.groq.com/openai/v1".into(),
+            transcriber_model: None,
+            max_file_size_bytes: Self::DEFAULT_MAX_FILE_SIZE_BYTES,
+            glossary: None,
+            context_words: TranscriptionOptions::DEFAULT_CONTEXT_WORDS,
+            segment_filter: None,
+            chunk_timeout: None,
+            usage_tracker: UsageTracker::default(),
+            events: ProcessorEvents::default(),
+        }
+    }
+
+    /// Send requests through `client`, e.g. one shared with the rest of the run for its
+    /// proxy and timeout settings
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = retrying_client(client);
+        self
+    }
+
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Override the model used for transcription requests, which otherwise
+    /// defaults to [`Transcriber::TRANSCRIBER_MODEL`]
+    pub fn with_transcriber_model(mut self, model: impl Into<String>) -> Self {
+        self.transcriber_model = Some(model.into());
+        self
+    }
+
+    /// Override the per-file upload limit, e.g. for accounts on the dev tier
+    pub fn with_max_file_size_bytes(mut self, max_file_size_bytes: u64) -> Self {
+        self.max_file_size_bytes = max_file_size_bytes;
+        self
+    }
+
+    /// Prime the transcription of the first chunk with the names and terms in `glossary`
+    pub fn with_glossary(mut self, glossary: Glossary) -> Self {
+        self.glossary = Some(glossary);
+        self
+    }
+
+    /// Prime each chunk with up to `context_words` words from the end of the previous
+    /// chunk's transcript, 0 to disable
+    pub fn with_context_words(mut self, context_words: usize) -> Self {
+        self.context_words = context_words;
+        self
+    }
+
+    /// Drop segments that `segment_filter` takes to be hallucinations
+    pub fn with_segment_filter(mut self, segment_filter: SegmentFilter) -> Self {
+        self.segment_filter = Some(segment_filter);
+        self
+    }
+
+    /// Fail the transcription of a chunk that takes longer than `timeout`, retries
+    /// included
+    pub fn with_chunk_timeout(mut self, timeout: Duration) -> Self {
+        self.chunk_timeout = Some(timeout);
+        self
+    }
+
+    /// Record transcribed audio duration into `usage_tracker`
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = usage_tracker;
+        self
+    }
+
+    /// Report each transcribed chunk to `events`
+    pub fn with_events(mut self, events: ProcessorEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub async fn send_transcribe_request(
+        &self,
+        file: &Path,
+        model_name: impl Into<String>,
+        prompt: Option<String>,
+    ) -> Result<TranscribeResponse, GroqError> {
+        let size = tokio::fs::metadata(file).await?.len();
+        if size > self.max_file_size_bytes {
+            return Err(GroqError::FileTooLarge {
+                path: file.display().to_string(),
+                size,
+                limit: self.max_file_size_bytes,
+            });
+        }
+
+        let bytes = tokio::fs::read(file).await?;
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name("chunk.mp3")
+            .mime_str("audio/mpeg")
+            .unwrap();
+
+        let model_name = model_name.into();
+        let mut form = reqwest::multipart::Form::new()
+            .text("model", model_name.clone())
+            .text("response_format", "verbose_json")
+            .text("timestamp_granularities[]", "segment")
+            .part("file", part);
+
+        if let Some(prompt) = prompt {
+            form = form.text("prompt", prompt);
+        }
+
+        let resp = self
+            .client
+            .post(format!("{}/audio/transcriptions", self.base_url))
+            .bearer_auth(&self.api_key)
+            .multipart(form)
+            .send()
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+
+        if let Some(wait) = rate_limit_wait(resp.headers()) {
+            tracing::warn!(wait = ?wait, "Groq request quota exhausted, pausing until reset");
+            tokio::time::sleep(wait).await;
+        }
+
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let message = resp.text().await.unwrap_or_default();
+            return Err(GroqError::Api { status, message });
+        }
+
+        let response: TranscribeResponse = resp.json::<GroqTranscribeResponse>().await?.into();
+        self.usage_tracker
+            .record_transcription(&model_name, response.duration);
+
+        Ok(response)
+    }
+}
+
+/// Returns how long to wait before the next request when Groq reports that the
+/// request quota for the current window is exhausted.
+fn rate_limit_wait(headers: &HeaderMap) -> Option<Duration> {
+    let remaining = headers
+        .get("x-ratelimit-remaining-requests")?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()?;
+
+    if remaining > 0 {
+        return None;
+    }
+
+    headers
+        .get("x-ratelimit-reset-requests")
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_reset_duration)
+}
+
+/// Parses Groq's reset header format, e.g. `"2m59.56s"`, `"7.66s"` or `"1h2m3s"`
+fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut total = 0.0_f64;
+    let mut number = String::new();
+
+    for c in value.trim().chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'h' | 'm' | 's' => {
+                let amount = number.parse::<f64>().ok()?;
+                number.clear();
+                total += match c {
+                    'h' => amount * 3600.0,
+                    'm' => amount * 60.0,
+                    _ => amount,
+                };
+            }
+            _ => return None,
+        }
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+
+    Some(Duration::from_secs_f64(total))
+}
+
+impl<F: AudioProcessor + Send + Sync> Transcriber for GroqTranscriber<F> {
+    const TRANSCRIBER_MODEL: &'static str = "whisper-large-v3-turbo";
+
+    type Error = GroqError;
+
+    async fn transcribe(&self, input: AudioInput) -> Result<TranscribeResponse, Self::Error> {
+        let AudioInput::Chunked {
+            file_path,
+            chunks_dir_path,
+            chunk_duration_seconds,
+            max_chunk_bytes,
+        } = input
+        else {
+            tracing::error!(audio_input = ?input, "Unspoorted audio_input");
+            return Err(GroqError::UnsupportedInput);
+        };
+
+        let chunk_duration_seconds = chunk_duration(
+            &self.ffmpeg,
+            &file_path,
+            chunk_duration_seconds,
+            max_chunk_bytes,
+        )?;
+        let chunks = prepare_chunks(
+            &self.ffmpeg,
+            &file_path,
+            &chunks_dir_path,
+            chunk_duration_seconds,
+        )?;
+
+        let model = self
+            .transcriber_model
+            .as_deref()
+            .unwrap_or(Self::TRANSCRIBER_MODEL);
+
+        Ok(transcribe_chunks(
+            &chunks,
+            chunk_duration_seconds,
+            self.glossary.as_ref().and_then(Glossary::prompt),
+            self.context_words,
+            |chunk, prompt| {
+                within_chunk_timeout(
+                    self.chunk_timeout,
+                    self.send_transcribe_request(chunk, model, prompt),
+                    GroqError::Timeout,
+                )
+            },
+            |response| match &self.segment_filter {
+                Some(filter) => filter.apply(response),
+                None => response,
+            },
+            &self.events,
+        )
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_parse_reset_duration() {
+        assert_eq!(
+            parse_reset_duration("7.66s"),
+            Some(Duration::from_secs_f64(7.66))
+        );
+        assert_eq!(
+            parse_reset_duration("2m30.5s"),
+            Some(Duration::from_secs_f64(150.5))
+        );
+        assert_eq!(
+            parse_reset_duration("1h2m3s"),
+            Some(Duration::from_secs(3723))
+        );
+        assert_eq!(parse_reset_duration("12"), None);
+        assert_eq!(parse_reset_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_rate_limit_wait_only_when_exhausted() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-ratelimit-remaining-requests",
+            HeaderValue::from_static("3"),
+        );
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("2s"));
+        assert_eq!(rate_limit_wait(&headers), None);
+
+        headers.insert(
+            "x-ratelimit-remaining-requests",
+            HeaderValue::from_static("0"),
+        );
+        assert_eq!(rate_limit_wait(&headers), Some(Duration::from_secs(2)));
+    }
+}
@@ -1,2 +1,7 @@
+pub mod anthropic;
+#[cfg(feature = "bedrock")]
+pub mod bedrock;
+pub mod groq;
 pub mod openai;
+pub mod openrouter;
 pub mod whisper_cpp;
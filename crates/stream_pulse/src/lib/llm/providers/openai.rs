@@ -1,23 +1,98 @@
-use std::path::PathBuf;
+#[cfg(feature = "tiktoken")]
+use std::sync::LazyLock;
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use another_tiktoken_rs::cl100k_base;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
-use reqwest_retry_after::RetryAfterMiddleware;
+#[cfg(feature = "tiktoken")]
+use another_tiktoken_rs::{cl100k_base, CoreBPE};
+use futures::{stream, stream::BoxStream, Stream, StreamExt, TryStreamExt};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder};
 use serde::Deserialize;
+use stream_datastore::{Division, StreamCategory, StreamEntities, StructuredSummary};
 use ytdlp_bindings::AudioProcessor;
 
 use crate::{
-    llm::{summarizer::SummaryResponse, transcriber::TranscribeResponse},
-    AudioInput, Summarizer, Transcriber,
+    llm::{
+        classifier::{parse_category, CategoryClassifier, CLASSIFICATION_PROMPT},
+        divisions::{division_system_prompt, parse_divisions, DivisionExtractor},
+        embedder::Embedder,
+        entities::{entity_system_prompt, parse_entities, EntityExtractor},
+        prompt::PromptTemplate,
+        rate_limit::RateLimiter,
+        retry::{idempotency_key, send_with_retry_via, RetryPolicy, IDEMPOTENCY_KEY_HEADER},
+        sse::SseParser,
+        summarizer::{CompletionOptions, SummaryContext, SummaryResponse, WebSearchOptions},
+        transcriber::{
+            chunk_duration, prepare_chunks, transcribe_chunks, within_chunk_timeout, ChunkFailure,
+            ChunkingError, TranscribeResponse, TranscriptionOptions, TranscriptionResponseFormat,
+        },
+        transport::Transport,
+        usage::{CompletionUsage, UsageTracker},
+        verification::{
+            parse_verdict, verification_content, verification_system_prompt, SummaryVerdict,
+            SummaryVerifier,
+        },
+    },
+    AudioInput, ProcessorEvents, Summarizer, Transcriber,
 };
 
+/// Dimensions of the `vector` columns embeddings are stored in
+const EMBEDDING_DIMENSIONS: usize = 1536;
+
+/// Largest audio file the transcriptions API accepts
+const MAX_UPLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Chunk length for [`AudioInput::File`] inputs over [`MAX_UPLOAD_BYTES`]. Chunks are
+/// re-encoded as 16kHz mono mp3, so this stays well under the limit.
+const FILE_CHUNK_DURATION_SECONDS: u16 = 900;
+
+/// Loading the BPE ranks is expensive, so the tokenizer is built once and shared
+#[cfg(feature = "tiktoken")]
+static CL100K: LazyLock<Result<CoreBPE, String>> =
+    LazyLock::new(|| cl100k_base().map_err(|e| e.to_string()));
+
+/// How request URLs are built and authenticated
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum OpenAIEndpoint {
+    /// `{base_url}/{operation}` with bearer auth
+    #[default]
+    OpenAI,
+    /// `{base_url}/openai/deployments/{deployment}/{operation}?api-version=...` with `api-key`
+    /// header auth. The configured model names are used as deployment names.
+    Azure { api_version: String },
+}
+
+impl OpenAIEndpoint {
+    pub const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
+}
+
 #[derive(Debug, Clone)]
 pub struct OpenAIClient<F: AudioProcessor> {
     client: ClientWithMiddleware,
+    /// Sends the requests built with `client`
+    transport: Arc<dyn Transport>,
     api_key: String,
     ffmpeg: F,
     base_url: String,
+    endpoint: OpenAIEndpoint,
+    transcription_options: TranscriptionOptions,
+    summarizer_model: Option<String>,
+    extraction_model: Option<String>,
+    verification_model: Option<String>,
+    embedding_model: Option<String>,
+    completion_options: CompletionOptions,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    structured_output: bool,
+    tldr: bool,
+    streaming: bool,
+    idempotency_keys: bool,
+    system_prompt: PromptTemplate,
+    usage_tracker: UsageTracker,
+    events: ProcessorEvents,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -30,31 +105,218 @@ pub enum OpenAIError {
     Io(#[from] std::io::Error),
     #[error("API error: {status} - {message}")]
     Api { status: u16, message: String },
+    #[error("Malformed {0}")]
+    MalformedResponse(String),
     #[error("FFmpeg error: {0}")]
     Ffmpeg(String),
-    #[error("Unsupported input: OpenAI transcriber only supports chunked input")]
-    UnsupportedInput,
+    #[error("Transcription timed out after {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("Chunk {chunk} of {chunks} failed to transcribe: {source}")]
+    ChunkFailed {
+        chunk: usize,
+        chunks: usize,
+        source: Box<OpenAIError>,
+    },
 }
 
-impl<F: AudioProcessor> OpenAIClient<F> {
-    const SYSTEM_PROMPT: &str = include_str!("../prompts/system_0.txt");
+impl From<ChunkFailure<OpenAIError>> for OpenAIError {
+    fn from(failure: ChunkFailure<OpenAIError>) -> Self {
+        OpenAIError::ChunkFailed {
+            chunk: failure.chunk,
+            chunks: failure.chunks,
+            source: Box::new(failure.error),
+        }
+    }
+}
+
+impl From<ChunkingError> for OpenAIError {
+    fn from(err: ChunkingError) -> Self {
+        match err {
+            ChunkingError::Io(e) => OpenAIError::Io(e),
+            ChunkingError::Ffmpeg(msg) => OpenAIError::Ffmpeg(msg),
+        }
+    }
+}
 
+impl<F: AudioProcessor> OpenAIClient<F> {
     pub fn new(api_key: impl Into<String>, ffmpeg: F) -> Self {
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
-        let client = ClientBuilder::new(reqwest::Client::new())
-            .with(RetryAfterMiddleware::new())
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
+        // retries are handled by `send_with_retry_via`, since multipart requests cannot be
+        // cloned by retry middleware
+        let client = ClientBuilder::new(reqwest::Client::new()).build();
         Self {
+            transport: Arc::new(client.clone()),
             client,
             api_key: api_key.into(),
             base_url: "https://api.openai.com/v1".into(),
+            endpoint: OpenAIEndpoint::OpenAI,
             ffmpeg,
+            transcription_options: TranscriptionOptions::default(),
+            summarizer_model: None,
+            extraction_model: None,
+            verification_model: None,
+            embedding_model: None,
+            completion_options: CompletionOptions::default(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+            structured_output: false,
+            tldr: false,
+            streaming: false,
+            idempotency_keys: false,
+            system_prompt: PromptTemplate::default(),
+            usage_tracker: UsageTracker::default(),
+            events: ProcessorEvents::default(),
         }
     }
 
+    /// Client for an Azure OpenAI resource, e.g. `https://<resource>.openai.azure.com`.
+    /// Set the deployment names with [`Self::with_transcriber_model`] and
+    /// [`Self::with_summarizer_model`].
+    pub fn azure(
+        api_key: impl Into<String>,
+        resource_url: impl Into<String>,
+        api_version: impl Into<String>,
+        ffmpeg: F,
+    ) -> Self {
+        Self::new(api_key, ffmpeg)
+            .with_base_url(resource_url)
+            .with_endpoint(OpenAIEndpoint::Azure {
+                api_version: api_version.into(),
+            })
+    }
+
     pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
-        self.base_url = url.into();
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Send requests through `client`, e.g. one shared with the rest of the run for its
+    /// proxy and timeout settings. Replaces the transport, so call it before
+    /// `with_cassette`
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = ClientBuilder::new(client).build();
+        self.transport = Arc::new(self.client.clone());
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: OpenAIEndpoint) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Override the model used for transcription requests, which otherwise
+    /// defaults to [`Transcriber::TRANSCRIBER_MODEL`]
+    pub fn with_transcriber_model(mut self, model: impl Into<String>) -> Self {
+        self.transcription_options.model = Some(model.into());
+        self
+    }
+
+    /// Configure the model, language hint, temperature and response format of
+    /// transcription requests
+    pub fn with_transcription_options(mut self, options: TranscriptionOptions) -> Self {
+        self.transcription_options = options;
+        self
+    }
+
+    /// Override the model used for summarization, which otherwise
+    /// defaults to [`Summarizer::SUMMARIZER_MODEL`]
+    pub fn with_summarizer_model(mut self, model: impl Into<String>) -> Self {
+        self.summarizer_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for entity and division extraction and title classification,
+    /// which otherwise defaults to [`EntityExtractor::EXTRACTION_MODEL`],
+    /// [`DivisionExtractor::DIVISION_EXTRACTION_MODEL`] and
+    /// [`CategoryClassifier::CLASSIFICATION_MODEL`]
+    pub fn with_extraction_model(mut self, model: impl Into<String>) -> Self {
+        self.extraction_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for summary verification, which otherwise
+    /// defaults to [`SummaryVerifier::VERIFICATION_MODEL`]
+    pub fn with_verification_model(mut self, model: impl Into<String>) -> Self {
+        self.verification_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for embeddings, which otherwise
+    /// defaults to [`Embedder::EMBEDDING_MODEL`]
+    pub fn with_embedding_model(mut self, model: impl Into<String>) -> Self {
+        self.embedding_model = Some(model.into());
+        self
+    }
+
+    /// Configure web search and sampling for completions. Search is enabled by default for
+    /// the search-preview default model, and must be disabled for other models
+    pub fn with_completion_options(mut self, options: CompletionOptions) -> Self {
+        self.completion_options = options;
+        self
+    }
+
+    /// Record token and audio usage into `usage_tracker`
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = usage_tracker;
+        self
+    }
+
+    /// Report each transcribed chunk to `events`
+    pub fn with_events(mut self, events: ProcessorEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Receive summaries over a streamed completion instead of a single response
+    pub fn with_streaming(mut self, enabled: bool) -> Self {
+        self.streaming = enabled;
+        self
+    }
+
+    /// Send an `Idempotency-Key` derived from the request body with completion requests.
+    /// Retries, and requests resent when a stream is processed again, reuse the same key,
+    /// so the API can answer them without billing or generating a second completion.
+    pub fn with_idempotency_keys(mut self, enabled: bool) -> Self {
+        self.idempotency_keys = enabled;
+        self
+    }
+
+    /// Replace the bundled system prompt used for summarization
+    pub fn with_system_prompt(mut self, system_prompt: PromptTemplate) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Record requests to, or replay them from, `cassette` instead of only sending them
+    #[cfg(feature = "cassette")]
+    pub fn with_cassette(mut self, cassette: crate::llm::cassette::Cassette) -> Self {
+        self.transport = Arc::new(cassette.transport(self.transport));
+        self
+    }
+
+    /// Configure how 429 and 5xx responses are retried
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Throttle requests through `rate_limiter`. Pass clones of the same limiter to
+    /// every client that shares an organisation-level quota.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Request summaries as JSON matching [`structured_summary_schema`], so that
+    /// [`SummaryResponse::structured`] is populated alongside the markdown summary
+    pub fn with_structured_output(mut self, enabled: bool) -> Self {
+        self.structured_output = enabled;
+        self
+    }
+
+    /// Also request a one-paragraph TL;DR, populating [`SummaryResponse::tldr`]. Like
+    /// structured output, this makes the response JSON rather than plain markdown.
+    pub fn with_tldr(mut self, enabled: bool) -> Self {
+        self.tldr = enabled;
         self
     }
 
@@ -67,29 +329,54 @@ impl<F: AudioProcessor> OpenAIClient<F> {
         let audio_path = file.into();
 
         let bytes = tokio::fs::read(&audio_path).await?;
-        let part = reqwest::multipart::Part::bytes(bytes)
-            .file_name("chunk.mp3")
-            .mime_str("audio/mpeg")
-            .unwrap();
-
-        let mut form = reqwest::multipart::Form::new()
-            .text("model", model_name.into())
-            .text("response_format", "verbose_json")
-            .text("timestamp_granularities[]", "segment")
-            .part("file", part);
-
-        if let Some(prompt) = prompt {
-            form = form.text("prompt", prompt);
-        }
+        let model_name = model_name.into();
+        let options = &self.transcription_options;
+        let file_name = audio_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("chunk.mp3")
+            .to_string();
+        let mime_type = audio_mime_type(&audio_path);
 
-        let resp = self
-            .client
-            .post(format!("{}/audio/transcriptions", self.base_url))
-            .bearer_auth(&self.api_key)
-            .multipart(form)
-            .send()
-            .await
-            .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+        // the multipart form is consumed on send, so it is rebuilt for every attempt
+        let build_form = || {
+            let part = reqwest::multipart::Part::bytes(bytes.clone())
+                .file_name(file_name.clone())
+                .mime_str(mime_type)
+                .unwrap();
+
+            let mut form = reqwest::multipart::Form::new()
+                .text("model", model_name.clone())
+                .text("response_format", options.response_format.as_str())
+                .part("file", part);
+
+            if options.response_format == TranscriptionResponseFormat::VerboseJson {
+                form = form.text("timestamp_granularities[]", "segment");
+            }
+            if let Some(language) = &options.language {
+                form = form.text("language", language.clone());
+            }
+            if let Some(temperature) = options.temperature {
+                form = form.text("temperature", temperature.to_string());
+            }
+            if let Some(prompt) = &prompt {
+                form = form.text("prompt", prompt.clone());
+            }
+            form
+        };
+
+        let resp = send_with_retry_via(
+            &self.retry_policy,
+            self.transport.as_ref(),
+            &self.rate_limiter,
+            0,
+            || {
+                self.post("audio/transcriptions", &model_name)
+                    .multipart(build_form())
+            },
+        )
+        .await
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
 
         if !resp.status().is_success() {
             let status = resp.status().as_u16();
@@ -97,7 +384,20 @@ impl<F: AudioProcessor> OpenAIClient<F> {
             return Err(OpenAIError::Api { status, message });
         }
 
-        let response = resp.json::<TranscribeResponse>().await?;
+        let response = match options.response_format {
+            TranscriptionResponseFormat::VerboseJson => resp.json::<TranscribeResponse>().await?,
+            // XXX: no duration is reported, so chunk offsets fall back to the chunk length
+            TranscriptionResponseFormat::Json => {
+                let text = resp.json::<TextTranscribeResponse>().await?.text;
+                TranscribeResponse {
+                    duration: 0.0,
+                    text,
+                    segments: None,
+                }
+            }
+        };
+        self.usage_tracker
+            .record_transcription(&model_name, response.duration);
 
         Ok(response)
     }
@@ -105,42 +405,300 @@ impl<F: AudioProcessor> OpenAIClient<F> {
     pub async fn send_completion_request(
         &self,
         model_name: impl Into<String>,
+        system_prompt: &str,
         user_content: impl Into<String>,
     ) -> Result<CompletionResponse, OpenAIError> {
-        let body = serde_json::json!({
-            "model": model_name.into(),
-            // XXX: for best accuracy, web search will always be performed
-            "web_search_options": {
-                "search_context_size": "medium",
-                "user_location": {
-                    "type": "approximate",
-                    "approximate": {
-                        "country": "KE",
-                        "city": "Nairobi",
-                        "region": "Nairobi"
+        let model_name = model_name.into();
+        let body = self.completion_body(model_name.clone(), system_prompt, user_content.into());
+        let resp = self.post_completion(&body).await?;
+
+        let response = resp.json::<CompletionResponse>().await?;
+        if let Some(usage) = response.usage {
+            self.usage_tracker.record_completion(&model_name, usage);
+        }
+
+        Ok(response)
+    }
+
+    /// Streaming variant of [`Self::send_completion_request`], yielding content deltas as
+    /// they arrive. Long completions are delivered incrementally rather than behind a
+    /// single response that proxies may time out.
+    pub async fn send_completion_stream(
+        &self,
+        model_name: impl Into<String>,
+        system_prompt: &str,
+        user_content: impl Into<String>,
+    ) -> Result<impl Stream<Item = Result<String, OpenAIError>> + Send, OpenAIError> {
+        let model_name = model_name.into();
+        let mut body = self.completion_body(model_name.clone(), system_prompt, user_content.into());
+        body["stream"] = serde_json::Value::Bool(true);
+        // usage is reported in a final chunk with no choices
+        body["stream_options"] = serde_json::json!({ "include_usage": true });
+
+        let resp = self.post_completion(&body).await?;
+
+        let state = CompletionStream {
+            body: resp.bytes_stream().boxed(),
+            parser: SseParser::default(),
+            pending: VecDeque::new(),
+            done: false,
+            model: model_name,
+            usage_tracker: self.usage_tracker.clone(),
+        };
+        let deltas = stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some(delta) = state.pending.pop_front() {
+                    return Ok::<_, OpenAIError>(Some((delta, state)));
+                }
+                if state.done {
+                    return Ok(None);
+                }
+
+                let Some(bytes) = state.body.next().await.transpose()? else {
+                    return Ok(None);
+                };
+
+                for event in state.parser.push(&bytes) {
+                    if event == "[DONE]" {
+                        state.done = true;
+                        break;
                     }
+                    let chunk = serde_json::from_str::<CompletionChunk>(&event).map_err(|e| {
+                        OpenAIError::MalformedResponse(format!("completion chunk: {e}"))
+                    })?;
+                    if let Some(usage) = chunk.usage {
+                        state.usage_tracker.record_completion(&state.model, usage);
+                    }
+                    state.pending.extend(
+                        chunk
+                            .choices
+                            .into_iter()
+                            .filter_map(|choice| choice.delta.content)
+                            .filter(|content| !content.is_empty()),
+                    );
                 }
-            },
+            }
+        });
+
+        Ok(deltas)
+    }
+
+    /// Completion constrained to the JSON `schema`, returning the raw message content.
+    /// Web search is left out, since only the search-preview models accept it.
+    async fn send_structured_request(
+        &self,
+        model_name: &str,
+        system_prompt: &str,
+        user_content: &str,
+        schema_name: &str,
+        schema: serde_json::Value,
+    ) -> Result<String, OpenAIError> {
+        let mut body =
+            self.completion_body(model_name.to_string(), system_prompt, user_content.into());
+        if let Some(body) = body.as_object_mut() {
+            body.remove("web_search_options");
+        }
+        body["response_format"] = serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": schema_name,
+                "strict": true,
+                "schema": schema
+            }
+        });
+
+        let resp = self.post_completion(&body).await?;
+        let response = resp.json::<CompletionResponse>().await?;
+        if let Some(usage) = response.usage {
+            self.usage_tracker.record_completion(model_name, usage);
+        }
+
+        Ok(response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .unwrap_or_default())
+    }
+
+    pub async fn send_embedding_request(
+        &self,
+        model_name: impl Into<String>,
+        inputs: &[String],
+    ) -> Result<EmbeddingResponse, OpenAIError> {
+        let model_name = model_name.into();
+        let mut body = serde_json::json!({
+            "model": model_name,
+            "input": inputs,
+            "encoding_format": "float"
+        });
+        // the text-embedding-3 models can be shortened to fit the `vector(1536)` columns
+        if model_name.starts_with("text-embedding-3") {
+            body["dimensions"] = serde_json::json!(EMBEDDING_DIMENSIONS);
+        }
+
+        let mut tokens = 0;
+        if self.rate_limiter.limits_tokens() {
+            for input in inputs {
+                tokens += count_cl100k_tokens(input)?;
+            }
+        }
+
+        let resp = send_with_retry_via(
+            &self.retry_policy,
+            self.transport.as_ref(),
+            &self.rate_limiter,
+            tokens,
+            || self.post("embeddings", &model_name).json(&body),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let message = resp.text().await.unwrap_or_default();
+            return Err(OpenAIError::Api { status, message });
+        }
+
+        let mut response = resp.json::<EmbeddingResponse>().await?;
+        if let Some(usage) = response.usage {
+            self.usage_tracker.record_completion(
+                &model_name,
+                CompletionUsage {
+                    prompt_tokens: usage.prompt_tokens,
+                    completion_tokens: 0,
+                },
+            );
+        }
+        response.data.sort_by_key(|embedding| embedding.index);
+
+        Ok(response)
+    }
+
+    fn filter_segments(&self, response: TranscribeResponse) -> TranscribeResponse {
+        match &self.transcription_options.segment_filter {
+            Some(filter) => filter.apply(response),
+            None => response,
+        }
+    }
+
+    /// Builds an authenticated POST request for `operation`, e.g. `chat/completions`
+    fn post(&self, operation: &str, model_name: &str) -> RequestBuilder {
+        match &self.endpoint {
+            OpenAIEndpoint::OpenAI => self
+                .client
+                .post(format!("{}/{operation}", self.base_url))
+                .bearer_auth(&self.api_key),
+            OpenAIEndpoint::Azure { api_version } => self
+                .client
+                .post(format!(
+                    "{}/openai/deployments/{model_name}/{operation}?api-version={api_version}",
+                    self.base_url
+                ))
+                .header("api-key", &self.api_key),
+        }
+    }
+
+    fn completion_body(
+        &self,
+        model_name: String,
+        system_prompt: &str,
+        user_content: String,
+    ) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "model": model_name,
             "messages": [
                 {
                     "role": "system",
-                    "content": Self::SYSTEM_PROMPT
+                    "content": system_prompt
                 },
                 {
                     "role": "user",
-                    "content": user_content.into()
+                    "content": user_content
                 }
             ]
         });
 
-        let resp = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
-            .await
-            .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+        // Azure does not offer the search-preview models
+        let web_search = match self.endpoint {
+            OpenAIEndpoint::OpenAI => self.completion_options.web_search.as_ref(),
+            OpenAIEndpoint::Azure { .. } => None,
+        };
+        if let Some(web_search) = web_search {
+            body["web_search_options"] = web_search_options(web_search);
+        }
+        self.completion_options.apply_sampling(&mut body);
+
+        if let Some(schema) = self.summary_schema() {
+            body["response_format"] = serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "structured_summary",
+                    "strict": true,
+                    "schema": schema
+                }
+            });
+        }
+
+        body
+    }
+
+    /// Schema for summaries in structured output and/or TL;DR mode, `None` when
+    /// summaries are plain markdown
+    fn summary_schema(&self) -> Option<serde_json::Value> {
+        if !self.structured_output && !self.tldr {
+            return None;
+        }
+
+        let mut schema = match self.structured_output {
+            true => structured_summary_schema(),
+            false => object(serde_json::json!({ "summary_md": { "type": "string" } })),
+        };
+        if self.tldr {
+            schema["properties"]["tldr"] = serde_json::json!({
+                "type": "string",
+                "description": "A single plain-text paragraph of at most 80 words summarising \
+                                the sitting for social media, without markdown"
+            });
+            if let Some(required) = schema["required"].as_array_mut() {
+                required.push("tldr".into());
+            }
+        }
+
+        Some(schema)
+    }
+
+    /// Posts `body` to the chat completions endpoint, each attempt waiting for rate limit
+    /// budget
+    async fn post_completion(
+        &self,
+        body: &serde_json::Value,
+    ) -> Result<reqwest::Response, OpenAIError> {
+        let mut tokens = 0;
+        if self.rate_limiter.limits_tokens() {
+            for message in body["messages"].as_array().into_iter().flatten() {
+                tokens += count_cl100k_tokens(message["content"].as_str().unwrap_or_default())?;
+            }
+        }
+
+        let model_name = body["model"].as_str().unwrap_or_default();
+        // computed once, so that every attempt is sent with the same key
+        let idempotency_key = self.idempotency_keys.then(|| idempotency_key(body));
+        let resp = send_with_retry_via(
+            &self.retry_policy,
+            self.transport.as_ref(),
+            &self.rate_limiter,
+            tokens,
+            || {
+                let request = self.post("chat/completions", model_name).json(body);
+                match &idempotency_key {
+                    Some(key) => request.header(IDEMPOTENCY_KEY_HEADER, key),
+                    None => request,
+                }
+            },
+        )
+        .await
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
 
         if !resp.status().is_success() {
             let status = resp.status().as_u16();
@@ -148,7 +706,145 @@ impl<F: AudioProcessor> OpenAIClient<F> {
             return Err(OpenAIError::Api { status, message });
         }
 
-        Ok(resp.json::<CompletionResponse>().await?)
+        Ok(resp)
+    }
+}
+
+/// JSON schema for structured summaries: the usual markdown summary under `summary_md`,
+/// plus the fields of [`StructuredSummary`]. Strict mode requires every property to be
+/// listed as required, so optional values are nullable instead.
+pub fn structured_summary_schema() -> serde_json::Value {
+    let string = serde_json::json!({ "type": "string" });
+    let nullable_string = serde_json::json!({ "type": ["string", "null"] });
+    let nullable_count = serde_json::json!({ "type": ["integer", "null"], "minimum": 0 });
+
+    object(serde_json::json!({
+        "summary_md": string,
+        "bills": array_of(object(serde_json::json!({
+            "name": string,
+            "number": nullable_string,
+            "stage": nullable_string,
+            "summary": string
+        }))),
+        "motions": array_of(object(serde_json::json!({
+            "title": string,
+            "mover": nullable_string,
+            "outcome": nullable_string
+        }))),
+        "votes": array_of(object(serde_json::json!({
+            "subject": string,
+            "result": string,
+            "ayes": nullable_count,
+            "noes": nullable_count,
+            "abstentions": nullable_count
+        }))),
+        "key_speakers": array_of(object(serde_json::json!({
+            "name": string,
+            "role": nullable_string,
+            "summary": string
+        }))),
+        "action_items": array_of(string)
+    }))
+}
+
+/// JSON schema for [`StreamEntities`], in the same strict form as [`structured_summary_schema`]
+pub fn stream_entities_schema() -> serde_json::Value {
+    let string = serde_json::json!({ "type": "string" });
+    let nullable_string = serde_json::json!({ "type": ["string", "null"] });
+
+    object(serde_json::json!({
+        "members": array_of(object(serde_json::json!({
+            "name": string,
+            "constituency": nullable_string,
+            "party": nullable_string
+        }))),
+        "bills": array_of(object(serde_json::json!({
+            "name": string,
+            "number": nullable_string
+        }))),
+        "committees": array_of(object(serde_json::json!({
+            "name": string
+        })))
+    }))
+}
+
+/// JSON schema for the [`Division`]s extracted from a sitting, in the same strict form as
+/// [`structured_summary_schema`]
+pub fn divisions_schema() -> serde_json::Value {
+    let nullable_count = serde_json::json!({ "type": ["integer", "null"], "minimum": 0 });
+
+    object(serde_json::json!({
+        "divisions": array_of(object(serde_json::json!({
+            "motion": { "type": "string" },
+            "ayes": nullable_count,
+            "noes": nullable_count,
+            "abstentions": nullable_count,
+            "outcome": { "type": "string", "enum": ["agreed", "negatived", "unknown"] }
+        })))
+    }))
+}
+
+/// JSON schema for the [`StreamCategory`] a stream's title is classified as, in the same
+/// strict form as [`structured_summary_schema`]
+pub fn stream_category_schema() -> serde_json::Value {
+    object(serde_json::json!({
+        "category": {
+            "type": "string",
+            "enum": ["national_assembly", "senate", "committee", "other"]
+        }
+    }))
+}
+
+/// JSON schema for [`SummaryVerdict`], in the same strict form as [`structured_summary_schema`]
+pub fn summary_verdict_schema() -> serde_json::Value {
+    let string = serde_json::json!({ "type": "string" });
+
+    object(serde_json::json!({
+        "confidence": { "type": "number", "minimum": 0, "maximum": 1 },
+        "issues": array_of(object(serde_json::json!({
+            "kind": { "type": "string", "enum": ["name", "figure", "bill_number", "other"] },
+            "claim": string,
+            "explanation": string
+        })))
+    }))
+}
+
+/// A strict schema object requiring every one of `properties`
+fn object(properties: serde_json::Value) -> serde_json::Value {
+    let required = properties
+        .as_object()
+        .map(|props| props.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    serde_json::json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false
+    })
+}
+
+fn array_of(items: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "type": "array", "items": items })
+}
+
+/// Message content returned in structured output and TL;DR modes
+#[derive(Debug, Deserialize)]
+struct StructuredCompletion {
+    summary_md: String,
+    #[serde(default)]
+    tldr: Option<String>,
+    #[serde(flatten)]
+    structured: StructuredSummary,
+}
+
+impl From<StructuredCompletion> for SummaryResponse {
+    fn from(completion: StructuredCompletion) -> Self {
+        SummaryResponse {
+            summary: completion.summary_md,
+            structured: Some(completion.structured),
+            tldr: completion.tldr.filter(|tldr| !tldr.trim().is_empty()),
+            verification: None,
+        }
     }
 }
 
@@ -156,6 +852,8 @@ impl<F: AudioProcessor> OpenAIClient<F> {
 pub struct CompletionResponse {
     pub id: String,
     pub choices: Vec<CompletionChoice>,
+    #[serde(default)]
+    pub usage: Option<CompletionUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -171,86 +869,146 @@ pub struct CompletionMessage {
     pub content: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingResponse {
+    pub data: Vec<EmbeddingData>,
+    #[serde(default)]
+    pub usage: Option<EmbeddingUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingData {
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct EmbeddingUsage {
+    pub prompt_tokens: u64,
+}
+
+/// Response body for `response_format=json`
+#[derive(Debug, Deserialize)]
+struct TextTranscribeResponse {
+    text: String,
+}
+
+/// A single event of a streamed completion
+#[derive(Debug, Deserialize)]
+pub struct CompletionChunk {
+    pub id: String,
+    pub choices: Vec<CompletionChunkChoice>,
+    #[serde(default)]
+    pub usage: Option<CompletionUsage>,
+}
+
+/// State threaded through the stream returned by [`OpenAIClient::send_completion_stream`]
+struct CompletionStream<B> {
+    body: BoxStream<'static, reqwest::Result<B>>,
+    parser: SseParser,
+    pending: VecDeque<String>,
+    /// Set once `[DONE]` is received, after which only pending deltas are yielded
+    done: bool,
+    model: String,
+    usage_tracker: UsageTracker,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompletionChunkChoice {
+    pub index: u32,
+    pub delta: CompletionDelta,
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompletionDelta {
+    pub content: Option<String>,
+}
+
 impl<F: AudioProcessor + Send + Sync> Transcriber for OpenAIClient<F> {
     const TRANSCRIBER_MODEL: &'static str = "whisper-1";
 
     type Error = OpenAIError;
 
+    /// [`AudioInput::File`] inputs are uploaded as they are when they fit the API's upload
+    /// limit, and are otherwise split into chunks in a directory next to the file
     async fn transcribe(&self, input: AudioInput) -> Result<TranscribeResponse, Self::Error> {
-        let AudioInput::Chunked {
-            file_path,
-            chunks_dir_path,
-            chunk_duration_seconds,
-        } = input
-        else {
-            tracing::error!(audio_input = ?input, "Unspoorted audio_input");
-            return Err(OpenAIError::UnsupportedInput);
-        };
-
-        let chunks_exist = std::fs::read_dir(&chunks_dir_path)
-            .map(|mut entries| entries.any(|e| e.is_ok()))
-            .unwrap_or(false);
-
-        // chunk via ffmpeg if not already done
-        if !chunks_exist {
-            std::fs::create_dir_all(&chunks_dir_path)?;
-            let base_name = file_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| OpenAIError::Ffmpeg("Invalid file path".into()))?;
-
-            tracing::info!("Splitting audio to chunks");
-            // XXX: intentional blocking
-            self.ffmpeg
-                .split_audio_to_chunks(
-                    &file_path,
-                    chunk_duration_seconds,
-                    chunks_dir_path.join(format!("{base_name}_%03d.mp3")),
-                )
-                .inspect_err(|e| tracing::error!(error = %e, "Failed to split audio to chunks"))
-                .map_err(|e| OpenAIError::Ffmpeg(e.to_string()))?;
-        }
-
-        // collect and sort chunk files
-        let mut chunks: Vec<PathBuf> = std::fs::read_dir(&chunks_dir_path)?
-            .filter_map(|e| e.ok())
-            .map(|e| e.path())
-            .collect();
-        chunks.sort();
-
-        let mut all_segments = Vec::new();
-        let mut all_text = String::new();
-        let mut time_offset = 0.0_f64;
-        let mut duration = 0.0_f64;
-        let mut previous_text = None;
-
-        for chunk in &chunks {
-            let response = self
-                .send_transcribe_request(chunk, Self::TRANSCRIBER_MODEL, previous_text)
-                .await
-                .inspect_err(|e| tracing::error!(error = %e, "Failed to transcribe audio"))?;
+        let model = self
+            .transcription_options
+            .model
+            .as_deref()
+            .unwrap_or(Self::TRANSCRIBER_MODEL);
 
-            duration += response.duration;
-
-            if let Some(segments) = response.segments {
-                for mut seg in segments {
-                    seg.start += time_offset;
-                    seg.end += time_offset;
-                    all_segments.push(seg);
+        let (file_path, chunks_dir_path, chunk_duration_seconds, max_chunk_bytes) = match input {
+            AudioInput::Chunked {
+                file_path,
+                chunks_dir_path,
+                chunk_duration_seconds,
+                max_chunk_bytes,
+            } => (
+                file_path,
+                chunks_dir_path,
+                chunk_duration_seconds,
+                max_chunk_bytes,
+            ),
+            AudioInput::File(file_path) => {
+                let size = tokio::fs::metadata(&file_path).await?.len();
+                if size <= MAX_UPLOAD_BYTES {
+                    let prompt = self.transcription_options.glossary_prompt();
+                    let response = within_chunk_timeout(
+                        self.transcription_options.chunk_timeout,
+                        self.send_transcribe_request(&file_path, model, prompt),
+                        OpenAIError::Timeout,
+                    )
+                    .await
+                    .inspect_err(|e| tracing::error!(error = %e, "Failed to transcribe audio"))?;
+                    return Ok(self.filter_segments(response));
                 }
+
+                tracing::info!(
+                    file = ?file_path,
+                    size,
+                    "Audio exceeds the upload limit, transcribing in chunks"
+                );
+                let chunks_dir_path = file_path.with_extension("chunks");
+                (
+                    file_path,
+                    chunks_dir_path,
+                    FILE_CHUNK_DURATION_SECONDS,
+                    None,
+                )
             }
+        };
 
-            all_text.push_str(&response.text);
-            all_text.push(' ');
-            previous_text = Some(response.text);
-            time_offset += chunk_duration_seconds as f64;
-        }
+        let chunk_duration_seconds = chunk_duration(
+            &self.ffmpeg,
+            &file_path,
+            chunk_duration_seconds,
+            max_chunk_bytes,
+        )?;
+        let chunks = prepare_chunks(
+            &self.ffmpeg,
+            &file_path,
+            &chunks_dir_path,
+            chunk_duration_seconds,
+        )?;
 
-        Ok(TranscribeResponse {
-            duration,
-            text: all_text.trim().to_string(),
-            segments: Some(all_segments),
-        })
+        Ok(transcribe_chunks(
+            &chunks,
+            chunk_duration_seconds,
+            self.transcription_options.glossary_prompt(),
+            self.transcription_options.context_words(),
+            |chunk, prompt| {
+                within_chunk_timeout(
+                    self.transcription_options.chunk_timeout,
+                    self.send_transcribe_request(chunk, model, prompt),
+                    OpenAIError::Timeout,
+                )
+            },
+            |response| self.filter_segments(response),
+            &self.events,
+        )
+        .await?)
     }
 }
 
@@ -260,7 +1018,11 @@ impl<F: AudioProcessor + Send + Sync> Summarizer for OpenAIClient<F> {
 
     type Error = OpenAIError;
 
-    async fn summarize(&self, content: &str) -> Result<SummaryResponse, Self::Error> {
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
         let token_count = self.count_tokens(content)?;
 
         if token_count > Self::CONTEXT_WINDOW_LIMIT {
@@ -270,28 +1032,360 @@ impl<F: AudioProcessor + Send + Sync> Summarizer for OpenAIClient<F> {
             });
         }
 
-        let response = self
-            .send_completion_request(Self::SUMMARIZER_MODEL, content)
-            .await
-            .inspect_err(|e| tracing::error!(error = %e, "Failed to summarize content"))?;
+        let model = self
+            .summarizer_model
+            .as_deref()
+            .unwrap_or(Self::SUMMARIZER_MODEL);
 
-        let summary = response
-            .choices
-            .first()
-            .and_then(|c| c.message.content.clone())
-            .ok_or_else(|| OpenAIError::Api {
+        let system_prompt = self.system_prompt.render(&context.prompt_variables());
+
+        let summary = if self.streaming {
+            self.send_completion_stream(model, &system_prompt, content)
+                .await
+                .inspect_err(|e| tracing::error!(error = %e, "Failed to summarize content"))?
+                .try_collect::<String>()
+                .await
+                .inspect_err(|e| tracing::error!(error = %e, "Summary stream interrupted"))?
+        } else {
+            self.send_completion_request(model, &system_prompt, content)
+                .await
+                .inspect_err(|e| tracing::error!(error = %e, "Failed to summarize content"))?
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.message.content)
+                .unwrap_or_default()
+        };
+
+        if summary.is_empty() {
+            return Err(OpenAIError::Api {
                 status: 0,
-                message: "No conent in response".into(),
-            })?;
+                message: "No content in response".into(),
+            });
+        }
 
-        Ok(SummaryResponse { summary })
+        if self.structured_output || self.tldr {
+            let completion = serde_json::from_str::<StructuredCompletion>(&summary)
+                .inspect_err(|e| tracing::error!(error = %e, "Malformed structured summary"))
+                .map_err(|e| OpenAIError::MalformedResponse(format!("structured summary: {e}")))?;
+            let mut response = SummaryResponse::from(completion);
+            if !self.structured_output {
+                response.structured = None;
+            }
+            return Ok(response);
+        }
+
+        Ok(SummaryResponse {
+            summary,
+            structured: None,
+            tldr: None,
+            verification: None,
+        })
     }
 
     fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
-        let bpe = cl100k_base().map_err(|e| OpenAIError::Api {
-            status: 0,
-            message: e.to_string(),
-        })?;
-        Ok(bpe.encode_with_special_tokens(content).len())
+        count_cl100k_tokens(content)
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> EntityExtractor for OpenAIClient<F> {
+    const EXTRACTION_MODEL: &'static str = "gpt-4o-mini";
+
+    type Error = OpenAIError;
+
+    async fn extract_entities(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<StreamEntities, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::EXTRACTION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                &entity_system_prompt(context),
+                transcript,
+                "stream_entities",
+                stream_entities_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract entities"))?;
+
+        parse_entities(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed entity extraction response"))
+            .map_err(|e| OpenAIError::MalformedResponse(format!("entity extraction response: {e}")))
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> DivisionExtractor for OpenAIClient<F> {
+    const DIVISION_EXTRACTION_MODEL: &'static str = "gpt-4o-mini";
+
+    type Error = OpenAIError;
+
+    async fn extract_divisions(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<Vec<Division>, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::DIVISION_EXTRACTION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                &division_system_prompt(context),
+                transcript,
+                "divisions",
+                divisions_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract divisions"))?;
+
+        parse_divisions(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed division extraction response"))
+            .map_err(|e| {
+                OpenAIError::MalformedResponse(format!("division extraction response: {e}"))
+            })
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> SummaryVerifier for OpenAIClient<F> {
+    const VERIFICATION_MODEL: &'static str = "gpt-4o-mini";
+
+    type Error = OpenAIError;
+
+    async fn verify_summary(
+        &self,
+        transcript: &str,
+        summary: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryVerdict, Self::Error> {
+        let model = self
+            .verification_model
+            .as_deref()
+            .unwrap_or(Self::VERIFICATION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                &verification_system_prompt(context),
+                &verification_content(transcript, summary),
+                "summary_verdict",
+                summary_verdict_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to verify summary"))?;
+
+        parse_verdict(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed verification response"))
+            .map_err(|e| OpenAIError::MalformedResponse(format!("verification response: {e}")))
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> CategoryClassifier for OpenAIClient<F> {
+    const CLASSIFICATION_MODEL: &'static str = "gpt-4o-mini";
+
+    type Error = OpenAIError;
+
+    async fn classify_title(&self, title: &str) -> Result<StreamCategory, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::CLASSIFICATION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                CLASSIFICATION_PROMPT,
+                title,
+                "stream_category",
+                stream_category_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to classify stream title"))?;
+
+        parse_category(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed classification response"))
+            .map_err(|e| OpenAIError::MalformedResponse(format!("classification response: {e}")))
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> Embedder for OpenAIClient<F> {
+    const EMBEDDING_MODEL: &'static str = "text-embedding-3-small";
+
+    type Error = OpenAIError;
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, Self::Error> {
+        let model = self
+            .embedding_model
+            .as_deref()
+            .unwrap_or(Self::EMBEDDING_MODEL);
+
+        let response = self
+            .send_embedding_request(model, inputs)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to embed inputs"))?;
+
+        if response.data.len() != inputs.len() {
+            return Err(OpenAIError::MalformedResponse(format!(
+                "embedding response: expected {} embeddings, got {}",
+                inputs.len(),
+                response.data.len()
+            )));
+        }
+
+        Ok(response.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// MIME type of an audio file the transcriptions API accepts, going by its extension
+fn audio_mime_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "flac" => "audio/flac",
+        "m4a" | "mp4" => "audio/mp4",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "webm" => "audio/webm",
+        _ => "audio/mpeg",
+    }
+}
+
+/// `web_search_options` for the chat completions API
+fn web_search_options(options: &WebSearchOptions) -> serde_json::Value {
+    let mut web_search = serde_json::json!({
+        "search_context_size": options.context_size.as_str(),
+    });
+    if let Some(location) = &options.user_location {
+        web_search["user_location"] = serde_json::json!({
+            "type": "approximate",
+            "approximate": location
+        });
+    }
+    web_search
+}
+
+#[cfg(feature = "tiktoken")]
+fn count_cl100k_tokens(content: &str) -> Result<usize, OpenAIError> {
+    let bpe = CL100K
+        .as_ref()
+        .map_err(|e| OpenAIError::MalformedResponse(format!("cl100k tokenizer: {e}")))?;
+    Ok(bpe.encode_with_special_tokens(content).len())
+}
+
+/// Without a tokenizer, fall back to [`crate::llm::estimate_tokens`]
+#[cfg(not(feature = "tiktoken"))]
+fn count_cl100k_tokens(content: &str) -> Result<usize, OpenAIError> {
+    Ok(crate::llm::estimate_tokens(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_completion_deserializes_into_summary_response() {
+        let content = serde_json::json!({
+            "summary_md": "# National Assembly",
+            "bills": [{
+                "name": "Finance Bill",
+                "number": null,
+                "stage": "Second Reading",
+                "summary": "Tax measures for the financial year"
+            }],
+            "motions": [],
+            "votes": [{
+                "subject": "Finance Bill",
+                "result": "Passed",
+                "ayes": 195,
+                "noes": 106,
+                "abstentions": null
+            }],
+            "key_speakers": [],
+            "action_items": ["Committee to table report"]
+        })
+        .to_string();
+
+        let response: SummaryResponse = serde_json::from_str::<StructuredCompletion>(&content)
+            .unwrap()
+            .into();
+
+        assert_eq!(response.summary, "# National Assembly");
+        let structured = response.structured.unwrap();
+        assert_eq!(structured.bills[0].stage.as_deref(), Some("Second Reading"));
+        assert_eq!(structured.votes[0].ayes, Some(195));
+        assert_eq!(structured.action_items.len(), 1);
+    }
+
+    #[test]
+    fn test_tldr_completion_deserializes_without_structured_fields() {
+        let content = serde_json::json!({
+            "summary_md": "# Senate Sitting",
+            "tldr": "Senators debated the County Allocation of Revenue Bill."
+        })
+        .to_string();
+
+        let response: SummaryResponse = serde_json::from_str::<StructuredCompletion>(&content)
+            .unwrap()
+            .into();
+
+        assert_eq!(
+            response.tldr.as_deref(),
+            Some("Senators debated the County Allocation of Revenue Bill.")
+        );
+        assert_eq!(response.structured, Some(StructuredSummary::default()));
+    }
+
+    #[test]
+    fn test_audio_mime_type_follows_extension() {
+        assert_eq!(audio_mime_type(Path::new("clip.M4A")), "audio/mp4");
+        assert_eq!(audio_mime_type(Path::new("chunk_000.mp3")), "audio/mpeg");
+        assert_eq!(audio_mime_type(Path::new("clip")), "audio/mpeg");
+    }
+
+    #[test]
+    fn test_embedding_response_deserializes() {
+        let content = serde_json::json!({
+            "object": "list",
+            "data": [
+                { "object": "embedding", "index": 1, "embedding": [0.5, 0.5] },
+                { "object": "embedding", "index": 0, "embedding": [1.0, 0.0] }
+            ],
+            "model": "text-embedding-3-small",
+            "usage": { "prompt_tokens": 8, "total_tokens": 8 }
+        })
+        .to_string();
+
+        let response = serde_json::from_str::<EmbeddingResponse>(&content).unwrap();
+
+        assert_eq!(response.data[1].index, 0);
+        assert_eq!(response.data[1].embedding, vec![1.0, 0.0]);
+        assert_eq!(response.usage.unwrap().prompt_tokens, 8);
+    }
+
+    #[test]
+    fn test_schema_requires_every_property() {
+        for schema in [
+            structured_summary_schema(),
+            stream_entities_schema(),
+            divisions_schema(),
+            stream_category_schema(),
+            summary_verdict_schema(),
+        ] {
+            let properties = schema["properties"].as_object().unwrap();
+            let required = schema["required"].as_array().unwrap();
+            assert_eq!(properties.len(), required.len());
+            assert_eq!(schema["additionalProperties"], false);
+        }
     }
 }
@@ -0,0 +1,504 @@
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Serialize;
+use stream_datastore::{Division, StreamCategory, StreamEntities};
+
+use crate::{
+    llm::{
+        classifier::{parse_category, CategoryClassifier, CLASSIFICATION_PROMPT},
+        divisions::{division_system_prompt, parse_divisions, DivisionExtractor},
+        entities::{entity_system_prompt, parse_entities, EntityExtractor},
+        estimate_tokens,
+        prompt::PromptTemplate,
+        retry::retrying_client,
+        summarizer::{CompletionOptions, SummaryContext, SummaryResponse},
+        usage::UsageTracker,
+        verification::{
+            parse_verdict, verification_content, verification_system_prompt, SummaryVerdict,
+            SummaryVerifier,
+        },
+    },
+    openai::{
+        divisions_schema, stream_category_schema, stream_entities_schema, summary_verdict_schema,
+        CompletionResponse,
+    },
+    Summarizer,
+};
+
+/// Summarizer backed by OpenRouter's OpenAI-compatible chat completions API, which
+/// proxies models from many providers behind a single key.
+#[derive(Debug, Clone)]
+pub struct OpenRouterClient {
+    client: ClientWithMiddleware,
+    api_key: String,
+    base_url: String,
+    summarizer_model: Option<String>,
+    extraction_model: Option<String>,
+    verification_model: Option<String>,
+    routing: OpenRouterRouting,
+    completion_options: CompletionOptions,
+    /// Sent as `HTTP-Referer` and `X-Title` for attribution on openrouter.ai
+    app_url: Option<String>,
+    app_title: Option<String>,
+    system_prompt: PromptTemplate,
+    usage_tracker: UsageTracker,
+}
+
+/// Model routing preferences, see <https://openrouter.ai/docs/features/provider-routing>
+#[derive(Debug, Clone, Default)]
+pub struct OpenRouterRouting {
+    /// Models to try, in order, if the primary model is unavailable or refuses the request
+    pub fallback_models: Vec<String>,
+    pub provider: Option<ProviderPreferences>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProviderPreferences {
+    /// Upstream providers to try in order, e.g. `["openai", "azure"]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_fallbacks: Option<bool>,
+    /// "price", "throughput" or "latency"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// "allow" or "deny" providers that may store prompts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_collection: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OpenRouterError {
+    #[error("HTTP error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("HTTP middleare error: {0}")]
+    RequestMiddleware(#[from] reqwest_middleware::Error),
+    #[error("API error: {status} - {message}")]
+    Api { status: u16, message: String },
+    #[error("Malformed {0}")]
+    MalformedResponse(String),
+}
+
+impl OpenRouterClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: retrying_client(reqwest::Client::new()),
+            api_key: api_key.into(),
+            base_url: "https://openrouter.ai/api/v1".into(),
+            summarizer_model: None,
+            extraction_model: None,
+            verification_model: None,
+            routing: OpenRouterRouting::default(),
+            completion_options: CompletionOptions::default(),
+            app_url: None,
+            app_title: None,
+            system_prompt: PromptTemplate::default(),
+            usage_tracker: UsageTracker::default(),
+        }
+    }
+
+    /// Send requests through `client`, e.g. one shared with the rest of the run for its
+    /// proxy and timeout settings
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = retrying_client(client);
+        self
+    }
+
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Override the model used for summarization, which otherwise
+    /// defaults to [`Summarizer::SUMMARIZER_MODEL`]
+    pub fn with_summarizer_model(mut self, model: impl Into<String>) -> Self {
+        self.summarizer_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for entity and division extraction and title classification,
+    /// which otherwise defaults to [`EntityExtractor::EXTRACTION_MODEL`],
+    /// [`DivisionExtractor::DIVISION_EXTRACTION_MODEL`] and
+    /// [`CategoryClassifier::CLASSIFICATION_MODEL`]
+    pub fn with_extraction_model(mut self, model: impl Into<String>) -> Self {
+        self.extraction_model = Some(model.into());
+        self
+    }
+
+    /// Override the model used for summary verification, which otherwise
+    /// defaults to [`SummaryVerifier::VERIFICATION_MODEL`]
+    pub fn with_verification_model(mut self, model: impl Into<String>) -> Self {
+        self.verification_model = Some(model.into());
+        self
+    }
+
+    pub fn with_routing(mut self, routing: OpenRouterRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Configure sampling and the web plugin, which is enabled by default
+    pub fn with_completion_options(mut self, options: CompletionOptions) -> Self {
+        self.completion_options = options;
+        self
+    }
+
+    /// Identify the app on OpenRouter's leaderboards and usage pages
+    pub fn with_app(mut self, url: impl Into<String>, title: impl Into<String>) -> Self {
+        self.app_url = Some(url.into());
+        self.app_title = Some(title.into());
+        self
+    }
+
+    /// Replace the bundled system prompt used for summarization
+    pub fn with_system_prompt(mut self, system_prompt: PromptTemplate) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Record token usage into `usage_tracker`
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = usage_tracker;
+        self
+    }
+
+    pub async fn send_completion_request(
+        &self,
+        model_name: impl Into<String>,
+        system_prompt: &str,
+        user_content: impl Into<String>,
+    ) -> Result<CompletionResponse, OpenRouterError> {
+        let model_name = model_name.into();
+        let body = self.completion_body(&model_name, system_prompt, user_content.into());
+        self.post_completion(&model_name, &body).await
+    }
+
+    /// Posts `body` to the chat completions endpoint, recording usage against `model_name`
+    async fn post_completion(
+        &self,
+        model_name: &str,
+        body: &serde_json::Value,
+    ) -> Result<CompletionResponse, OpenRouterError> {
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(body);
+        if let Some(app_url) = &self.app_url {
+            request = request.header("HTTP-Referer", app_url);
+        }
+        if let Some(app_title) = &self.app_title {
+            request = request.header("X-Title", app_title);
+        }
+
+        let resp = request
+            .send()
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to make http request"))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let message = resp.text().await.unwrap_or_default();
+            return Err(OpenRouterError::Api { status, message });
+        }
+
+        let response = resp.json::<CompletionResponse>().await?;
+        if let Some(usage) = response.usage {
+            self.usage_tracker.record_completion(model_name, usage);
+        }
+
+        Ok(response)
+    }
+
+    /// Completion constrained to the JSON `schema`, returning the raw message content.
+    /// The web plugin is left out, since these requests only work from their input.
+    async fn send_structured_request(
+        &self,
+        model_name: &str,
+        system_prompt: &str,
+        user_content: &str,
+        schema_name: &str,
+        schema: serde_json::Value,
+    ) -> Result<String, OpenRouterError> {
+        let mut body = self.completion_body(model_name, system_prompt, user_content.into());
+        if let Some(body) = body.as_object_mut() {
+            body.remove("plugins");
+        }
+        body["response_format"] = serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": schema_name,
+                "strict": true,
+                "schema": schema
+            }
+        });
+
+        let response = self.post_completion(model_name, &body).await?;
+        Ok(response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .unwrap_or_default())
+    }
+
+    fn completion_body(
+        &self,
+        model_name: &str,
+        system_prompt: &str,
+        user_content: String,
+    ) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "model": model_name,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": system_prompt
+                },
+                {
+                    "role": "user",
+                    "content": user_content
+                }
+            ]
+        });
+
+        if let Some(web_search) = &self.completion_options.web_search {
+            body["plugins"] = serde_json::json!([
+                { "id": "web", "max_results": web_search.max_results }
+            ]);
+        }
+        self.completion_options.apply_sampling(&mut body);
+        if !self.routing.fallback_models.is_empty() {
+            body["models"] = serde_json::json!(self.routing.fallback_models);
+        }
+        if let Some(provider) = &self.routing.provider {
+            body["provider"] = serde_json::json!(provider);
+        }
+
+        body
+    }
+}
+
+impl Summarizer for OpenRouterClient {
+    const SUMMARIZER_MODEL: &'static str = "openai/gpt-4o";
+    // Context windows vary by model, so assume the smallest we are likely to route to
+    const CONTEXT_WINDOW_LIMIT: usize = 128_000 - 1_000;
+
+    type Error = OpenRouterError;
+
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
+        let token_count = self.count_tokens(content)?;
+
+        if token_count > Self::CONTEXT_WINDOW_LIMIT {
+            return Err(OpenRouterError::Api {
+                status: 0,
+                message: "Token limit exceeded".into(),
+            });
+        }
+
+        let model = self
+            .summarizer_model
+            .as_deref()
+            .unwrap_or(Self::SUMMARIZER_MODEL);
+
+        let system_prompt = self.system_prompt.render(&context.prompt_variables());
+
+        let response = self
+            .send_completion_request(model, &system_prompt, content)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to summarize content"))?;
+
+        let summary = response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .filter(|content| !content.trim().is_empty())
+            .ok_or_else(|| OpenRouterError::Api {
+                status: 0,
+                message: "No content in response".into(),
+            })?;
+
+        Ok(SummaryResponse {
+            summary,
+            structured: None,
+            tldr: None,
+            verification: None,
+        })
+    }
+
+    /// Tokenizers differ between routed models, so this is an estimate
+    fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
+        Ok(estimate_tokens(content))
+    }
+}
+
+impl EntityExtractor for OpenRouterClient {
+    const EXTRACTION_MODEL: &'static str = "openai/gpt-4o-mini";
+
+    type Error = OpenRouterError;
+
+    async fn extract_entities(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<StreamEntities, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::EXTRACTION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                &entity_system_prompt(context),
+                transcript,
+                "stream_entities",
+                stream_entities_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract entities"))?;
+
+        // not every routed model honours `response_format`, hence the lenient parsing
+        parse_entities(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed entity extraction response"))
+            .map_err(|e| {
+                OpenRouterError::MalformedResponse(format!("entity extraction response: {e}"))
+            })
+    }
+}
+
+impl DivisionExtractor for OpenRouterClient {
+    const DIVISION_EXTRACTION_MODEL: &'static str = "openai/gpt-4o-mini";
+
+    type Error = OpenRouterError;
+
+    async fn extract_divisions(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<Vec<Division>, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::DIVISION_EXTRACTION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                &division_system_prompt(context),
+                transcript,
+                "divisions",
+                divisions_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to extract divisions"))?;
+
+        parse_divisions(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed division extraction response"))
+            .map_err(|e| {
+                OpenRouterError::MalformedResponse(format!("division extraction response: {e}"))
+            })
+    }
+}
+
+impl SummaryVerifier for OpenRouterClient {
+    const VERIFICATION_MODEL: &'static str = "openai/gpt-4o-mini";
+
+    type Error = OpenRouterError;
+
+    async fn verify_summary(
+        &self,
+        transcript: &str,
+        summary: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryVerdict, Self::Error> {
+        let model = self
+            .verification_model
+            .as_deref()
+            .unwrap_or(Self::VERIFICATION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                &verification_system_prompt(context),
+                &verification_content(transcript, summary),
+                "summary_verdict",
+                summary_verdict_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to verify summary"))?;
+
+        parse_verdict(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed verification response"))
+            .map_err(|e| OpenRouterError::MalformedResponse(format!("verification response: {e}")))
+    }
+}
+
+impl CategoryClassifier for OpenRouterClient {
+    const CLASSIFICATION_MODEL: &'static str = "openai/gpt-4o-mini";
+
+    type Error = OpenRouterError;
+
+    async fn classify_title(&self, title: &str) -> Result<StreamCategory, Self::Error> {
+        let model = self
+            .extraction_model
+            .as_deref()
+            .unwrap_or(Self::CLASSIFICATION_MODEL);
+
+        let content = self
+            .send_structured_request(
+                model,
+                CLASSIFICATION_PROMPT,
+                title,
+                "stream_category",
+                stream_category_schema(),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, "Failed to classify stream title"))?;
+
+        parse_category(&content)
+            .inspect_err(|e| tracing::error!(error = %e, "Malformed classification response"))
+            .map_err(|e| {
+                OpenRouterError::MalformedResponse(format!("classification response: {e}"))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routing_preferences_are_included_in_body() {
+        let client = OpenRouterClient::new("key").with_routing(OpenRouterRouting {
+            fallback_models: vec!["anthropic/claude-sonnet-4".into()],
+            provider: Some(ProviderPreferences {
+                sort: Some("price".into()),
+                ..Default::default()
+            }),
+        });
+
+        let body = client.completion_body("openai/gpt-4o", "system", "transcript".into());
+        assert_eq!(body["models"][0], "anthropic/claude-sonnet-4");
+        assert_eq!(body["provider"], serde_json::json!({ "sort": "price" }));
+
+        let body = OpenRouterClient::new("key").completion_body("m", "system", "t".into());
+        assert!(body.get("models").is_none());
+        assert!(body.get("provider").is_none());
+    }
+
+    #[test]
+    fn test_web_plugin_follows_completion_options() {
+        let body = OpenRouterClient::new("key").completion_body("m", "system", "t".into());
+        assert_eq!(body["plugins"][0]["id"], "web");
+
+        let body = OpenRouterClient::new("key")
+            .with_completion_options(CompletionOptions::without_web_search())
+            .completion_body("m", "system", "t".into());
+        assert!(body.get("plugins").is_none());
+    }
+}
@@ -0,0 +1,101 @@
+//! # Rate Limit
+//!
+//! Client-side request and token budgets for LLM providers, so that back-to-back
+//! sessions don't trip organisation-level rate limits.
+
+use std::{fmt::Debug, num::NonZeroU32, sync::Arc};
+
+use governor::{DefaultDirectRateLimiter, Quota};
+
+/// Per-minute budgets. `None` leaves the corresponding dimension unlimited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitConfig {
+    pub requests_per_minute: Option<NonZeroU32>,
+    pub tokens_per_minute: Option<NonZeroU32>,
+}
+
+/// A cheaply cloneable rate limiter. Clones share the same budget.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    requests: Option<Arc<DefaultDirectRateLimiter>>,
+    tokens: Option<Arc<DefaultDirectRateLimiter>>,
+    tokens_per_minute: u32,
+}
+
+impl Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("requests_limited", &self.requests.is_some())
+            .field("tokens_per_minute", &self.tokens_per_minute)
+            .finish()
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            requests: config
+                .requests_per_minute
+                .map(|rpm| Arc::new(governor::RateLimiter::direct(Quota::per_minute(rpm)))),
+            tokens: config
+                .tokens_per_minute
+                .map(|tpm| Arc::new(governor::RateLimiter::direct(Quota::per_minute(tpm)))),
+            tokens_per_minute: config.tokens_per_minute.map_or(0, NonZeroU32::get),
+        }
+    }
+
+    /// Whether a token budget is configured, i.e. whether callers need to count tokens
+    pub fn limits_tokens(&self) -> bool {
+        self.tokens.is_some()
+    }
+
+    /// Waits until one request and `tokens` tokens fit within the budget.
+    ///
+    /// Requests larger than the whole per-minute token budget wait for a full
+    /// budget rather than failing, leaving the provider to reject them if needed.
+    pub async fn acquire(&self, tokens: usize) {
+        if let Some(requests) = &self.requests {
+            requests.until_ready().await;
+        }
+
+        if let Some(limiter) = &self.tokens {
+            let tokens = (tokens.min(u32::MAX as usize) as u32).min(self.tokens_per_minute);
+            if let Some(tokens) = NonZeroU32::new(tokens) {
+                tracing::debug!(tokens = tokens.get(), "Waiting for token budget");
+                // capacity was clamped above, so this cannot fail
+                let _ = limiter.until_n_ready(tokens).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unlimited_acquire_returns_immediately() {
+        let limiter = RateLimiter::default();
+        tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            limiter.acquire(1_000_000),
+        )
+        .await
+        .expect("Unlimited limiter should not wait");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_token_request_is_clamped() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_minute: None,
+            tokens_per_minute: NonZeroU32::new(100),
+        });
+        // the initial burst equals the per-minute budget, so this should not block
+        tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            limiter.acquire(10_000),
+        )
+        .await
+        .expect("Oversized request should be clamped to the budget");
+    }
+}
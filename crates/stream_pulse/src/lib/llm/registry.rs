@@ -0,0 +1,652 @@
+//! # Provider Registry
+//!
+//! Builds [`Transcriber`] and [`Summarizer`] implementations from runtime configuration,
+//! so that switching providers is a config change rather than a code change.
+
+use std::str::FromStr;
+
+use stream_datastore::{Division, StreamCategory, StreamEntities};
+use ytdlp_bindings::AudioProcessor;
+
+#[cfg(feature = "bedrock")]
+use crate::bedrock::{AmazonTranscriber, AwsCredentials, BedrockClient, BedrockError};
+use crate::{
+    anthropic::{AnthropicClient, AnthropicError},
+    groq::{GroqError, GroqTranscriber},
+    llm::{
+        classifier::CategoryClassifier,
+        divisions::DivisionExtractor,
+        entities::EntityExtractor,
+        providers::openai::OpenAIError,
+        summarizer::{CompletionOptions, SummaryContext, SummaryResponse},
+        transcriber::{TranscribeResponse, TranscriptionOptions},
+        verification::{SummaryVerdict, SummaryVerifier},
+    },
+    openai::{OpenAIClient, OpenAIEndpoint},
+    openrouter::{OpenRouterClient, OpenRouterError, OpenRouterRouting},
+    AudioInput, ProcessorEvents, PromptTemplate, RateLimiter, Summarizer, Transcriber,
+    UsageTracker,
+};
+
+/// Supported transcription providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriberProviderKind {
+    OpenAI,
+    /// OpenAI models deployed to an Azure OpenAI resource
+    Azure,
+    Groq,
+    /// Amazon Transcribe, with audio staged in an S3 bucket
+    #[cfg(feature = "bedrock")]
+    Bedrock,
+}
+
+impl FromStr for TranscriberProviderKind {
+    type Err = ProviderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "openai" => Ok(TranscriberProviderKind::OpenAI),
+            "azure" => Ok(TranscriberProviderKind::Azure),
+            "groq" => Ok(TranscriberProviderKind::Groq),
+            #[cfg(feature = "bedrock")]
+            "bedrock" | "aws" => Ok(TranscriberProviderKind::Bedrock),
+            other => Err(ProviderError::UnknownProvider(other.to_string())),
+        }
+    }
+}
+
+/// Runtime configuration for a transcription provider
+#[derive(Debug, Clone)]
+pub struct TranscriberConfig {
+    pub provider: TranscriberProviderKind,
+    pub api_key: String,
+    /// Overrides the provider's default API base URL
+    pub base_url: Option<String>,
+    /// Overrides the provider's default transcription model. For Azure, the deployment name
+    pub model: Option<String>,
+    /// API version, currently only used by Azure
+    pub api_version: Option<String>,
+    /// AWS region, only used by Bedrock
+    pub region: Option<String>,
+    /// S3 bucket audio is staged in for Amazon Transcribe, only used by Bedrock
+    pub bucket: Option<String>,
+    /// Language hint, temperature and response format, currently honoured by the OpenAI
+    /// provider. The glossary, context window and segment filter are honoured by the OpenAI
+    /// and Groq providers.
+    pub options: TranscriptionOptions,
+    /// Client-side request budget, currently honoured by the OpenAI provider
+    pub rate_limiter: Option<RateLimiter>,
+    /// Records audio transcribed by the provider
+    pub usage_tracker: Option<UsageTracker>,
+    /// Reports each chunk transcribed, currently honoured by the OpenAI, Groq and Bedrock
+    /// providers
+    pub events: Option<ProcessorEvents>,
+    /// Sends the provider's requests, e.g. to share proxy and timeout settings
+    pub http_client: Option<reqwest::Client>,
+}
+
+/// Supported summarization providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummarizerProviderKind {
+    OpenAI,
+    /// OpenAI models deployed to an Azure OpenAI resource
+    Azure,
+    Anthropic,
+    OpenRouter,
+    /// Models served by the Bedrock Converse API
+    #[cfg(feature = "bedrock")]
+    Bedrock,
+}
+
+impl FromStr for SummarizerProviderKind {
+    type Err = ProviderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "openai" => Ok(SummarizerProviderKind::OpenAI),
+            "azure" => Ok(SummarizerProviderKind::Azure),
+            "anthropic" => Ok(SummarizerProviderKind::Anthropic),
+            "openrouter" => Ok(SummarizerProviderKind::OpenRouter),
+            #[cfg(feature = "bedrock")]
+            "bedrock" | "aws" => Ok(SummarizerProviderKind::Bedrock),
+            other => Err(ProviderError::UnknownProvider(other.to_string())),
+        }
+    }
+}
+
+/// Runtime configuration for a summarization provider
+#[derive(Debug, Clone)]
+pub struct SummarizerConfig {
+    pub provider: SummarizerProviderKind,
+    pub api_key: String,
+    /// Overrides the provider's default API base URL
+    pub base_url: Option<String>,
+    /// Overrides the provider's default summarization model. For Azure, the deployment name
+    pub model: Option<String>,
+    /// Overrides the provider's default entity and division extraction model
+    pub extraction_model: Option<String>,
+    /// Overrides the provider's default summary verification model
+    pub verification_model: Option<String>,
+    /// API version, currently only used by Azure
+    pub api_version: Option<String>,
+    /// AWS region, only used by Bedrock
+    pub region: Option<String>,
+    /// Client-side request budget, currently honoured by the OpenAI provider
+    pub rate_limiter: Option<RateLimiter>,
+    /// Records tokens consumed by the provider
+    pub usage_tracker: Option<UsageTracker>,
+    /// Request a [`StructuredSummary`](stream_datastore::StructuredSummary) alongside the
+    /// markdown summary, currently honoured by the OpenAI provider
+    pub structured_output: bool,
+    /// Request a one-paragraph TL;DR alongside the markdown summary, currently honoured
+    /// by the OpenAI provider
+    pub tldr: bool,
+    /// Receive summaries over streamed completions, currently honoured by the OpenAI provider
+    pub streaming: bool,
+    /// Send an idempotency key with completion requests, so retries are not billed twice.
+    /// Currently honoured by the OpenAI provider
+    pub idempotency_keys: bool,
+    /// Web search and sampling settings. Web search is honoured by every provider except
+    /// Azure and Bedrock
+    pub completion_options: CompletionOptions,
+    /// Overrides the bundled system prompt
+    pub system_prompt: Option<PromptTemplate>,
+    /// Fallback models and upstream provider preferences, only used by OpenRouter
+    pub routing: OpenRouterRouting,
+    /// Sends the provider's requests, e.g. to share proxy and timeout settings
+    pub http_client: Option<reqwest::Client>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    #[error("Unknown provider: {0}")]
+    UnknownProvider(String),
+    #[error("Missing provider configuration: {0}")]
+    MissingConfig(&'static str),
+    #[error(transparent)]
+    OpenAI(#[from] OpenAIError),
+    #[error(transparent)]
+    Anthropic(#[from] AnthropicError),
+    #[error(transparent)]
+    Groq(#[from] GroqError),
+    #[error(transparent)]
+    OpenRouter(#[from] OpenRouterError),
+    #[cfg(feature = "bedrock")]
+    #[error(transparent)]
+    Bedrock(#[from] BedrockError),
+}
+
+/// Azure has no shared base URL, so the resource URL must be configured
+fn azure_endpoint(
+    base_url: Option<&str>,
+    api_version: Option<&str>,
+) -> Result<OpenAIEndpoint, ProviderError> {
+    if base_url.is_none() {
+        return Err(ProviderError::MissingConfig(
+            "base URL of the Azure OpenAI resource",
+        ));
+    }
+    Ok(OpenAIEndpoint::Azure {
+        api_version: api_version
+            .unwrap_or(OpenAIEndpoint::DEFAULT_AZURE_API_VERSION)
+            .to_string(),
+    })
+}
+
+/// Bedrock is signed with the standard AWS environment credentials rather than an API key
+#[cfg(feature = "bedrock")]
+fn aws_credentials(region: Option<&str>) -> Result<(AwsCredentials, &str), ProviderError> {
+    let credentials = AwsCredentials::from_env().ok_or(ProviderError::MissingConfig(
+        "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY",
+    ))?;
+    let region = region.ok_or(ProviderError::MissingConfig("AWS region"))?;
+    Ok((credentials, region))
+}
+
+/// A [`Transcriber`] whose concrete implementation is selected at runtime
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum TranscriberProvider<F: AudioProcessor> {
+    OpenAI(OpenAIClient<F>),
+    Groq(GroqTranscriber<F>),
+    #[cfg(feature = "bedrock")]
+    Bedrock(AmazonTranscriber<F>),
+}
+
+impl<F: AudioProcessor> TranscriberProvider<F> {
+    pub fn from_config(config: &TranscriberConfig, ffmpeg: F) -> Result<Self, ProviderError> {
+        match config.provider {
+            TranscriberProviderKind::OpenAI | TranscriberProviderKind::Azure => {
+                let mut client = OpenAIClient::new(&config.api_key, ffmpeg)
+                    .with_transcription_options(config.options.clone());
+                if let Some(http_client) = &config.http_client {
+                    client = client.with_http_client(http_client.clone());
+                }
+                if let Some(base_url) = &config.base_url {
+                    client = client.with_base_url(base_url);
+                }
+                if let Some(model) = &config.model {
+                    client = client.with_transcriber_model(model);
+                }
+                if let Some(rate_limiter) = &config.rate_limiter {
+                    client = client.with_rate_limiter(rate_limiter.clone());
+                }
+                if let Some(usage_tracker) = &config.usage_tracker {
+                    client = client.with_usage_tracker(usage_tracker.clone());
+                }
+                if let Some(events) = &config.events {
+                    client = client.with_events(events.clone());
+                }
+                if config.provider == TranscriberProviderKind::Azure {
+                    client = client.with_endpoint(azure_endpoint(
+                        config.base_url.as_deref(),
+                        config.api_version.as_deref(),
+                    )?);
+                }
+                Ok(TranscriberProvider::OpenAI(client))
+            }
+            TranscriberProviderKind::Groq => {
+                let mut client = GroqTranscriber::new(&config.api_key, ffmpeg);
+                if let Some(http_client) = &config.http_client {
+                    client = client.with_http_client(http_client.clone());
+                }
+                if let Some(base_url) = &config.base_url {
+                    client = client.with_base_url(base_url);
+                }
+                if let Some(model) = &config.model {
+                    client = client.with_transcriber_model(model);
+                }
+                if let Some(glossary) = &config.options.glossary {
+                    client = client.with_glossary(glossary.clone());
+                }
+                if let Some(context_words) = config.options.context_words {
+                    client = client.with_context_words(context_words);
+                }
+                if let Some(segment_filter) = config.options.segment_filter {
+                    client = client.with_segment_filter(segment_filter);
+                }
+                if let Some(timeout) = config.options.chunk_timeout {
+                    client = client.with_chunk_timeout(timeout);
+                }
+                if let Some(usage_tracker) = &config.usage_tracker {
+                    client = client.with_usage_tracker(usage_tracker.clone());
+                }
+                if let Some(events) = &config.events {
+                    client = client.with_events(events.clone());
+                }
+                Ok(TranscriberProvider::Groq(client))
+            }
+            #[cfg(feature = "bedrock")]
+            TranscriberProviderKind::Bedrock => {
+                let (credentials, region) = aws_credentials(config.region.as_deref())?;
+                let bucket = config
+                    .bucket
+                    .as_deref()
+                    .ok_or(ProviderError::MissingConfig(
+                        "S3 bucket for Amazon Transcribe",
+                    ))?;
+                let mut client = AmazonTranscriber::new(credentials, region, bucket, ffmpeg);
+                if let Some(http_client) = &config.http_client {
+                    client = client.with_http_client(http_client.clone());
+                }
+                if let Some(usage_tracker) = &config.usage_tracker {
+                    client = client.with_usage_tracker(usage_tracker.clone());
+                }
+                if let Some(events) = &config.events {
+                    client = client.with_events(events.clone());
+                }
+                Ok(TranscriberProvider::Bedrock(client))
+            }
+        }
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> Transcriber for TranscriberProvider<F> {
+    // The model actually used is resolved by the selected provider's config
+    const TRANSCRIBER_MODEL: &'static str = <OpenAIClient<F> as Transcriber>::TRANSCRIBER_MODEL;
+
+    type Error = ProviderError;
+
+    async fn transcribe(&self, audio_input: AudioInput) -> Result<TranscribeResponse, Self::Error> {
+        match self {
+            TranscriberProvider::OpenAI(client) => Ok(client.transcribe(audio_input).await?),
+            TranscriberProvider::Groq(client) => Ok(client.transcribe(audio_input).await?),
+            #[cfg(feature = "bedrock")]
+            TranscriberProvider::Bedrock(client) => Ok(client.transcribe(audio_input).await?),
+        }
+    }
+}
+
+/// A [`Summarizer`] whose concrete implementation is selected at runtime
+#[derive(Debug, Clone)]
+pub enum SummarizerProvider<F: AudioProcessor> {
+    OpenAI(OpenAIClient<F>),
+    Anthropic(AnthropicClient),
+    OpenRouter(OpenRouterClient),
+    #[cfg(feature = "bedrock")]
+    Bedrock(BedrockClient),
+}
+
+impl<F: AudioProcessor> SummarizerProvider<F> {
+    pub fn from_config(config: &SummarizerConfig, ffmpeg: F) -> Result<Self, ProviderError> {
+        match config.provider {
+            SummarizerProviderKind::OpenAI | SummarizerProviderKind::Azure => {
+                let mut client = OpenAIClient::new(&config.api_key, ffmpeg);
+                if let Some(http_client) = &config.http_client {
+                    client = client.with_http_client(http_client.clone());
+                }
+                if let Some(base_url) = &config.base_url {
+                    client = client.with_base_url(base_url);
+                }
+                if let Some(model) = &config.model {
+                    client = client.with_summarizer_model(model);
+                }
+                if let Some(model) = &config.extraction_model {
+                    client = client.with_extraction_model(model);
+                }
+                if let Some(model) = &config.verification_model {
+                    client = client.with_verification_model(model);
+                }
+                if let Some(rate_limiter) = &config.rate_limiter {
+                    client = client.with_rate_limiter(rate_limiter.clone());
+                }
+                if let Some(usage_tracker) = &config.usage_tracker {
+                    client = client.with_usage_tracker(usage_tracker.clone());
+                }
+                if let Some(system_prompt) = &config.system_prompt {
+                    client = client.with_system_prompt(system_prompt.clone());
+                }
+                if config.provider == SummarizerProviderKind::Azure {
+                    client = client.with_endpoint(azure_endpoint(
+                        config.base_url.as_deref(),
+                        config.api_version.as_deref(),
+                    )?);
+                }
+                Ok(SummarizerProvider::OpenAI(
+                    client
+                        .with_structured_output(config.structured_output)
+                        .with_tldr(config.tldr)
+                        .with_streaming(config.streaming)
+                        .with_idempotency_keys(config.idempotency_keys)
+                        .with_completion_options(config.completion_options.clone()),
+                ))
+            }
+            SummarizerProviderKind::Anthropic => {
+                let mut client = AnthropicClient::new(&config.api_key);
+                if let Some(http_client) = &config.http_client {
+                    client = client.with_http_client(http_client.clone());
+                }
+                if let Some(base_url) = &config.base_url {
+                    client = client.with_base_url(base_url);
+                }
+                if let Some(model) = &config.model {
+                    client = client.with_summarizer_model(model);
+                }
+                if let Some(model) = &config.extraction_model {
+                    client = client.with_extraction_model(model);
+                }
+                if let Some(model) = &config.verification_model {
+                    client = client.with_verification_model(model);
+                }
+                if let Some(system_prompt) = &config.system_prompt {
+                    client = client.with_system_prompt(system_prompt.clone());
+                }
+                if let Some(usage_tracker) = &config.usage_tracker {
+                    client = client.with_usage_tracker(usage_tracker.clone());
+                }
+                Ok(SummarizerProvider::Anthropic(
+                    client.with_completion_options(config.completion_options.clone()),
+                ))
+            }
+            SummarizerProviderKind::OpenRouter => {
+                let mut client =
+                    OpenRouterClient::new(&config.api_key).with_routing(config.routing.clone());
+                if let Some(http_client) = &config.http_client {
+                    client = client.with_http_client(http_client.clone());
+                }
+                if let Some(base_url) = &config.base_url {
+                    client = client.with_base_url(base_url);
+                }
+                if let Some(model) = &config.model {
+                    client = client.with_summarizer_model(model);
+                }
+                if let Some(model) = &config.extraction_model {
+                    client = client.with_extraction_model(model);
+                }
+                if let Some(model) = &config.verification_model {
+                    client = client.with_verification_model(model);
+                }
+                if let Some(system_prompt) = &config.system_prompt {
+                    client = client.with_system_prompt(system_prompt.clone());
+                }
+                if let Some(usage_tracker) = &config.usage_tracker {
+                    client = client.with_usage_tracker(usage_tracker.clone());
+                }
+                Ok(SummarizerProvider::OpenRouter(
+                    client
+                        .with_completion_options(config.completion_options.clone())
+                        .with_app("https://github.com/c12i/bunge-bits", "Bunge Bits"),
+                ))
+            }
+            #[cfg(feature = "bedrock")]
+            SummarizerProviderKind::Bedrock => {
+                let (credentials, region) = aws_credentials(config.region.as_deref())?;
+                let mut client = BedrockClient::new(credentials, region);
+                if let Some(http_client) = &config.http_client {
+                    client = client.with_http_client(http_client.clone());
+                }
+                if let Some(base_url) = &config.base_url {
+                    client = client.with_base_url(base_url);
+                }
+                if let Some(model) = &config.model {
+                    client = client.with_summarizer_model(model);
+                }
+                if let Some(model) = &config.extraction_model {
+                    client = client.with_extraction_model(model);
+                }
+                if let Some(model) = &config.verification_model {
+                    client = client.with_verification_model(model);
+                }
+                if let Some(system_prompt) = &config.system_prompt {
+                    client = client.with_system_prompt(system_prompt.clone());
+                }
+                if let Some(usage_tracker) = &config.usage_tracker {
+                    client = client.with_usage_tracker(usage_tracker.clone());
+                }
+                Ok(SummarizerProvider::Bedrock(client.with_completion_options(
+                    config.completion_options.clone(),
+                )))
+            }
+        }
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> Summarizer for SummarizerProvider<F> {
+    // The limits that apply are those of the selected provider, enforced in `summarize`
+    const CONTEXT_WINDOW_LIMIT: usize = <OpenAIClient<F> as Summarizer>::CONTEXT_WINDOW_LIMIT;
+    const SUMMARIZER_MODEL: &'static str = <OpenAIClient<F> as Summarizer>::SUMMARIZER_MODEL;
+
+    type Error = ProviderError;
+
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
+        match self {
+            SummarizerProvider::OpenAI(client) => Ok(client.summarize(content, context).await?),
+            SummarizerProvider::Anthropic(client) => Ok(client.summarize(content, context).await?),
+            SummarizerProvider::OpenRouter(client) => {
+                Ok(client.summarize(content, context).await?)
+            }
+            #[cfg(feature = "bedrock")]
+            SummarizerProvider::Bedrock(client) => Ok(client.summarize(content, context).await?),
+        }
+    }
+
+    fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
+        match self {
+            SummarizerProvider::OpenAI(client) => Ok(client.count_tokens(content)?),
+            SummarizerProvider::Anthropic(client) => Ok(client.count_tokens(content)?),
+            SummarizerProvider::OpenRouter(client) => Ok(client.count_tokens(content)?),
+            #[cfg(feature = "bedrock")]
+            SummarizerProvider::Bedrock(client) => Ok(client.count_tokens(content)?),
+        }
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> EntityExtractor for SummarizerProvider<F> {
+    const EXTRACTION_MODEL: &'static str = <OpenAIClient<F> as EntityExtractor>::EXTRACTION_MODEL;
+
+    type Error = ProviderError;
+
+    async fn extract_entities(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<StreamEntities, Self::Error> {
+        match self {
+            SummarizerProvider::OpenAI(client) => {
+                Ok(client.extract_entities(transcript, context).await?)
+            }
+            SummarizerProvider::Anthropic(client) => {
+                Ok(client.extract_entities(transcript, context).await?)
+            }
+            SummarizerProvider::OpenRouter(client) => {
+                Ok(client.extract_entities(transcript, context).await?)
+            }
+            #[cfg(feature = "bedrock")]
+            SummarizerProvider::Bedrock(client) => {
+                Ok(client.extract_entities(transcript, context).await?)
+            }
+        }
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> DivisionExtractor for SummarizerProvider<F> {
+    const DIVISION_EXTRACTION_MODEL: &'static str =
+        <OpenAIClient<F> as DivisionExtractor>::DIVISION_EXTRACTION_MODEL;
+
+    type Error = ProviderError;
+
+    async fn extract_divisions(
+        &self,
+        transcript: &str,
+        context: &SummaryContext,
+    ) -> Result<Vec<Division>, Self::Error> {
+        match self {
+            SummarizerProvider::OpenAI(client) => {
+                Ok(client.extract_divisions(transcript, context).await?)
+            }
+            SummarizerProvider::Anthropic(client) => {
+                Ok(client.extract_divisions(transcript, context).await?)
+            }
+            SummarizerProvider::OpenRouter(client) => {
+                Ok(client.extract_divisions(transcript, context).await?)
+            }
+            #[cfg(feature = "bedrock")]
+            SummarizerProvider::Bedrock(client) => {
+                Ok(client.extract_divisions(transcript, context).await?)
+            }
+        }
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> SummaryVerifier for SummarizerProvider<F> {
+    const VERIFICATION_MODEL: &'static str =
+        <OpenAIClient<F> as SummaryVerifier>::VERIFICATION_MODEL;
+
+    type Error = ProviderError;
+
+    async fn verify_summary(
+        &self,
+        transcript: &str,
+        summary: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryVerdict, Self::Error> {
+        match self {
+            SummarizerProvider::OpenAI(client) => {
+                Ok(client.verify_summary(transcript, summary, context).await?)
+            }
+            SummarizerProvider::Anthropic(client) => {
+                Ok(client.verify_summary(transcript, summary, context).await?)
+            }
+            SummarizerProvider::OpenRouter(client) => {
+                Ok(client.verify_summary(transcript, summary, context).await?)
+            }
+            #[cfg(feature = "bedrock")]
+            SummarizerProvider::Bedrock(client) => {
+                Ok(client.verify_summary(transcript, summary, context).await?)
+            }
+        }
+    }
+}
+
+impl<F: AudioProcessor + Send + Sync> CategoryClassifier for SummarizerProvider<F> {
+    const CLASSIFICATION_MODEL: &'static str =
+        <OpenAIClient<F> as CategoryClassifier>::CLASSIFICATION_MODEL;
+
+    type Error = ProviderError;
+
+    async fn classify_title(&self, title: &str) -> Result<StreamCategory, Self::Error> {
+        match self {
+            SummarizerProvider::OpenAI(client) => Ok(client.classify_title(title).await?),
+            SummarizerProvider::Anthropic(client) => Ok(client.classify_title(title).await?),
+            SummarizerProvider::OpenRouter(client) => Ok(client.classify_title(title).await?),
+            #[cfg(feature = "bedrock")]
+            SummarizerProvider::Bedrock(client) => Ok(client.classify_title(title).await?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_kind_from_str() {
+        assert_eq!(
+            "openai".parse::<TranscriberProviderKind>().unwrap(),
+            TranscriberProviderKind::OpenAI
+        );
+        assert_eq!(
+            " OpenAI ".parse::<TranscriberProviderKind>().unwrap(),
+            TranscriberProviderKind::OpenAI
+        );
+        assert_eq!(
+            "groq".parse::<TranscriberProviderKind>().unwrap(),
+            TranscriberProviderKind::Groq
+        );
+        assert!(matches!(
+            "unknown".parse::<TranscriberProviderKind>(),
+            Err(ProviderError::UnknownProvider(_))
+        ));
+    }
+
+    #[test]
+    fn test_summarizer_provider_kind_from_str() {
+        assert_eq!(
+            "anthropic".parse::<SummarizerProviderKind>().unwrap(),
+            SummarizerProviderKind::Anthropic
+        );
+        assert_eq!(
+            "openai".parse::<SummarizerProviderKind>().unwrap(),
+            SummarizerProviderKind::OpenAI
+        );
+        assert!("claude".parse::<SummarizerProviderKind>().is_err());
+    }
+
+    #[test]
+    fn test_azure_requires_resource_url() {
+        assert!(matches!(
+            azure_endpoint(None, None),
+            Err(ProviderError::MissingConfig(_))
+        ));
+        assert_eq!(
+            azure_endpoint(Some("https://bunge.openai.azure.com"), None).unwrap(),
+            OpenAIEndpoint::Azure {
+                api_version: OpenAIEndpoint::DEFAULT_AZURE_API_VERSION.into()
+            }
+        );
+    }
+}
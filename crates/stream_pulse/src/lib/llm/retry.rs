@@ -0,0 +1,239 @@
+//! # Retry
+//!
+//! Retry policy for LLM provider requests. Multipart uploads cannot be cloned by
+//! `reqwest-retry`, so requests are rebuilt and resent explicitly instead.
+
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+use reqwest::{header::HeaderMap, StatusCode};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use reqwest_retry_after::RetryAfterMiddleware;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    llm::{rate_limit::RateLimiter, transport::Transport},
+    metrics,
+};
+
+/// Exponential backoff with full jitter, honoring `Retry-After` when present.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Number of retries after the initial attempt
+    pub max_retries: u32,
+    /// Backoff ceiling for the first retry, doubled on each subsequent retry
+    pub base_delay: Duration,
+    /// Upper bound for backoff delays, including those asked for by `Retry-After`
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Jittered delay before retry number `attempt` (zero based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let millis = ceiling.as_millis() as u64;
+        if millis == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(0..=millis))
+    }
+}
+
+/// Wraps `client` in middleware retrying transient failures and honoring `Retry-After`,
+/// for providers whose requests can be cloned
+pub(crate) fn retrying_client(client: reqwest::Client) -> ClientWithMiddleware {
+    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
+    ClientBuilder::new(client)
+        .with(RetryAfterMiddleware::new())
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build()
+}
+
+/// 429s and server errors are worth retrying, everything else is not
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Reads `retry-after-ms` (sent by OpenAI) or `retry-after` in seconds. Values too large
+/// for a [`Duration`] are ignored.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+    };
+
+    header("retry-after-ms")
+        .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok())
+        .or_else(|| header("retry-after").and_then(|s| Duration::try_from_secs_f64(s).ok()))
+}
+
+/// Header providers use to recognise a request they have already processed
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Key derived from a JSON request body. The same request, whether retried after a
+/// network failure or resent when a stream is processed again, always gets the same key.
+pub(crate) fn idempotency_key(body: &serde_json::Value) -> String {
+    Sha256::digest(body.to_string())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Sends the request produced by `build` through `transport`, rebuilding and resending
+/// it on retryable responses and transient transport errors. Every attempt waits for
+/// `tokens` of `rate_limiter`'s budget first, retries counting against it too.
+pub(crate) async fn send_with_retry_via<B>(
+    policy: &RetryPolicy,
+    transport: &dyn Transport,
+    rate_limiter: &RateLimiter,
+    tokens: usize,
+    mut build: B,
+) -> Result<reqwest::Response, reqwest_middleware::Error>
+where
+    B: FnMut() -> RequestBuilder,
+{
+    retry(policy, || {
+        let request = build().build();
+        async move {
+            rate_limiter.acquire(tokens).await;
+            transport.send(request?).await
+        }
+    })
+    .await
+}
+
+/// Sends the request `send` makes, making it again on retryable responses and transient
+/// transport errors, e.g. for streamed bodies that have to be reopened for each attempt
+pub(crate) async fn retry<S, Fut>(
+    policy: &RetryPolicy,
+    mut send: S,
+) -> Result<reqwest::Response, reqwest_middleware::Error>
+where
+    S: FnMut() -> Fut,
+    Fut: Future<Output = Result<reqwest::Response, reqwest_middleware::Error>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        let result = send().await;
+
+        let delay = match &result {
+            Ok(resp) if is_retryable_status(resp.status()) && attempt < policy.max_retries => {
+                let delay = retry_after(resp.headers())
+                    .map(|delay| delay.min(policy.max_delay))
+                    .unwrap_or_else(|| policy.backoff(attempt));
+                tracing::warn!(
+                    status = resp.status().as_u16(),
+                    attempt = attempt + 1,
+                    max_retries = policy.max_retries,
+                    delay = ?delay,
+                    "Retryable response from provider"
+                );
+                metrics::api_retried("status");
+                Some(delay)
+            }
+            Err(reqwest_middleware::Error::Reqwest(e))
+                if (e.is_timeout() || e.is_connect()) && attempt < policy.max_retries =>
+            {
+                let delay = policy.backoff(attempt);
+                tracing::warn!(
+                    error = %e,
+                    attempt = attempt + 1,
+                    max_retries = policy.max_retries,
+                    delay = ?delay,
+                    "Transient request failure"
+                );
+                metrics::api_retried("transport");
+                Some(delay)
+            }
+            _ => None,
+        };
+
+        let Some(delay) = delay else {
+            return result;
+        };
+
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff_is_bounded() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+
+        for attempt in 0..10 {
+            let ceiling = Duration::from_millis(100 * 2_u64.pow(attempt)).min(policy.max_delay);
+            assert!(policy.backoff(attempt) <= ceiling);
+        }
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn test_idempotency_key_follows_body() {
+        let body = serde_json::json!({ "model": "gpt-4o", "messages": [] });
+        let key = idempotency_key(&body);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, idempotency_key(&body.clone()));
+        assert_ne!(
+            key,
+            idempotency_key(&serde_json::json!({ "model": "gpt-4o-mini", "messages": [] }))
+        );
+    }
+
+    #[test]
+    fn test_retry_after_prefers_milliseconds() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("250"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(250)));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("1e300"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+    }
+}
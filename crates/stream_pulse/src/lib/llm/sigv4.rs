@@ -0,0 +1,232 @@
+//! # SigV4
+//!
+//! AWS Signature Version 4 request signing for the Bedrock, Transcribe and S3 APIs.
+//! See <https://docs.aws.amazon.com/IAM/latest/UserGuide/create-signed-request.html>.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use sha2::{Digest, Sha256};
+
+use crate::llm::providers::bedrock::AwsCredentials;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Payload hash of requests whose bodies are streamed
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Signs `request` for `service` in `region`, adding the `Authorization`,
+/// `x-amz-date` and, for temporary credentials, `x-amz-security-token` headers.
+/// Every header already set on the request is signed.
+pub(crate) fn sign(
+    request: &mut reqwest::Request,
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    time: DateTime<Utc>,
+) {
+    let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = &amz_date[..8];
+    // streamed bodies can't be hashed up front, which S3 allows over HTTPS
+    let payload_hash = match request.body().map(|body| body.as_bytes()) {
+        Some(None) => UNSIGNED_PAYLOAD.to_string(),
+        Some(Some(bytes)) => hex(&Sha256::digest(bytes)),
+        None => hex(&Sha256::digest(b"")),
+    };
+
+    let mut insert = |name: &'static str, value: &str| {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            request
+                .headers_mut()
+                .insert(HeaderName::from_static(name), value);
+        }
+    };
+    insert("x-amz-date", &amz_date);
+    if let Some(token) = &credentials.session_token {
+        insert("x-amz-security-token", token);
+    }
+    // S3 refuses requests without it, other services don't need it
+    if service == "s3" {
+        insert("x-amz-content-sha256", &payload_hash);
+    }
+
+    let url = request.url();
+    let mut headers = BTreeMap::new();
+    if let Some(host) = url.host_str() {
+        let host = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        headers.insert("host".to_string(), host);
+    }
+    for (name, value) in request.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes()).trim().to_string();
+        headers
+            .entry(name.as_str().to_ascii_lowercase())
+            .and_modify(|existing: &mut String| {
+                existing.push(',');
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+    let canonical_headers = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect::<String>();
+
+    // S3 paths are used as they are, other services encode each segment a second time
+    let canonical_uri = match service {
+        "s3" => url.path().to_string(),
+        _ => url
+            .path()
+            .split('/')
+            .map(|segment| uri_encode(segment, true))
+            .collect::<Vec<_>>()
+            .join("/"),
+    };
+    let mut query = url
+        .query_pairs()
+        .map(|(k, v)| (uri_encode(&k, true), uri_encode(&v, true)))
+        .collect::<Vec<_>>();
+    query.sort();
+    let canonical_query = query
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let canonical_request = format!(
+        "{}\n{canonical_uri}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}",
+        request.method()
+    );
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request))
+    );
+
+    let key = [region, service, "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            date,
+        ),
+        |key, part| hmac_sha256(&key, part),
+    );
+    let signature = hex(&hmac_sha256(&key, &string_to_sign));
+
+    let authorization = format!(
+        "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    );
+    if let Ok(value) = HeaderValue::from_str(&authorization) {
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+}
+
+/// Percent-encodes everything but unreserved characters, and `/` unless `encode_slash`
+pub(crate) fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn hmac_sha256(key: &[u8], message: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn credentials() -> AwsCredentials {
+        AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY")
+    }
+
+    fn time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap()
+    }
+
+    #[test]
+    fn test_get_vanilla() {
+        // from the AWS SigV4 test suite
+        let mut request = reqwest::Request::new(
+            reqwest::Method::GET,
+            "https://example.amazonaws.com/".parse().unwrap(),
+        );
+        sign(&mut request, &credentials(), "us-east-1", "service", time());
+
+        assert_eq!(
+            request.headers()[AUTHORIZATION],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+        assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+    }
+
+    #[test]
+    fn test_path_segments_are_encoded_twice() {
+        let mut request = reqwest::Request::new(
+            reqwest::Method::POST,
+            "https://bedrock-runtime.us-east-1.amazonaws.com/model/anthropic.claude-v2%3A1/converse"
+                .parse()
+                .unwrap(),
+        );
+        request
+            .headers_mut()
+            .insert("content-type", HeaderValue::from_static("application/json"));
+        *request.body_mut() = Some(r#"{"a":1}"#.into());
+        sign(&mut request, &credentials(), "us-east-1", "bedrock", time());
+
+        let authorization = request.headers()[AUTHORIZATION].to_str().unwrap();
+        assert!(authorization.contains("SignedHeaders=content-type;host;x-amz-date,"));
+        assert!(authorization.ends_with(
+            "Signature=dc7a6912d154b37525f30cd184a866f2550faaa57bce09355b583a94512397a6"
+        ));
+    }
+
+    #[test]
+    fn test_streamed_s3_bodies_are_unsigned() {
+        let mut request = reqwest::Request::new(
+            reqwest::Method::PUT,
+            "https://audio.s3.us-east-1.amazonaws.com/stream-pulse/a.mp3"
+                .parse()
+                .unwrap(),
+        );
+        let chunks = futures::stream::iter([Ok::<_, std::io::Error>(vec![0_u8; 16])]);
+        *request.body_mut() = Some(reqwest::Body::wrap_stream(chunks));
+        sign(&mut request, &credentials(), "us-east-1", "s3", time());
+        assert_eq!(request.headers()["x-amz-content-sha256"], UNSIGNED_PAYLOAD);
+
+        *request.body_mut() = Some("audio".into());
+        sign(&mut request, &credentials(), "us-east-1", "s3", time());
+        assert_ne!(request.headers()["x-amz-content-sha256"], UNSIGNED_PAYLOAD);
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(
+            uri_encode("anthropic.claude-v2:1", true),
+            "anthropic.claude-v2%3A1"
+        );
+        assert_eq!(uri_encode("audio/a b.mp3", false), "audio/a%20b.mp3");
+    }
+}
@@ -0,0 +1,68 @@
+//! # Server-Sent Events
+//!
+//! Minimal incremental parser for the `text/event-stream` bodies returned by
+//! streaming chat completion endpoints.
+
+/// Accumulates raw body bytes and yields the `data` payload of each complete event
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    // kept as bytes so multi-byte characters split across pushes are not mangled
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    /// Feeds `bytes` into the parser, returning the data of every event completed by them.
+    /// Comments, `event:` and `id:` fields are ignored.
+    pub(crate) fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend(bytes.iter().filter(|b| **b != b'\r'));
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let raw = String::from_utf8_lossy(&self.buffer[..end]).into_owned();
+            self.buffer.drain(..end + 2);
+
+            let data = raw
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect::<Vec<_>>();
+
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_split_across_pushes() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"data: {\"a\"").is_empty());
+        assert_eq!(
+            parser.push(b":1}\n\ndata: [DONE]\n\n"),
+            vec!["{\"a\":1}", "[DONE]"]
+        );
+    }
+
+    #[test]
+    fn test_multibyte_characters_split_across_pushes() {
+        let mut parser = SseParser::default();
+        let bytes = "data: Wetang’ula\n\n".as_bytes();
+        let (head, tail) = bytes.split_at(13);
+        assert!(parser.push(head).is_empty());
+        assert_eq!(parser.push(tail), vec!["Wetang’ula"]);
+    }
+
+    #[test]
+    fn test_comments_and_crlf_are_handled() {
+        let mut parser = SseParser::default();
+        let events = parser.push(b": keep-alive\r\n\r\nevent: message\r\ndata: hello\r\n\r\n");
+        assert_eq!(events, vec!["hello"]);
+    }
+}
@@ -1,6 +1,12 @@
-use std::{fmt::Debug, future::Future};
+use std::{fmt::Debug, future::Future, str::FromStr};
 
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use chrono_tz::Africa::Nairobi;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use stream_datastore::{Chapter, Stream, StreamCategory, StructuredSummary, SummaryVerification};
+
+use crate::PromptVariables;
 
 pub trait Summarizer {
     const CONTEXT_WINDOW_LIMIT: usize;
@@ -11,6 +17,7 @@ pub trait Summarizer {
     fn summarize(
         &self,
         content: &str,
+        context: &SummaryContext,
     ) -> impl Future<Output = Result<SummaryResponse, Self::Error>> + Send;
 
     fn count_tokens(&self, _content: &str) -> Result<usize, Self::Error> {
@@ -18,8 +25,451 @@ pub trait Summarizer {
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// Metadata about the sitting being summarized, used to ground the summary
+/// instead of leaving the model to guess dates and chambers
+#[derive(Debug, Clone, Default)]
+pub struct SummaryContext {
+    pub title: String,
+    /// Start of the stream. Approximate unless it was resolved from the watch page,
+    /// being otherwise inferred from YouTube's "time ago" text
+    pub streamed_at: Option<DateTime<Utc>>,
+    /// Stream length as displayed by YouTube, e.g. "3:12:45"
+    pub duration: Option<String>,
+    /// "National Assembly" or "Senate", if it can be told from the title
+    pub house: Option<String>,
+    /// Video description, which often lists the order paper's items
+    pub description: Option<String>,
+    /// Chapter markers listed in the description
+    pub chapters: Vec<Chapter>,
+    /// Items of business on the sitting's order paper, when one was found
+    pub agenda: Vec<String>,
+}
+
+impl From<&Stream> for SummaryContext {
+    fn from(stream: &Stream) -> Self {
+        let house = match stream.category() {
+            StreamCategory::Committee | StreamCategory::Other => None,
+            category => Some(category.to_string()),
+        };
+        Self {
+            title: stream.title.clone(),
+            streamed_at: stream.published_at(),
+            duration: Some(stream.duration.clone()).filter(|d| !d.is_empty()),
+            house,
+            description: stream.description.clone(),
+            chapters: stream
+                .chapters
+                .as_ref()
+                .map(|chapters| chapters.0.clone())
+                .unwrap_or_default(),
+            agenda: stream
+                .order_paper
+                .as_ref()
+                .map(|order_paper| order_paper.0.agenda.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl SummaryContext {
+    /// Variables for rendering a [`PromptTemplate`](crate::PromptTemplate)
+    pub fn prompt_variables(&self) -> PromptVariables {
+        PromptVariables {
+            title: Some(self.title.clone()).filter(|t| !t.is_empty()),
+            date: self
+                .streamed_at
+                .map(|date| date.with_timezone(&Nairobi).format("%-d %B %Y").to_string()),
+            house: self.house.clone(),
+            duration: self.duration.clone(),
+            description: self.description.clone(),
+            chapters: Some(self.chapters.iter().join("; ")).filter(|c| !c.is_empty()),
+            agenda: Some(self.agenda.join("; ")).filter(|a| !a.is_empty()),
+        }
+    }
+}
+
+/// Per-deployment chat completion settings. Sampling parameters left unset are not
+/// sent, so the provider's defaults apply.
+#[derive(Debug, Clone)]
+pub struct CompletionOptions {
+    /// Ground summaries with web search. Must be `None` for models without search
+    /// support, such as `gpt-4o` or `o3-mini` on OpenAI
+    pub web_search: Option<WebSearchOptions>,
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    /// Upper bound on generated tokens. Overrides the Anthropic and Bedrock clients'
+    /// own `max_tokens`
+    pub max_tokens: Option<u32>,
+    /// Best-effort deterministic sampling, honoured by OpenAI and OpenRouter
+    pub seed: Option<u64>,
+    /// Honoured by OpenAI and OpenRouter
+    pub frequency_penalty: Option<f64>,
+}
+
+impl Default for CompletionOptions {
+    /// Searches from Nairobi, as expected by the default search-preview model
+    fn default() -> Self {
+        Self {
+            web_search: Some(WebSearchOptions::default()),
+            ..Self::without_web_search()
+        }
+    }
+}
+
+impl CompletionOptions {
+    /// Completions without any tools, for models that don't support web search
+    pub fn without_web_search() -> Self {
+        Self {
+            web_search: None,
+            temperature: None,
+            top_p: None,
+            max_tokens: None,
+            seed: None,
+            frequency_penalty: None,
+        }
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_top_p(mut self, top_p: f64) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_frequency_penalty(mut self, frequency_penalty: f64) -> Self {
+        self.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    /// Adds the configured sampling parameters to an OpenAI-compatible chat completion `body`
+    pub(crate) fn apply_sampling(&self, body: &mut serde_json::Value) {
+        if let Some(temperature) = self.temperature {
+            body["temperature"] = temperature.into();
+        }
+        if let Some(top_p) = self.top_p {
+            body["top_p"] = top_p.into();
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            body["max_tokens"] = max_tokens.into();
+        }
+        if let Some(seed) = self.seed {
+            body["seed"] = seed.into();
+        }
+        if let Some(frequency_penalty) = self.frequency_penalty {
+            body["frequency_penalty"] = frequency_penalty.into();
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebSearchOptions {
+    /// How much search context is retrieved, currently honoured by the OpenAI provider
+    pub context_size: SearchContextSize,
+    /// Upper bound on searches or results per request, honoured by Anthropic and OpenRouter
+    pub max_results: u32,
+    /// Approximate location search results are localized to
+    pub user_location: Option<UserLocation>,
+}
+
+impl Default for WebSearchOptions {
+    fn default() -> Self {
+        Self {
+            context_size: SearchContextSize::default(),
+            max_results: 5,
+            user_location: Some(UserLocation::default()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserLocation {
+    /// ISO 3166-1 alpha-2 country code, e.g. "KE"
+    pub country: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// IANA time zone, e.g. "Africa/Nairobi"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+impl Default for UserLocation {
+    fn default() -> Self {
+        Self {
+            country: "KE".into(),
+            city: Some("Nairobi".into()),
+            region: Some("Nairobi".into()),
+            timezone: Some("Africa/Nairobi".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchContextSize {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl SearchContextSize {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchContextSize::Low => "low",
+            SearchContextSize::Medium => "medium",
+            SearchContextSize::High => "high",
+        }
+    }
+}
+
+impl FromStr for SearchContextSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "low" => Ok(SearchContextSize::Low),
+            "medium" => Ok(SearchContextSize::Medium),
+            "high" => Ok(SearchContextSize::High),
+            other => Err(format!("Unsupported search context size: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SummaryResponse {
     // define based on your prompt structure
     pub summary: String,
+    /// Populated by summarizers running in structured output mode
+    #[serde(default)]
+    pub structured: Option<StructuredSummary>,
+    /// One-paragraph summary for social media, populated by summarizers with TL;DRs enabled
+    #[serde(default)]
+    pub tldr: Option<String>,
+    /// Populated by [`VerifiedSummarizer`](crate::VerifiedSummarizer)
+    #[serde(default)]
+    pub verification: Option<SummaryVerification>,
+}
+
+/// Summarizes `content` in a single request when it fits within the summarizer's
+/// context window. Otherwise each part of the transcript is summarized on its own (map)
+/// and the partial summaries are then merged into one (reduce).
+#[tracing::instrument(skip_all)]
+pub async fn summarize_transcript<S: Summarizer + Sync>(
+    summarizer: &S,
+    content: &str,
+    context: &SummaryContext,
+) -> Result<SummaryResponse, S::Error> {
+    let budget = S::CONTEXT_WINDOW_LIMIT;
+    // leave room for the header prepended to every part
+    let part_budget = budget.saturating_sub(summarizer.count_tokens(&part_header(999, 999))?);
+    let mut content = content.to_string();
+    let mut previous_token_count = None;
+
+    loop {
+        let token_count = summarizer.count_tokens(&content)?;
+        if token_count <= budget {
+            return summarizer.summarize(&content, context).await;
+        }
+
+        // bail out if a reduce pass stopped making progress, and let the provider decide
+        if previous_token_count.is_some_and(|previous| token_count >= previous) {
+            return summarizer.summarize(&content, context).await;
+        }
+        previous_token_count = Some(token_count);
+
+        let parts = split_to_fit(summarizer, &content, token_count, part_budget)?;
+        if parts.len() < 2 {
+            return summarizer.summarize(&content, context).await;
+        }
+
+        tracing::info!(
+            token_count,
+            budget,
+            parts = parts.len(),
+            "Transcript exceeds context window, summarizing in parts"
+        );
+
+        let total = parts.len();
+        let mut partial_summaries = Vec::with_capacity(total);
+        for (idx, part) in parts.iter().enumerate() {
+            let part = format!("{}\n\n{part}", part_header(idx + 1, total));
+            partial_summaries.push(summarizer.summarize(&part, context).await?.summary);
+        }
+
+        content = format!(
+            "The following are summaries of consecutive parts of the same sitting. \
+             Merge them into a single summary:\n\n{}",
+            partial_summaries.join("\n\n---\n\n")
+        );
+    }
+}
+
+fn part_header(part: usize, total: usize) -> String {
+    format!("Part {part} of {total} of the sitting's transcript:")
+}
+
+/// Splits `content` on word boundaries into the fewest equal-sized parts
+/// that each fit within `budget` tokens.
+fn split_to_fit<S: Summarizer>(
+    summarizer: &S,
+    content: &str,
+    token_count: usize,
+    budget: usize,
+) -> Result<Vec<String>, S::Error> {
+    let words = content.split_whitespace().collect::<Vec<_>>();
+    let mut parts_count = token_count.div_ceil(budget.max(1)).max(2);
+
+    loop {
+        let chunk_len = words.len().div_ceil(parts_count).max(1);
+        let parts = words
+            .chunks(chunk_len)
+            .map(|chunk| chunk.join(" "))
+            .collect::<Vec<_>>();
+
+        let mut fits = true;
+        for part in &parts {
+            if summarizer.count_tokens(part)? > budget {
+                fits = false;
+                break;
+            }
+        }
+
+        if fits || chunk_len == 1 {
+            return Ok(parts);
+        }
+        parts_count += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use stream_datastore::{Json, OrderPaper};
+
+    /// Counts whitespace separated words as tokens
+    struct WordSummarizer {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl Summarizer for WordSummarizer {
+        const CONTEXT_WINDOW_LIMIT: usize = 40;
+        const SUMMARIZER_MODEL: &'static str = "words";
+
+        type Error = ();
+
+        async fn summarize(
+            &self,
+            content: &str,
+            _context: &SummaryContext,
+        ) -> Result<SummaryResponse, Self::Error> {
+            self.calls.lock().unwrap().push(content.to_string());
+            Ok(SummaryResponse {
+                summary: "summary".into(),
+                structured: None,
+                tldr: None,
+                verification: None,
+            })
+        }
+
+        fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
+            Ok(content.split_whitespace().count())
+        }
+    }
+
+    #[test]
+    fn test_only_configured_sampling_parameters_are_sent() {
+        let mut body = serde_json::json!({ "model": "gpt-4o" });
+        CompletionOptions::without_web_search()
+            .with_temperature(0.2)
+            .with_seed(42)
+            .apply_sampling(&mut body);
+
+        assert_eq!(
+            body,
+            serde_json::json!({ "model": "gpt-4o", "temperature": 0.2, "seed": 42 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_short_transcript_is_summarized_once() {
+        let summarizer = WordSummarizer {
+            calls: Mutex::new(Vec::new()),
+        };
+        summarize_transcript(
+            &summarizer,
+            "a short transcript",
+            &SummaryContext::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summarizer.calls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_long_transcript_is_map_reduced() {
+        let summarizer = WordSummarizer {
+            calls: Mutex::new(Vec::new()),
+        };
+        let transcript = vec!["word"; 100].join(" ");
+        summarize_transcript(&summarizer, &transcript, &SummaryContext::default())
+            .await
+            .unwrap();
+
+        let calls = summarizer.calls.lock().unwrap();
+        // every call, including the parts, must fit within the context window
+        assert!(calls.len() > 2);
+        for call in calls.iter() {
+            assert!(call.split_whitespace().count() <= WordSummarizer::CONTEXT_WINDOW_LIMIT);
+        }
+        assert!(calls
+            .last()
+            .unwrap()
+            .contains("Merge them into a single summary"));
+    }
+
+    #[test]
+    fn test_context_from_stream() {
+        let stream = Stream {
+            title: "National Assembly | Afternoon Sitting".into(),
+            streamed_date: "2 days ago".into(),
+            duration: "3:12:45".into(),
+            ..Default::default()
+        };
+
+        let variables = SummaryContext::from(&stream).prompt_variables();
+        assert_eq!(variables.house.as_deref(), Some("National Assembly"));
+        assert_eq!(variables.duration.as_deref(), Some("3:12:45"));
+        assert!(variables.date.is_some());
+        assert!(variables.chapters.is_none());
+        assert!(variables.agenda.is_none());
+
+        let stream = Stream {
+            order_paper: Some(Json(OrderPaper {
+                url: "https://www.parliament.go.ke/order-paper.pdf".into(),
+                agenda: vec!["PRAYERS".into(), "PAPERS".into()],
+            })),
+            ..stream
+        };
+        let variables = SummaryContext::from(&stream).prompt_variables();
+        assert_eq!(variables.agenda.as_deref(), Some("PRAYERS; PAPERS"));
+
+        let context = SummaryContext::from(&Stream::default());
+        assert!(context.house.is_none());
+        assert!(context.prompt_variables().title.is_none());
+    }
 }
@@ -0,0 +1,146 @@
+//! # Timestamps
+//!
+//! Lets summaries cite the moment a point was made. The transcript is given to the
+//! summarizer with `[HH:MM:SS]` offsets taken from its segments, and the offsets the
+//! model cites are then turned into links that open the stream at that moment.
+
+use std::{fmt::Write, sync::LazyLock};
+
+use regex::{Captures, Regex};
+
+use crate::TranscribeResponse;
+
+/// Segments are only a few seconds long, so offsets are stamped at most this often
+/// to keep the overhead on the context window small
+const STAMP_INTERVAL_SECONDS: f64 = 30.0;
+
+/// `[HH:MM:SS]` or `[MM:SS]`
+static TIMESTAMP_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[(?:(\d{1,2}):)?(\d{1,2}):(\d{2})\]").expect("timestamp regex is valid")
+});
+
+const PREAMBLE: &str = "Each paragraph of the transcript below starts with its offset into the \
+    stream as [HH:MM:SS]. After each key point in the summary, cite the offset of the paragraph \
+    it comes from in the same form, e.g. [01:02:03].";
+
+/// The transcript with an offset at the start of each paragraph, preceded by instructions
+/// for citing them. `None` when the transcript has no segment timestamps.
+pub(crate) fn timestamped_transcript(transcript: &TranscribeResponse) -> Option<String> {
+    let segments = transcript.segments.as_ref().filter(|s| !s.is_empty())?;
+
+    let mut content = String::from(PREAMBLE);
+    let mut last_stamp = None;
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if last_stamp.is_none_or(|last| segment.start - last >= STAMP_INTERVAL_SECONDS) {
+            let _ = write!(content, "\n\n[{}] ", format_offset(segment.start));
+            last_stamp = Some(segment.start);
+        } else {
+            content.push(' ');
+        }
+        content.push_str(text);
+    }
+
+    Some(content)
+}
+
+/// Turns the `[HH:MM:SS]` offsets cited in `markdown` into links to that moment of the
+/// YouTube video `video_id`. Offsets that are already links are left as they are.
+pub(crate) fn link_timestamps(markdown: &str, video_id: &str) -> String {
+    TIMESTAMP_RE
+        .replace_all(markdown, |caps: &Captures| {
+            let matched = caps.get(0).expect("group 0 always matches");
+            let already_linked = markdown[matched.end()..].starts_with('(');
+
+            match parse_offset(caps) {
+                Some(seconds) if !already_linked => format!(
+                    "{}(https://youtu.be/{video_id}?t={seconds})",
+                    matched.as_str()
+                ),
+                _ => matched.as_str().to_string(),
+            }
+        })
+        .into_owned()
+}
+
+fn format_offset(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn parse_offset(caps: &Captures) -> Option<u64> {
+    let group = |i| caps.get(i).map(|m| m.as_str().parse::<u64>().ok());
+    let hours = group(1).unwrap_or(Some(0))?;
+    let minutes = group(2)??;
+    let seconds = group(3)??;
+
+    // minutes may run past 59 only when no hours are given, e.g. [75:30]
+    if seconds >= 60 || (caps.get(1).is_some() && minutes >= 60) {
+        return None;
+    }
+    Some(hours * 3600 + minutes * 60 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::transcriber::TranscribeSegment;
+
+    #[test]
+    fn test_timestamps_are_linked() {
+        let markdown = "- The Finance Bill was read a second time [01:02:03]\n\
+                        - Recess [5:07]\n\
+                        - Already linked [00:00:10](https://youtu.be/abc?t=10)\n\
+                        - Invalid [00:75:00]";
+
+        assert_eq!(
+            link_timestamps(markdown, "abc"),
+            "- The Finance Bill was read a second time \
+             [01:02:03](https://youtu.be/abc?t=3723)\n\
+             - Recess [5:07](https://youtu.be/abc?t=307)\n\
+             - Already linked [00:00:10](https://youtu.be/abc?t=10)\n\
+             - Invalid [00:75:00]"
+        );
+    }
+
+    #[test]
+    fn test_transcript_is_stamped_at_intervals() {
+        let segment = |start: f64, text: &str| TranscribeSegment {
+            start,
+            end: start + 5.0,
+            text: text.into(),
+            ..Default::default()
+        };
+        let transcript = TranscribeResponse {
+            duration: 3_700.0,
+            text: String::new(),
+            segments: Some(vec![
+                segment(0.0, "Order."),
+                segment(5.0, "Prayers."),
+                segment(3_661.0, "Adjourned."),
+            ]),
+        };
+
+        let content = timestamped_transcript(&transcript).unwrap();
+        assert!(content.ends_with("\n\n[00:00:00] Order. Prayers.\n\n[01:01:01] Adjourned."));
+    }
+
+    #[test]
+    fn test_transcript_without_segments_is_not_stamped() {
+        let transcript = TranscribeResponse {
+            duration: 0.0,
+            text: "Order.".into(),
+            segments: Some(Vec::new()),
+        };
+        assert!(timestamped_transcript(&transcript).is_none());
+    }
+}
@@ -1,6 +1,17 @@
-use std::{fmt::Debug, future::Future, path::PathBuf};
+use std::{
+    fmt::Debug,
+    future::Future,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
-use serde::Deserialize;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ytdlp_bindings::AudioProcessor;
+
+use crate::{llm::glossary::Glossary, ProcessorEvents};
 
 pub trait Transcriber {
     const TRANSCRIBER_MODEL: &'static str;
@@ -17,22 +28,760 @@ pub trait Transcriber {
 pub enum AudioInput {
     Chunked {
         chunk_duration_seconds: u16,
+        /// Chunks are shortened below `chunk_duration_seconds` to stay under this size,
+        /// going by the file's bit rate
+        max_chunk_bytes: Option<u64>,
         chunks_dir_path: PathBuf,
         file_path: PathBuf,
     },
     File(PathBuf),
 }
 
-#[derive(Debug, Deserialize)]
+/// Per-deployment transcription settings
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptionOptions {
+    /// Overrides [`Transcriber::TRANSCRIBER_MODEL`]
+    pub model: Option<String>,
+    /// ISO-639-1 language of the audio, e.g. "en" or "sw". Detected automatically when unset
+    pub language: Option<String>,
+    /// Sampling temperature between 0 and 1
+    pub temperature: Option<f32>,
+    pub response_format: TranscriptionResponseFormat,
+    /// Names and terms to prime the transcription of the first chunk with
+    pub glossary: Option<Glossary>,
+    /// Drop segments that are likely hallucinations, currently honoured by every provider.
+    /// Requires the `verbose_json` response format.
+    pub segment_filter: Option<SegmentFilter>,
+    /// Words from the end of each chunk's transcript to prime the next chunk with.
+    /// Overrides [`Self::DEFAULT_CONTEXT_WORDS`], 0 disables priming.
+    pub context_words: Option<usize>,
+    /// Longest the transcription of each chunk may take, retries included, currently
+    /// honoured by the OpenAI and Groq providers
+    pub chunk_timeout: Option<Duration>,
+}
+
+/// Awaits the transcription of a chunk, failing with `timed_out` if it takes longer
+/// than `timeout`. Dropping the request on timeout cancels it.
+pub(crate) async fn within_chunk_timeout<E>(
+    timeout: Option<Duration>,
+    request: impl Future<Output = Result<TranscribeResponse, E>>,
+    timed_out: impl FnOnce(Duration) -> E,
+) -> Result<TranscribeResponse, E> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, request)
+            .await
+            .unwrap_or_else(|_| Err(timed_out(timeout))),
+        None => request.await,
+    }
+}
+
+impl TranscriptionOptions {
+    /// Whisper only considers the last 224 tokens of its prompt, and most words are one
+    /// or two tokens
+    pub const DEFAULT_CONTEXT_WORDS: usize = 100;
+
+    pub(crate) fn context_words(&self) -> usize {
+        self.context_words.unwrap_or(Self::DEFAULT_CONTEXT_WORDS)
+    }
+
+    /// Prompt for the first chunk, if a glossary is set
+    pub(crate) fn glossary_prompt(&self) -> Option<String> {
+        self.glossary.as_ref().and_then(Glossary::prompt)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TranscriptionResponseFormat {
+    /// Includes duration and timestamped segments
+    #[default]
+    VerboseJson,
+    /// Text only, for models that do not support `verbose_json` such as `gpt-4o-transcribe`
+    Json,
+}
+
+impl TranscriptionResponseFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptionResponseFormat::VerboseJson => "verbose_json",
+            TranscriptionResponseFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for TranscriptionResponseFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "verbose_json" => Ok(TranscriptionResponseFormat::VerboseJson),
+            "json" => Ok(TranscriptionResponseFormat::Json),
+            other => Err(format!(
+                "Unsupported transcription response format: {other}"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscribeResponse {
     pub duration: f64,
     pub text: String,
     pub segments: Option<Vec<TranscribeSegment>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TranscribeSegment {
     pub start: f64,
     pub end: f64,
     pub text: String,
+    /// Average log probability of the segment's tokens
+    #[serde(default)]
+    pub avg_logprob: Option<f64>,
+    /// Probability that the segment's audio contains no speech
+    #[serde(default)]
+    pub no_speech_prob: Option<f64>,
+    /// gzip compression ratio of the segment's text, high for repetitive text
+    #[serde(default)]
+    pub compression_ratio: Option<f64>,
+}
+
+/// Thresholds past which a segment is taken to be hallucinated, as Whisper tends to do
+/// over long silences. The defaults are the thresholds Whisper itself decodes with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentFilter {
+    /// Segments more compressible than this are repeating themselves
+    pub max_compression_ratio: f64,
+    /// Segments more likely than this to be silence...
+    pub max_no_speech_prob: f64,
+    /// ...are dropped when their average log probability is also below this
+    pub min_avg_logprob: f64,
+}
+
+impl Default for SegmentFilter {
+    fn default() -> Self {
+        Self {
+            max_compression_ratio: 2.4,
+            max_no_speech_prob: 0.6,
+            min_avg_logprob: -1.0,
+        }
+    }
+}
+
+impl SegmentFilter {
+    /// Segments without confidence metrics are never taken to be hallucinated
+    pub fn is_hallucinated(&self, segment: &TranscribeSegment) -> bool {
+        let repetitive = segment
+            .compression_ratio
+            .is_some_and(|ratio| ratio > self.max_compression_ratio);
+        let silent = segment
+            .no_speech_prob
+            .is_some_and(|prob| prob > self.max_no_speech_prob)
+            && segment
+                .avg_logprob
+                .is_some_and(|logprob| logprob < self.min_avg_logprob);
+        repetitive || silent
+    }
+
+    /// Drops hallucinated segments from `response`, rebuilding its text from the rest
+    pub(crate) fn apply(&self, mut response: TranscribeResponse) -> TranscribeResponse {
+        let Some(segments) = response.segments.take() else {
+            return response;
+        };
+
+        let total = segments.len();
+        let kept = segments
+            .into_iter()
+            .filter(|segment| {
+                let hallucinated = self.is_hallucinated(segment);
+                if hallucinated {
+                    tracing::debug!(
+                        start = segment.start,
+                        text = %segment.text,
+                        "Dropping hallucinated segment"
+                    );
+                }
+                !hallucinated
+            })
+            .collect::<Vec<_>>();
+
+        if kept.len() < total {
+            tracing::info!(
+                dropped = total - kept.len(),
+                total,
+                "Dropped hallucinated segments"
+            );
+            response.text = kept
+                .iter()
+                .map(|segment| segment.text.trim())
+                .filter(|text| !text.is_empty())
+                .join(" ");
+        }
+        response.segments = Some(kept);
+        response
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChunkingError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("FFmpeg error: {0}")]
+    Ffmpeg(String),
+}
+
+/// Length to split `file_path` into chunks of: `chunk_duration_seconds`, or shorter if
+/// chunks that long would be over `max_chunk_bytes` at the file's bit rate
+pub(crate) fn chunk_duration<F: AudioProcessor>(
+    ffmpeg: &F,
+    file_path: &Path,
+    chunk_duration_seconds: u16,
+    max_chunk_bytes: Option<u64>,
+) -> Result<u16, ChunkingError> {
+    let Some(max_chunk_bytes) = max_chunk_bytes else {
+        return Ok(chunk_duration_seconds);
+    };
+    let bit_rate = ffmpeg
+        .bit_rate(file_path)
+        .map_err(|e| ChunkingError::Ffmpeg(e.to_string()))?;
+    let duration = chunk_duration_within(chunk_duration_seconds, max_chunk_bytes, bit_rate);
+    if duration < chunk_duration_seconds {
+        tracing::info!(
+            bit_rate,
+            max_chunk_bytes,
+            chunk_duration_seconds = duration,
+            "Shortening chunks to stay under the size limit"
+        );
+    }
+    Ok(duration)
+}
+
+/// Longest duration up to `chunk_duration_seconds` whose audio at `bit_rate` bits per
+/// second fits in `max_chunk_bytes`, leaving a tenth of it as headroom for the bit rate
+/// varying within the file
+fn chunk_duration_within(chunk_duration_seconds: u16, max_chunk_bytes: u64, bit_rate: u64) -> u16 {
+    if bit_rate == 0 {
+        return chunk_duration_seconds;
+    }
+    let seconds = max_chunk_bytes.saturating_mul(8) / 10 * 9 / bit_rate;
+    seconds.clamp(1, chunk_duration_seconds.max(1) as u64) as u16
+}
+
+/// Splits `file_path` into fixed-length mp3 chunks inside `chunks_dir_path`, unless an
+/// earlier run already split the same audio into chunks of the same length, and returns
+/// the chunk paths in playback order.
+pub(crate) fn prepare_chunks<F: AudioProcessor>(
+    ffmpeg: &F,
+    file_path: &Path,
+    chunks_dir_path: &Path,
+    chunk_duration_seconds: u16,
+) -> Result<Vec<PathBuf>, ChunkingError> {
+    let source_bytes = std::fs::metadata(file_path)?.len();
+    if let Some(manifest) = ChunkManifest::load(chunks_dir_path) {
+        let chunks = list_chunks(chunks_dir_path)?;
+        let complete = ChunkManifest {
+            source_bytes,
+            chunk_duration_seconds,
+            chunks: chunks.len(),
+        };
+        if manifest == complete {
+            tracing::info!(
+                chunks = chunks.len(),
+                "Reusing audio chunks from an earlier run"
+            );
+            return Ok(chunks);
+        }
+    }
+
+    // chunks left by a split that was cut short, or made from other audio or at another
+    // length, are made again. Their cached transcriptions are kept, as they are only
+    // reused for chunks with the same audio.
+    if chunks_dir_path.exists() {
+        for chunk in list_chunks(chunks_dir_path)? {
+            std::fs::remove_file(chunk)?;
+        }
+    }
+    std::fs::create_dir_all(chunks_dir_path)?;
+    let base_name = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| ChunkingError::Ffmpeg("Invalid file path".into()))?;
+
+    tracing::info!("Splitting audio to chunks");
+    // XXX: intentional blocking
+    ffmpeg
+        .split_audio_to_chunks(
+            file_path,
+            chunk_duration_seconds,
+            chunks_dir_path.join(format!("{base_name}_%03d.mp3")),
+        )
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to split audio to chunks"))
+        .map_err(|e| ChunkingError::Ffmpeg(e.to_string()))?;
+
+    let chunks = list_chunks(chunks_dir_path)?;
+    ChunkManifest {
+        source_bytes,
+        chunk_duration_seconds,
+        chunks: chunks.len(),
+    }
+    .store(chunks_dir_path)?;
+    Ok(chunks)
+}
+
+/// The mp3 chunks in `chunks_dir_path` in playback order, skipping cached transcriptions
+fn list_chunks(chunks_dir_path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut chunks: Vec<PathBuf> = std::fs::read_dir(chunks_dir_path)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "mp3"))
+        .collect();
+    chunks.sort();
+    Ok(chunks)
+}
+
+/// Written next to the chunks once audio is split, so that a later run only reuses the
+/// chunks of a split that finished, from the same audio and at the same chunk length
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct ChunkManifest {
+    source_bytes: u64,
+    chunk_duration_seconds: u16,
+    chunks: usize,
+}
+
+impl ChunkManifest {
+    const FILE_NAME: &str = "chunks.json";
+
+    fn load(chunks_dir_path: &Path) -> Option<Self> {
+        let contents = std::fs::read(chunks_dir_path.join(Self::FILE_NAME)).ok()?;
+        serde_json::from_slice(&contents)
+            .inspect_err(|e| tracing::warn!(error = %e, "Corrupt chunk manifest"))
+            .ok()
+    }
+
+    fn store(&self, chunks_dir_path: &Path) -> std::io::Result<()> {
+        let contents = serde_json::to_vec(self).map_err(std::io::Error::from)?;
+        std::fs::write(chunks_dir_path.join(Self::FILE_NAME), contents)
+    }
+}
+
+/// Transcription of a single chunk persisted next to the chunk file, so that a failed
+/// stream resumes from the first untranscribed chunk instead of starting over.
+///
+/// Entries are keyed by the SHA-256 of the chunk, so re-split audio is never matched
+/// against a stale transcription.
+#[derive(Debug)]
+pub(crate) struct ChunkCache {
+    path: PathBuf,
+    hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkCacheEntry {
+    sha256: String,
+    response: TranscribeResponse,
+}
+
+impl ChunkCache {
+    pub(crate) fn open(chunk_path: &Path) -> std::io::Result<Self> {
+        let hash = Sha256::digest(std::fs::read(chunk_path)?)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        Ok(Self {
+            path: chunk_path.with_extension("transcript.json"),
+            hash,
+        })
+    }
+
+    /// Returns the cached transcription if one exists for this exact chunk
+    pub(crate) fn load(&self) -> Option<TranscribeResponse> {
+        let contents = std::fs::read(&self.path).ok()?;
+        let entry = serde_json::from_slice::<ChunkCacheEntry>(&contents)
+            .inspect_err(|e| tracing::warn!(error = %e, path = ?self.path, "Corrupt chunk cache"))
+            .ok()?;
+        (entry.sha256 == self.hash).then_some(entry.response)
+    }
+
+    /// Persists `response`. Failures are logged rather than returned, since the cache is
+    /// only an optimisation.
+    pub(crate) fn store(&self, response: TranscribeResponse) -> TranscribeResponse {
+        let entry = ChunkCacheEntry {
+            sha256: self.hash.clone(),
+            response,
+        };
+        let result = serde_json::to_vec(&entry)
+            .map_err(std::io::Error::from)
+            .and_then(|contents| std::fs::write(&self.path, contents));
+        if let Err(e) = result {
+            tracing::warn!(error = %e, path = ?self.path, "Failed to cache chunk transcription");
+        }
+        entry.response
+    }
+}
+
+/// The end of a chunk's transcript to prime the next chunk with, at most `max_words` long.
+/// When the window is cut from a longer text it starts at a sentence boundary, if it
+/// contains one, so the prompt doesn't open mid-sentence.
+pub(crate) fn trailing_context(text: &str, max_words: usize) -> Option<String> {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let window = &words[words.len().saturating_sub(max_words)..];
+    if window.is_empty() {
+        return None;
+    }
+
+    let start = match words.len() > window.len() {
+        true => window[..window.len() - 1]
+            .iter()
+            .position(|word| word.ends_with(['.', '?', '!']))
+            .map_or(0, |i| i + 1),
+        false => 0,
+    };
+    Some(window[start..].join(" "))
+}
+
+/// A chunk that failed to transcribe on both passes over a stream's chunks
+#[derive(Debug)]
+pub(crate) struct ChunkFailure<E> {
+    /// Position of the chunk, from 1
+    pub(crate) chunk: usize,
+    pub(crate) chunks: usize,
+    pub(crate) error: E,
+}
+
+/// Transcribes `chunks` in order through `transcribe`, reusing cached transcriptions and
+/// priming each chunk with the end of the one before it.
+///
+/// A chunk that fails doesn't fail the stream straight away: the chunks after it are
+/// transcribed, and the chunks that failed are tried again once the rest are done, then
+/// spliced back into place. Two chunks failing in a row stop the first pass, as the
+/// provider is more likely down than the audio at fault. Transcribed chunks are cached
+/// either way, so that a stream failed by a chunk only transcribes that chunk when it is
+/// retried.
+pub(crate) async fn transcribe_chunks<'a, E, Fut>(
+    chunks: &'a [PathBuf],
+    chunk_duration_seconds: u16,
+    first_prompt: Option<String>,
+    context_words: usize,
+    mut transcribe: impl FnMut(&'a Path, Option<String>) -> Fut,
+    filter: impl Fn(TranscribeResponse) -> TranscribeResponse,
+    events: &ProcessorEvents,
+) -> Result<TranscribeResponse, ChunkFailure<E>>
+where
+    E: From<std::io::Error> + std::fmt::Display,
+    Fut: Future<Output = Result<TranscribeResponse, E>>,
+{
+    let fail = |i: usize, error| ChunkFailure {
+        chunk: i + 1,
+        chunks: chunks.len(),
+        error,
+    };
+    // the glossary primes the first chunk, later ones are primed with the text before them
+    let prompt = |responses: &[Option<TranscribeResponse>], i: usize| match i {
+        0 => first_prompt.clone(),
+        i => responses[i - 1]
+            .as_ref()
+            .and_then(|response| trailing_context(&response.text, context_words)),
+    };
+
+    let mut responses = Vec::with_capacity(chunks.len());
+    let mut failed = Vec::new();
+    let mut transcribed = 0;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let cache = ChunkCache::open(chunk).map_err(|e| fail(i, e.into()))?;
+        let response = match cache.load() {
+            Some(response) => {
+                tracing::info!(chunk = ?chunk, "Using cached chunk transcription");
+                response
+            }
+            None => match transcribe(chunk, prompt(&responses, i)).await {
+                Ok(response) => cache.store(response),
+                Err(e) if failed.last().is_some_and(|(last, _)| last + 1 == i) => {
+                    tracing::error!(error = %e, chunk = ?chunk, "Failed to transcribe audio");
+                    return Err(fail(i, e));
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        error = %e,
+                        chunk = ?chunk,
+                        "Failed to transcribe chunk, trying it again after the rest"
+                    );
+                    failed.push((i, cache));
+                    responses.push(None);
+                    continue;
+                }
+            },
+        };
+        // filtered after caching, so that changed thresholds apply to cached chunks
+        responses.push(Some(filter(response)));
+        transcribed += 1;
+        events.chunk_transcribed(transcribed, chunks.len());
+    }
+
+    for (i, cache) in failed {
+        let response = transcribe(&chunks[i], prompt(&responses, i))
+            .await
+            .inspect_err(
+                |e| tracing::error!(error = %e, chunk = ?chunks[i], "Failed to transcribe audio"),
+            )
+            .map_err(|e| fail(i, e))?;
+        tracing::info!(chunk = ?chunks[i], "Transcribed chunk on the second pass");
+        responses[i] = Some(filter(cache.store(response)));
+        transcribed += 1;
+        events.chunk_transcribed(transcribed, chunks.len());
+    }
+
+    let mut transcript = ChunkedTranscript::default();
+    for response in responses.into_iter().flatten() {
+        transcript.push(response, chunk_duration_seconds);
+    }
+    Ok(transcript.finish())
+}
+
+/// Accumulates per-chunk transcriptions into a single response, shifting
+/// segment timestamps by the offset of each chunk.
+#[derive(Debug, Default)]
+pub(crate) struct ChunkedTranscript {
+    segments: Vec<TranscribeSegment>,
+    text: String,
+    time_offset: f64,
+    duration: f64,
+}
+
+impl ChunkedTranscript {
+    pub(crate) fn push(&mut self, response: TranscribeResponse, chunk_duration_seconds: u16) {
+        self.duration += response.duration;
+
+        if let Some(segments) = response.segments {
+            for mut seg in segments {
+                seg.start += self.time_offset;
+                seg.end += self.time_offset;
+                self.segments.push(seg);
+            }
+        }
+
+        self.text.push_str(&response.text);
+        self.text.push(' ');
+        self.time_offset += chunk_duration_seconds as f64;
+    }
+
+    pub(crate) fn finish(self) -> TranscribeResponse {
+        TranscribeResponse {
+            duration: self.duration,
+            text: self.text.trim().to_string(),
+            segments: Some(self.segments),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_are_shortened_to_fit_the_size_limit() {
+        let limit = 25 * 1024 * 1024;
+        // 25MB at 128kbps is about 27 minutes, so 15 minute chunks fit
+        assert_eq!(chunk_duration_within(900, limit, 128_000), 900);
+        // at 320kbps chunks are cut to 90% of the ~655s that would fit
+        assert_eq!(chunk_duration_within(900, limit, 320_000), 589);
+        assert_eq!(chunk_duration_within(900, 1, 320_000), 1);
+    }
+
+    fn response(text: &str) -> TranscribeResponse {
+        TranscribeResponse {
+            duration: 1.0,
+            text: text.into(),
+            segments: None,
+        }
+    }
+
+    #[test]
+    fn test_hallucinated_segments_are_dropped() {
+        let segment = |text: &str, no_speech_prob: f64, compression_ratio: f64| TranscribeSegment {
+            text: text.into(),
+            avg_logprob: Some(-1.5),
+            no_speech_prob: Some(no_speech_prob),
+            compression_ratio: Some(compression_ratio),
+            ..Default::default()
+        };
+        let response = TranscribeResponse {
+            duration: 30.0,
+            text: "Order, order. Thank you. Thank you. Thank you. Please be seated.".into(),
+            segments: Some(vec![
+                segment(" Order, order.", 0.1, 1.2),
+                segment(" Thank you. Thank you. Thank you.", 0.2, 3.1),
+                segment(" Thank you for watching.", 0.9, 1.1),
+                segment(" Please be seated.", 0.1, 1.0),
+            ]),
+        };
+
+        let response = SegmentFilter::default().apply(response);
+        assert_eq!(response.text, "Order, order. Please be seated.");
+        assert_eq!(response.segments.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_trailing_context_starts_at_a_sentence() {
+        let text = "Order, order. The Finance Bill is now read a second time.";
+        assert_eq!(
+            trailing_context(text, 10).as_deref(),
+            Some("The Finance Bill is now read a second time.")
+        );
+        // short texts are kept whole, even if they open mid-sentence
+        assert_eq!(trailing_context("order.", 8).as_deref(), Some("order."));
+        assert_eq!(trailing_context(text, 0), None);
+    }
+
+    #[test]
+    fn test_chunk_cache_is_keyed_by_content() {
+        let dir = std::env::temp_dir().join(format!("chunk-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let chunk = dir.join("audio_000.mp3");
+
+        std::fs::write(&chunk, b"first").unwrap();
+        let cache = ChunkCache::open(&chunk).unwrap();
+        assert!(cache.load().is_none());
+        cache.store(response("Order, order"));
+        assert_eq!(
+            ChunkCache::open(&chunk).unwrap().load().unwrap().text,
+            "Order, order"
+        );
+
+        // a re-split chunk with different audio must not reuse the old transcription
+        std::fs::write(&chunk, b"second").unwrap();
+        assert!(ChunkCache::open(&chunk).unwrap().load().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_chunks_are_spliced_back_in_on_a_second_pass() {
+        let dir = std::env::temp_dir().join(format!("chunk-retry-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let chunks = (0..3)
+            .map(|i| {
+                let chunk = dir.join(format!("audio_{i:03}.mp3"));
+                std::fs::write(&chunk, format!("chunk {i}")).unwrap();
+                chunk
+            })
+            .collect::<Vec<_>>();
+
+        let attempts = std::cell::RefCell::new(Vec::new());
+        let transcribe = |chunk: &Path, prompt: Option<String>| {
+            let name = chunk.file_stem().unwrap().to_string_lossy().into_owned();
+            attempts.borrow_mut().push((name.clone(), prompt));
+            let first_attempt = attempts.borrow().iter().filter(|(n, _)| *n == name).count() == 1;
+            async move {
+                match name.as_str() {
+                    "audio_001" if first_attempt => Err(std::io::Error::other("timed out")),
+                    _ => Ok(response(&format!("{name}."))),
+                }
+            }
+        };
+        let transcript = transcribe_chunks(
+            &chunks,
+            60,
+            None,
+            10,
+            transcribe,
+            |response| response,
+            &ProcessorEvents::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(transcript.text, "audio_000. audio_001. audio_002.");
+        // the second attempt is primed with the chunk before it, as the first was
+        let attempts = attempts.into_inner();
+        let primed = |name: &str| {
+            attempts
+                .iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, prompt)| prompt.as_deref())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            primed("audio_001"),
+            [Some("audio_000."), Some("audio_000.")]
+        );
+        assert_eq!(primed("audio_002"), [None]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Writes three chunks per split, counting the splits
+    #[derive(Default)]
+    struct SplittingFfmpeg {
+        splits: std::cell::Cell<usize>,
+    }
+
+    impl AudioProcessor for SplittingFfmpeg {
+        fn split_audio_to_chunks(
+            &self,
+            _file_input_path: impl AsRef<Path>,
+            _segment_time_s: u16,
+            out_template: impl AsRef<Path>,
+        ) -> Result<(), ytdlp_bindings::YtDlpError> {
+            self.splits.set(self.splits.get() + 1);
+            let template = out_template.as_ref().to_string_lossy().into_owned();
+            for i in 0..3 {
+                std::fs::write(template.replace("%03d", &format!("{i:03}")), b"chunk").unwrap();
+            }
+            Ok(())
+        }
+
+        fn bit_rate(
+            &self,
+            _input_path: impl AsRef<Path>,
+        ) -> Result<u64, ytdlp_bindings::YtDlpError> {
+            Ok(128_000)
+        }
+
+        fn normalize_volume(
+            &self,
+            _input_path: impl AsRef<Path>,
+            _output_path: impl AsRef<Path>,
+        ) -> Result<(), ytdlp_bindings::YtDlpError> {
+            Ok(())
+        }
+
+        fn denoise_audio(
+            &self,
+            _input_path: impl AsRef<Path>,
+            _output_path: impl AsRef<Path>,
+        ) -> Result<(), ytdlp_bindings::YtDlpError> {
+            Ok(())
+        }
+
+        fn trim_silence(
+            &self,
+            _input_path: impl AsRef<Path>,
+            _output_path: impl AsRef<Path>,
+        ) -> Result<(), ytdlp_bindings::YtDlpError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_only_chunks_of_a_finished_split_are_reused() {
+        let dir = std::env::temp_dir().join(format!("chunk-reuse-{}", std::process::id()));
+        let chunks_dir = dir.join("audio.chunks");
+        std::fs::create_dir_all(&chunks_dir).unwrap();
+        let audio = dir.join("audio.mp3");
+        std::fs::write(&audio, b"audio").unwrap();
+        // left by a split that was cut short
+        std::fs::write(chunks_dir.join("audio_000.mp3"), b"partial").unwrap();
+
+        let ffmpeg = SplittingFfmpeg::default();
+        let chunks = prepare_chunks(&ffmpeg, &audio, &chunks_dir, 600).unwrap();
+        assert_eq!((chunks.len(), ffmpeg.splits.get()), (3, 1));
+
+        let reused = prepare_chunks(&ffmpeg, &audio, &chunks_dir, 600).unwrap();
+        assert_eq!((reused, ffmpeg.splits.get()), (chunks, 1));
+
+        // chunks of another length are made again
+        prepare_chunks(&ffmpeg, &audio, &chunks_dir, 300).unwrap();
+        assert_eq!(ffmpeg.splits.get(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,26 @@
+//! # Transport
+//!
+//! Sends the requests built by the LLM clients. The reqwest client is the only
+//! production transport; with the `cassette` feature, requests can instead be recorded
+//! to or replayed from a [`Cassette`](crate::llm::cassette::Cassette).
+
+use std::fmt::Debug;
+
+use futures::future::BoxFuture;
+use reqwest_middleware::ClientWithMiddleware;
+
+pub(crate) trait Transport: Debug + Send + Sync {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, reqwest_middleware::Error>>;
+}
+
+impl Transport for ClientWithMiddleware {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, reqwest_middleware::Error>> {
+        Box::pin(self.execute(request))
+    }
+}
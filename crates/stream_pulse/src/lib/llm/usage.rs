@@ -0,0 +1,99 @@
+//! # Usage
+//!
+//! Token and audio usage reported by LLM providers, accumulated across requests so
+//! that the processor can report what each stream cost to process.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Token usage as reported in OpenAI-compatible completion responses
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+pub struct CompletionUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// Usage accumulated for a single model
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ModelUsage {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Seconds of audio transcribed
+    pub audio_seconds: f64,
+}
+
+/// Usage per model, keyed by model name
+pub type UsageReport = BTreeMap<String, ModelUsage>;
+
+/// A cheaply cloneable usage accumulator. Clones record into the same report.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    inner: Arc<Mutex<UsageReport>>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_completion(&self, model: &str, usage: CompletionUsage) {
+        self.update(model, |entry| {
+            entry.prompt_tokens += usage.prompt_tokens;
+            entry.completion_tokens += usage.completion_tokens;
+        });
+    }
+
+    pub fn record_transcription(&self, model: &str, audio_seconds: f64) {
+        self.update(model, |entry| entry.audio_seconds += audio_seconds);
+    }
+
+    /// Usage recorded so far
+    pub fn report(&self) -> UsageReport {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Returns usage recorded so far and resets the tracker, e.g. at the end of each stream
+    pub fn take(&self) -> UsageReport {
+        std::mem::take(&mut *self.inner.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn update(&self, model: &str, f: impl FnOnce(&mut ModelUsage)) {
+        let mut report = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = report.entry(model.to_string()).or_default();
+        entry.requests += 1;
+        f(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_is_accumulated_per_model_and_reset_on_take() {
+        let tracker = UsageTracker::new();
+        let clone = tracker.clone();
+
+        tracker.record_transcription("whisper-1", 600.0);
+        clone.record_transcription("whisper-1", 300.5);
+        clone.record_completion(
+            "gpt-4o",
+            CompletionUsage {
+                prompt_tokens: 1_000,
+                completion_tokens: 200,
+            },
+        );
+
+        let report = tracker.take();
+        assert_eq!(report["whisper-1"].requests, 2);
+        assert_eq!(report["whisper-1"].audio_seconds, 900.5);
+        assert_eq!(report["gpt-4o"].prompt_tokens, 1_000);
+        assert_eq!(report["gpt-4o"].completion_tokens, 200);
+        assert!(clone.report().is_empty());
+    }
+}
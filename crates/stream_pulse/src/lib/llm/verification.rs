@@ -0,0 +1,328 @@
+//! # Summary Verification
+//!
+//! An optional guardrail that checks generated summaries against the transcript for
+//! hallucinated names, figures and bill numbers, regenerating summaries the verifier is
+//! not confident in and flagging those that remain below the threshold.
+
+use std::{fmt::Debug, future::Future, sync::LazyLock};
+
+use serde::Deserialize;
+use stream_datastore::{SummaryVerification, VerificationIssue};
+
+use crate::{
+    llm::{
+        entities::strip_code_fence,
+        summarizer::{SummaryContext, SummaryResponse},
+    },
+    PromptTemplate, Summarizer,
+};
+
+static VERIFICATION_PROMPT: LazyLock<PromptTemplate> = LazyLock::new(|| {
+    PromptTemplate::new(include_str!("prompts/verification_0.txt"))
+        .expect("bundled verification prompt is not empty")
+});
+
+pub trait SummaryVerifier {
+    /// Model used for verification unless overridden
+    const VERIFICATION_MODEL: &'static str;
+
+    type Error: Debug;
+
+    fn verify_summary(
+        &self,
+        transcript: &str,
+        summary: &str,
+        context: &SummaryContext,
+    ) -> impl Future<Output = Result<SummaryVerdict, Self::Error>> + Send;
+}
+
+/// A verifier's assessment of a single summary
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SummaryVerdict {
+    /// From 0 to 1
+    pub confidence: f32,
+    #[serde(default)]
+    pub issues: Vec<VerificationIssue>,
+}
+
+/// Summarizes with `S`, checking every summary with `V`.
+///
+/// Summaries below `min_confidence` are regenerated up to `max_regenerations` times, with
+/// the verifier's findings passed back to the summarizer. If none reach the threshold the
+/// most confident one is returned and flagged. Verifier failures are logged and the
+/// summary is returned unverified, so the guardrail never blocks a stream on its own.
+///
+/// When a transcript is summarized in parts, each part and the merged summary are
+/// verified against their own input.
+#[derive(Debug, Clone)]
+pub struct VerifiedSummarizer<S, V> {
+    summarizer: S,
+    verifier: V,
+    min_confidence: f32,
+    max_regenerations: u32,
+}
+
+impl<S, V> VerifiedSummarizer<S, V> {
+    pub const DEFAULT_MIN_CONFIDENCE: f32 = 0.7;
+    pub const DEFAULT_MAX_REGENERATIONS: u32 = 1;
+
+    pub fn new(summarizer: S, verifier: V) -> Self {
+        Self {
+            summarizer,
+            verifier,
+            min_confidence: Self::DEFAULT_MIN_CONFIDENCE,
+            max_regenerations: Self::DEFAULT_MAX_REGENERATIONS,
+        }
+    }
+
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_max_regenerations(mut self, max_regenerations: u32) -> Self {
+        self.max_regenerations = max_regenerations;
+        self
+    }
+}
+
+impl<S, V> Summarizer for VerifiedSummarizer<S, V>
+where
+    S: Summarizer + Sync,
+    S::Error: Send,
+    V: SummaryVerifier + Sync,
+{
+    const CONTEXT_WINDOW_LIMIT: usize = S::CONTEXT_WINDOW_LIMIT;
+    const SUMMARIZER_MODEL: &'static str = S::SUMMARIZER_MODEL;
+
+    type Error = S::Error;
+
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
+        let mut response = self.summarizer.summarize(content, context).await?;
+        let mut attempts = 1;
+        let mut best: Option<(SummaryResponse, SummaryVerdict)> = None;
+
+        loop {
+            let verdict = match self
+                .verifier
+                .verify_summary(content, &response.summary, context)
+                .await
+            {
+                Ok(verdict) => verdict,
+                // a summary that already failed verification is still flagged below
+                Err(e) if best.is_some() => {
+                    tracing::warn!(error = ?e, "Failed to verify regenerated summary");
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(error = ?e, "Failed to verify summary, keeping it unverified");
+                    return Ok(response);
+                }
+            };
+
+            if verdict.confidence >= self.min_confidence {
+                response.verification = Some(verification(verdict, false, attempts));
+                return Ok(response);
+            }
+
+            tracing::warn!(
+                attempts,
+                confidence = verdict.confidence,
+                issues = verdict.issues.len(),
+                "Summary failed verification"
+            );
+
+            let feedback = regeneration_feedback(&verdict);
+            if best
+                .as_ref()
+                .is_none_or(|(_, best)| verdict.confidence > best.confidence)
+            {
+                best = Some((response, verdict));
+            }
+
+            if attempts > self.max_regenerations {
+                break;
+            }
+
+            // only pass the findings back if they still fit in the context window
+            let retry_content = format!("{content}\n\n{feedback}");
+            let retry_content = match self.summarizer.count_tokens(&retry_content)? {
+                tokens if tokens <= S::CONTEXT_WINDOW_LIMIT => retry_content,
+                _ => content.to_string(),
+            };
+            response = self.summarizer.summarize(&retry_content, context).await?;
+            attempts += 1;
+        }
+
+        let (mut response, verdict) = best.expect("loop only breaks once a summary failed");
+        tracing::warn!(
+            confidence = verdict.confidence,
+            "Flagging summary that stayed below the confidence threshold"
+        );
+        response.verification = Some(verification(verdict, true, attempts));
+        Ok(response)
+    }
+
+    fn count_tokens(&self, content: &str) -> Result<usize, Self::Error> {
+        self.summarizer.count_tokens(content)
+    }
+}
+
+fn verification(verdict: SummaryVerdict, flagged: bool, attempts: u32) -> SummaryVerification {
+    SummaryVerification {
+        confidence: verdict.confidence,
+        issues: verdict.issues,
+        flagged,
+        attempts,
+    }
+}
+
+fn regeneration_feedback(verdict: &SummaryVerdict) -> String {
+    let issues = verdict
+        .issues
+        .iter()
+        .map(|issue| format!("- \"{}\": {}", issue.claim, issue.explanation))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "A previous summary of this transcript contained claims the transcript does not \
+         support. Do not repeat them:\n{issues}"
+    )
+}
+
+/// System prompt for verification, grounded with the sitting's metadata
+pub(crate) fn verification_system_prompt(context: &SummaryContext) -> String {
+    VERIFICATION_PROMPT.render(&context.prompt_variables())
+}
+
+/// User message holding both the transcript and the summary to check
+pub(crate) fn verification_content(transcript: &str, summary: &str) -> String {
+    format!("## Transcript\n\n{transcript}\n\n## Summary\n\n{summary}")
+}
+
+/// Parses the model's response, tolerating a surrounding markdown code fence
+/// from providers that can't be constrained to a JSON schema
+pub(crate) fn parse_verdict(content: &str) -> Result<SummaryVerdict, serde_json::Error> {
+    serde_json::from_str(strip_code_fence(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+    use stream_datastore::VerificationIssueKind;
+
+    struct Counting {
+        calls: AtomicUsize,
+    }
+
+    impl Summarizer for Counting {
+        const CONTEXT_WINDOW_LIMIT: usize = 10_000;
+        const SUMMARIZER_MODEL: &'static str = "counting";
+
+        type Error = String;
+
+        async fn summarize(
+            &self,
+            content: &str,
+            _context: &SummaryContext,
+        ) -> Result<SummaryResponse, Self::Error> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(SummaryResponse {
+                summary: format!("summary {call}: {}", content.contains("previous summary")),
+                structured: None,
+                tldr: None,
+                verification: None,
+            })
+        }
+    }
+
+    /// Returns the given confidences in order
+    struct Scripted {
+        confidences: Mutex<Vec<f32>>,
+    }
+
+    impl SummaryVerifier for Scripted {
+        const VERIFICATION_MODEL: &'static str = "scripted";
+
+        type Error = String;
+
+        async fn verify_summary(
+            &self,
+            _transcript: &str,
+            _summary: &str,
+            _context: &SummaryContext,
+        ) -> Result<SummaryVerdict, Self::Error> {
+            let mut confidences = self.confidences.lock().unwrap();
+            if confidences.is_empty() {
+                return Err("verifier unavailable".into());
+            }
+            Ok(SummaryVerdict {
+                confidence: confidences.remove(0),
+                issues: vec![VerificationIssue {
+                    kind: VerificationIssueKind::Figure,
+                    claim: "passed 200 to 100".into(),
+                    explanation: "the transcript records 195 to 106".into(),
+                }],
+            })
+        }
+    }
+
+    fn summarizer(confidences: Vec<f32>) -> VerifiedSummarizer<Counting, Scripted> {
+        VerifiedSummarizer::new(
+            Counting {
+                calls: AtomicUsize::new(0),
+            },
+            Scripted {
+                confidences: Mutex::new(confidences),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_summary_is_regenerated_with_feedback() {
+        let summarizer = summarizer(vec![0.3, 0.9]);
+        let response = summarizer
+            .summarize("transcript", &SummaryContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(response.summary, "summary 2: true");
+        let verification = response.verification.unwrap();
+        assert!(!verification.flagged);
+        assert_eq!(verification.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_most_confident_summary_is_flagged_when_all_fail() {
+        let summarizer = summarizer(vec![0.5, 0.2]);
+        let response = summarizer
+            .summarize("transcript", &SummaryContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(response.summary, "summary 1: false");
+        let verification = response.verification.unwrap();
+        assert!(verification.flagged);
+        assert_eq!(verification.confidence, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_verifier_failure_keeps_summary_unverified() {
+        let summarizer = summarizer(vec![]);
+        let response = summarizer
+            .summarize("transcript", &SummaryContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(response.summary, "summary 1: false");
+        assert!(response.verification.is_none());
+    }
+}
@@ -0,0 +1,58 @@
+//! # Metrics
+//!
+//! Counters and histograms of the processor's runs, recorded through the [`metrics`]
+//! facade. They go nowhere until a recorder is installed, e.g. a Prometheus exporter in
+//! the binary.
+
+use std::time::Instant;
+
+/// Streams listed on the channels that were due for processing
+pub const STREAMS_DISCOVERED: &str = "stream_pulse_streams_discovered_total";
+/// Streams summarized and stored
+pub const STREAMS_PROCESSED: &str = "stream_pulse_streams_processed_total";
+/// Runs that failed, each failing the stream being processed if there was one
+pub const RUNS_FAILED: &str = "stream_pulse_runs_failed_total";
+/// Seconds each stage of processing a stream took, labelled with the `stage`:
+/// "download", "clean", "transcribe", "summarize" or "store"
+pub const STAGE_DURATION: &str = "stream_pulse_stage_duration_seconds";
+/// Provider requests retried, labelled with the `reason`: "status" for retryable
+/// responses, "transport" for timeouts and failed connections
+pub const API_RETRIES: &str = "stream_pulse_api_retries_total";
+
+/// Describes the metrics to the installed recorder, for exporters that publish
+/// descriptions. Call after installing it.
+pub fn describe() {
+    metrics::describe_counter!(
+        STREAMS_DISCOVERED,
+        "Streams listed on the channels that were due for processing"
+    );
+    metrics::describe_counter!(STREAMS_PROCESSED, "Streams summarized and stored");
+    metrics::describe_counter!(RUNS_FAILED, "Processor runs that failed");
+    metrics::describe_histogram!(
+        STAGE_DURATION,
+        metrics::Unit::Seconds,
+        "Time each stage of processing a stream took"
+    );
+    metrics::describe_counter!(API_RETRIES, "Provider requests retried");
+}
+
+pub(crate) fn streams_discovered(count: usize) {
+    metrics::counter!(STREAMS_DISCOVERED).increment(count as u64);
+}
+
+pub(crate) fn stream_processed() {
+    metrics::counter!(STREAMS_PROCESSED).increment(1);
+}
+
+pub(crate) fn run_failed() {
+    metrics::counter!(RUNS_FAILED).increment(1);
+}
+
+/// Records how long `stage` took, from `started` until now
+pub(crate) fn record_stage_duration(stage: &'static str, started: Instant) {
+    metrics::histogram!(STAGE_DURATION, "stage" => stage).record(started.elapsed().as_secs_f64());
+}
+
+pub(crate) fn api_retried(reason: &'static str) {
+    metrics::counter!(API_RETRIES, "reason" => reason).increment(1);
+}
@@ -3,12 +3,14 @@
 //! This module provides functionality to scrape and parse stream data from YouTube,
 //! specifically tailored for the Parliament of Kenya Channel live streams.
 
-use std::{ops::Deref, sync::LazyLock};
+use std::{ops::Deref, sync::LazyLock, time::Duration};
 
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
 use regex::Regex;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use stream_datastore::Stream;
+use stream_datastore::{Stream, StreamCategory, StreamStatus};
 
 use crate::{error::Error, types::VideoRenderer};
 
@@ -17,59 +19,288 @@ static YT_INTIALDATA_RE: LazyLock<Regex> = LazyLock::new(|| {
         .unwrap()
 });
 
-/// Parses multiple streams from the provided JSON data.
+/// Where a watch page states when the video was streamed, most precise first
+static PUBLISHED_AT_RES: LazyLock<[Regex; 3]> = LazyLock::new(|| {
+    [
+        r#""startTimestamp":"([^"]+)""#,
+        r#"<meta itemprop="startDate" content="([^"]+)""#,
+        r#""publishDate":"([^"]+)""#,
+    ]
+    .map(|re| regex::Regex::new(re).unwrap())
+});
+
+/// The full description in the watch page's player response, as a JSON string
+static SHORT_DESCRIPTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| regex::Regex::new(r#""shortDescription":("(?:[^"\\]|\\.)*")"#).unwrap());
+
+static PLAYER_RESPONSE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?s)var\s+ytInitialPlayerResponse\s*=\s*(\{.*?\});\s*(?:var\s|</script>)")
+        .unwrap()
+});
+
+static CLIENT_VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| regex::Regex::new(r#""INNERTUBE_CLIENT_VERSION":"([^"]+)""#).unwrap());
+
+/// Which listed streams are kept. By default, streams of at least 10 minutes with any
+/// title, which leaves out trailers and short procedural clips.
+#[derive(Debug, Clone)]
+pub struct ParseFilters {
+    min_duration: Duration,
+    max_duration: Option<Duration>,
+    title_include: Option<Regex>,
+    title_exclude: Option<Regex>,
+}
+
+impl Default for ParseFilters {
+    fn default() -> Self {
+        Self {
+            min_duration: Duration::from_secs(600),
+            max_duration: None,
+            title_include: None,
+            title_exclude: None,
+        }
+    }
+}
+
+impl ParseFilters {
+    /// Streams shorter than this are skipped
+    pub fn with_min_duration(mut self, min_duration: Duration) -> Self {
+        self.min_duration = min_duration;
+        self
+    }
+
+    /// Streams longer than this are skipped
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Only streams with titles matching `title_include` are kept,
+    /// e.g. `(?i)sitting` for plenary sittings only
+    pub fn with_title_include(mut self, title_include: Regex) -> Self {
+        self.title_include = Some(title_include);
+        self
+    }
+
+    /// Streams with titles matching `title_exclude` are skipped
+    pub fn with_title_exclude(mut self, title_exclude: Regex) -> Self {
+        self.title_exclude = Some(title_exclude);
+        self
+    }
+
+    /// Whether `stream` passes the filters. Live and scheduled streams have no duration
+    /// yet, so are filtered by title only; past streams whose duration can't be parsed
+    /// are skipped.
+    pub fn accepts(&self, stream: &Stream) -> bool {
+        let title_accepted = self
+            .title_include
+            .as_ref()
+            .is_none_or(|re| re.is_match(&stream.title))
+            && !self
+                .title_exclude
+                .as_ref()
+                .is_some_and(|re| re.is_match(&stream.title));
+        if !title_accepted || stream.status != StreamStatus::Archived {
+            return title_accepted;
+        }
+
+        parse_duration_to_seconds(&stream.duration)
+            .map(Duration::from_secs)
+            .is_some_and(|duration| {
+                duration >= self.min_duration && self.max_duration.is_none_or(|max| duration <= max)
+            })
+    }
+}
+
+/// Parses multiple streams from the provided JSON data. Streams being broadcast now
+/// are included with [`StreamStatus::Live`], and upcoming ones with
+/// [`StreamStatus::Scheduled`].
 ///
 /// # Parameters
 /// * `json`: A reference to a `Value` containing the YouTube page's JSON data.
+/// * `filters`: Which streams to keep, see [`ParseFilters`].
 ///
 /// # Returns
 /// * `Ok(Vec<Stream>)` containing all successfully parsed streams.
 /// * `Err(YtScrapeError)` if the JSON structure is unexpected or parsing fails.
 #[tracing::instrument(skip(json))]
-pub fn parse_streams(json: &Value) -> Result<Vec<Stream>, Error> {
-    let mut streams = Vec::new();
+pub fn parse_streams(json: &Value, filters: &ParseFilters) -> Result<Vec<Stream>, Error> {
+    Ok(parse_streams_page(json, filters)?.streams)
+}
 
-    if let Some(contents) = json["contents"]["twoColumnBrowseResultsRenderer"]["tabs"]
-        .get(2)
-        .ok_or(Error::ParseError("Failed to get item at idx 2 from ytInitialData['contents']['twoColumnBrowseResultsRenderer']['tabs']"))
-        .map(|tab| tab["tabRenderer"]["content"]["richGridRenderer"]["contents"].as_array())?
-    {
-        for item in contents {
-            if let Ok(video_renderer) =
-                item["richItemRenderer"]["content"]["videoRenderer"].as_object()
-                .ok_or(Error::ParseError("Failed to get item['richItemRenderer']['content']['videoRenderer']"))
+/// A page of streams from the /streams tab, and the token to fetch the next one with
+#[derive(Debug, Default)]
+pub struct StreamsPage {
+    pub streams: Vec<Stream>,
+    /// Continuation token for the browse endpoint, `None` on the last page
+    pub continuation: Option<String>,
+}
+
+/// Parses the first page of streams, and its continuation token, from the
+/// YouTube page's JSON data. See [`parse_streams`].
+#[tracing::instrument(skip(json))]
+pub fn parse_streams_page(json: &Value, filters: &ParseFilters) -> Result<StreamsPage, Error> {
+    let tabs = json["contents"]["twoColumnBrowseResultsRenderer"]["tabs"]
+        .as_array()
+        .ok_or(Error::ParseError(
+            "Failed to get ytInitialData['contents']['twoColumnBrowseResultsRenderer']['tabs']",
+        ))?;
+
+    if let Some(contents) = streams_tab_contents(tabs) {
+        parse_items(contents, filters)
+    } else {
+        Err(Error::ParseError(
+            "Failed to get script contents, structure might have changed",
+        ))
+    }
+}
+
+/// Grid contents of the streams tab. Channels don't all have the same tabs, and YouTube
+/// adds and reorders them, so the tab is found by the url it links to or its title,
+/// falling back to whichever tab has a grid rendered.
+fn streams_tab_contents(tabs: &[Value]) -> Option<&Vec<Value>> {
+    fn grid(tab: &Value) -> Option<&Vec<Value>> {
+        tab["tabRenderer"]["content"]["richGridRenderer"]["contents"].as_array()
+    }
+    let is_streams_tab = |tab: &&Value| {
+        let renderer = &tab["tabRenderer"];
+        renderer["endpoint"]["commandMetadata"]["webCommandMetadata"]["url"]
+            .as_str()
+            .is_some_and(|url| url.ends_with("/streams"))
+            || renderer["title"]
+                .as_str()
+                .is_some_and(|title| title.eq_ignore_ascii_case("live"))
+    };
+
+    tabs.iter()
+        .find(is_streams_tab)
+        .and_then(grid)
+        .or_else(|| tabs.iter().find_map(grid))
+}
+
+/// Parses a page of streams from a browse endpoint response to a continuation request
+///
+/// # Returns
+/// * `Ok(StreamsPage)` containing the page's streams and the next continuation token.
+/// * `Err(YtScrapeError)` if the response has no continuation items.
+#[tracing::instrument(skip(json))]
+pub fn parse_continuation(json: &Value, filters: &ParseFilters) -> Result<StreamsPage, Error> {
+    let actions = json["onResponseReceivedActions"]
+        .as_array()
+        .ok_or(Error::ParseError(
+            "Failed to get ['onResponseReceivedActions'] from the browse response",
+        ))?;
+
+    parse_items(
+        actions
+            .iter()
+            .filter_map(|action| {
+                action["appendContinuationItemsAction"]["continuationItems"].as_array()
+            })
+            .flatten(),
+        filters,
+    )
+}
+
+fn parse_items<'a>(
+    items: impl IntoIterator<Item = &'a Value>,
+    filters: &ParseFilters,
+) -> Result<StreamsPage, Error> {
+    let mut page = StreamsPage::default();
+
+    for item in items {
+        if let Some(token) = item["continuationItemRenderer"]["continuationEndpoint"]
+            ["continuationCommand"]["token"]
+            .as_str()
+        {
+            page.continuation = Some(token.to_string());
+            continue;
+        }
+
+        if let Ok(video_renderer) = item["richItemRenderer"]["content"]["videoRenderer"]
+            .as_object()
+            .ok_or(Error::ParseError(
+                "Failed to get item['richItemRenderer']['content']['videoRenderer']",
+            ))
+        {
+            let video_renderer =
+                serde_json::from_value::<VideoRenderer>(Value::Object(video_renderer.clone()))?;
+            let stream = if video_renderer.is_live_now() {
+                live_stream(video_renderer)?
+            } else if video_renderer.upcoming_event_data.is_some() {
+                scheduled_stream(video_renderer)?
+            } else if video_renderer.view_count_text.is_none()
+                || video_renderer.published_time_text.is_none()
             {
-                let video_renderer =
-                    serde_json::from_value::<VideoRenderer>(Value::Object(video_renderer.clone()))?;
-                // Only process the video if it's not an upcoming / live event
-                if video_renderer.upcoming_event_data.is_some() || video_renderer.view_count_text.is_none() || video_renderer.published_time_text.is_none() {
-                    continue;
-                }
-                let stream = Stream::try_from(video_renderer)?;
-
-                //XXX: Skip if duration is < 10 minutes
-                if let Some(duration_secs) = parse_duration_to_seconds(&stream.duration) {
-                    if duration_secs < 600 {
-                        continue;
-                    }
-                } else {
-                    // XXX: skip if duration could not be parsed
-                    continue;
-                }
+                continue;
+            } else {
+                Stream::try_from(video_renderer)?
+            };
 
-                streams.push(stream);
+            if filters.accepts(&stream) {
+                page.streams.push(stream);
             }
         }
-    } else {
-        return Err(Error::ParseError(
-            "Failed to get script contents, structure might have changed",
-        ));
     }
 
-    Ok(streams)
+    Ok(page)
+}
+
+/// Builds a `Stream` for an upcoming video, dated by its scheduled start
+fn scheduled_stream(mut video_renderer: VideoRenderer) -> Result<Stream, Error> {
+    let scheduled_start = video_renderer
+        .upcoming_event_data
+        .take()
+        .and_then(|upcoming| upcoming.start_time.parse::<i64>().ok())
+        .and_then(|start| DateTime::from_timestamp(start, 0))
+        .ok_or(Error::ParseError(
+            "Failed to get the scheduled start via ['upcomingEventData']['startTime']",
+        ))?;
+
+    let mut stream = live_stream(video_renderer)?;
+    stream.status = StreamStatus::Scheduled;
+    stream.published_at_exact = Some(scheduled_start);
+    Ok(stream)
+}
+
+/// Builds a `Stream` for a video that is being streamed now, which has no
+/// duration or publish date yet
+fn live_stream(
+    VideoRenderer {
+        video_id,
+        thumbnail,
+        title,
+        description_snippet,
+        ..
+    }: VideoRenderer,
+) -> Result<Stream, Error> {
+    let title = title
+        .runs
+        .into_iter()
+        .next()
+        .ok_or(Error::ParseError(
+            "Failed to get video title via ['title']['runs'][0]['text']",
+        ))?
+        .text;
+
+    let mut stream = Stream {
+        video_id,
+        category: StreamCategory::from_title(&title),
+        title,
+        thumbnail_url: thumbnail.best_url(),
+        is_live_recording: true,
+        status: StreamStatus::Live,
+        ..Default::default()
+    };
+    if let Some(snippet) = description_snippet {
+        stream.set_description(snippet.runs.into_iter().map(|run| run.text).join(""));
+    }
+
+    Ok(stream)
 }
 
-fn parse_duration_to_seconds(duration_str: &str) -> Option<u64> {
+/// Seconds in a duration as the channel page shows it, e.g. `4:37:08`
+pub(crate) fn parse_duration_to_seconds(duration_str: &str) -> Option<u64> {
     let parts: Vec<u64> = duration_str
         .split(':')
         .filter_map(|p| p.parse::<u64>().ok())
@@ -94,16 +325,18 @@ impl TryFrom<VideoRenderer> for Stream {
     /// # Returns
     /// * `Ok(Stream)` if parsing is successful.
     /// * `Err(YtScrapeError)` if any required field is missing or cannot be parsed.
-    fn try_from(
-        VideoRenderer {
+    fn try_from(video_renderer: VideoRenderer) -> Result<Self, Self::Error> {
+        let is_live_recording = video_renderer.is_live_recording();
+        let VideoRenderer {
             video_id,
+            thumbnail,
             title,
             published_time_text,
             view_count_text,
             length_text,
+            description_snippet,
             ..
-        }: VideoRenderer,
-    ) -> Result<Self, Self::Error> {
+        } = video_renderer;
         let title = &title
             .runs
             .first()
@@ -127,14 +360,20 @@ impl TryFrom<VideoRenderer> for Stream {
             .ok_or(Error::ParseError("No value found for 'lengthText'"))?
             .simple_text;
 
-        let stream = Stream {
+        let mut stream = Stream {
             video_id,
+            category: StreamCategory::from_title(title),
             title: title.to_string(),
             view_count,
             streamed_date,
             duration,
+            thumbnail_url: thumbnail.best_url(),
+            is_live_recording,
             ..Default::default()
         };
+        if let Some(snippet) = description_snippet {
+            stream.set_description(snippet.runs.into_iter().map(|run| run.text).join(""));
+        }
 
         Ok(stream)
     }
@@ -155,6 +394,49 @@ impl YtHtmlDocument {
         YtHtmlDocument(doc)
     }
 
+    /// Web client version the page was served to, which browse endpoint
+    /// requests for further pages have to send
+    pub fn client_version(&self) -> Option<&str> {
+        CLIENT_VERSION_RE
+            .captures(self)
+            .and_then(|cap| cap.get(1))
+            .map(|m| m.as_str())
+    }
+
+    /// Exact start of the video on a watch page, from its live broadcast details or
+    /// its publish date. Dates without a time of day are not exact, so are ignored.
+    pub fn published_at(&self) -> Option<DateTime<Utc>> {
+        PUBLISHED_AT_RES
+            .iter()
+            .filter_map(|re| re.captures(self).and_then(|cap| cap.get(1)))
+            .find_map(|m| DateTime::parse_from_rfc3339(m.as_str()).ok())
+            .map(|date| date.with_timezone(&Utc))
+    }
+
+    /// Full description of the video on a watch page
+    pub fn description(&self) -> Option<String> {
+        SHORT_DESCRIPTION_RE
+            .captures(self)
+            .and_then(|cap| cap.get(1))
+            .and_then(|m| serde_json::from_str::<String>(m.as_str()).ok())
+            .filter(|description| !description.trim().is_empty())
+    }
+
+    /// The `ytInitialPlayerResponse` a watch page embeds, as the `player` endpoint of
+    /// the `youtubei` API returns it
+    pub fn player_response<T>(&self) -> Result<T, crate::error::Error>
+    where
+        T: DeserializeOwned,
+    {
+        PLAYER_RESPONSE_RE
+            .captures(self)
+            .and_then(|cap| cap.get(1))
+            .and_then(|m| serde_json::from_str(m.as_str()).ok())
+            .ok_or(Error::ParseError(
+                "Failed to extract ytInitialPlayerResponse from the page's script tag",
+            ))
+    }
+
     pub fn to_json<T>(&self) -> Result<T, crate::error::Error>
     where
         T: DeserializeOwned,
@@ -278,7 +560,8 @@ mod tests {
             .to_json::<Value>()
             .expect("Failed to extract ytInitialData");
 
-        let streams = parse_streams(&json).expect("Failed to parse streams");
+        let streams =
+            parse_streams(&json, &ParseFilters::default()).expect("Failed to parse streams");
 
         assert!(
             !streams.is_empty(),
@@ -290,7 +573,10 @@ mod tests {
             streams.len()
         );
 
-        for stream in &streams {
+        for stream in streams
+            .iter()
+            .filter(|s| s.status == StreamStatus::Archived)
+        {
             assert!(!stream.video_id.is_empty(), "video_id should not be empty");
             assert!(!stream.title.is_empty(), "title should not be empty");
             assert!(
@@ -322,5 +608,264 @@ mod tests {
                 secs
             );
         }
+
+        assert!(streams.iter().all(|stream| stream.is_live_recording));
+        assert!(streams
+            .iter()
+            .all(|stream| stream
+                .thumbnail_url
+                .as_ref()
+                .is_some_and(|url| *url
+                    == format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", stream.video_id))));
+        assert!(
+            streams.iter().any(|stream| stream
+                .description
+                .as_deref()
+                .is_some_and(|d| d.contains("order-paper"))),
+            "description snippet should be parsed"
+        );
+    }
+
+    #[test]
+    fn test_fixture_detects_live_streams() {
+        let html = include_str!("../../tests/fixtures/yt.html");
+        let json = YtHtmlDocument::new(html.to_string())
+            .to_json::<Value>()
+            .unwrap();
+
+        let live = parse_streams(&json, &ParseFilters::default())
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.status == StreamStatus::Live)
+            .map(|s| s.video_id)
+            .collect::<Vec<_>>();
+        assert_eq!(live, ["GC6YTi8bA3k", "GrBDrLvoJi8"]);
+    }
+
+    #[test]
+    fn test_fixture_has_continuation() {
+        let doc = YtHtmlDocument::new(include_str!("../../tests/fixtures/yt.html").to_string());
+        assert_eq!(doc.client_version(), Some("2.20260213.01.00"));
+
+        let page =
+            parse_streams_page(&doc.to_json::<Value>().unwrap(), &ParseFilters::default()).unwrap();
+        assert!(!page.streams.is_empty());
+        assert!(page
+            .continuation
+            .is_some_and(|token| token.starts_with("4qmFsgKnDB")));
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        let stream = |title: &str, duration: &str| Stream {
+            title: title.to_string(),
+            duration: duration.to_string(),
+            ..Default::default()
+        };
+
+        let defaults = ParseFilters::default();
+        assert!(defaults.accepts(&stream("Senate | Morning Sitting", "10:00")));
+        assert!(!defaults.accepts(&stream("Senate | Morning Sitting", "9:59")));
+        assert!(!defaults.accepts(&stream("Senate | Morning Sitting", "")));
+
+        let filters = ParseFilters::default()
+            .with_min_duration(Duration::from_secs(3600))
+            .with_max_duration(Duration::from_secs(6 * 3600))
+            .with_title_include(Regex::new("(?i)sitting").unwrap())
+            .with_title_exclude(Regex::new("(?i)committee").unwrap());
+        assert!(filters.accepts(&stream("National Assembly | Afternoon Sitting", "4:37:08")));
+        assert!(!filters.accepts(&stream("National Assembly | Afternoon Sitting", "45:00")));
+        assert!(!filters.accepts(&stream("National Assembly | Afternoon Sitting", "7:00:00")));
+        assert!(!filters.accepts(&stream("Committee on Finance | Sitting", "2:00:00")));
+        assert!(!filters.accepts(&stream("Madaraka Day Celebrations", "2:00:00")));
+
+        let live = Stream {
+            status: StreamStatus::Live,
+            ..stream("Senate | Morning Sitting", "")
+        };
+        assert!(filters.accepts(&live), "live streams have no duration yet");
+    }
+
+    /// The fixture's ytInitialData with its tabs rearranged by `f`
+    fn fixture_with_tabs(f: impl FnOnce(&mut Vec<Value>)) -> Value {
+        let html = include_str!("../../tests/fixtures/yt.html");
+        let mut json = YtHtmlDocument::new(html.to_string())
+            .to_json::<Value>()
+            .unwrap();
+        let tabs = json["contents"]["twoColumnBrowseResultsRenderer"]["tabs"]
+            .as_array_mut()
+            .unwrap();
+        f(tabs);
+        json
+    }
+
+    #[test]
+    fn test_streams_tab_found_when_reordered() {
+        let expected = parse_streams(&fixture_with_tabs(|_| {}), &ParseFilters::default())
+            .unwrap()
+            .len();
+
+        let first = fixture_with_tabs(|tabs| tabs.swap(0, 2));
+        assert_eq!(
+            parse_streams(&first, &ParseFilters::default())
+                .unwrap()
+                .len(),
+            expected
+        );
+
+        let last = fixture_with_tabs(|tabs| {
+            let streams_tab = tabs.remove(2);
+            tabs.push(streams_tab);
+        });
+        assert_eq!(
+            parse_streams(&last, &ParseFilters::default())
+                .unwrap()
+                .len(),
+            expected
+        );
+
+        let shorts = json!({ "tabRenderer": {
+            "title": "Shorts",
+            "endpoint": { "commandMetadata": { "webCommandMetadata": {
+                "url": "/@ParliamentofKenyaChannel/shorts"
+            } } }
+        } });
+        let inserted = fixture_with_tabs(|tabs| tabs.insert(1, shorts));
+        assert_eq!(
+            parse_streams(&inserted, &ParseFilters::default())
+                .unwrap()
+                .len(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_streams_tab_found_by_its_grid() {
+        let expected = parse_streams(&fixture_with_tabs(|_| {}), &ParseFilters::default())
+            .unwrap()
+            .len();
+
+        let renamed = fixture_with_tabs(|tabs| {
+            let renderer = &mut tabs[2]["tabRenderer"];
+            renderer["title"] = json!("Mubashara");
+            renderer["endpoint"] = json!({});
+            tabs.swap(1, 2);
+        });
+        assert_eq!(
+            parse_streams(&renamed, &ParseFilters::default())
+                .unwrap()
+                .len(),
+            expected
+        );
+
+        let without_grid = fixture_with_tabs(|tabs| {
+            tabs[2]["tabRenderer"]["content"] = json!({});
+        });
+        assert!(matches!(
+            parse_streams(&without_grid, &ParseFilters::default()),
+            Err(Error::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_continuation() {
+        let json = json!({
+            "onResponseReceivedActions": [{
+                "appendContinuationItemsAction": {
+                    "continuationItems": [
+                        { "richItemRenderer": { "content": { "videoRenderer": {
+                            "videoId": "abc123",
+                            "thumbnail": { "thumbnails": [] },
+                            "title": { "runs": [{ "text": "Senate | Morning Sitting" }] },
+                            "publishedTimeText": { "simpleText": "Streamed 3 months ago" },
+                            "viewCountText": { "simpleText": "1,024 views" },
+                            "lengthText": {
+                                "accessibility": { "accessibilityData": { "label": "2 hours" } },
+                                "simpleText": "2:00:00"
+                            }
+                        } } } },
+                        { "continuationItemRenderer": { "continuationEndpoint": {
+                            "continuationCommand": { "token": "next-page" }
+                        } } }
+                    ]
+                }
+            }]
+        });
+
+        let page = parse_continuation(&json, &ParseFilters::default()).unwrap();
+        assert_eq!(page.streams.len(), 1);
+        assert_eq!(page.streams[0].video_id, "abc123");
+        assert_eq!(page.continuation.as_deref(), Some("next-page"));
+
+        let last_page = parse_continuation(
+            &json!({ "onResponseReceivedActions": [] }),
+            &ParseFilters::default(),
+        )
+        .unwrap();
+        assert!(last_page.streams.is_empty() && last_page.continuation.is_none());
+        assert!(parse_continuation(&json!({}), &ParseFilters::default()).is_err());
+    }
+
+    #[test]
+    fn test_upcoming_streams_are_scheduled() {
+        let json = json!({
+            "onResponseReceivedActions": [{
+                "appendContinuationItemsAction": {
+                    "continuationItems": [
+                        { "richItemRenderer": { "content": { "videoRenderer": {
+                            "videoId": "upcoming01",
+                            "thumbnail": { "thumbnails": [] },
+                            "title": { "runs": [{ "text": "Senate | Afternoon Sitting" }] },
+                            "upcomingEventData": {
+                                "isReminderSet": false,
+                                "startTime": "1741185000",
+                                "upcomingEventText": { "runs": [{ "text": "Scheduled for DATE_PLACEHOLDER" }] }
+                            }
+                        } } } }
+                    ]
+                }
+            }]
+        });
+
+        let page = parse_continuation(&json, &ParseFilters::default()).unwrap();
+        assert_eq!(page.streams.len(), 1);
+        let stream = &page.streams[0];
+        assert_eq!(stream.status, StreamStatus::Scheduled);
+        assert_eq!(
+            stream.published_at_exact.unwrap().to_rfc3339(),
+            "2025-03-05T14:30:00+00:00"
+        );
+        assert_eq!(stream.category, Some(StreamCategory::Senate));
+    }
+
+    #[test]
+    fn test_watch_page_published_at() {
+        let doc = YtHtmlDocument::from(
+            r#"<meta itemprop="startDate" content="2025-03-04T04:00:14-08:00">
+            "publishDate":"2025-03-04","liveBroadcastDetails":{"isLiveNow":false,"startTimestamp":"2025-03-04T04:00:14-08:00"}"#
+                .to_string(),
+        );
+        assert_eq!(
+            doc.published_at().unwrap().to_rfc3339(),
+            "2025-03-04T12:00:14+00:00"
+        );
+
+        let doc = YtHtmlDocument::from(r#""publishDate":"2025-03-04""#.to_string());
+        assert_eq!(doc.published_at(), None);
+    }
+
+    #[test]
+    fn test_watch_page_description() {
+        let doc = YtHtmlDocument::from(
+            r#""videoDetails":{"shortDescription":"Order of business\n0:00 Prayers \"Part 1\"","isCrawlable":true}"#
+                .to_string(),
+        );
+        assert_eq!(
+            doc.description().as_deref(),
+            Some("Order of business\n0:00 Prayers \"Part 1\"")
+        );
+
+        let doc = YtHtmlDocument::from(r#""shortDescription":"""#.to_string());
+        assert_eq!(doc.description(), None);
     }
 }
@@ -0,0 +1,147 @@
+//! # Audit Log
+//!
+//! An append-only trail of what runs did outside the process: the channels they listed
+//! and the audio they downloaded from YouTube, the provider requests each stream took,
+//! the streams they stored and the audio they deleted. Each action is a JSON line
+//! appended to `{workdir}/audit.jsonl`, e.g.
+//!
+//! ```json
+//! {"at":"2025-07-23T08:00:00Z","action":"audio_downloaded","video_id":"dQw4w9WgXcQ"}
+//! ```
+//!
+//! for tracing a cost anomaly back to the run and stream behind it. Entries are never
+//! rewritten, and the file is left for the operator to rotate.
+
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::ModelUsage;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub(crate) enum AuditAction<'a> {
+    RunStarted,
+    /// A source's channels were listed, before listed streams were filtered
+    ChannelsListed {
+        channels: Vec<String>,
+        streams: usize,
+    },
+    CaptionsFetched {
+        video_id: &'a str,
+    },
+    AudioDownloaded {
+        video_id: &'a str,
+    },
+    /// Requests a stream took to transcribe, summarize and enhance, by model
+    ProviderRequests {
+        video_id: &'a str,
+        model: &'a str,
+        #[serde(flatten)]
+        usage: ModelUsage,
+    },
+    StreamStored {
+        video_id: &'a str,
+    },
+    FilesDeleted {
+        paths: &'a [PathBuf],
+    },
+    RunFinished {
+        processed: usize,
+        failed: Option<&'a str>,
+    },
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    /// RFC 3339, in UTC
+    at: String,
+    #[serde(flatten)]
+    action: AuditAction<'a>,
+}
+
+/// Where the audit log is appended to, if it is kept, shared by the processor's clones
+/// and download threads. Failing to append an entry is logged rather than returned, so
+/// that the log never fails a run.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditLog {
+    path: Option<PathBuf>,
+    /// Held while appending, so that entries from several threads don't interleave
+    appending: Arc<Mutex<()>>,
+}
+
+impl AuditLog {
+    pub(crate) fn new(workdir: &Path) -> Self {
+        Self {
+            path: Some(workdir.join("audit.jsonl")),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn record(&self, action: AuditAction<'_>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let entry = AuditEntry {
+            at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            action,
+        };
+
+        let _appending = self.appending.lock().unwrap_or_else(|e| e.into_inner());
+        let appended = serde_json::to_vec(&entry)
+            .map_err(std::io::Error::from)
+            .and_then(|mut line| {
+                line.push(b'\n');
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?
+                    .write_all(&line)
+            });
+        if let Err(e) = appended {
+            tracing::warn!(error = %e, path = ?path, "Failed to append to the audit log");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_are_appended_as_json_lines() {
+        let workdir = std::env::temp_dir().join(format!("audit-{}", std::process::id()));
+        let audit = AuditLog::new(&workdir);
+        audit.record(AuditAction::RunStarted);
+        audit.record(AuditAction::ProviderRequests {
+            video_id: "abc",
+            model: "whisper-1",
+            usage: ModelUsage {
+                requests: 3,
+                ..Default::default()
+            },
+        });
+        AuditLog::default().record(AuditAction::RunStarted);
+
+        let log = std::fs::read_to_string(workdir.join("audit.jsonl")).unwrap();
+        let entries = log
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["action"], "run_started");
+        assert_eq!(entries[1]["action"], "provider_requests");
+        assert_eq!(entries[1]["requests"], 3);
+
+        let _ = std::fs::remove_dir_all(&workdir);
+    }
+}
@@ -1,18 +1,129 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
-use stream_datastore::DataStore;
+use stream_datastore::StreamCategory;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    yt::{AudioHandler, ChannelScraper},
-    LiveStreamProcessor, Summarizer, Transcriber,
+    hansard::{NoOrderPaperSource, OrderPaperSource},
+    processor::{
+        audit::AuditLog,
+        checkpoint::Checkpoints,
+        cleanup::CleanupConfig,
+        events::ProcessorEvents,
+        hooks::{Hooks, ProcessorHook},
+        priority::PrioritizationStrategy,
+        retention::RetentionPolicy,
+        retries::StreamRetryPolicy,
+        reuploads::ReuploadPolicy,
+        sources::Source,
+        timeouts::StageTimeouts,
+        PipelineStore,
+    },
+    yt::{
+        timedtext::{CaptionSource, NoCaptionSource},
+        AudioHandler, ChannelScraper,
+    },
+    CaptionFormat, CategoryClassifier, DivisionExtractor, Embedder, EntityExtractor,
+    LiveStreamProcessor, NoCategoryClassifier, NoDivisionExtractor, NoEmbedder, NoEntityExtractor,
+    Summarizer, Transcriber, UsageTracker,
 };
 
 #[derive(Debug, Clone)]
 pub struct ChunkingConfig {
     pub chunk_duration_seconds: u16,
+    /// Largest chunk file the transcriber accepts, e.g. 25MB for OpenAI. Chunks are
+    /// shortened below `chunk_duration_seconds` to stay under it, going by the bit rate
+    /// `ffprobe` reads from each stream's audio
+    pub max_chunk_bytes: Option<u64>,
+}
+
+/// Caption files to generate from each stream's transcript segments
+#[derive(Debug, Clone)]
+pub struct CaptionsConfig {
+    pub formats: Vec<CaptionFormat>,
+    pub destination: CaptionDestination,
+}
+
+/// Where generated caption files are kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaptionDestination {
+    /// `{workdir}/captions/{video_id}.{extension}`, outside the audio directory so they
+    /// survive the end of the run
+    #[default]
+    Workdir,
+    /// [`DataStore::insert_stream_captions`](stream_datastore::DataStore::insert_stream_captions)
+    DataStore,
+}
+
+impl FromStr for CaptionDestination {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "workdir" => Ok(CaptionDestination::Workdir),
+            "datastore" => Ok(CaptionDestination::DataStore),
+            other => Err(format!("Unsupported caption destination: {other}")),
+        }
+    }
+}
+
+/// Where stream thumbnails are copied to, so the site doesn't depend on YouTube's
+#[derive(Debug, Clone)]
+pub struct ThumbnailMirror {
+    /// Directory thumbnails are written to, as `{video_id}.jpg`
+    pub dir: PathBuf,
+    /// URL the directory is served from. Streams are stored with their copy's URL
+    /// when set, and with YouTube's otherwise
+    pub base_url: Option<String>,
+}
+
+/// Shortest and longest chunks [`LiveStreamProcessorBuilder::try_build`] accepts. Shorter
+/// chunks cut sentences too often to transcribe well, and longer ones are over every
+/// provider's upload limit
+pub const CHUNK_DURATION_SECONDS: std::ops::RangeInclusive<u16> = 10..=3600;
+
+/// A setting [`LiveStreamProcessorBuilder::try_build`] found would fail the run
+#[derive(Debug, thiserror::Error)]
+pub enum BuilderError {
+    #[error("Cannot write to {}: {source}", path.display())]
+    UnwritableDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("max_streams is 0, so no stream would be processed")]
+    NoStreams,
+    #[error(
+        "Chunk duration of {0}s is outside {min}..={max}s",
+        min = CHUNK_DURATION_SECONDS.start(),
+        max = CHUNK_DURATION_SECONDS.end()
+    )]
+    ChunkDuration(u16),
+    #[error("Largest chunk size is 0 bytes")]
+    NoChunkBytes,
+    #[error("{0} timeout is 0, so every stream would time out")]
+    NoTimeout(&'static str),
+    #[error("Retry policy allows no attempts")]
+    NoRetryAttempts,
 }
 
-pub struct LiveStreamProcessorBuilder<D = (), T = (), S = (), A = (), P = ()> {
+pub struct LiveStreamProcessorBuilder<
+    D = (),
+    T = (),
+    S = (),
+    A = (),
+    P = (),
+    E = NoEntityExtractor,
+    M = NoEmbedder,
+    V = NoDivisionExtractor,
+    C = NoCategoryClassifier,
+    K = NoCaptionSource,
+    O = NoOrderPaperSource,
+> {
     workdir: PathBuf,
     store: D,
     transcriber: T,
@@ -20,7 +131,38 @@ pub struct LiveStreamProcessorBuilder<D = (), T = (), S = (), A = (), P = ()> {
     audio_handler: A,
     channel_scraper: P,
     max_streams: usize,
+    sources: Vec<Source<P>>,
+    prioritization: PrioritizationStrategy,
+    download_concurrency: usize,
     chunking_config: Option<ChunkingConfig>,
+    usage_tracker: Option<UsageTracker>,
+    events: Option<ProcessorEvents>,
+    hooks: Vec<Arc<dyn ProcessorHook>>,
+    entity_extractor: Option<E>,
+    embedder: Option<M>,
+    division_extractor: Option<V>,
+    category_classifier: Option<C>,
+    caption_source: Option<K>,
+    order_paper_source: Option<O>,
+    timestamp_links: bool,
+    live_recordings_only: bool,
+    captions: Option<CaptionsConfig>,
+    thumbnail_mirror: Option<ThumbnailMirror>,
+    http_client: reqwest::Client,
+    persistence_check: bool,
+    preflight_checks: bool,
+    retention: RetentionPolicy,
+    cleanup: CleanupConfig,
+    timeouts: StageTimeouts,
+    retries: Option<StreamRetryPolicy>,
+    reuploads: Option<ReuploadPolicy>,
+    persist_transcripts: bool,
+    track_states: bool,
+    max_run_duration: Option<Duration>,
+    checkpoints: bool,
+    audit_log: bool,
+    shutdown: CancellationToken,
+    cancellation: CancellationToken,
 }
 
 impl LiveStreamProcessorBuilder {
@@ -33,16 +175,47 @@ impl LiveStreamProcessorBuilder {
             audio_handler: (),
             channel_scraper: (),
             max_streams: 5,
+            sources: Vec::new(),
+            prioritization: PrioritizationStrategy::default(),
+            download_concurrency: 2,
             chunking_config: None,
+            usage_tracker: None,
+            events: None,
+            hooks: Vec::new(),
+            entity_extractor: None,
+            embedder: None,
+            division_extractor: None,
+            category_classifier: None,
+            caption_source: None,
+            order_paper_source: None,
+            timestamp_links: false,
+            live_recordings_only: false,
+            captions: None,
+            thumbnail_mirror: None,
+            http_client: reqwest::Client::default(),
+            persistence_check: false,
+            preflight_checks: false,
+            retention: RetentionPolicy::default(),
+            cleanup: CleanupConfig::default(),
+            timeouts: StageTimeouts::default(),
+            retries: None,
+            reuploads: None,
+            persist_transcripts: false,
+            track_states: false,
+            max_run_duration: None,
+            checkpoints: false,
+            audit_log: false,
+            shutdown: CancellationToken::new(),
+            cancellation: CancellationToken::new(),
         }
     }
 }
 
-impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P> {
-    pub fn store<D2: DataStore + Send + Sync + 'static>(
+impl<D, T, S, A, P, E, M, V, C, K, O> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C, K, O> {
+    pub fn store<D2: PipelineStore + Send + Sync + 'static>(
         self,
         store: D2,
-    ) -> LiveStreamProcessorBuilder<D2, T, S, A, P> {
+    ) -> LiveStreamProcessorBuilder<D2, T, S, A, P, E, M, V, C, K, O> {
         LiveStreamProcessorBuilder {
             workdir: self.workdir,
             store,
@@ -51,14 +224,45 @@ impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P> {
             audio_handler: self.audio_handler,
             channel_scraper: self.channel_scraper,
             max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
             chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
         }
     }
 
     pub fn transcriber<T2: Transcriber + Send + Sync + 'static>(
         self,
         transcriber: T2,
-    ) -> LiveStreamProcessorBuilder<D, T2, S, A, P> {
+    ) -> LiveStreamProcessorBuilder<D, T2, S, A, P, E, M, V, C, K, O> {
         LiveStreamProcessorBuilder {
             workdir: self.workdir,
             store: self.store,
@@ -67,14 +271,45 @@ impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P> {
             audio_handler: self.audio_handler,
             channel_scraper: self.channel_scraper,
             max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
             chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
         }
     }
 
     pub fn summarizer<S2: Summarizer + Send + Sync + 'static>(
         self,
         summarizer: S2,
-    ) -> LiveStreamProcessorBuilder<D, T, S2, A, P> {
+    ) -> LiveStreamProcessorBuilder<D, T, S2, A, P, E, M, V, C, K, O> {
         LiveStreamProcessorBuilder {
             workdir: self.workdir,
             store: self.store,
@@ -83,14 +318,45 @@ impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P> {
             audio_handler: self.audio_handler,
             channel_scraper: self.channel_scraper,
             max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
             chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
         }
     }
 
     pub fn audio_handler<A2: AudioHandler + Send + Sync + 'static>(
         self,
         audio_handler: A2,
-    ) -> LiveStreamProcessorBuilder<D, T, S, A2, P> {
+    ) -> LiveStreamProcessorBuilder<D, T, S, A2, P, E, M, V, C, K, O> {
         LiveStreamProcessorBuilder {
             workdir: self.workdir,
             store: self.store,
@@ -99,14 +365,45 @@ impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P> {
             audio_handler,
             channel_scraper: self.channel_scraper,
             max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
             chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
         }
     }
 
     pub fn channel_scraper<P2: ChannelScraper + Send + Sync + 'static>(
         self,
         channel_scraper: P2,
-    ) -> LiveStreamProcessorBuilder<D, T, S, A, P2> {
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P2, E, M, V, C, K, O> {
         LiveStreamProcessorBuilder {
             workdir: self.workdir,
             store: self.store,
@@ -115,7 +412,390 @@ impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P> {
             audio_handler: self.audio_handler,
             channel_scraper,
             max_streams: self.max_streams,
+            sources: Vec::new(),
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
+            chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
+        }
+    }
+
+    /// Extract the MPs, bills and committees mentioned in each stream with
+    /// `entity_extractor` and store them alongside it
+    pub fn entity_extractor<E2: EntityExtractor + Send + Sync + 'static>(
+        self,
+        entity_extractor: E2,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E2, M, V, C, K, O> {
+        self.maybe_entity_extractor(Some(entity_extractor))
+    }
+
+    /// Like [`Self::entity_extractor`], skipping extraction when `entity_extractor` is
+    /// `None`
+    pub fn maybe_entity_extractor<E2: EntityExtractor + Send + Sync + 'static>(
+        self,
+        entity_extractor: Option<E2>,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E2, M, V, C, K, O> {
+        LiveStreamProcessorBuilder {
+            workdir: self.workdir,
+            store: self.store,
+            transcriber: self.transcriber,
+            summarizer: self.summarizer,
+            audio_handler: self.audio_handler,
+            channel_scraper: self.channel_scraper,
+            max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
+            chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
+        }
+    }
+
+    /// Embed each stream's summary and transcript passages with `embedder`, for
+    /// semantic search, storing them with
+    /// [`EmbeddingStore::store_stream_embeddings`](stream_datastore::EmbeddingStore::store_stream_embeddings)
+    #[cfg(feature = "pgvector")]
+    pub fn embedder<M2: Embedder + Send + Sync + 'static>(
+        self,
+        embedder: M2,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M2, V, C, K, O> {
+        self.maybe_embedder(Some(embedder))
+    }
+
+    /// Like [`Self::embedder`], skipping embedding when `embedder` is `None`
+    #[cfg(feature = "pgvector")]
+    pub fn maybe_embedder<M2: Embedder + Send + Sync + 'static>(
+        self,
+        embedder: Option<M2>,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M2, V, C, K, O> {
+        LiveStreamProcessorBuilder {
+            workdir: self.workdir,
+            store: self.store,
+            transcriber: self.transcriber,
+            summarizer: self.summarizer,
+            audio_handler: self.audio_handler,
+            channel_scraper: self.channel_scraper,
+            max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
+            chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
+        }
+    }
+
+    /// Extract the divisions held during each stream with `division_extractor` and
+    /// store them alongside it
+    pub fn division_extractor<V2: DivisionExtractor + Send + Sync + 'static>(
+        self,
+        division_extractor: V2,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V2, C, K, O> {
+        self.maybe_division_extractor(Some(division_extractor))
+    }
+
+    /// Like [`Self::division_extractor`], skipping extraction when `division_extractor`
+    /// is `None`
+    pub fn maybe_division_extractor<V2: DivisionExtractor + Send + Sync + 'static>(
+        self,
+        division_extractor: Option<V2>,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V2, C, K, O> {
+        LiveStreamProcessorBuilder {
+            workdir: self.workdir,
+            store: self.store,
+            transcriber: self.transcriber,
+            summarizer: self.summarizer,
+            audio_handler: self.audio_handler,
+            channel_scraper: self.channel_scraper,
+            max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
+            chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
+        }
+    }
+
+    /// Classify the streams whose titles don't name a house with `category_classifier`,
+    /// so they are stored with one
+    pub fn category_classifier<C2: CategoryClassifier + Send + Sync + 'static>(
+        self,
+        category_classifier: C2,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C2, K, O> {
+        self.maybe_category_classifier(Some(category_classifier))
+    }
+
+    /// Like [`Self::category_classifier`], storing the streams whose titles don't name a
+    /// house as [`StreamCategory::Other`] when `category_classifier` is `None`
+    ///
+    /// [`StreamCategory::Other`]: stream_datastore::StreamCategory::Other
+    pub fn maybe_category_classifier<C2: CategoryClassifier + Send + Sync + 'static>(
+        self,
+        category_classifier: Option<C2>,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C2, K, O> {
+        LiveStreamProcessorBuilder {
+            workdir: self.workdir,
+            store: self.store,
+            transcriber: self.transcriber,
+            summarizer: self.summarizer,
+            audio_handler: self.audio_handler,
+            channel_scraper: self.channel_scraper,
+            max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
+            chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
+        }
+    }
+
+    /// Transcribe streams from the captions `caption_source` finds for them where it
+    /// can, instead of downloading their audio for the transcriber
+    pub fn caption_source<K2: CaptionSource + Send + Sync + 'static>(
+        self,
+        caption_source: K2,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C, K2, O> {
+        self.maybe_caption_source(Some(caption_source))
+    }
+
+    /// Like [`Self::caption_source`], downloading every stream's audio when
+    /// `caption_source` is `None`
+    pub fn maybe_caption_source<K2: CaptionSource + Send + Sync + 'static>(
+        self,
+        caption_source: Option<K2>,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C, K2, O> {
+        LiveStreamProcessorBuilder {
+            workdir: self.workdir,
+            store: self.store,
+            transcriber: self.transcriber,
+            summarizer: self.summarizer,
+            audio_handler: self.audio_handler,
+            channel_scraper: self.channel_scraper,
+            max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
+            chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
+        }
+    }
+
+    /// Attach the order paper `order_paper_source` finds for each stream's sitting,
+    /// which is passed to the summarizer and stored with the stream
+    pub fn order_paper_source<O2: OrderPaperSource + Send + Sync + 'static>(
+        self,
+        order_paper_source: O2,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C, K, O2> {
+        self.maybe_order_paper_source(Some(order_paper_source))
+    }
+
+    /// Like [`Self::order_paper_source`], attaching no order papers when
+    /// `order_paper_source` is `None`
+    pub fn maybe_order_paper_source<O2: OrderPaperSource + Send + Sync + 'static>(
+        self,
+        order_paper_source: Option<O2>,
+    ) -> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C, K, O2> {
+        LiveStreamProcessorBuilder {
+            workdir: self.workdir,
+            store: self.store,
+            transcriber: self.transcriber,
+            summarizer: self.summarizer,
+            audio_handler: self.audio_handler,
+            channel_scraper: self.channel_scraper,
+            max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
             chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: self.hooks,
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            checkpoints: self.checkpoints,
+            audit_log: self.audit_log,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
         }
     }
 
@@ -124,32 +804,362 @@ impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P> {
         self
     }
 
-    pub fn with_chunking(mut self, chunk_duration_seconds: u16) -> Self {
-        self.chunking_config = Some(ChunkingConfig {
+    /// The order listed streams are processed in, and so which of them are left for
+    /// later runs beyond [`Self::max_streams`]. Oldest first by default
+    pub fn with_prioritization(mut self, strategy: PrioritizationStrategy) -> Self {
+        self.prioritization = strategy;
+        self
+    }
+
+    /// Streams to download and clean the audio of at once, 2 by default, while earlier
+    /// streams are transcribed. Each download takes a thread, and more of them at once
+    /// mostly compete for bandwidth and get throttled by YouTube sooner.
+    pub fn download_concurrency(mut self, download_concurrency: usize) -> Self {
+        self.download_concurrency = download_concurrency.max(1);
+        self
+    }
+
+    pub fn with_chunking(self, chunk_duration_seconds: u16) -> Self {
+        self.with_chunking_config(ChunkingConfig {
             chunk_duration_seconds,
+            max_chunk_bytes: None,
+        })
+    }
+
+    /// Transcribe each stream's audio in chunks as `config` describes, e.g. to limit
+    /// their size as well as their duration
+    pub fn with_chunking_config(mut self, config: ChunkingConfig) -> Self {
+        self.chunking_config = Some(config);
+        self
+    }
+
+    /// Have summaries cite the offsets of key points in the transcript, linked to that
+    /// moment of the stream. Requires a transcriber that returns segment timestamps.
+    pub fn with_timestamp_links(mut self, enabled: bool) -> Self {
+        self.timestamp_links = enabled;
+        self
+    }
+
+    /// Skip videos that were uploaded rather than streamed live, e.g. clips and
+    /// edited recordings, going by [`Stream::is_live_recording`]
+    ///
+    /// [`Stream::is_live_recording`]: stream_datastore::Stream::is_live_recording
+    pub fn with_live_recordings_only(mut self, enabled: bool) -> Self {
+        self.live_recordings_only = enabled;
+        self
+    }
+
+    /// Generate caption files in each of `formats` from the transcript segments and
+    /// keep them in `destination`. Requires a transcriber that returns segment timestamps.
+    pub fn with_captions(
+        mut self,
+        formats: impl IntoIterator<Item = CaptionFormat>,
+        destination: CaptionDestination,
+    ) -> Self {
+        let formats = formats.into_iter().collect::<Vec<_>>();
+        self.captions = (!formats.is_empty()).then_some(CaptionsConfig {
+            formats,
+            destination,
+        });
+        self
+    }
+
+    /// Copy each stream's thumbnail into the mirror's directory before it is stored.
+    /// `None` stores streams with YouTube's thumbnail URLs only.
+    pub fn with_thumbnail_mirror(mut self, thumbnail_mirror: Option<ThumbnailMirror>) -> Self {
+        self.thumbnail_mirror = thumbnail_mirror;
+        self
+    }
+
+    /// Send the processor's own requests, e.g. for mirroring thumbnails, through
+    /// `client` instead of a default one. Pass the same client to the scraper and the
+    /// LLM providers to share its proxy, timeout, TLS and user agent settings, or point
+    /// a test at a local server.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = client;
+        self
+    }
+
+    /// Check that every stream processed in a run can be read back from the store once
+    /// the run is done, failing the run if any can't. Streams are stored as soon as they
+    /// are summarized either way, so that a run cut short keeps the streams before it.
+    pub fn with_persistence_check(mut self, enabled: bool) -> Self {
+        self.persistence_check = enabled;
+        self
+    }
+
+    /// Before downloading any audio, check that yt-dlp and ffmpeg can be run and that the
+    /// workdir's disk has room for the audio, estimated from the streams' durations
+    pub fn with_preflight_checks(mut self, enabled: bool) -> Self {
+        self.preflight_checks = enabled;
+        self
+    }
+
+    /// What to do with each stream's downloaded audio and chunks.
+    /// [`RetentionPolicy::KeepOnFailure`] by default
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    /// Which ffmpeg passes clean up each stream's downloaded audio before it is
+    /// transcribed, all of them by default
+    pub fn with_cleanup(mut self, cleanup: CleanupConfig) -> Self {
+        self.cleanup = cleanup;
+        self
+    }
+
+    /// Fail a stream whose transcription or summary takes longer than `timeouts` allow
+    pub fn with_timeouts(mut self, timeouts: StageTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Record the streams that fail with the store, as a
+    /// [`FailedStreamStore`](stream_datastore::FailedStreamStore), and retry them on later
+    /// runs as `policy` allows. Without a policy a stream that failed is listed again on
+    /// the next run like any other
+    pub fn with_retries(mut self, policy: Option<StreamRetryPolicy>) -> Self {
+        self.retries = policy;
+        self
+    }
+
+    /// Match listed streams against the stored ones by title, date and duration, and
+    /// handle those that are the same sitting uploaded again as `policy` says, recording
+    /// them with the store as a [`ReuploadStore`](stream_datastore::ReuploadStore).
+    /// Without a policy re-uploads are processed like any other stream
+    pub fn with_reupload_detection(mut self, policy: Option<ReuploadPolicy>) -> Self {
+        self.reuploads = policy;
+        self
+    }
+
+    /// Append each action a run takes outside the process, e.g. downloading a stream's
+    /// audio, the provider requests it took, storing it and deleting its audio, to
+    /// `{workdir}/audit.jsonl`, for accounting for what was fetched and what it cost
+    pub fn with_audit_log(mut self, enabled: bool) -> Self {
+        self.audit_log = enabled;
+        self
+    }
+
+    /// Start no more streams once a run has taken `max_run_duration`, finishing the one
+    /// being processed and leaving the rest for the next run, e.g. so that runs on a
+    /// schedule don't overlap. Unlimited by default
+    pub fn with_max_run_duration(mut self, max_run_duration: Option<Duration>) -> Self {
+        self.max_run_duration = max_run_duration;
+        self
+    }
+
+    /// Record each stream's progress through the pipeline in the workdir, so that a run
+    /// after a failed one resumes it from its last completed stage, e.g. summarizing a
+    /// stream transcribed before the failure without transcribing it again
+    pub fn with_checkpoints(mut self, enabled: bool) -> Self {
+        self.checkpoints = enabled;
+        self
+    }
+
+    /// Store each stream's transcript with the store as soon as it is transcribed, and
+    /// summarize streams with a stored transcript from it instead of transcribing them
+    /// again. Unlike checkpoints, stored transcripts outlive the workdir, e.g. for
+    /// summarizing streams again with a better model. They are stored with
+    /// [`TranscriptStore::insert_stream_transcript`](stream_datastore::TranscriptStore::insert_stream_transcript).
+    pub fn with_transcript_persistence(mut self, enabled: bool) -> Self {
+        self.persist_transcripts = enabled;
+        self
+    }
+
+    /// Record each stream's [`StreamState`](stream_datastore::StreamState) with the store
+    /// as it moves through the pipeline, and the stage it failed in if it fails, e.g. for
+    /// showing where the streams that aren't stored yet stopped, with
+    /// [`StreamStateStore::set_stream_state`](stream_datastore::StreamStateStore::set_stream_state).
+    pub fn with_state_tracking(mut self, enabled: bool) -> Self {
+        self.track_states = enabled;
+        self
+    }
+
+    /// Stop the run once `shutdown` is cancelled, e.g. on SIGTERM. The stream being
+    /// processed is finished, or left at its transcript checkpoint when checkpoints are
+    /// enabled, running downloads are killed, and the remaining streams are left for the
+    /// next run. The run then returns successfully.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Stop the run at the next stage boundary once `cancellation` is cancelled, e.g.
+    /// from an admin endpoint or a supervisor. Unlike a shutdown nothing is killed: the
+    /// stage in progress is finished, the stream being processed is left at its last
+    /// checkpoint, and the run returns a [`RunReport`](crate::RunReport) of what it did,
+    /// marked as cancelled.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Report the usage recorded in `usage_tracker` after each stream is processed.
+    /// The same tracker should be passed to the transcriber and summarizer.
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = Some(usage_tracker);
+        self
+    }
+
+    /// Send the run's progress to the subscribers of `events`. The same events should be
+    /// passed to the transcriber, which reports the chunks it transcribes.
+    pub fn with_events(mut self, events: ProcessorEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Call `hook` at each stage of processing a stream, after the hooks registered
+    /// before it
+    pub fn with_hook(mut self, hook: impl ProcessorHook + 'static) -> Self {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+}
+
+impl<D, T, S, A, P, E, M, V, C, K, O> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C, K, O>
+where
+    P: ChannelScraper + Send + Sync + 'static,
+{
+    /// List streams from `scraper` too, e.g. another house's channel, and process up to
+    /// `max_streams` of them per run on top of the channel scraper's
+    /// [`Self::max_streams`]. Streams listed without a category are given `category`.
+    /// The sources' streams are processed in turn, and looked up by video ID with the
+    /// channel scraper, so this is called after [`Self::channel_scraper`].
+    pub fn with_source(
+        mut self,
+        scraper: P,
+        category: Option<StreamCategory>,
+        max_streams: usize,
+    ) -> Self {
+        self.sources.push(Source {
+            scraper,
+            category,
+            max_streams,
         });
         self
     }
 }
 
-impl<D, T, S, A, P> LiveStreamProcessorBuilder<D, T, S, A, P>
+impl<D, T, S, A, P, E, M, V, C, K, O> LiveStreamProcessorBuilder<D, T, S, A, P, E, M, V, C, K, O>
 where
-    D: DataStore + Send + Sync + 'static,
+    D: PipelineStore + Send + Sync + 'static,
     T: Transcriber + Send + Sync + 'static,
     S: Summarizer + Send + Sync + 'static,
     A: AudioHandler + Send + Sync + 'static,
     P: ChannelScraper + Send + Sync + 'static,
+    E: EntityExtractor + Send + Sync + 'static,
+    M: Embedder + Send + Sync + 'static,
+    V: DivisionExtractor + Send + Sync + 'static,
+    C: CategoryClassifier + Send + Sync + 'static,
+    K: CaptionSource + Send + Sync + 'static,
+    O: OrderPaperSource + Send + Sync + 'static,
 {
-    pub fn build(self) -> LiveStreamProcessor<D, T, S, A, P> {
+    /// Like [`Self::build`], but checks the settings up front that would otherwise fail
+    /// the run once it gets to them, creating the workdir and the thumbnail mirror's
+    /// directory if they don't exist yet
+    #[allow(clippy::type_complexity)]
+    pub fn try_build(
+        self,
+    ) -> Result<LiveStreamProcessor<D, T, S, A, P, E, M, V, C, K, O>, BuilderError> {
+        self.validate()?;
+        Ok(self.build())
+    }
+
+    fn validate(&self) -> Result<(), BuilderError> {
+        check_writable(&self.workdir)?;
+        if let Some(mirror) = &self.thumbnail_mirror {
+            check_writable(&mirror.dir)?;
+        }
+        if self.max_streams == 0 && self.sources.iter().all(|s| s.max_streams == 0) {
+            return Err(BuilderError::NoStreams);
+        }
+        if let Some(config) = &self.chunking_config {
+            if !CHUNK_DURATION_SECONDS.contains(&config.chunk_duration_seconds) {
+                return Err(BuilderError::ChunkDuration(config.chunk_duration_seconds));
+            }
+            if config.max_chunk_bytes == Some(0) {
+                return Err(BuilderError::NoChunkBytes);
+            }
+        }
+        for (stage, timeout) in [
+            ("transcribe", self.timeouts.transcribe),
+            ("summarize", self.timeouts.summarize),
+        ] {
+            if timeout == Some(Duration::ZERO) {
+                return Err(BuilderError::NoTimeout(stage));
+            }
+        }
+        if self.retries.is_some_and(|policy| policy.max_attempts == 0) {
+            return Err(BuilderError::NoRetryAttempts);
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> LiveStreamProcessor<D, T, S, A, P, E, M, V, C, K, O> {
+        let checkpoints = match self.checkpoints {
+            true => Checkpoints::new(&self.workdir),
+            false => Checkpoints::default(),
+        };
+        let audit = match self.audit_log {
+            true => AuditLog::new(&self.workdir),
+            false => AuditLog::default(),
+        };
         LiveStreamProcessor {
             workdir: self.workdir,
             store: self.store,
             transcriber: self.transcriber,
             summarizer: self.summarizer,
-            audio_handler: self.audio_handler,
+            audio_handler: Arc::new(self.audio_handler),
             channel_scraper: self.channel_scraper,
             max_streams: self.max_streams,
+            sources: self.sources,
+            prioritization: self.prioritization,
+            download_concurrency: self.download_concurrency,
             chunking_config: self.chunking_config,
+            usage_tracker: self.usage_tracker,
+            events: self.events,
+            hooks: Hooks::new(self.hooks),
+            entity_extractor: self.entity_extractor,
+            embedder: self.embedder,
+            division_extractor: self.division_extractor,
+            category_classifier: self.category_classifier,
+            caption_source: self.caption_source,
+            order_paper_source: self.order_paper_source,
+            timestamp_links: self.timestamp_links,
+            live_recordings_only: self.live_recordings_only,
+            captions: self.captions,
+            thumbnail_mirror: self.thumbnail_mirror,
+            http_client: self.http_client,
+            persistence_check: self.persistence_check,
+            preflight_checks: self.preflight_checks,
+            retention: self.retention,
+            cleanup: self.cleanup,
+            timeouts: self.timeouts,
+            retries: self.retries,
+            reuploads: self.reuploads,
+            persist_transcripts: self.persist_transcripts,
+            track_states: self.track_states,
+            max_run_duration: self.max_run_duration,
+            deadline: None,
+            report: Default::default(),
+            checkpoints,
+            shutdown: self.shutdown,
+            cancellation: self.cancellation,
+            incomplete: false,
+            audit,
         }
     }
 }
+
+/// Creates `dir` if needed, and writes and removes a file in it
+fn check_writable(dir: &Path) -> Result<(), BuilderError> {
+    let unwritable = |source| BuilderError::UnwritableDir {
+        path: dir.to_path_buf(),
+        source,
+    };
+    std::fs::create_dir_all(dir).map_err(unwritable)?;
+    let probe = dir.join(".stream-pulse-write-check");
+    std::fs::write(&probe, b"").map_err(unwritable)?;
+    std::fs::remove_file(&probe).map_err(unwritable)
+}
@@ -0,0 +1,206 @@
+//! # Checkpoints
+//!
+//! Records how far each stream got through the pipeline in
+//! `{workdir}/checkpoints/{video_id}.json`, so that a run after a failed one resumes
+//! the stream from its last completed stage instead of paying for its transcription
+//! and summary again. Chunking is resumed by the transcriber itself, which caches the
+//! transcription of each chunk next to it.
+//!
+//! The [`StreamState`] of each stream of a run is kept alongside, whether or not
+//! checkpoints are, so that the processor knows which stage failed a stream and
+//! only moves streams through the pipeline's legal transitions.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use stream_datastore::StreamState;
+
+use crate::{SummaryResponse, TranscribeResponse};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Checkpoint {
+    /// Last completed stage, checkpoints written before states were named for the
+    /// stage having it as `stage`
+    #[serde(alias = "stage")]
+    pub(crate) state: StreamState,
+    /// The downloaded audio once downloaded, and the cleaned audio once cleaned
+    pub(crate) audio_path: Option<PathBuf>,
+    pub(crate) transcript: Option<TranscribeResponse>,
+    pub(crate) summary: Option<SummaryResponse>,
+}
+
+impl Checkpoint {
+    /// The audio recorded in `state`, if that is the stream's state. Audio removed since,
+    /// e.g. after a successful run that didn't include the stream, is downloaded again,
+    /// which is logged.
+    pub(crate) fn audio_at(&self, state: StreamState) -> Option<PathBuf> {
+        let path = self.audio_path.clone().filter(|_| self.state == state)?;
+        if !path.exists() {
+            tracing::warn!(path = ?path, %state, "Checkpointed audio is gone");
+            return None;
+        }
+        Some(path)
+    }
+
+    pub(crate) fn completed(&self, state: StreamState) -> bool {
+        self.state.reached(state)
+    }
+
+    /// Starts the stream over, once the audio it got to its state with is gone
+    pub(crate) fn restart(&mut self) {
+        self.state = StreamState::Discovered;
+        self.audio_path = None;
+    }
+}
+
+/// The directory checkpoints are kept in, if they are kept, and the states of the
+/// run's streams, shared by the processor's clones. Failing to read or write a
+/// checkpoint is logged rather than returned, since a missing checkpoint only costs the
+/// stream its progress.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Checkpoints {
+    dir: Option<PathBuf>,
+    states: Arc<Mutex<HashMap<String, StreamState>>>,
+}
+
+impl Checkpoints {
+    pub(crate) fn new(workdir: &Path) -> Self {
+        Self {
+            dir: Some(workdir.join("checkpoints")),
+            ..Default::default()
+        }
+    }
+
+    fn states(&self) -> std::sync::MutexGuard<'_, HashMap<String, StreamState>> {
+        self.states.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The stream's state in this run, `Discovered` until its checkpoint is loaded
+    pub(crate) fn state(&self, video_id: &str) -> StreamState {
+        self.states().get(video_id).copied().unwrap_or_default()
+    }
+
+    /// Moves the stream to the failed state of its next stage, without saving it, so
+    /// that the next run resumes it from its last completed stage. Returns the state.
+    pub(crate) fn fail(&self, video_id: &str) -> StreamState {
+        let mut states = self.states();
+        let state = states.entry(video_id.to_string()).or_default();
+        *state = state.failed();
+        *state
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    fn path(&self, video_id: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{video_id}.json")))
+    }
+
+    /// The stream's checkpoint, or an empty one if it has none, starting the stream's
+    /// state in this run from it
+    pub(crate) fn load(&self, video_id: &str) -> Checkpoint {
+        let checkpoint = self.read(video_id);
+        self.states().insert(video_id.to_string(), checkpoint.state);
+        checkpoint
+    }
+
+    fn read(&self, video_id: &str) -> Checkpoint {
+        let Some(path) = self.path(video_id) else {
+            return Checkpoint::default();
+        };
+        let Ok(contents) = std::fs::read(&path) else {
+            return Checkpoint::default();
+        };
+        serde_json::from_slice(&contents)
+            .inspect_err(|e| tracing::warn!(error = %e, path = ?path, "Corrupt checkpoint"))
+            .unwrap_or_default()
+    }
+
+    /// Records that the stream reached `state`, unless it can't from the state it is in,
+    /// which is logged and leaves the checkpoint as it was
+    pub(crate) fn save(&self, video_id: &str, checkpoint: &mut Checkpoint, state: StreamState) {
+        match checkpoint.state.transition(state) {
+            Ok(state) => checkpoint.state = state,
+            Err(e) => {
+                tracing::warn!(error = %e, video_id, "Skipped saving checkpoint");
+                return;
+            }
+        }
+        self.states().insert(video_id.to_string(), state);
+        let (Some(dir), Some(path)) = (&self.dir, self.path(video_id)) else {
+            return;
+        };
+        let result = std::fs::create_dir_all(dir)
+            .and_then(|_| serde_json::to_vec(checkpoint).map_err(std::io::Error::from))
+            .and_then(|contents| std::fs::write(&path, contents));
+        match result {
+            Ok(()) => tracing::debug!(video_id, %state, "Saved checkpoint"),
+            Err(e) => tracing::warn!(error = %e, path = ?path, "Failed to save checkpoint"),
+        }
+    }
+
+    /// Removes the stream's checkpoint once it is stored
+    pub(crate) fn clear(&self, video_id: &str) {
+        self.states()
+            .insert(video_id.to_string(), StreamState::Stored);
+        let Some(path) = self.path(video_id) else {
+            return;
+        };
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(error = %e, path = ?path, "Failed to remove checkpoint");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoints_round_trip() {
+        let workdir = std::env::temp_dir().join(format!("checkpoints-{}", std::process::id()));
+        let checkpoints = Checkpoints::new(&workdir);
+        assert_eq!(checkpoints.load("abc").state, StreamState::Discovered);
+
+        let mut checkpoint = Checkpoint {
+            transcript: Some(TranscribeResponse {
+                duration: 60.0,
+                text: "Order, order".into(),
+                segments: None,
+            }),
+            ..Default::default()
+        };
+        checkpoints.save("abc", &mut checkpoint, StreamState::Transcribed);
+
+        let loaded = checkpoints.load("abc");
+        assert!(loaded.completed(StreamState::Cleaned));
+        assert!(!loaded.completed(StreamState::Summarized));
+        assert_eq!(loaded.transcript.as_ref().unwrap().text, "Order, order");
+        // the audio of a later stage than the one recorded is not resumed from
+        assert!(loaded.audio_at(StreamState::Cleaned).is_none());
+
+        checkpoints.clear("abc");
+        assert_eq!(checkpoints.state("abc"), StreamState::Stored);
+        assert_eq!(checkpoints.load("abc").state, StreamState::Discovered);
+        let _ = std::fs::remove_dir_all(&workdir);
+    }
+
+    #[test]
+    fn test_disabled_checkpoints_are_not_kept() {
+        let checkpoints = Checkpoints::default();
+        let mut checkpoint = Checkpoint::default();
+        checkpoints.save("abc", &mut checkpoint, StreamState::Downloaded);
+        assert_eq!(checkpoint.state, StreamState::Downloaded);
+        assert_eq!(checkpoints.state("abc"), StreamState::Downloaded);
+        assert_eq!(checkpoints.load("abc").state, StreamState::Discovered);
+    }
+}
@@ -0,0 +1,99 @@
+//! # Cleanup
+//!
+//! The ffmpeg passes run over each stream's downloaded audio before it is transcribed:
+//! denoising, normalizing its volume and trimming its silences, in that order. Each pass
+//! writes another copy of the audio and takes a while on a long sitting, and Whisper
+//! copes with the raw download well, so deployments short on disk or CPU can skip some
+//! or all of them.
+
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanupConfig {
+    pub denoise: bool,
+    pub normalize: bool,
+    pub trim_silence: bool,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromStr for CleanupConfig {
+    type Err = String;
+
+    /// Parses "all", "none", or the passes to run separated by commas, e.g.
+    /// "normalize,trim-silence"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "all" => return Ok(Self::all()),
+            "none" | "" => return Ok(Self::none()),
+            _ => {}
+        }
+        let mut config = Self::none();
+        for pass in s.split(',').map(str::trim) {
+            match pass {
+                "denoise" => config.denoise = true,
+                "normalize" => config.normalize = true,
+                "trim-silence" => config.trim_silence = true,
+                other => return Err(format!("Unsupported audio cleanup pass: {other}")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl CleanupConfig {
+    /// Every pass, the default
+    pub fn all() -> Self {
+        Self {
+            denoise: true,
+            normalize: true,
+            trim_silence: true,
+        }
+    }
+
+    /// Transcribe the downloaded audio as is
+    pub fn none() -> Self {
+        Self {
+            denoise: false,
+            normalize: false,
+            trim_silence: false,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::none()
+    }
+
+    /// Copies of the audio the passes write
+    pub(crate) fn copies(&self) -> u64 {
+        [self.denoise, self.normalize, self.trim_silence]
+            .into_iter()
+            .filter(|&pass| pass)
+            .count() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleanup_passes_are_parsed() {
+        assert_eq!("all".parse(), Ok(CleanupConfig::all()));
+        assert_eq!(" None ".parse(), Ok(CleanupConfig::none()));
+        assert_eq!(
+            "normalize, trim-silence".parse(),
+            Ok(CleanupConfig {
+                denoise: false,
+                normalize: true,
+                trim_silence: true,
+            })
+        );
+        assert!("denoise,compress".parse::<CleanupConfig>().is_err());
+    }
+}
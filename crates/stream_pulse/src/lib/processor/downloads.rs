@@ -0,0 +1,174 @@
+//! # Downloads
+//!
+//! Streams' audio is downloaded and cleaned in the background while the streams before
+//! them are transcribed and summarized, so that a run's downloads and transcriptions
+//! overlap instead of one waiting on all of the other. At most `download_concurrency`
+//! downloads run at once, and at most as many prepared streams wait to be transcribed,
+//! so that a run that stops early hasn't downloaded audio for every stream it listed.
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::Context;
+use futures::StreamExt;
+use stream_datastore::{Stream, StreamState};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    processor::{
+        audit::{AuditAction, AuditLog},
+        checkpoint::{Checkpoint, Checkpoints},
+        cleanup::CleanupConfig,
+        report::RunReport,
+    },
+    yt::AudioHandler,
+    TranscribeResponse,
+};
+
+/// Where a stream's transcript comes from
+pub(crate) enum TranscriptSource {
+    /// Transcribed on an earlier run that failed later on
+    Checkpoint(TranscribeResponse),
+    Captions(TranscribeResponse),
+    /// Audio downloaded for the transcriber
+    Audio(PathBuf),
+}
+
+/// A stream's transcript source and checkpoint, or `None` once the run is stopping
+pub(crate) type Prepared = anyhow::Result<Option<(TranscriptSource, Checkpoint)>>;
+
+/// What preparing a stream's audio needs from the processor, shared with the
+/// download threads
+pub(crate) struct Downloader<A> {
+    pub(crate) audio_handler: Arc<A>,
+    pub(crate) checkpoints: Checkpoints,
+    pub(crate) report: Arc<Mutex<RunReport>>,
+    pub(crate) audio_dl_path: PathBuf,
+    pub(crate) cleanup: CleanupConfig,
+    pub(crate) shutdown: CancellationToken,
+    pub(crate) deadline: Option<Instant>,
+    /// Cancelled once the prepared streams are no longer being processed, e.g. after a
+    /// stream failed or the run was cancelled, so that the downloads yet to start are
+    /// skipped
+    pub(crate) abandoned: CancellationToken,
+    pub(crate) audit: AuditLog,
+}
+
+impl<A: AudioHandler + Send + Sync + 'static> Downloader<A> {
+    /// Prepares each of `streams` in order, sending them to the returned receiver as
+    /// they become ready. The handle finishes once the downloads that started have.
+    pub(crate) fn spawn(
+        self,
+        streams: Vec<(Stream, Option<TranscribeResponse>, Checkpoint)>,
+        concurrency: usize,
+    ) -> (mpsc::Receiver<Prepared>, JoinHandle<()>) {
+        let (tx, rx) = mpsc::channel(concurrency);
+        let downloader = Arc::new(self);
+        let handle = tokio::spawn(async move {
+            let mut prepared = futures::stream::iter(streams)
+                .map(|(stream, captions, checkpoint)| {
+                    let downloader = downloader.clone();
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            downloader.prepare(&stream, captions, checkpoint)
+                        })
+                        .await
+                        .context("Download thread panicked")?
+                    }
+                })
+                .buffered(concurrency);
+            while let Some(prepared) = prepared.next().await {
+                // the receiver is dropped once the run is no longer processing streams,
+                // and the downloads still running are waited on
+                let _ = tx.send(prepared).await;
+            }
+        });
+        (rx, handle)
+    }
+
+    fn stopping(&self) -> bool {
+        self.abandoned.is_cancelled()
+            || self.shutdown.is_cancelled()
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    fn prepare(
+        &self,
+        stream: &Stream,
+        captions: Option<TranscribeResponse>,
+        mut checkpoint: Checkpoint,
+    ) -> Prepared {
+        if self.stopping() {
+            return Ok(None);
+        }
+        let source = match (checkpoint.transcript.clone(), captions) {
+            (Some(transcript), _) => TranscriptSource::Checkpoint(transcript),
+            (None, Some(transcript)) => TranscriptSource::Captions(transcript),
+            (None, None) => match self.prepare_audio(stream, &mut checkpoint) {
+                Ok(audio_path) => TranscriptSource::Audio(audio_path),
+                // yt-dlp and ffmpeg are killed on shutdown
+                Err(_) if self.shutdown.is_cancelled() => return Ok(None),
+                Err(e) => return Err(e),
+            },
+        };
+        Ok(Some((source, checkpoint)))
+    }
+
+    /// Downloads and cleans the stream's audio, skipping the stages its checkpoint shows
+    /// were completed on an earlier run
+    fn prepare_audio(
+        &self,
+        stream: &Stream,
+        checkpoint: &mut Checkpoint,
+    ) -> anyhow::Result<PathBuf> {
+        if let Some(cleaned) = checkpoint.audio_at(StreamState::Cleaned) {
+            return Ok(cleaned);
+        }
+
+        let downloaded = match checkpoint.audio_at(StreamState::Downloaded) {
+            Some(downloaded) => downloaded,
+            None => {
+                if checkpoint.completed(StreamState::Downloaded) {
+                    checkpoint.restart();
+                }
+                let started = Instant::now();
+                let downloaded = self.audio_handler.download(stream, &self.audio_dl_path)?;
+                self.stage_finished("download", started);
+                self.audit.record(AuditAction::AudioDownloaded {
+                    video_id: &stream.video_id,
+                });
+                checkpoint.audio_path = Some(downloaded.clone());
+                self.checkpoints
+                    .save(&stream.video_id, checkpoint, StreamState::Downloaded);
+                downloaded
+            }
+        };
+
+        let cleaned = match self.cleanup.is_none() {
+            true => downloaded,
+            false => {
+                let started = Instant::now();
+                let cleaned = self
+                    .audio_handler
+                    .clean_up(stream, &downloaded, &self.cleanup)?;
+                self.stage_finished("clean", started);
+                cleaned
+            }
+        };
+        checkpoint.audio_path = Some(cleaned.clone());
+        self.checkpoints
+            .save(&stream.video_id, checkpoint, StreamState::Cleaned);
+        Ok(cleaned)
+    }
+
+    fn stage_finished(&self, stage: &'static str, started: Instant) {
+        self.report
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .stage_finished(stage, started);
+    }
+}
@@ -0,0 +1,161 @@
+//! # Events
+//!
+//! Progress of a run as it happens, for callers to follow a run that takes hours, e.g.
+//! to render a progress bar or push it to a dashboard.
+
+use std::sync::{Arc, Mutex};
+
+use stream_datastore::Stream;
+use tokio::sync::broadcast;
+
+/// Events buffered for each subscriber before the oldest are dropped
+const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessorEvent {
+    /// The stream's transcript is about to be read, transcribed or resumed
+    StreamStarted {
+        video_id: String,
+        title: String,
+    },
+    /// Chunk `n` of `total` of the stream's audio was transcribed, or read from the
+    /// chunk cache
+    ChunkTranscribed {
+        video_id: String,
+        n: usize,
+        total: usize,
+    },
+    Summarized {
+        video_id: String,
+    },
+    Inserted {
+        video_id: String,
+    },
+    /// The run failed, in the stream with `video_id` if one was being processed
+    Failed {
+        video_id: Option<String>,
+        error: String,
+    },
+}
+
+/// A cheaply cloneable sender of [`ProcessorEvent`]s. Clones send to the same
+/// subscribers. Pass the same one to the transcriber, which reports chunk progress, and
+/// the processor.
+#[derive(Debug, Clone)]
+pub struct ProcessorEvents {
+    sender: broadcast::Sender<ProcessorEvent>,
+    /// The stream being processed, which the transcriber reports chunks of. Streams are
+    /// transcribed one at a time.
+    current: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for ProcessorEvents {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(CAPACITY).0,
+            current: Arc::default(),
+        }
+    }
+}
+
+impl ProcessorEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receives the events sent from now on. A subscriber that falls more than 256
+    /// events behind misses the oldest of them.
+    pub fn subscribe(&self) -> broadcast::Receiver<ProcessorEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn stream_started(&self, stream: &Stream) {
+        *self.current() = Some(stream.video_id.clone());
+        self.send(ProcessorEvent::StreamStarted {
+            video_id: stream.video_id.clone(),
+            title: stream.title.clone(),
+        });
+    }
+
+    pub(crate) fn chunk_transcribed(&self, n: usize, total: usize) {
+        let Some(video_id) = self.current().clone() else {
+            return;
+        };
+        self.send(ProcessorEvent::ChunkTranscribed { video_id, n, total });
+    }
+
+    pub(crate) fn summarized(&self, video_id: &str) {
+        self.send(ProcessorEvent::Summarized {
+            video_id: video_id.to_string(),
+        });
+    }
+
+    pub(crate) fn inserted(&self, video_id: &str) {
+        *self.current() = None;
+        self.send(ProcessorEvent::Inserted {
+            video_id: video_id.to_string(),
+        });
+    }
+
+    pub(crate) fn failed(&self, error: &anyhow::Error) {
+        let video_id = self.current().take();
+        self.send(ProcessorEvent::Failed {
+            video_id,
+            error: format!("{error:#}"),
+        });
+    }
+
+    fn current(&self) -> std::sync::MutexGuard<'_, Option<String>> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sending fails only without subscribers, whom the event is of no use to then
+    fn send(&self, event: ProcessorEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_are_reported_for_the_current_stream() {
+        let events = ProcessorEvents::new();
+        let mut receiver = events.clone().subscribe();
+        events.chunk_transcribed(1, 2);
+
+        let stream = Stream {
+            video_id: "abc123".into(),
+            title: "Senate Plenary".into(),
+            ..Default::default()
+        };
+        events.stream_started(&stream);
+        events.chunk_transcribed(1, 2);
+        events.inserted("abc123");
+        events.failed(&anyhow::anyhow!("Connection refused"));
+
+        let received = std::iter::from_fn(|| receiver.try_recv().ok()).collect::<Vec<_>>();
+        assert_eq!(
+            received,
+            vec![
+                ProcessorEvent::StreamStarted {
+                    video_id: "abc123".into(),
+                    title: "Senate Plenary".into(),
+                },
+                ProcessorEvent::ChunkTranscribed {
+                    video_id: "abc123".into(),
+                    n: 1,
+                    total: 2,
+                },
+                ProcessorEvent::Inserted {
+                    video_id: "abc123".into(),
+                },
+                ProcessorEvent::Failed {
+                    video_id: None,
+                    error: "Connection refused".into(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! # Hooks
+//!
+//! Callbacks a deployment registers on the builder to act on a run's streams at each
+//! stage, e.g. to post summaries to a chat channel or copy transcripts to a bucket,
+//! without changing the processor.
+
+use std::sync::{Arc, Mutex};
+
+use futures::future::BoxFuture;
+use stream_datastore::Stream;
+
+/// Called at each stage of processing a stream. Every method defaults to doing nothing,
+/// so implementers override only the stages they need. A hook that fails is logged
+/// and does not fail the stream.
+pub trait ProcessorHook: Send + Sync {
+    /// `stream` was listed and is about to be processed
+    fn on_stream_discovered<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        let _ = stream;
+        Box::pin(async { Ok(()) })
+    }
+
+    /// `stream`'s transcript is about to be read from its captions or checkpoint, or
+    /// transcribed from its audio
+    fn before_transcribe<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        let _ = stream;
+        Box::pin(async { Ok(()) })
+    }
+
+    /// `stream` was summarized, and is about to be stored with its summary
+    fn after_summarize<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        let _ = stream;
+        Box::pin(async { Ok(()) })
+    }
+
+    /// `stream` was stored with its summary
+    fn on_stream_stored<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        let _ = stream;
+        Box::pin(async { Ok(()) })
+    }
+
+    /// The run failed, in `stream` if one was being processed
+    fn on_failure<'a>(
+        &'a self,
+        stream: Option<&'a Stream>,
+        error: &'a anyhow::Error,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        let _ = (stream, error);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// The hooks registered on the builder, called in the order they were registered
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    hooks: Vec<Arc<dyn ProcessorHook>>,
+    /// The stream being processed, which failures are reported in. Streams are
+    /// transcribed one at a time.
+    current: Arc<Mutex<Option<Stream>>>,
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("hooks", &self.hooks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Hooks {
+    pub(crate) fn new(hooks: Vec<Arc<dyn ProcessorHook>>) -> Self {
+        Self {
+            hooks,
+            current: Arc::default(),
+        }
+    }
+
+    pub(crate) async fn stream_discovered(&self, stream: &Stream) {
+        for hook in &self.hooks {
+            log_failure(
+                hook.on_stream_discovered(stream).await,
+                "on_stream_discovered",
+            );
+        }
+    }
+
+    pub(crate) async fn before_transcribe(&self, stream: &Stream) {
+        self.stream_failing(stream);
+        for hook in &self.hooks {
+            log_failure(hook.before_transcribe(stream).await, "before_transcribe");
+        }
+    }
+
+    pub(crate) async fn after_summarize(&self, stream: &Stream) {
+        for hook in &self.hooks {
+            log_failure(hook.after_summarize(stream).await, "after_summarize");
+        }
+    }
+
+    /// Marks `stream` as the one a failure of the run is in, for failures before it is
+    /// transcribed, e.g. while downloading its audio
+    pub(crate) fn stream_failing(&self, stream: &Stream) {
+        *self.current() = Some(stream.clone());
+    }
+
+    /// Marks the stream being processed as stored, after which failures are not in it
+    pub(crate) async fn stream_stored(&self, stream: &Stream) {
+        *self.current() = None;
+        for hook in &self.hooks {
+            log_failure(hook.on_stream_stored(stream).await, "on_stream_stored");
+        }
+    }
+
+    /// Reports the run's failure, returning the stream it was in if there was one
+    pub(crate) async fn failed(&self, error: &anyhow::Error) -> Option<Stream> {
+        let stream = self.current().take();
+        for hook in &self.hooks {
+            log_failure(hook.on_failure(stream.as_ref(), error).await, "on_failure");
+        }
+        stream
+    }
+
+    fn current(&self) -> std::sync::MutexGuard<'_, Option<Stream>> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn log_failure(result: anyhow::Result<()>, stage: &str) {
+    if let Err(e) = result {
+        tracing::warn!(error = ?e, stage, "Processor hook failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl ProcessorHook for Recorder {
+        fn before_transcribe<'a>(
+            &'a self,
+            stream: &'a Stream,
+        ) -> BoxFuture<'a, anyhow::Result<()>> {
+            self.0.lock().unwrap().push(stream.video_id.clone());
+            Box::pin(async { anyhow::bail!("Slack is down") })
+        }
+
+        fn on_failure<'a>(
+            &'a self,
+            stream: Option<&'a Stream>,
+            error: &'a anyhow::Error,
+        ) -> BoxFuture<'a, anyhow::Result<()>> {
+            let video_id = stream.map_or("none", |s| &s.video_id);
+            self.0.lock().unwrap().push(format!("{video_id}: {error}"));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_reported_in_the_current_stream() {
+        let recorder = Arc::new(Recorder::default());
+        let hooks = Hooks::new(vec![recorder.clone()]);
+        let stream = Stream {
+            video_id: "abc123".into(),
+            ..Default::default()
+        };
+
+        hooks.before_transcribe(&stream).await;
+        hooks
+            .failed(&anyhow::anyhow!("Failed to summarize transcript"))
+            .await;
+        hooks.failed(&anyhow::anyhow!("Connection refused")).await;
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                "abc123",
+                "abc123: Failed to summarize transcript",
+                "none: Connection refused",
+            ]
+        );
+    }
+}
@@ -1,56 +1,206 @@
+mod audit;
 pub mod builder;
+mod checkpoint;
+pub mod cleanup;
+mod downloads;
+pub mod events;
+pub mod hooks;
+mod preflight;
+pub mod priority;
+pub mod report;
+pub mod retention;
+pub mod retries;
+pub mod reuploads;
+mod sources;
+pub mod timeouts;
+pub mod webhook;
 
-use std::{fs::remove_dir_all, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use itertools::Itertools;
-use rayon::prelude::*;
-use stream_datastore::{DataStore, Stream};
+#[cfg(feature = "pgvector")]
+use stream_datastore::EmbeddingStore;
+use stream_datastore::{
+    DataStore, FailedStreamStore, Json, ReuploadStore, StoredStream, Stream, StreamCategory,
+    StreamState, StreamStateStore, StreamStatus, TranscriptStore,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
+#[cfg(feature = "pgvector")]
+use crate::llm::embedder::embed_stream;
 use crate::{
-    parser::{parse_streams, YtHtmlDocument},
-    processor::builder::ChunkingConfig,
-    yt::{AudioHandler, ChannelScraper},
-    AudioInput, Summarizer, Transcriber,
+    hansard::{NoOrderPaperSource, OrderPaperSource},
+    llm::{
+        summarizer::{summarize_transcript, SummaryContext},
+        timestamps::{link_timestamps, timestamped_transcript},
+    },
+    metrics,
+    processor::{
+        audit::{AuditAction, AuditLog},
+        builder::{CaptionDestination, CaptionsConfig, ChunkingConfig, ThumbnailMirror},
+        checkpoint::{Checkpoint, Checkpoints},
+        cleanup::CleanupConfig,
+        downloads::{Downloader, Prepared, TranscriptSource},
+        events::ProcessorEvents,
+        hooks::Hooks,
+        priority::PrioritizationStrategy,
+        report::RunReport,
+        retention::RetentionPolicy,
+        retries::StreamRetryPolicy,
+        reuploads::ReuploadPolicy,
+        sources::Source,
+        timeouts::{within, StageTimeouts},
+    },
+    yt::{
+        timedtext::{CaptionSource, NoCaptionSource},
+        AudioHandler, ChannelScraper,
+    },
+    AudioInput, CategoryClassifier, DivisionExtractor, Embedder, EntityExtractor,
+    NoCategoryClassifier, NoDivisionExtractor, NoEmbedder, NoEntityExtractor, Summarizer,
+    TranscribeResponse, Transcriber, UsageTracker,
 };
 
+/// What a processor needs of its store: besides the streams themselves, their
+/// embeddings, failed attempts, re-uploads, states and transcripts, for when those are
+/// enabled
+#[cfg(feature = "pgvector")]
+pub trait PipelineStore:
+    DataStore + EmbeddingStore + FailedStreamStore + ReuploadStore + StreamStateStore + TranscriptStore
+{
+}
+
+#[cfg(feature = "pgvector")]
+impl<T> PipelineStore for T where
+    T: DataStore
+        + EmbeddingStore
+        + FailedStreamStore
+        + ReuploadStore
+        + StreamStateStore
+        + TranscriptStore
+{
+}
+
+/// What a processor needs of its store: besides the streams themselves, their failed
+/// attempts, re-uploads, states and transcripts, for when those are enabled. Embeddings
+/// are only stored when built with the `pgvector` feature.
+#[cfg(not(feature = "pgvector"))]
+pub trait PipelineStore:
+    DataStore + FailedStreamStore + ReuploadStore + StreamStateStore + TranscriptStore
+{
+}
+
+#[cfg(not(feature = "pgvector"))]
+impl<T> PipelineStore for T where
+    T: DataStore + FailedStreamStore + ReuploadStore + StreamStateStore + TranscriptStore
+{
+}
+
 #[derive(Debug, Clone)]
-pub struct LiveStreamProcessor<D, T, S, A, P>
-where
-    D: DataStore + Send + Sync + 'static,
+pub struct LiveStreamProcessor<
+    D,
+    T,
+    S,
+    A,
+    P,
+    E = NoEntityExtractor,
+    M = NoEmbedder,
+    V = NoDivisionExtractor,
+    C = NoCategoryClassifier,
+    K = NoCaptionSource,
+    O = NoOrderPaperSource,
+> where
+    D: PipelineStore + Send + Sync + 'static,
     T: Transcriber + Send + Sync + 'static,
     S: Summarizer + Send + Sync + 'static,
     A: AudioHandler + Send + Sync + 'static,
     P: ChannelScraper + Send + Sync + 'static,
+    E: EntityExtractor + Send + Sync + 'static,
+    M: Embedder + Send + Sync + 'static,
+    V: DivisionExtractor + Send + Sync + 'static,
+    C: CategoryClassifier + Send + Sync + 'static,
+    K: CaptionSource + Send + Sync + 'static,
+    O: OrderPaperSource + Send + Sync + 'static,
 {
     workdir: PathBuf,
     store: D,
     transcriber: T,
     summarizer: S,
-    audio_handler: A,
+    audio_handler: Arc<A>,
     channel_scraper: P,
     max_streams: usize,
+    sources: Vec<Source<P>>,
+    prioritization: PrioritizationStrategy,
+    download_concurrency: usize,
     chunking_config: Option<ChunkingConfig>,
+    usage_tracker: Option<UsageTracker>,
+    events: Option<ProcessorEvents>,
+    hooks: Hooks,
+    entity_extractor: Option<E>,
+    /// Only set when built with the `pgvector` feature, without which embeddings have
+    /// nowhere to be stored
+    #[cfg_attr(not(feature = "pgvector"), allow(dead_code))]
+    embedder: Option<M>,
+    division_extractor: Option<V>,
+    category_classifier: Option<C>,
+    caption_source: Option<K>,
+    order_paper_source: Option<O>,
+    timestamp_links: bool,
+    live_recordings_only: bool,
+    captions: Option<CaptionsConfig>,
+    thumbnail_mirror: Option<ThumbnailMirror>,
+    http_client: reqwest::Client,
+    persistence_check: bool,
+    preflight_checks: bool,
+    checkpoints: Checkpoints,
+    shutdown: CancellationToken,
+    cancellation: CancellationToken,
+    retention: RetentionPolicy,
+    cleanup: CleanupConfig,
+    timeouts: StageTimeouts,
+    retries: Option<StreamRetryPolicy>,
+    reuploads: Option<ReuploadPolicy>,
+    persist_transcripts: bool,
+    track_states: bool,
+    max_run_duration: Option<Duration>,
+    /// When the run's `max_run_duration` is spent, set as it starts
+    deadline: Option<Instant>,
+    /// What the run did so far, replaced as it starts
+    report: Arc<Mutex<RunReport>>,
+    /// Set when a run fails or is shut down, so that downloaded audio and cached chunk
+    /// transcriptions can be kept for the next run to resume from
+    incomplete: bool,
+    audit: AuditLog,
 }
 
-impl<D, T, S, A, P> LiveStreamProcessor<D, T, S, A, P>
+impl<D, T, S, A, P, E, M, V, C, K, O> LiveStreamProcessor<D, T, S, A, P, E, M, V, C, K, O>
 where
-    D: DataStore + Send + Sync + 'static,
+    D: PipelineStore + Send + Sync + 'static,
     T: Transcriber + Send + Sync + 'static,
     S: Summarizer + Send + Sync + 'static,
     A: AudioHandler + Send + Sync + 'static,
     P: ChannelScraper + Send + Sync + 'static,
+    E: EntityExtractor + Send + Sync + 'static,
+    M: Embedder + Send + Sync + 'static,
+    V: DivisionExtractor + Send + Sync + 'static,
+    C: CategoryClassifier + Send + Sync + 'static,
+    K: CaptionSource + Send + Sync + 'static,
+    O: OrderPaperSource + Send + Sync + 'static,
 {
-    /// Parses the `ytInitialData` script data from the youtube html document
     #[tracing::instrument(skip_all)]
-    async fn parse_streams(&self, doc: &YtHtmlDocument) -> anyhow::Result<Vec<Stream>> {
-        let json = doc.to_json::<serde_json::Value>()?;
-        let streams = parse_streams(&json)?;
-        Ok(streams)
-    }
-
-    #[tracing::instrument(skip_all)]
-    async fn sort_filter_limit_streams(&self, streams: Vec<Stream>) -> anyhow::Result<Vec<Stream>> {
+    /// Also returns whether each source has streams left beyond its quota, going by
+    /// `source_of`, the index of the source that listed each stream
+    async fn sort_filter_limit_streams(
+        &self,
+        streams: Vec<Stream>,
+        source_of: &HashMap<String, usize>,
+    ) -> anyhow::Result<(Vec<Stream>, Vec<bool>)> {
         let stream_ids = streams
             .iter()
             .map(|s| s.video_id.as_str())
@@ -64,101 +214,1106 @@ where
                 tracing::error!(error = ?e, "Failed to get existing stream IDs");
             })
             .context("Failed to get existing stream IDs")?;
+        // streams the site already shows as scheduled or live are summarized first
+        let tracked_stream_ids = self
+            .store
+            .get_tracked_stream_ids(&stream_ids)
+            .await
+            .inspect_err(|e| {
+                tracing::error!(error = ?e, "Failed to get tracked stream IDs");
+            })
+            .context("Failed to get tracked stream IDs")?;
 
-        let result = streams
-            .iter()
+        // channels can list the same stream, the first listing is kept
+        let streams = streams
+            .into_iter()
+            .unique_by(|s| s.video_id.clone())
             .filter(|s| !existing_stream_ids.contains(&s.video_id))
+            .filter(|s| !self.live_recordings_only || s.is_live_recording)
+            .collect::<Vec<_>>();
+        let sorted = self
+            .filter_reuploads(streams)
+            .await?
+            .into_iter()
             .sorted_by(|a, b| {
-                a.timestamp_from_time_ago()
-                    .cmp(&b.timestamp_from_time_ago())
+                let untracked = |s: &Stream| !tracked_stream_ids.contains(&s.video_id);
+                untracked(a)
+                    .cmp(&untracked(b))
+                    .then_with(|| self.prioritization.compare(a, b))
             })
-            .take(self.max_streams)
-            .cloned()
             .collect::<Vec<_>>();
 
+        let quotas = self.sources().map(|(.., quota)| quota).collect::<Vec<_>>();
+        Ok(sources::take_quotas(sorted, source_of, &quotas))
+    }
+
+    /// The scrapers streams are listed from with the category given to their streams and
+    /// their quota, the builder's channel scraper first
+    fn sources(&self) -> impl Iterator<Item = (&P, Option<StreamCategory>, usize)> {
+        std::iter::once((&self.channel_scraper, None, self.max_streams)).chain(
+            self.sources
+                .iter()
+                .map(|source| (&source.scraper, source.category, source.max_streams)),
+        )
+    }
+
+    /// Stored streams that may have been uploaded again as one of `streams`
+    async fn stored_originals(&self, streams: &[Stream]) -> anyhow::Result<Vec<StoredStream>> {
+        let now = chrono::Utc::now();
+        let earliest = streams
+            .iter()
+            .filter_map(Stream::published_at)
+            .min()
+            .unwrap_or(now);
+        self.store
+            .get_streams_between(earliest - reuploads::LOOKBACK, now)
+            .await
+            .inspect_err(|e| tracing::error!(error = ?e, "Failed to get stored streams"))
+            .context("Failed to get stored streams to match re-uploads against")
+    }
+
+    /// Leaves out streams that are re-uploads of stored ones, unless they replace them
+    async fn filter_reuploads(&self, streams: Vec<Stream>) -> anyhow::Result<Vec<Stream>> {
+        let Some(policy) = self.reuploads else {
+            return Ok(streams);
+        };
+        if policy == ReuploadPolicy::Replace || streams.is_empty() {
+            return Ok(streams);
+        }
+        let stored = self.stored_originals(&streams).await?;
+
+        let mut result = Vec::with_capacity(streams.len());
+        for stream in streams {
+            let Some(original) = reuploads::find_original(&stream, &stored) else {
+                result.push(stream);
+                continue;
+            };
+            tracing::info!(
+                video_id = %stream.video_id,
+                original = %original.video_id,
+                "Skipping re-upload of a stored stream"
+            );
+            if policy == ReuploadPolicy::Link {
+                // the re-upload is matched again on the next run
+                if let Err(e) = self
+                    .store
+                    .insert_stream_alias(&stream.video_id, &original.video_id)
+                    .await
+                {
+                    tracing::warn!(error = ?e, video_id = %stream.video_id, "Failed to link re-upload");
+                }
+            }
+        }
         Ok(result)
     }
 
-    #[tracing::instrument(skip(self))]
-    pub async fn run(self) -> anyhow::Result<()> {
-        let yt_html_doc = self
-            .channel_scraper
-            .scrape_channel()
+    /// Deletes the stored stream the just stored `stream` is a re-upload of, if any.
+    /// Only a failure to look it up fails the stream, as that is done before any changes
+    async fn replace_original(&self, stream: &Stream) -> anyhow::Result<()> {
+        let stored = self.stored_originals(std::slice::from_ref(stream)).await?;
+        let Some(original) = reuploads::find_original(stream, &stored) else {
+            return Ok(());
+        };
+        tracing::info!(
+            video_id = %stream.video_id,
+            original = %original.video_id,
+            "Replacing the stored stream the re-upload is of"
+        );
+        if let Err(e) = self
+            .store
+            .replace_stream(&original.video_id, &stream.video_id)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to scrape yt html document: {e:?}"))?;
+        {
+            tracing::warn!(error = ?e, video_id = %stream.video_id, "Failed to replace stored stream");
+            self.supplementary_failed(&stream.video_id, "replace its original upload", &e);
+        }
+        Ok(())
+    }
+
+    /// Records the streams being broadcast now or scheduled, which are processed on the
+    /// first run after they end
+    #[tracing::instrument(skip_all, fields(tracked = streams.len()))]
+    async fn track_upcoming_streams(&self, streams: &[Stream]) {
+        // the stream is recorded again on the next run, so a failure does not fail the run
+        for stream in streams.iter().unique_by(|s| s.video_id.as_str()) {
+            let result = match stream.status {
+                StreamStatus::Scheduled => self.store.insert_scheduled_stream(stream).await,
+                _ => self.store.insert_live_stream(stream).await,
+            };
+            if let Err(e) = result {
+                tracing::warn!(
+                    error = ?e,
+                    video_id = %stream.video_id,
+                    status = stream.status.as_str(),
+                    "Failed to record upcoming stream"
+                );
+            }
+        }
+    }
+
+    /// Replaces the approximate dates of streams listed with "time ago" text with
+    /// exact ones, and their description snippets with full descriptions, where the
+    /// channel scraper can resolve them
+    #[tracing::instrument(skip_all)]
+    async fn resolve_video_details(&self, streams: &mut [Stream]) {
+        for stream in streams
+            .iter_mut()
+            .filter(|s| s.published_at_exact.is_none())
+        {
+            // the listing's details still work, so a failed lookup does not fail the stream
+            match self.channel_scraper.resolve_details(&stream.video_id).await {
+                Ok(details) => {
+                    stream.published_at_exact = details.published_at;
+                    if let Some(description) = details.description {
+                        stream.set_description(description);
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    error = ?e,
+                    video_id = %stream.video_id,
+                    "Failed to resolve video details"
+                ),
+            }
+        }
+    }
+
+    /// Classifies the streams whose titles don't name a house, where a category
+    /// classifier is configured
+    #[tracing::instrument(skip_all)]
+    async fn classify_categories(&self, streams: &mut [Stream]) {
+        let Some(classifier) = &self.category_classifier else {
+            return;
+        };
+
+        // unclassified streams are stored as `Other`, so a failure does not fail the stream
+        for stream in streams.iter_mut().filter(|s| s.category.is_none()) {
+            match classifier.classify_title(&stream.title).await {
+                Ok(category) => stream.category = Some(category),
+                Err(e) => tracing::warn!(
+                    error = ?e,
+                    video_id = %stream.video_id,
+                    "Failed to classify stream category"
+                ),
+            }
+        }
+    }
+
+    /// Attaches the order paper of each stream's sitting, where an order paper source is
+    /// configured
+    #[tracing::instrument(skip_all)]
+    async fn attach_order_papers(&self, streams: &mut [Stream]) {
+        let Some(order_paper_source) = &self.order_paper_source else {
+            return;
+        };
+
+        // the description still lists the business, so a failed lookup does not fail the stream
+        for stream in streams.iter_mut() {
+            match order_paper_source.find_order_paper(stream).await {
+                Ok(Some(order_paper)) => stream.order_paper = Some(Json(order_paper)),
+                Ok(None) => tracing::info!(video_id = %stream.video_id, "Found no order paper"),
+                Err(e) => tracing::warn!(
+                    error = ?e,
+                    video_id = %stream.video_id,
+                    "Failed to find order paper"
+                ),
+            }
+        }
+    }
+
+    /// Reads the transcripts of the streams captioned on YouTube, where a caption source
+    /// is configured, in the order of `streams`. Streams transcribed on an earlier run
+    /// are skipped.
+    #[tracing::instrument(skip_all)]
+    async fn fetch_captions(
+        &self,
+        streams: &[Stream],
+        checkpoints: &[Checkpoint],
+    ) -> Vec<Option<TranscribeResponse>> {
+        let mut transcripts = Vec::with_capacity(streams.len());
+        for (stream, checkpoint) in streams.iter().zip(checkpoints) {
+            if checkpoint.completed(StreamState::Transcribed) {
+                transcripts.push(None);
+                continue;
+            }
+            let transcript = match &self.caption_source {
+                // the audio can still be transcribed, so a failed fetch does not fail the stream
+                Some(caption_source) => caption_source
+                    .fetch_captions(&stream.video_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!(
+                            error = ?e,
+                            video_id = %stream.video_id,
+                            "Failed to fetch captions, transcribing audio instead"
+                        );
+                        None
+                    }),
+                None => None,
+            };
+            if transcript.is_some() {
+                self.audit.record(AuditAction::CaptionsFetched {
+                    video_id: &stream.video_id,
+                });
+            }
+            transcripts.push(transcript);
+        }
+        transcripts
+    }
 
-        let streams = self.parse_streams(&yt_html_doc).await?;
+    /// Resumes streams without a transcript checkpoint from their stored transcript, if
+    /// an earlier run stored one. Failing to read them only costs the transcription.
+    async fn load_stored_transcripts(&self, streams: &[Stream], checkpoints: &mut [Checkpoint]) {
+        let video_ids = streams
+            .iter()
+            .zip(checkpoints.iter())
+            .filter(|(_, checkpoint)| checkpoint.transcript.is_none())
+            .map(|(stream, _)| stream.video_id.as_str())
+            .collect::<Vec<_>>();
+        if video_ids.is_empty() {
+            return;
+        }
+        let stored = match self.store.get_stream_transcripts(&video_ids).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to fetch stored transcripts");
+                return;
+            }
+        };
 
-        let mut streams = self.sort_filter_limit_streams(streams).await?;
+        for (stream, checkpoint) in streams.iter().zip(checkpoints) {
+            let Some(transcript) = stored.get(&stream.video_id) else {
+                continue;
+            };
+            match serde_json::from_str(transcript) {
+                Ok(transcript) => {
+                    tracing::info!(video_id = %stream.video_id, "Resuming from stored transcript");
+                    checkpoint.transcript = Some(transcript);
+                }
+                Err(e) => tracing::warn!(
+                    error = ?e,
+                    video_id = %stream.video_id,
+                    "Failed to read stored transcript, transcribing again"
+                ),
+            }
+        }
+    }
+
+    /// Stores `transcript` ahead of the summary. The stream can still be summarized
+    /// without it, so a failure to store it does not fail the stream.
+    async fn store_transcript(&self, video_id: &str, transcript: &TranscribeResponse) {
+        let stored = match serde_json::to_string(transcript) {
+            Ok(transcript) => self
+                .store
+                .insert_stream_transcript(video_id, &transcript)
+                .await
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = stored {
+            tracing::warn!(error = ?e, video_id, "Failed to store transcript");
+            self.supplementary_failed(video_id, "store transcript", &e);
+        }
+    }
+
+    /// Records the stream's state in this run with the store, when tracking states. It
+    /// is recorded again at the stream's next stage, so a failure is logged rather than
+    /// failing the stream.
+    async fn record_state(&self, video_id: &str) {
+        if !self.track_states {
+            return;
+        }
+        let state = self.checkpoints.state(video_id);
+        if let Err(e) = self.store.set_stream_state(video_id, state).await {
+            tracing::warn!(error = ?e, video_id, %state, "Failed to record stream state");
+        }
+    }
+
+    /// Processes the streams listed on the channels that are not stored yet, returning
+    /// what the run did. A failed run's [`RunReport`] is attached to its error.
+    #[tracing::instrument(skip(self))]
+    pub async fn run(mut self) -> anyhow::Result<RunReport> {
+        let started = self.start_run();
+        let result = self.process_streams().await;
+        self.finish_run(started, result).await
+    }
+
+    /// Processes the videos with `video_ids` instead of the streams listed on the
+    /// channels, e.g. to process a sitting again on request. The videos are looked up
+    /// with the channel scraper and processed whether or not they are already stored,
+    /// replacing their summaries.
+    #[tracing::instrument(skip(self))]
+    pub async fn process_video_ids(mut self, video_ids: &[&str]) -> anyhow::Result<RunReport> {
+        let started = self.start_run();
+        let result = self.process_videos(video_ids).await;
+        self.finish_run(started, result).await
+    }
+
+    fn start_run(&mut self) -> Instant {
+        let started = Instant::now();
+        self.deadline = self.max_run_duration.map(|d| started + d);
+        self.report = Arc::default();
+        self.audit.record(AuditAction::RunStarted);
+        started
+    }
+
+    async fn finish_run(
+        &mut self,
+        started: Instant,
+        result: anyhow::Result<()>,
+    ) -> anyhow::Result<RunReport> {
+        self.report_failure(&result).await;
+        self.incomplete = result.is_err() || self.stopping();
+        let mut report = std::mem::take(&mut *self.report());
+        report.duration = started.elapsed();
+        report.cancelled = self.shutdown.is_cancelled() || self.cancellation.is_cancelled();
+        self.audit.record(AuditAction::RunFinished {
+            processed: report.processed.len(),
+            failed: report.failed.as_deref(),
+        });
+        match result {
+            Ok(()) => Ok(report),
+            Err(e) => Err(e.context(report)),
+        }
+    }
+
+    fn report(&self) -> MutexGuard<'_, RunReport> {
+        self.report.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether the run starts no more streams, once shut down, cancelled or out of its
+    /// `max_run_duration`. A stream being processed is finished unless shut down or
+    /// cancelled
+    fn stopping(&self) -> bool {
+        self.shutdown.is_cancelled()
+            || self.cancellation.is_cancelled()
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    async fn report_failure(&self, result: &anyhow::Result<()>) {
+        let Err(e) = result else {
+            return;
+        };
+        metrics::run_failed();
+        if let Some(events) = &self.events {
+            events.failed(e);
+        }
+        let stream = self.hooks.failed(e).await;
+        if let Some(stream) = &stream {
+            self.checkpoints.fail(&stream.video_id);
+            self.record_state(&stream.video_id).await;
+        }
+        {
+            let mut report = self.report();
+            report.failed = stream.as_ref().map(|s| s.video_id.clone());
+            report.errors.push(format!("{e:#}"));
+        }
+        if let (Some(_), Some(stream)) = (&self.retries, stream) {
+            // the stream is recorded again if it fails on its retry
+            if let Err(err) = self
+                .store
+                .record_stream_failure(&stream.video_id, &format!("{e:#}"))
+                .await
+            {
+                tracing::warn!(error = ?err, video_id = %stream.video_id, "Failed to record stream failure");
+            }
+        }
+    }
+
+    async fn process_videos(&self, video_ids: &[&str]) -> anyhow::Result<()> {
+        let mut streams = Vec::with_capacity(video_ids.len());
+        for video_id in video_ids.iter().unique() {
+            let stream = self
+                .channel_scraper
+                .fetch_stream(video_id)
+                .await
+                .with_context(|| format!("Failed to look up video {video_id}"))?;
+            if stream.status != StreamStatus::Archived {
+                anyhow::bail!(
+                    "{video_id} is {} and can't be processed yet",
+                    stream.status.as_str()
+                );
+            }
+            streams.push(stream);
+        }
         if streams.is_empty() {
-            tracing::info!("No streams to process at this time");
             return Ok(());
         }
+        self.report().discovered = streams.len();
+        self.process(streams).await
+    }
+
+    /// Lists the streams the channels have finished broadcasting and that are not stored
+    /// yet, in the order they would be processed, without processing them. Like a run,
+    /// at most [`max_streams`](builder::LiveStreamProcessorBuilder::max_streams) are listed
+    /// from each source, and the rest are left for the next listing. For queueing the streams to be processed
+    /// elsewhere with [`Self::process_video_ids`].
+    #[tracing::instrument(skip(self))]
+    pub async fn discover_streams(mut self) -> anyhow::Result<Vec<Stream>> {
+        // nothing is downloaded, and the audio directory may be shared with runs
+        // processing the streams
+        self.retention = RetentionPolicy::KeepAll;
+        let (streams, listed_in_full) = self.list_streams().await?;
+        for scraper in listed_in_full {
+            scraper.listing_processed();
+        }
+        Ok(streams)
+    }
+
+    async fn process_streams(&self) -> anyhow::Result<()> {
+        let (streams, listed_in_full) = self.list_streams().await?;
+        if streams.is_empty() {
+            return Ok(());
+        }
+        self.process(streams).await?;
+
+        if !self.stopping() {
+            for scraper in listed_in_full {
+                scraper.listing_processed();
+            }
+        }
+        Ok(())
+    }
+
+    /// The streams due for processing, and the scrapers that listed no streams beyond
+    /// their quota, to be marked processed once the streams are. The others' streams
+    /// beyond the quota are left for the next run, which must list them again
+    async fn list_streams(&self) -> anyhow::Result<(Vec<Stream>, Vec<&P>)> {
+        let mut streams = Vec::new();
+        let mut source_of = HashMap::new();
+        for (source, (scraper, category, _)) in self.sources().enumerate() {
+            tracing::info!(channels = ?scraper.channel_urls(), "Listing streams");
+            let listed = scraper
+                .scrape_streams()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to scrape channel streams: {e:?}"))?;
+            self.audit.record(AuditAction::ChannelsListed {
+                channels: scraper.channel_urls(),
+                streams: listed.len(),
+            });
+            for mut stream in listed {
+                stream.category = stream.category.or(category);
+                source_of.entry(stream.video_id.clone()).or_insert(source);
+                streams.push(stream);
+            }
+        }
+        if streams.is_empty() {
+            tracing::info!("No streams listed at this time");
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let (upcoming, streams): (Vec<_>, Vec<_>) = streams
+            .into_iter()
+            .partition(|s| s.status != StreamStatus::Archived);
+        self.track_upcoming_streams(&upcoming).await;
+
+        let streams = self.include_retries(streams).await?;
+        let discovered = streams.len();
+        let (streams, backlogged) = self.sort_filter_limit_streams(streams, &source_of).await?;
+        metrics::streams_discovered(streams.len());
+        {
+            let mut report = self.report();
+            report.discovered = discovered;
+            report.filtered = discovered - streams.len();
+        }
+        let listed_in_full = self
+            .sources()
+            .zip(backlogged)
+            .filter(|(_, backlogged)| !backlogged)
+            .map(|((scraper, ..), _)| scraper)
+            .collect::<Vec<_>>();
+        if streams.is_empty() {
+            tracing::info!("No streams to process at this time");
+            for scraper in listed_in_full {
+                scraper.listing_processed();
+            }
+            return Ok((streams, Vec::new()));
+        }
+        Ok((streams, listed_in_full))
+    }
 
+    /// Adds the failed streams due for a retry to the listed `streams`, looking up those
+    /// no longer listed, and drops listed streams that failed and are not due yet
+    async fn include_retries(&self, mut streams: Vec<Stream>) -> anyhow::Result<Vec<Stream>> {
+        let Some(policy) = &self.retries else {
+            return Ok(streams);
+        };
+        let failed = self
+            .store
+            .get_failed_streams()
+            .await
+            .inspect_err(|e| tracing::error!(error = ?e, "Failed to get failed streams"))
+            .context("Failed to get failed streams")?;
+        if failed.is_empty() {
+            return Ok(streams);
+        }
+
+        let now = chrono::Utc::now();
+        let (due, waiting): (Vec<_>, Vec<_>) =
+            failed.into_iter().partition(|f| policy.is_due(f, now));
+        let waiting = waiting
+            .iter()
+            .map(|f| f.video_id.as_str())
+            .collect::<HashSet<_>>();
+        streams.retain(|s| !waiting.contains(s.video_id.as_str()));
+
+        let listed = streams
+            .iter()
+            .map(|s| s.video_id.clone())
+            .collect::<HashSet<_>>();
+        for failed in due.iter().filter(|f| !listed.contains(&f.video_id)) {
+            // a stream that can't be looked up is retried on a later run
+            match self.channel_scraper.fetch_stream(&failed.video_id).await {
+                Ok(stream) if stream.status == StreamStatus::Archived => streams.push(stream),
+                Ok(_) => {}
+                Err(e) => tracing::warn!(
+                    error = ?e,
+                    video_id = %failed.video_id,
+                    "Failed to look up failed stream for a retry"
+                ),
+            }
+        }
+        tracing::info!(
+            retrying = due.len(),
+            waiting = waiting.len(),
+            "Including failed streams due for a retry"
+        );
+        Ok(streams)
+    }
+
+    /// Runs `streams` through the pipeline, storing each once it is summarized
+    async fn process(&self, mut streams: Vec<Stream>) -> anyhow::Result<()> {
+        self.resolve_video_details(&mut streams).await;
+        self.classify_categories(&mut streams).await;
+        self.attach_order_papers(&mut streams).await;
+        for stream in &streams {
+            self.hooks.stream_discovered(stream).await;
+        }
+
+        let mut checkpoints = streams
+            .iter()
+            .map(|s| self.checkpoints.load(&s.video_id))
+            .collect::<Vec<_>>();
+        for stream in &streams {
+            self.record_state(&stream.video_id).await;
+        }
+        if self.persist_transcripts {
+            self.load_stored_transcripts(&streams, &mut checkpoints)
+                .await;
+        }
+        let captions = self.fetch_captions(&streams, &checkpoints).await;
+        if self.preflight_checks {
+            let needs_audio = streams
+                .iter()
+                .zip(&captions)
+                .zip(&checkpoints)
+                .filter(|((_, captions), checkpoint)| {
+                    captions.is_none() && checkpoint.transcript.is_none()
+                })
+                .map(|((stream, _), _)| stream)
+                .collect::<Vec<_>>();
+            if !needs_audio.is_empty() {
+                self.audio_handler.check_dependencies()?;
+                preflight::check_disk_space(
+                    &self.workdir.join("audio"),
+                    &needs_audio,
+                    &self.cleanup,
+                )?;
+            }
+        }
+
+        let downloader = Downloader {
+            audio_handler: self.audio_handler.clone(),
+            checkpoints: self.checkpoints.clone(),
+            report: self.report.clone(),
+            audio_dl_path: self.workdir.join("audio"),
+            cleanup: self.cleanup,
+            shutdown: self.shutdown.clone(),
+            deadline: self.deadline,
+            abandoned: self.cancellation.child_token(),
+            audit: self.audit.clone(),
+        };
+        let abandoned = downloader.abandoned.clone();
+        let to_prepare = streams
+            .iter()
+            .cloned()
+            .zip(captions)
+            .zip(checkpoints)
+            .map(|((stream, captions), checkpoint)| (stream, captions, checkpoint))
+            .collect();
+        let (mut prepared, downloads) = downloader.spawn(to_prepare, self.download_concurrency);
+
+        let mut stored = Vec::new();
+        let processed = self
+            .process_prepared(&mut streams, &mut prepared, &mut stored)
+            .await;
+        // downloads yet to start are skipped, and those running are waited on so that
+        // none outlive the run
+        abandoned.cancel();
+        drop(prepared);
+        downloads.await.context("Download task panicked")?;
+        processed?;
+
+        if self.persistence_check {
+            self.check_persisted(&stored).await?;
+        }
+        if self.shutdown.is_cancelled() {
+            tracing::info!(
+                processed = stored.len(),
+                remaining = streams.len() - stored.len(),
+                "Shut down, leaving the remaining streams for the next run"
+            );
+        } else if self.cancellation.is_cancelled() {
+            tracing::info!(
+                processed = stored.len(),
+                remaining = streams.len() - stored.len(),
+                "Cancelled, leaving the remaining streams for the next run"
+            );
+        } else if stored.len() < streams.len() && self.stopping() {
+            tracing::info!(
+                processed = stored.len(),
+                remaining = streams.len() - stored.len(),
+                "Run duration spent, leaving the remaining streams for the next run"
+            );
+        }
+        Ok(())
+    }
+
+    /// Transcribes, summarizes and stores each of the streams as it is prepared, until
+    /// one fails or the run is stopping
+    async fn process_prepared(
+        &self,
+        streams: &mut [Stream],
+        prepared: &mut mpsc::Receiver<Prepared>,
+        stored: &mut Vec<String>,
+    ) -> anyhow::Result<()> {
         let workdir_ref = self.workdir.as_path();
         let audio_dl_path = workdir_ref.join("audio");
 
-        let stream_audio_paths = streams
-            .par_iter_mut()
-            .map(|stream| {
-                self.audio_handler
-                    .download(stream, &audio_dl_path)
-                    .and_then(|dl_path| self.audio_handler.clean_up(stream, &dl_path))
-                    .map(|processed_audio_path| (processed_audio_path, stream))
-            })
-            .collect::<anyhow::Result<Vec<_>>>()?;
-
-        for (audio_path, stream) in stream_audio_paths {
-            let audio_input = match &self.chunking_config {
-                Some(config) => AudioInput::Chunked {
-                    chunk_duration_seconds: config.chunk_duration_seconds,
-                    chunks_dir_path: workdir_ref.join("audio").join(&stream.video_id),
-                    file_path: audio_path,
-                },
-                None => AudioInput::File(audio_path),
+        for stream in streams.iter_mut() {
+            if self.stopping() {
+                break;
+            }
+            let (source, mut checkpoint) = match prepared.recv().await {
+                Some(Ok(Some(prepared))) => prepared,
+                Some(Ok(None)) | None => break,
+                Some(Err(e)) => {
+                    self.hooks.stream_failing(stream);
+                    return Err(e);
+                }
             };
+            self.record_state(&stream.video_id).await;
+            if let Some(events) = &self.events {
+                events.stream_started(stream);
+            }
+            self.hooks.before_transcribe(stream).await;
+            let transcribed = !matches!(source, TranscriptSource::Checkpoint(_));
+            let transcribe_resp = match source {
+                TranscriptSource::Checkpoint(transcript) => {
+                    tracing::info!(video_id = %stream.video_id, "Resuming from transcript checkpoint");
+                    transcript
+                }
+                TranscriptSource::Captions(transcript) => {
+                    tracing::info!(video_id = %stream.video_id, "Transcribed from captions");
+                    transcript
+                }
+                TranscriptSource::Audio(audio_path) => {
+                    let audio_input = match &self.chunking_config {
+                        Some(config) => AudioInput::Chunked {
+                            chunk_duration_seconds: config.chunk_duration_seconds,
+                            max_chunk_bytes: config.max_chunk_bytes,
+                            chunks_dir_path: workdir_ref.join("audio").join(&stream.video_id),
+                            file_path: audio_path,
+                        },
+                        None => AudioInput::File(audio_path),
+                    };
 
-            let transcribe_resp = self
-                .transcriber
-                .transcribe(audio_input)
-                .await
-                .inspect_err(|e| tracing::error!(error = ?e, "Failed to transcribe audio"))
-                .map_err(|e| anyhow::anyhow!("Failed to transcribe audio: {e:?}"))?;
+                    let started = Instant::now();
+                    let transcribe = self.transcriber.transcribe(audio_input);
+                    let transcribed =
+                        within(self.timeouts.transcribe, "Transcribing", transcribe).await?;
+                    self.report().stage_finished("transcribe", started);
+                    match transcribed {
+                        Ok(transcript) => transcript,
+                        // ffmpeg is killed on shutdown while chunking the audio
+                        Err(_) if self.shutdown.is_cancelled() => break,
+                        Err(e) => {
+                            tracing::error!(error = ?e, "Failed to transcribe audio");
+                            anyhow::bail!("Failed to transcribe audio: {e:?}");
+                        }
+                    }
+                }
+            };
+            if !checkpoint.completed(StreamState::Transcribed) {
+                checkpoint.transcript = Some(transcribe_resp.clone());
+                self.checkpoints
+                    .save(&stream.video_id, &mut checkpoint, StreamState::Transcribed);
+                self.record_state(&stream.video_id).await;
+            }
+            if self.persist_transcripts && transcribed {
+                self.store_transcript(&stream.video_id, &transcribe_resp)
+                    .await;
+            }
+            // without a checkpoint to resume from, the stream is finished instead
+            if self.shutdown.is_cancelled() && self.checkpoints.enabled() {
+                tracing::info!(video_id = %stream.video_id, "Stopping at transcript checkpoint");
+                break;
+            }
+            if self.cancellation.is_cancelled() {
+                tracing::info!(video_id = %stream.video_id, "Cancelled after transcribing");
+                break;
+            }
 
-            let summary_resp = self
-                .summarizer
-                .summarize(&transcribe_resp.text)
-                .await
-                .inspect_err(|e| tracing::error!(error = ?e, "Failed to summarize transcript"))
-                .map_err(|e| anyhow::anyhow!("Failed to summarize transcript: {e:?}"))?;
+            let timestamped = match self.timestamp_links {
+                true => timestamped_transcript(&transcribe_resp).or_else(|| {
+                    tracing::warn!(
+                        video_id = %stream.video_id,
+                        "Transcript has no segment timestamps, summarizing without them"
+                    );
+                    None
+                }),
+                false => None,
+            };
+            let content = timestamped.as_deref().unwrap_or(&transcribe_resp.text);
 
-            stream.summary_md = Some(summary_resp.summary);
+            let context = SummaryContext::from(&*stream);
+            let summary_resp = match checkpoint.summary.clone() {
+                Some(summary) => {
+                    tracing::info!(video_id = %stream.video_id, "Resuming from summary checkpoint");
+                    summary
+                }
+                None => {
+                    let started = Instant::now();
+                    let summarize = summarize_transcript(&self.summarizer, content, &context);
+                    let summarized =
+                        within(self.timeouts.summarize, "Summarizing", summarize).await?;
+                    self.report().stage_finished("summarize", started);
+                    let summary = summarized
+                        .inspect_err(
+                            |e| tracing::error!(error = ?e, "Failed to summarize transcript"),
+                        )
+                        .map_err(|e| anyhow::anyhow!("Failed to summarize transcript: {e:?}"))?;
+                    checkpoint.summary = Some(summary.clone());
+                    self.checkpoints.save(
+                        &stream.video_id,
+                        &mut checkpoint,
+                        StreamState::Summarized,
+                    );
+                    self.record_state(&stream.video_id).await;
+                    summary
+                }
+            };
+            if let Some(events) = &self.events {
+                events.summarized(&stream.video_id);
+            }
+            if self.cancellation.is_cancelled() {
+                tracing::info!(video_id = %stream.video_id, "Cancelled after summarizing");
+                break;
+            }
 
+            stream.summary_md = Some(match self.timestamp_links {
+                true => link_timestamps(&summary_resp.summary, &stream.video_id),
+                false => summary_resp.summary,
+            });
+            stream.summary_tldr = summary_resp.tldr;
+            stream.structured_summary = summary_resp.structured.map(Json);
+            stream.summary_verification = summary_resp.verification.map(Json);
+            self.hooks.after_summarize(stream).await;
+
+            if let Some(mirror) = &self.thumbnail_mirror {
+                mirror_thumbnail(&self.http_client, mirror, stream).await;
+            }
+            let started = Instant::now();
             self.store.insert_stream(stream).await?;
+            self.report().stage_finished("store", started);
+            self.audit.record(AuditAction::StreamStored {
+                video_id: &stream.video_id,
+            });
+            metrics::stream_processed();
+            stored.push(stream.video_id.clone());
+            self.report().processed.push(stream.video_id.clone());
+            if let Some(events) = &self.events {
+                events.inserted(&stream.video_id);
+            }
+            self.hooks.stream_stored(stream).await;
+            if self.reuploads == Some(ReuploadPolicy::Replace) {
+                self.replace_original(stream).await?;
+            }
+            if self.retries.is_some() {
+                if let Err(e) = self.store.clear_stream_failure(&stream.video_id).await {
+                    tracing::warn!(error = ?e, video_id = %stream.video_id, "Failed to clear stream failure");
+                }
+            }
+            // the stream is no longer listed for processing once stored
+            self.checkpoints.clear(&stream.video_id);
+            self.record_state(&stream.video_id).await;
+            let deleted = self
+                .retention
+                .stream_stored(&audio_dl_path, &stream.video_id);
+            if !deleted.is_empty() {
+                self.audit
+                    .record(AuditAction::FilesDeleted { paths: &deleted });
+            }
+
+            if let Some(entity_extractor) = &self.entity_extractor {
+                // entities are supplementary, so a failed extraction does not fail the stream
+                match entity_extractor
+                    .extract_entities(&transcribe_resp.text, &context)
+                    .await
+                {
+                    Ok(entities) => {
+                        if let Err(e) = self
+                            .store
+                            .insert_stream_entities(&stream.video_id, &entities)
+                            .await
+                        {
+                            tracing::warn!(
+                                error = ?e,
+                                video_id = %stream.video_id,
+                                "Failed to store entities"
+                            );
+                            self.supplementary_failed(&stream.video_id, "store entities", &e);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            error = ?e,
+                            video_id = %stream.video_id,
+                            "Failed to extract entities, storing stream without them"
+                        );
+                        self.supplementary_failed(&stream.video_id, "extract entities", &e);
+                    }
+                }
+            }
+
+            if let Some(division_extractor) = &self.division_extractor {
+                match division_extractor
+                    .extract_divisions(&transcribe_resp.text, &context)
+                    .await
+                {
+                    Ok(divisions) => {
+                        if let Err(e) = self
+                            .store
+                            .insert_stream_divisions(&stream.video_id, &divisions)
+                            .await
+                        {
+                            tracing::warn!(
+                                error = ?e,
+                                video_id = %stream.video_id,
+                                "Failed to store divisions"
+                            );
+                            self.supplementary_failed(&stream.video_id, "store divisions", &e);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            error = ?e,
+                            video_id = %stream.video_id,
+                            "Failed to extract divisions, storing stream without them"
+                        );
+                        self.supplementary_failed(&stream.video_id, "extract divisions", &e);
+                    }
+                }
+            }
+
+            if let Some(captions) = &self.captions {
+                self.store_captions(captions, &stream.video_id, &transcribe_resp)
+                    .await;
+            }
+
+            #[cfg(feature = "pgvector")]
+            if let Some(embedder) = &self.embedder {
+                let summary = stream.summary_md.as_deref().unwrap_or_default();
+                // like entities, embeddings only enhance search and do not fail the stream
+                match embed_stream(embedder, summary, &transcribe_resp).await {
+                    Ok(embeddings) => {
+                        if let Err(e) = self
+                            .store
+                            .store_stream_embeddings(&stream.video_id, &embeddings)
+                            .await
+                        {
+                            tracing::warn!(
+                                error = ?e,
+                                video_id = %stream.video_id,
+                                "Failed to store embeddings"
+                            );
+                            self.supplementary_failed(&stream.video_id, "store embeddings", &e);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            error = ?e,
+                            video_id = %stream.video_id,
+                            "Failed to embed stream, storing it without embeddings"
+                        );
+                        self.supplementary_failed(&stream.video_id, "embed stream", &e);
+                    }
+                }
+            }
+
+            if let Some(usage_tracker) = &self.usage_tracker {
+                let usage = usage_tracker.take();
+                for (model, usage) in &usage {
+                    tracing::info!(
+                        video_id = %stream.video_id,
+                        model = %model,
+                        requests = usage.requests,
+                        prompt_tokens = usage.prompt_tokens,
+                        completion_tokens = usage.completion_tokens,
+                        audio_seconds = usage.audio_seconds,
+                        "LLM usage for stream"
+                    );
+                    self.audit.record(AuditAction::ProviderRequests {
+                        video_id: &stream.video_id,
+                        model,
+                        usage: *usage,
+                    });
+                }
+                self.report().add_usage(usage);
+            }
         }
+        Ok(())
+    }
 
+    /// Reports a failed stage that only enhances `video_id` and does not fail it
+    fn supplementary_failed(&self, video_id: &str, stage: &str, error: &impl std::fmt::Debug) {
+        self.report()
+            .errors
+            .push(format!("{video_id}: Failed to {stage}: {error:?}"));
+    }
+
+    /// Fails if any of the streams inserted while processing can't be read back from
+    /// the store
+    #[tracing::instrument(skip_all)]
+    async fn check_persisted(&self, stored: &[String]) -> anyhow::Result<()> {
+        let video_ids = stored.iter().map(String::as_str).collect::<Vec<_>>();
+        let stored = self
+            .store
+            .get_existing_stream_ids(&video_ids)
+            .await
+            .context("Failed to check processed streams were stored")?;
+
+        let missing = video_ids
+            .into_iter()
+            .filter(|video_id| !stored.contains(*video_id))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            tracing::error!(?missing, "Processed streams are missing from the store");
+            anyhow::bail!(
+                "Processed streams are missing from the store: {}",
+                missing.join(", ")
+            );
+        }
         Ok(())
     }
+
+    /// Writes the caption files for `transcript` to the configured destination. Captions
+    /// only enhance the stream, so failing to write or store them does not fail it.
+    async fn store_captions(
+        &self,
+        config: &CaptionsConfig,
+        video_id: &str,
+        transcript: &TranscribeResponse,
+    ) {
+        let captions_dir = self.workdir.join("captions");
+        for format in &config.formats {
+            let Some(content) = transcript.to_captions(*format) else {
+                tracing::warn!(
+                    video_id,
+                    "Transcript has no segment timestamps, skipping captions"
+                );
+                return;
+            };
+
+            match config.destination {
+                CaptionDestination::Workdir => {
+                    let path = captions_dir.join(format!("{video_id}.{}", format.extension()));
+                    match std::fs::create_dir_all(&captions_dir)
+                        .and_then(|_| std::fs::write(&path, content))
+                    {
+                        Ok(()) => tracing::info!(path = ?path, "Wrote captions"),
+                        Err(e) => {
+                            tracing::warn!(error = ?e, path = ?path, "Failed to write captions")
+                        }
+                    }
+                }
+                CaptionDestination::DataStore => {
+                    if let Err(e) = self
+                        .store
+                        .insert_stream_captions(video_id, format.extension(), &content)
+                        .await
+                    {
+                        tracing::warn!(error = ?e, video_id, "Failed to store captions");
+                        self.supplementary_failed(video_id, "store captions", &e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Copies the stream's thumbnail into the mirror, and points the stream at the copy if
+/// the mirror is served from a URL. YouTube's URL still works, so a failed copy does
+/// not fail the stream.
+async fn mirror_thumbnail(client: &reqwest::Client, mirror: &ThumbnailMirror, stream: &mut Stream) {
+    let Some(url) = &stream.thumbnail_url else {
+        return;
+    };
+
+    let path = mirror.dir.join(format!("{}.jpg", stream.video_id));
+    let copied = async {
+        let image = client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        tokio::fs::create_dir_all(&mirror.dir).await?;
+        tokio::fs::write(&path, image).await?;
+        anyhow::Ok(())
+    }
+    .await;
+
+    match copied {
+        Ok(()) => {
+            tracing::info!(path = ?path, "Mirrored thumbnail");
+            if let Some(base_url) = &mirror.base_url {
+                stream.thumbnail_url = Some(format!(
+                    "{}/{}.jpg",
+                    base_url.trim_end_matches('/'),
+                    stream.video_id
+                ));
+            }
+        }
+        Err(e) => tracing::warn!(
+            error = ?e,
+            video_id = %stream.video_id,
+            "Failed to mirror thumbnail"
+        ),
+    }
 }
 
-impl<D, T, S, A, P> Drop for LiveStreamProcessor<D, T, S, A, P>
+impl<D, T, S, A, P, E, M, V, C, K, O> Drop for LiveStreamProcessor<D, T, S, A, P, E, M, V, C, K, O>
 where
-    D: DataStore + Send + Sync + 'static,
+    D: PipelineStore + Send + Sync + 'static,
     T: Transcriber + Send + Sync + 'static,
     S: Summarizer + Send + Sync + 'static,
     A: AudioHandler + Send + Sync + 'static,
     P: ChannelScraper + Send + Sync + 'static,
+    E: EntityExtractor + Send + Sync + 'static,
+    M: Embedder + Send + Sync + 'static,
+    V: DivisionExtractor + Send + Sync + 'static,
+    C: CategoryClassifier + Send + Sync + 'static,
+    K: CaptionSource + Send + Sync + 'static,
+    O: OrderPaperSource + Send + Sync + 'static,
 {
     fn drop(&mut self) {
-        let workdir_ref = self.workdir.as_path();
-        let audio_path = workdir_ref.join("audio");
-
-        if audio_path.exists() {
-            if let Err(e) = remove_dir_all(&audio_path) {
-                tracing::warn!(error = ?e, path = ?audio_path, "Failed to clean up audio directory");
-            } else {
-                tracing::info!(path = ?audio_path, "Cleaned up audio directory");
-            }
+        let deleted = self
+            .retention
+            .run_finished(&self.workdir.join("audio"), self.incomplete);
+        if !deleted.is_empty() {
+            self.audit
+                .record(AuditAction::FilesDeleted { paths: &deleted });
         }
     }
 }
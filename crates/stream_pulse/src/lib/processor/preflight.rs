@@ -0,0 +1,109 @@
+//! # Pre-flight checks
+//!
+//! Run before any audio is downloaded, so that a full disk or a missing binary fails the
+//! run up front, instead of surfacing as an ffmpeg error an hour into downloading.
+
+use std::path::Path;
+
+use anyhow::Context;
+use stream_datastore::Stream;
+
+use crate::{parser::parse_duration_to_seconds, processor::cleanup::CleanupConfig};
+
+/// Disk taken per second of each copy of a stream's audio, an mp3 at about 128 kbps.
+/// Besides the download, there is a copy for each cleanup pass and the chunks split
+/// from the last of them
+const BYTES_PER_AUDIO_SECOND: u64 = 16_000;
+/// Assumed for streams listed without a duration, about as long as a long sitting
+const UNKNOWN_DURATION_SECS: u64 = 4 * 3600;
+/// Left free on top of the estimate, for whatever else shares the disk
+const HEADROOM_BYTES: u64 = 1 << 30;
+
+/// Estimated disk space downloading and processing the audio of `streams` takes
+pub(crate) fn required_space(streams: &[&Stream], cleanup: &CleanupConfig) -> u64 {
+    let copies = 2 + cleanup.copies();
+    streams
+        .iter()
+        .map(|stream| {
+            parse_duration_to_seconds(&stream.duration).unwrap_or(UNKNOWN_DURATION_SECS)
+                * copies
+                * BYTES_PER_AUDIO_SECOND
+        })
+        .sum()
+}
+
+/// Fails unless the disk `workdir` is on has room for the audio of `streams`
+pub(crate) fn check_disk_space(
+    workdir: &Path,
+    streams: &[&Stream],
+    cleanup: &CleanupConfig,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(workdir)
+        .with_context(|| format!("Failed to create workdir {}", workdir.display()))?;
+    let available = fs2::available_space(workdir)
+        .with_context(|| format!("Failed to read free disk space of {}", workdir.display()))?;
+
+    let required = required_space(streams, cleanup);
+    tracing::info!(
+        required_gb = gigabytes(required),
+        available_gb = gigabytes(available),
+        "Checked disk space for audio"
+    );
+    if available < required + HEADROOM_BYTES {
+        anyhow::bail!(
+            "Not enough disk space in {}: the audio of {} streams takes about {:.1} GB, \
+             and {:.1} GB is free",
+            workdir.display(),
+            streams.len(),
+            gigabytes(required),
+            gigabytes(available),
+        );
+    }
+    Ok(())
+}
+
+fn gigabytes(bytes: u64) -> f64 {
+    bytes as f64 / 1e9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(duration: &str) -> Stream {
+        Stream {
+            duration: duration.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_required_space_is_estimated_from_durations() {
+        let (long, short, unknown) = (stream("4:37:08"), stream("12:26"), stream(""));
+        let all = CleanupConfig::all();
+        assert_eq!(
+            required_space(&[&short], &all),
+            746 * 5 * BYTES_PER_AUDIO_SECOND
+        );
+        assert_eq!(
+            required_space(&[&long, &unknown], &all),
+            (16628 + UNKNOWN_DURATION_SECS) * 5 * BYTES_PER_AUDIO_SECOND
+        );
+        assert_eq!(
+            required_space(&[&short], &CleanupConfig::none()),
+            746 * 2 * BYTES_PER_AUDIO_SECOND
+        );
+    }
+
+    #[test]
+    fn test_check_disk_space_fails_without_room() {
+        let workdir = std::env::temp_dir().join("stream-pulse-preflight-test");
+        let endless = stream("1000000000:00:00");
+
+        let all = CleanupConfig::all();
+        let err = check_disk_space(&workdir, &[&endless], &all).unwrap_err();
+        assert!(err.to_string().contains("Not enough disk space"));
+        assert!(check_disk_space(&workdir, &[], &all).is_ok());
+        let _ = std::fs::remove_dir_all(&workdir);
+    }
+}
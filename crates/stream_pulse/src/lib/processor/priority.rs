@@ -0,0 +1,122 @@
+//! # Prioritization
+//!
+//! The order listed streams are processed in. Only the first
+//! [`max_streams`](crate::LiveStreamProcessorBuilder::max_streams) are processed on a
+//! run, so the order decides which streams wait for later runs in a backlog.
+
+use std::{cmp::Ordering, str::FromStr};
+
+use stream_datastore::{Stream, StreamCategory};
+
+use crate::parser::parse_duration_to_seconds;
+
+/// Streams the site already shows as scheduled or live come first whatever the
+/// strategy, and are ordered by it among themselves
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PrioritizationStrategy {
+    /// Streams published earliest first, working through a backlog in order
+    #[default]
+    OldestFirst,
+    /// Streams published most recently first, e.g. to summarize today's sitting while
+    /// later runs pick up the backlog
+    NewestFirst,
+    /// Shortest streams first, oldest first among streams as long
+    ShortestFirst,
+    /// Streams of the listed categories first, in the order listed, then the rest.
+    /// Oldest first within each category
+    ByCategory(Vec<StreamCategory>),
+}
+
+impl FromStr for PrioritizationStrategy {
+    type Err = String;
+
+    /// Parses "oldest-first", "newest-first", "shortest-first" or
+    /// "by-category:<category>,<category>...", e.g. "by-category:senate,national_assembly"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        if let Some(categories) = s.strip_prefix("by-category:") {
+            return categories
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map(PrioritizationStrategy::ByCategory);
+        }
+        match s.as_str() {
+            "oldest-first" => Ok(PrioritizationStrategy::OldestFirst),
+            "newest-first" => Ok(PrioritizationStrategy::NewestFirst),
+            "shortest-first" => Ok(PrioritizationStrategy::ShortestFirst),
+            other => Err(format!("Unsupported prioritization strategy: {other}")),
+        }
+    }
+}
+
+impl PrioritizationStrategy {
+    /// Orders `a` before `b` if it should be processed first. Streams without a
+    /// publish date count as the oldest, and streams without a duration as the longest.
+    pub(crate) fn compare(&self, a: &Stream, b: &Stream) -> Ordering {
+        let oldest_first = a.published_at().cmp(&b.published_at());
+        match self {
+            PrioritizationStrategy::OldestFirst => oldest_first,
+            PrioritizationStrategy::NewestFirst => oldest_first.reverse(),
+            PrioritizationStrategy::ShortestFirst => {
+                let seconds =
+                    |s: &Stream| parse_duration_to_seconds(&s.duration).unwrap_or(u64::MAX);
+                seconds(a).cmp(&seconds(b)).then(oldest_first)
+            }
+            PrioritizationStrategy::ByCategory(categories) => {
+                let rank = |s: &Stream| {
+                    categories
+                        .iter()
+                        .position(|category| *category == s.category())
+                        .unwrap_or(categories.len())
+                };
+                rank(a).cmp(&rank(b)).then(oldest_first)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(video_id: &str, title: &str, duration: &str, streamed_date: &str) -> Stream {
+        Stream {
+            video_id: video_id.into(),
+            title: title.into(),
+            duration: duration.into(),
+            streamed_date: streamed_date.into(),
+            ..Default::default()
+        }
+    }
+
+    fn ordered(strategy: &str) -> Vec<String> {
+        let mut streams = [
+            stream("a", "Senate Plenary", "5:02:11", "3 days ago"),
+            stream("b", "National Assembly Plenary", "2:14:09", "1 day ago"),
+            stream("c", "Committee on Health", "48:30", "2 days ago"),
+        ];
+        let strategy = strategy.parse::<PrioritizationStrategy>().unwrap();
+        streams.sort_by(|a, b| strategy.compare(a, b));
+        streams.into_iter().map(|s| s.video_id).collect()
+    }
+
+    #[test]
+    fn test_streams_are_ordered_by_the_strategy() {
+        assert_eq!(ordered("oldest-first"), ["a", "c", "b"]);
+        assert_eq!(ordered("newest-first"), ["b", "c", "a"]);
+        assert_eq!(ordered("shortest-first"), ["c", "b", "a"]);
+        assert_eq!(
+            ordered("by-category:national_assembly,committee"),
+            ["b", "c", "a"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_categories_fail_to_parse() {
+        assert!("by-category:senate,assembly"
+            .parse::<PrioritizationStrategy>()
+            .is_err());
+        assert!("alphabetical".parse::<PrioritizationStrategy>().is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! # Run Reports
+//!
+//! What a run did, returned by [`LiveStreamProcessor::run`](crate::LiveStreamProcessor::run)
+//! so that callers can print, log or persist it. A failed run's report is attached to
+//! its error, and can be read back with [`anyhow::Error::downcast_ref`].
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{metrics, UsageReport};
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunReport {
+    /// Archived streams listed on the channels or looked up by ID, failed streams due for
+    /// a retry included
+    pub discovered: usize,
+    /// Discovered streams left out of the run: already stored, not live recordings,
+    /// waiting for a retry, or beyond
+    /// [`max_streams`](crate::LiveStreamProcessorBuilder::max_streams)
+    pub filtered: usize,
+    /// IDs of the streams summarized and stored, in the order they were
+    pub processed: Vec<String>,
+    /// ID of the stream the run failed in, if it failed in one
+    pub failed: Option<String>,
+    /// Time spent in each stage, summed across streams: "download", "clean",
+    /// "transcribe", "summarize" and "store"
+    pub stage_durations: BTreeMap<&'static str, Duration>,
+    /// Provider usage by model, for estimating what the run cost
+    pub usage: UsageReport,
+    /// The run's failure, and failures of supplementary stages that did not fail their
+    /// stream, e.g. extracting entities
+    pub errors: Vec<String>,
+    /// Whether the run was shut down or cancelled before it got through its streams
+    pub cancelled: bool,
+    pub duration: Duration,
+}
+
+impl Display for RunReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Processed {} of {} discovered streams in {:.1?}",
+            self.processed.len(),
+            self.discovered,
+            self.duration
+        )?;
+        if let Some(video_id) = &self.failed {
+            write!(f, ", failing in {video_id}")?;
+        }
+        if self.cancelled {
+            write!(f, ", cancelled")?;
+        }
+        Ok(())
+    }
+}
+
+impl RunReport {
+    /// Records how long `stage` took, from `started` until now, in the report and the
+    /// metrics
+    pub(crate) fn stage_finished(&mut self, stage: &'static str, started: Instant) {
+        *self.stage_durations.entry(stage).or_default() += started.elapsed();
+        metrics::record_stage_duration(stage, started);
+    }
+
+    pub(crate) fn add_usage(&mut self, usage: UsageReport) {
+        for (model, usage) in usage {
+            let entry = self.usage.entry(model).or_default();
+            entry.requests += usage.requests;
+            entry.prompt_tokens += usage.prompt_tokens;
+            entry.completion_tokens += usage.completion_tokens;
+            entry.audio_seconds += usage.audio_seconds;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ModelUsage;
+
+    #[test]
+    fn test_stage_durations_and_usage_are_summed_across_streams() {
+        let mut report = RunReport::default();
+        let started = Instant::now() - Duration::from_secs(2);
+        report.stage_finished("transcribe", started);
+        report.stage_finished("transcribe", started);
+        assert!(report.stage_durations["transcribe"] >= Duration::from_secs(4));
+
+        let usage = |audio_seconds| {
+            UsageReport::from([(
+                "whisper-1".to_string(),
+                ModelUsage {
+                    requests: 1,
+                    audio_seconds,
+                    ..Default::default()
+                },
+            )])
+        };
+        report.add_usage(usage(600.0));
+        report.add_usage(usage(300.0));
+        assert_eq!(report.usage["whisper-1"].requests, 2);
+        assert_eq!(report.usage["whisper-1"].audio_seconds, 900.0);
+    }
+}
@@ -0,0 +1,192 @@
+//! # Retention
+//!
+//! What becomes of the audio downloaded for each stream in `{workdir}/audio`: the
+//! download, its denoised, normalized and trimmed copies, and the chunks split from
+//! them along with their cached transcriptions. Keeping them costs disk, but they are
+//! what a run resumes from, and what a bad transcription is debugged with.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// Delete each stream's audio once the stream is stored, and the rest when the run
+    /// ends, whether or not it failed
+    DeleteAll,
+    /// Delete each stream's audio once the stream is stored, and the rest when the run
+    /// succeeds. A failed or shut down run keeps it for the next run to resume from
+    #[default]
+    KeepOnFailure,
+    /// Never delete any audio
+    KeepAll,
+    /// Delete audio last written more than this many days ago when a run ends
+    KeepForDays(u32),
+}
+
+impl FromStr for RetentionPolicy {
+    type Err = String;
+
+    /// Parses "delete-all", "keep-on-failure", "keep-all" or "keep-for-days:<n>"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        if let Some(days) = s.strip_prefix("keep-for-days:") {
+            return days
+                .trim()
+                .parse()
+                .map(RetentionPolicy::KeepForDays)
+                .map_err(|_| format!("Invalid number of days to keep audio for: {days}"));
+        }
+        match s.as_str() {
+            "delete-all" => Ok(RetentionPolicy::DeleteAll),
+            "keep-on-failure" => Ok(RetentionPolicy::KeepOnFailure),
+            "keep-all" => Ok(RetentionPolicy::KeepAll),
+            other => Err(format!("Unsupported retention policy: {other}")),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Applies the policy to the audio of a stream that was just stored, returning the
+    /// paths removed
+    pub(crate) fn stream_stored(self, audio_dir: &Path, video_id: &str) -> Vec<PathBuf> {
+        match self {
+            RetentionPolicy::DeleteAll | RetentionPolicy::KeepOnFailure => {
+                stream_artifacts(audio_dir, video_id)
+                    .into_iter()
+                    .filter(|path| remove(path))
+                    .collect()
+            }
+            RetentionPolicy::KeepAll | RetentionPolicy::KeepForDays(_) => Vec::new(),
+        }
+    }
+
+    /// Applies the policy to the audio left in `audio_dir` when a run ends, which is
+    /// `incomplete` if it failed or was shut down, returning the paths removed
+    pub(crate) fn run_finished(self, audio_dir: &Path, incomplete: bool) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(audio_dir) else {
+            return Vec::new();
+        };
+        let cutoff = match self {
+            RetentionPolicy::KeepOnFailure if incomplete => {
+                tracing::info!(path = ?audio_dir, "Keeping audio for the next run to resume");
+                return Vec::new();
+            }
+            RetentionPolicy::KeepAll => return Vec::new(),
+            RetentionPolicy::KeepForDays(days) => {
+                SystemTime::now().checked_sub(Duration::from_secs(u64::from(days) * 86_400))
+            }
+            RetentionPolicy::DeleteAll | RetentionPolicy::KeepOnFailure => None,
+        };
+
+        let mut removed = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(cutoff) = cutoff {
+                let modified = entry.metadata().and_then(|m| m.modified());
+                if !modified.is_ok_and(|modified| modified < cutoff) {
+                    continue;
+                }
+            }
+            if remove(&path) {
+                removed.push(path);
+            }
+        }
+        tracing::info!(path = ?audio_dir, removed = removed.len(), "Cleaned up audio directory");
+        removed
+    }
+}
+
+/// The files and chunk directory in `audio_dir` that belong to the stream, all of
+/// which are named after its video ID
+fn stream_artifacts(audio_dir: &Path, video_id: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(audio_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                return false;
+            };
+            name.strip_prefix(video_id)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '_']))
+        })
+        .collect()
+}
+
+/// Removes the file or directory at `path`, logging rather than returning a failure
+fn remove(path: &Path) -> bool {
+    let removed = match path.is_dir() {
+        true => std::fs::remove_dir_all(path),
+        false => std::fs::remove_file(path),
+    };
+    match removed {
+        Ok(()) => {
+            tracing::debug!(path = ?path, "Removed audio");
+            true
+        }
+        Err(e) => {
+            tracing::warn!(error = ?e, path = ?path, "Failed to remove audio");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("abc123")).unwrap();
+        for file in ["abc123.mp3", "abc123_trimmed.mp3", "abc1234.mp3"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_stored_streams_audio_is_removed() {
+        let dir = audio_dir("stream-pulse-retention-stored-test");
+
+        RetentionPolicy::KeepAll.stream_stored(&dir, "abc123");
+        assert!(dir.join("abc123.mp3").exists());
+
+        RetentionPolicy::KeepOnFailure.stream_stored(&dir, "abc123");
+        let left = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(left, vec!["abc1234.mp3"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_audio_is_kept_after_a_failed_run() {
+        let dir = audio_dir("stream-pulse-retention-finished-test");
+
+        RetentionPolicy::KeepOnFailure.run_finished(&dir, true);
+        RetentionPolicy::KeepForDays(1).run_finished(&dir, false);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 4);
+
+        RetentionPolicy::DeleteAll.run_finished(&dir, true);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_policies_are_parsed() {
+        assert_eq!(
+            "keep-for-days:7".parse(),
+            Ok(RetentionPolicy::KeepForDays(7))
+        );
+        assert_eq!("Delete-All".parse(), Ok(RetentionPolicy::DeleteAll));
+        assert!("keep-for-days:a week".parse::<RetentionPolicy>().is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! # Retries
+//!
+//! Streams that failed on earlier runs are recorded with the store, and retried on
+//! later runs alongside the newly listed streams once their backoff has passed, so
+//! that transient failures like rate limits or a provider outage heal themselves.
+//! Streams that keep failing are left for an operator after their last attempt, and
+//! no longer hold up the streams behind them.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use stream_datastore::FailedStream;
+
+/// How often, and how soon, failed streams are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamRetryPolicy {
+    /// Attempts at processing a stream, the first included, before it is no longer
+    /// retried
+    pub max_attempts: u32,
+    /// Wait after the first failed attempt, doubled after each failed attempt since
+    pub backoff: Duration,
+}
+
+impl Default for StreamRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            backoff: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl StreamRetryPolicy {
+    /// When `failed` is next due for an attempt, or `None` once it has run out of them
+    pub(crate) fn next_attempt_at(&self, failed: &FailedStream) -> Option<DateTime<Utc>> {
+        if failed.attempts >= self.max_attempts {
+            return None;
+        }
+        let doublings = failed.attempts.saturating_sub(1).min(31);
+        let backoff = self.backoff.saturating_mul(1 << doublings);
+        let backoff = TimeDelta::from_std(backoff).unwrap_or(TimeDelta::MAX);
+        Some(
+            failed
+                .last_failed_at
+                .checked_add_signed(backoff)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        )
+    }
+
+    pub(crate) fn is_due(&self, failed: &FailedStream, now: DateTime<Utc>) -> bool {
+        self.next_attempt_at(failed).is_some_and(|at| at <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(attempts: u32, last_failed_at: DateTime<Utc>) -> FailedStream {
+        FailedStream {
+            video_id: "abc123".into(),
+            attempts,
+            last_error: "Failed to transcribe audio".into(),
+            last_failed_at,
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_with_each_failed_attempt() {
+        let policy = StreamRetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_secs(60),
+        };
+        let now = Utc::now();
+        let minutes = |n| now + TimeDelta::minutes(n);
+
+        assert_eq!(policy.next_attempt_at(&failed(1, now)), Some(minutes(1)));
+        assert_eq!(policy.next_attempt_at(&failed(2, now)), Some(minutes(2)));
+        assert_eq!(policy.next_attempt_at(&failed(3, now)), None);
+
+        assert!(policy.is_due(&failed(2, minutes(-3)), now));
+        assert!(!policy.is_due(&failed(2, minutes(-1)), now));
+        assert!(!policy.is_due(&failed(3, minutes(-60)), now));
+    }
+}
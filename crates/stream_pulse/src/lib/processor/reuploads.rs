@@ -0,0 +1,150 @@
+//! # Re-uploads
+//!
+//! Parliament's channels occasionally take a sitting down and upload it again under a
+//! new video ID, which would otherwise be summarized a second time. A listed stream is
+//! taken to be a re-upload of a stored one when their titles match once normalized,
+//! the stored one started no more than [`LOOKBACK`] before it, and their durations are
+//! within [`DURATION_TOLERANCE_SECS`] of each other.
+
+use std::{cmp::Reverse, str::FromStr};
+
+use chrono::TimeDelta;
+use stream_datastore::{StoredStream, Stream};
+
+use crate::parser::parse_duration_to_seconds;
+
+/// How long after a sitting was first uploaded it may be uploaded again
+pub(crate) const LOOKBACK: TimeDelta = TimeDelta::days(7);
+/// Re-uploads are often trimmed or padded by a few minutes
+pub(crate) const DURATION_TOLERANCE_SECS: u64 = 5 * 60;
+
+/// What to do with a listed stream that is a re-upload of a stored one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReuploadPolicy {
+    /// Leave the re-upload out of the run. It is checked again on every run it is listed
+    Skip,
+    /// Record the re-upload as an alias of the stored stream, without processing it
+    #[default]
+    Link,
+    /// Process the re-upload, then delete the stored stream in favour of it, e.g. when
+    /// the first upload was cut short
+    Replace,
+}
+
+impl FromStr for ReuploadPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "skip" => Ok(ReuploadPolicy::Skip),
+            "link" => Ok(ReuploadPolicy::Link),
+            "replace" => Ok(ReuploadPolicy::Replace),
+            other => Err(format!("Unsupported re-upload policy: {other}")),
+        }
+    }
+}
+
+/// The stored stream among `stored` that `stream` is a re-upload of, if any. Sittings
+/// of a multi-day event share their title, so the one closest in duration is taken, then
+/// the one streamed last before it.
+pub(crate) fn find_original<'a>(
+    stream: &Stream,
+    stored: &'a [StoredStream],
+) -> Option<&'a StoredStream> {
+    let title = normalize_title(&stream.title);
+    let duration = parse_duration_to_seconds(&stream.duration);
+    let published_at = stream.published_at();
+    stored
+        .iter()
+        .filter(|original| {
+            original.video_id != stream.video_id
+                && normalize_title(&original.title) == title
+                && published_at.is_none_or(|at| {
+                    original.stream_timestamp <= at && at - original.stream_timestamp <= LOOKBACK
+                })
+                && match (duration, parse_duration_to_seconds(&original.duration)) {
+                    (Some(a), Some(b)) => a.abs_diff(b) <= DURATION_TOLERANCE_SECS,
+                    _ => false,
+                }
+        })
+        .min_by_key(|original| {
+            let drift = duration
+                .zip(parse_duration_to_seconds(&original.duration))
+                .map(|(a, b)| a.abs_diff(b));
+            (drift, Reverse(original.stream_timestamp))
+        })
+}
+
+/// Lowercase words of the title, leaving out punctuation and marks re-uploads are
+/// commonly given, e.g. "Senate Plenary, Tuesday 4th March 2025 (Re-upload)" becomes
+/// "senate plenary tuesday 4th march 2025"
+fn normalize_title(title: &str) -> String {
+    const REUPLOAD_MARKS: &[&str] = &["reupload", "reuploaded", "re", "upload", "uploaded"];
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !REUPLOAD_MARKS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+
+    fn stored(video_id: &str, title: &str, duration: &str, at: DateTime<Utc>) -> StoredStream {
+        StoredStream {
+            video_id: video_id.into(),
+            title: title.into(),
+            duration: duration.into(),
+            stream_timestamp: at,
+        }
+    }
+
+    #[test]
+    fn test_reuploads_match_their_original_by_title_date_and_duration() {
+        let uploaded = "2025-03-04T11:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let reupload = Stream {
+            video_id: "def456".into(),
+            title: "SENATE PLENARY | Tuesday 4th March 2025 (Re-upload)".into(),
+            duration: "3:02:10".into(),
+            published_at_exact: Some(uploaded + TimeDelta::days(1)),
+            ..Default::default()
+        };
+        let title = "Senate Plenary, Tuesday 4th March 2025";
+
+        let original = [stored("abc123", title, "3:00:00", uploaded)];
+        assert_eq!(
+            find_original(&reupload, &original).map(|s| s.video_id.as_str()),
+            Some("abc123")
+        );
+
+        let other_sitting = [stored(
+            "abc123",
+            "Senate Plenary, Wednesday 5th March 2025",
+            "3:00:00",
+            uploaded,
+        )];
+        let shorter = [stored("abc123", title, "1:30:00", uploaded)];
+        let long_ago = [stored("abc123", title, "3:00:00", uploaded - LOOKBACK)];
+        assert!(find_original(&reupload, &other_sitting).is_none());
+        assert!(find_original(&reupload, &shorter).is_none());
+        assert!(find_original(&reupload, &long_ago).is_none());
+
+        let sittings = [
+            stored("day1", title, "3:00:00", uploaded - TimeDelta::days(1)),
+            stored("day2", title, "3:00:00", uploaded),
+            stored("day3", title, "3:02:00", uploaded - TimeDelta::days(2)),
+        ];
+        assert_eq!(
+            find_original(&reupload, &sittings[..2]).map(|s| s.video_id.as_str()),
+            Some("day2")
+        );
+        assert_eq!(
+            find_original(&reupload, &sittings).map(|s| s.video_id.as_str()),
+            Some("day3")
+        );
+    }
+}
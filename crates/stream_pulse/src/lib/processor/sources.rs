@@ -0,0 +1,88 @@
+//! # Sources
+//!
+//! A run can list streams from more than one channel scraper, e.g. the National
+//! Assembly's and the Senate's channels, each with its own share of the run. The streams
+//! of each source are sorted as usual and cut to its quota, then taken from each source
+//! in turn, so that a backlog on one channel doesn't starve the others and a run cut
+//! short has made progress on all of them.
+
+use std::collections::HashMap;
+
+use stream_datastore::{Stream, StreamCategory};
+
+/// A channel scraper listing streams alongside the builder's own
+#[derive(Debug, Clone)]
+pub(crate) struct Source<P> {
+    pub(crate) scraper: P,
+    /// Given to the streams listed without one
+    pub(crate) category: Option<StreamCategory>,
+    /// Streams processed from this source per run
+    pub(crate) max_streams: usize,
+}
+
+/// Cuts the sorted `streams` to each source's quota, and interleaves the sources' streams
+/// in turn, keeping their order within each source. `source_of` maps a video ID to the
+/// index in `quotas` of the source that listed it, and streams no source listed, e.g.
+/// failed streams looked up for a retry, go to the first. Also returns whether each
+/// source has streams left beyond its quota.
+pub(crate) fn take_quotas(
+    streams: Vec<Stream>,
+    source_of: &HashMap<String, usize>,
+    quotas: &[usize],
+) -> (Vec<Stream>, Vec<bool>) {
+    let mut by_source = vec![Vec::new(); quotas.len()];
+    for stream in streams {
+        let source = source_of.get(&stream.video_id).copied().unwrap_or(0);
+        by_source[source].push(stream);
+    }
+    let backlogged = by_source
+        .iter()
+        .zip(quotas)
+        .map(|(streams, &quota)| streams.len() >= quota)
+        .collect();
+
+    let mut by_source = by_source
+        .into_iter()
+        .zip(quotas)
+        .map(|(streams, &quota)| streams.into_iter().take(quota))
+        .collect::<Vec<_>>();
+    let mut result = Vec::new();
+    loop {
+        let before = result.len();
+        result.extend(by_source.iter_mut().filter_map(Iterator::next));
+        if result.len() == before {
+            return (result, backlogged);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(video_id: &str) -> Stream {
+        Stream {
+            video_id: video_id.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sources_are_interleaved_within_their_quotas() {
+        let streams = ["na1", "na2", "na3", "s1", "retry"].map(stream).to_vec();
+        let source_of = HashMap::from([
+            ("na1".to_string(), 0),
+            ("na2".to_string(), 0),
+            ("na3".to_string(), 0),
+            ("s1".to_string(), 1),
+        ]);
+
+        let (streams, backlogged) = take_quotas(streams, &source_of, &[3, 2]);
+        let video_ids = streams
+            .iter()
+            .map(|s| s.video_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(video_ids, ["na1", "s1", "na2", "na3"]);
+        assert_eq!(backlogged, [true, false]);
+    }
+}
@@ -0,0 +1,56 @@
+//! # Timeouts
+//!
+//! Limits on how long each stream may spend being transcribed and summarized, so that a
+//! stalled provider fails the stream instead of holding up the run until it is killed.
+//! Downloading and cleaning audio run in yt-dlp and ffmpeg processes, which are limited
+//! and killed by [`ytdlp_bindings::YtDlp`] itself, and each chunk's transcription is
+//! limited by [`TranscriptionOptions::chunk_timeout`](crate::TranscriptionOptions).
+
+use std::{future::Future, time::Duration};
+
+/// Unset stages are not limited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageTimeouts {
+    /// Longest a stream's audio may take to transcribe, all of its chunks included
+    pub transcribe: Option<Duration>,
+    /// Longest a stream's transcript may take to summarize, all of its parts included
+    pub summarize: Option<Duration>,
+}
+
+/// A stage ran for longer than its timeout, and was cancelled
+#[derive(Debug, thiserror::Error)]
+#[error("{stage} timed out after {timeout:?}")]
+pub struct StageTimeout {
+    pub stage: &'static str,
+    pub timeout: Duration,
+}
+
+/// Awaits `stage`, dropping it and failing if it is still running after `timeout`
+pub(crate) async fn within<F: Future>(
+    timeout: Option<Duration>,
+    stage: &'static str,
+    future: F,
+) -> Result<F::Output, StageTimeout> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| StageTimeout { stage, timeout }),
+        None => Ok(future.await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stages_are_cancelled_after_their_timeout() {
+        let stalled = std::future::pending::<()>();
+        let err = within(Some(Duration::from_millis(10)), "Summarizing", stalled)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Summarizing timed out after 10ms");
+
+        assert_eq!(within(None, "Summarizing", async { 1 }).await.unwrap(), 1);
+    }
+}
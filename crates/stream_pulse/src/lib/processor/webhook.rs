@@ -0,0 +1,160 @@
+//! # Webhooks
+//!
+//! A [`ProcessorHook`] that POSTs each stored stream to a URL, so that the site and
+//! bots are told about new summaries instead of polling the database for them.
+//!
+//! Payloads are signed with HMAC-SHA256 of the raw body under a shared secret, sent
+//! hex encoded in the [`SIGNATURE_HEADER`] as `sha256=<signature>`. Receivers should
+//! compute the signature of the body they received and compare the two in constant time.
+
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use stream_datastore::Stream;
+
+use crate::processor::hooks::ProcessorHook;
+
+pub const SIGNATURE_HEADER: &str = "X-Bunge-Signature-256";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Payload of a stored stream's notification
+#[derive(Debug, Serialize, PartialEq)]
+pub struct StreamSummarized<'a> {
+    /// Always "stream.summarized"
+    pub event: &'a str,
+    pub video_id: &'a str,
+    pub title: &'a str,
+    pub url: String,
+    pub category: &'a str,
+    /// RFC 3339, when the stream's date could be worked out
+    pub published_at: Option<String>,
+    pub thumbnail_url: Option<&'a str>,
+    pub summary_tldr: Option<&'a str>,
+    pub summary_md: Option<&'a str>,
+}
+
+impl<'a> From<&'a Stream> for StreamSummarized<'a> {
+    fn from(stream: &'a Stream) -> Self {
+        Self {
+            event: "stream.summarized",
+            video_id: &stream.video_id,
+            title: &stream.title,
+            url: stream.url(),
+            category: stream.category().as_str(),
+            published_at: stream.published_at().map(|at| at.to_rfc3339()),
+            thumbnail_url: stream.thumbnail_url.as_deref(),
+            summary_tldr: stream.summary_tldr.as_deref(),
+            summary_md: stream.summary_md.as_deref(),
+        }
+    }
+}
+
+/// Notifies `url` of each stream once it is stored. A failed delivery is logged and
+/// not retried.
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+    secret: String,
+}
+
+impl std::fmt::Debug for WebhookNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookNotifier")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            url: url.into(),
+            secret: secret.into(),
+        }
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Posts `stream`'s notification, failing unless the receiver responds with success
+    pub async fn notify(&self, stream: &Stream) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(&StreamSummarized::from(stream))?;
+        let response = self
+            .client
+            .post(&self.url)
+            .timeout(TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature(&self.secret, &body))
+            .body(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Webhook responded with {} for {}",
+                response.status(),
+                stream.video_id
+            );
+        }
+        Ok(())
+    }
+}
+
+impl ProcessorHook for WebhookNotifier {
+    fn on_stream_stored<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.notify(stream))
+    }
+}
+
+/// `sha256=<hex HMAC-SHA256 of body>`
+pub(crate) fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    let signature = mac.finalize().into_bytes();
+    let hex = signature
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+    format!("sha256={hex}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_is_hmac_sha256_of_the_body() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_payload_describes_the_stored_stream() {
+        let stream = Stream {
+            video_id: "abc123".into(),
+            title: "Senate Plenary, Tuesday 4th March 2025".into(),
+            summary_tldr: Some("The Senate passed the Finance Bill.".into()),
+            ..Default::default()
+        };
+        let payload = serde_json::to_value(StreamSummarized::from(&stream)).unwrap();
+        assert_eq!(payload["event"], "stream.summarized");
+        assert_eq!(payload["url"], "https://www.youtube.com/watch?v=abc123");
+        assert_eq!(payload["category"], "senate");
+        assert_eq!(
+            payload["summary_tldr"],
+            "The Senate passed the Finance Bill."
+        );
+        assert!(payload["summary_md"].is_null());
+    }
+}
@@ -0,0 +1,341 @@
+//! # Server
+//!
+//! The HTTP endpoints, served on one address of their own: the
+//! [health endpoints](crate::health) probes are sent to, along with the
+//! [admin endpoints](crate::admin), the [streams API](crate::api) and
+//! [webhook subscriptions](crate::webhooks) among them, and the
+//! [newsletter endpoints](crate::newsletter) when they are enabled.
+//!
+//! Each request is answered and its connection closed, only the
+//! [streams API](crate::api)'s reads being cacheable. Requests taking longer than the
+//! request timeout to arrive are answered 408, and no more than [`MAX_CONNECTIONS`] are
+//! read at once, later connections waiting to be accepted.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+};
+
+use crate::{
+    admin::AdminApi,
+    api,
+    health::{self, ReadinessCheck},
+    newsletter::NewsletterApi,
+};
+
+/// Largest request read, headers and body included
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+
+/// Connections answered at once
+pub const MAX_CONNECTIONS: usize = 256;
+
+/// A request as far as the endpoints read it
+pub(crate) struct Request {
+    pub(crate) method: String,
+    /// Path without the query
+    pub(crate) path: String,
+    /// Query without the leading `?`, still percent-encoded
+    pub(crate) query: String,
+    /// Header names lowercased
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+    /// Address the request was sent from, the proxy's when served behind one
+    pub(crate) peer: Option<SocketAddr>,
+}
+
+impl Request {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The first value of the query parameter `name`, decoded, `None` if it isn't given
+    pub(crate) fn query_param(&self, name: &str) -> Option<String> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+            .find(|(key, _)| decode_component(key) == name)
+            .map(|(_, value)| decode_component(value))
+    }
+}
+
+/// Decodes a percent-encoded query component, `+` standing for a space. Invalid escapes
+/// are kept as they are.
+fn decode_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Status line, content type and body of a response
+pub(crate) type Response = (&'static str, &'static str, String);
+
+/// The endpoints served, the health endpoints always and the others once given
+#[derive(Clone)]
+pub struct Server {
+    version: &'static str,
+    ready: ReadinessCheck,
+    admin: Option<AdminApi>,
+    newsletter: Option<NewsletterApi>,
+    request_timeout: Duration,
+}
+
+impl Server {
+    /// Serves the health endpoints, answering `/version` with `version` and `/readyz`
+    /// with `ready`
+    pub fn new(version: &'static str, ready: ReadinessCheck) -> Self {
+        Self {
+            version,
+            ready,
+            admin: None,
+            newsletter: None,
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Also serve the admin endpoints, and the streams API if they were given a source
+    pub fn with_admin(mut self, admin: AdminApi) -> Self {
+        self.admin = Some(admin);
+        self
+    }
+
+    /// Also serve the newsletter endpoints
+    pub fn with_newsletter(mut self, newsletter: NewsletterApi) -> Self {
+        self.newsletter = Some(newsletter);
+        self
+    }
+
+    /// Answer requests that haven't arrived whole after `timeout` with 408, instead of
+    /// after 10 seconds
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Answers requests on `listener` until accepting a connection fails
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        let server = Arc::new(self);
+        let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        loop {
+            let permit = connections
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("the semaphore is never closed");
+            let (stream, peer) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.respond(stream, peer).await {
+                    tracing::debug!(error = %e, "Failed to answer request");
+                }
+                drop(permit);
+            });
+        }
+    }
+
+    async fn respond(&self, mut stream: TcpStream, peer: SocketAddr) -> std::io::Result<()> {
+        let request =
+            match tokio::time::timeout(self.request_timeout, read_request(&mut stream)).await {
+                Ok(request) => request?.map(|request| Request {
+                    peer: Some(peer),
+                    ..request
+                }),
+                Err(_) => {
+                    return write_response(
+                        &mut stream,
+                        (
+                            "408 Request Timeout",
+                            "text/plain",
+                            "request timeout".into(),
+                        ),
+                        "no-store",
+                    )
+                    .await
+                }
+            };
+        let response = match &request {
+            Some(request)
+                if request.path.starts_with("/admin/") || request.path.starts_with("/api/") =>
+            {
+                match &self.admin {
+                    Some(admin) => admin.route(request).await,
+                    None => ("404 Not Found", "text/plain", "not found".into()),
+                }
+            }
+            Some(request) if request.path.starts_with("/newsletter/") => match &self.newsletter {
+                Some(newsletter) => newsletter.route(request).await,
+                None => ("404 Not Found", "text/plain", "not found".into()),
+            },
+            Some(request) => {
+                health::route(&request.method, &request.path, self.version, &self.ready).await
+            }
+            None => ("400 Bad Request", "text/plain", "bad request".into()),
+        };
+        let cache_control = match &request {
+            Some(request) if request.path.starts_with("/api/") => {
+                api::cache_control(request, response.0)
+            }
+            _ => "no-store".into(),
+        };
+        write_response(&mut stream, response, &cache_control).await
+    }
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    (status, content_type, body): Response,
+    cache_control: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: {cache_control}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Reads a request's head and, once it is all in, its body. `None` if the request is
+/// malformed or larger than [`MAX_REQUEST_BYTES`].
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<Request>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 1024];
+    let head_end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 || buffer.len() + read > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect::<Vec<_>>();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers,
+        body: buffer.split_off(head_end + 4),
+        peer: None,
+    };
+
+    let length = match request.header("content-length").map(str::parse::<usize>) {
+        Some(Ok(length)) => length,
+        Some(Err(_)) => return Ok(None),
+        None => 0,
+    };
+    if head_end + 4 + length > MAX_REQUEST_BYTES {
+        return Ok(None);
+    }
+    while request.body.len() < length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        request.body.extend_from_slice(&chunk[..read]);
+    }
+    request.body.truncate(length);
+    Ok(Some(request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn spawn(server: Server) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(server.serve(listener));
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_probes_are_answered() {
+        let ready: ReadinessCheck =
+            Arc::new(|| Box::pin(async { Err("Database unreachable: refused".to_string()) }));
+        let addr = spawn(Server::new("1.2.3", ready)).await;
+
+        let get = |path: &str| reqwest::get(format!("http://{addr}{path}"));
+        let healthz = get("/healthz").await.unwrap();
+        assert_eq!(healthz.status(), 200);
+
+        let readyz = get("/readyz").await.unwrap();
+        assert_eq!(readyz.status(), 503);
+        assert_eq!(readyz.text().await.unwrap(), "not ready");
+
+        let version = get("/version").await.unwrap();
+        let version = version.json::<serde_json::Value>().await.unwrap();
+        assert_eq!(version["version"], "1.2.3");
+
+        assert_eq!(get("/missing").await.unwrap().status(), 404);
+        assert_eq!(get("/admin/run").await.unwrap().status(), 404);
+    }
+
+    #[test]
+    fn test_query_params_are_decoded() {
+        let request = Request {
+            method: "GET".into(),
+            path: "/api/search".into(),
+            query: "q=Housing+Levy%2C%202025&page=2&empty".into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            peer: None,
+        };
+        assert_eq!(
+            request.query_param("q").as_deref(),
+            Some("Housing Levy, 2025")
+        );
+        assert_eq!(request.query_param("page").as_deref(), Some("2"));
+        assert_eq!(request.query_param("empty").as_deref(), Some(""));
+        assert_eq!(request.query_param("category"), None);
+    }
+
+    #[tokio::test]
+    async fn test_requests_that_never_arrive_time_out() {
+        let ready: ReadinessCheck = Arc::new(|| Box::pin(async { Ok(()) }));
+        let server = Server::new("1.2.3", ready).with_request_timeout(Duration::from_millis(100));
+        let addr = spawn(server).await;
+
+        let mut slow = TcpStream::connect(addr).await.unwrap();
+        slow.write_all(b"GET /healthz HTTP/1.1\r\n").await.unwrap();
+        let mut response = String::new();
+        slow.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout"));
+    }
+}
@@ -0,0 +1,350 @@
+//! # Store
+//!
+//! The datastore the endpoints and notifiers read and write through, connected on
+//! first use so that a database down at startup doesn't stop the
+//! [health endpoints](crate::health) from being served. Until it can be reached each
+//! request fails with why, and the connection is tried again on the next one.
+
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use stream_datastore::{
+    ApiKeyStore, DataStoreError, PgDataStore, SearchMatch, StreamDetail, StreamPage, StreamQuery,
+    StreamReader, SubscriberStore, TranscriptStore, WebhookEvent, WebhookSubscription,
+    WebhookSubscriptionStore, WebhookTarget, WhatsAppStore,
+};
+#[cfg(feature = "graphql")]
+use stream_datastore::{EntityKind, StreamEntities};
+use tokio::sync::OnceCell;
+
+#[cfg(feature = "graphql")]
+use crate::graphql::EntitySource;
+use crate::{
+    admin::ApiKeyLookup,
+    api::StreamSource,
+    newsletter::{Subscription, Subscriptions},
+    webhooks::{WebhookStore, WebhookUpdate},
+    whatsapp::{BroadcastLists, ListChange},
+    TranscribeResponse,
+};
+
+/// The store at a database URL, connected on first use
+#[derive(Clone)]
+pub struct LazyStore {
+    db_url: String,
+    store: Arc<OnceCell<PgDataStore>>,
+}
+
+impl LazyStore {
+    /// The store at `db_url`, not connected to until it is first used
+    pub fn new(db_url: &str) -> Self {
+        Self {
+            db_url: db_url.to_string(),
+            store: Default::default(),
+        }
+    }
+
+    /// The store, connecting to it unless an earlier call has
+    pub async fn get(&self) -> Result<&PgDataStore, DataStoreError> {
+        self.store
+            .get_or_try_init(|| PgDataStore::init(&self.db_url))
+            .await
+    }
+
+    /// Looks up API keys for the admin endpoints
+    pub fn api_key_lookup(&self) -> ApiKeyLookup {
+        let store = self.clone();
+        Arc::new(move |key_hash| {
+            let store = store.clone();
+            Box::pin(async move {
+                let store = store.get().await.map_err(|e| e.to_string())?;
+                store
+                    .get_api_key(&key_hash)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        })
+    }
+
+    /// Applies changes to the WhatsApp broadcast lists and lists their numbers
+    pub fn broadcast_lists(&self) -> BroadcastLists {
+        let store = self.clone();
+        Arc::new(move |list, change| {
+            let store = store.clone();
+            Box::pin(async move {
+                let store = store.get().await.map_err(|e| e.to_string())?;
+                match change {
+                    Some(ListChange::OptIn { phone_number }) => {
+                        store.set_whatsapp_opt_in(&list, &phone_number, true).await
+                    }
+                    Some(ListChange::OptOut { phone_number }) => {
+                        store.set_whatsapp_opt_in(&list, &phone_number, false).await
+                    }
+                    Some(ListChange::Remove { phone_number }) => store
+                        .remove_whatsapp_subscriber(&list, &phone_number)
+                        .await
+                        .map(|_| ()),
+                    None => Ok(()),
+                }
+                .map_err(|e| e.to_string())?;
+                store
+                    .list_whatsapp_subscribers(&list)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        })
+    }
+
+    /// Stores newsletter subscriptions requested through the newsletter endpoints
+    pub fn subscriptions(&self) -> Subscriptions {
+        let store = self.clone();
+        Arc::new(move |subscription| {
+            let store = store.clone();
+            Box::pin(async move {
+                let store = store.get().await.map_err(|e| e.to_string())?;
+                match subscription {
+                    Subscription::Subscribe { email, token } => {
+                        store.add_subscriber(&email, &token).await
+                    }
+                    Subscription::Confirm { token } => store.confirm_subscriber(&token).await,
+                    Subscription::Unsubscribe { token } => store.unsubscribe(&token).await,
+                }
+                .map_err(|e| e.to_string())
+            })
+        })
+    }
+}
+
+/// Webhook subscriptions for the webhook endpoints and deliveries
+impl WebhookStore for LazyStore {
+    fn create<'a>(
+        &'a self,
+        owner: &'a str,
+        url: &'a str,
+        secret: &'a str,
+        events: &'a [WebhookEvent],
+    ) -> BoxFuture<'a, Result<WebhookSubscription, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .insert_webhook_subscription(owner, url, secret, events)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn list<'a>(
+        &'a self,
+        owner: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<WebhookSubscription>, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .list_webhook_subscriptions(owner)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn update<'a>(
+        &'a self,
+        owner: &'a str,
+        id: i64,
+        update: &'a WebhookUpdate,
+    ) -> BoxFuture<'a, Result<Option<WebhookSubscription>, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .update_webhook_subscription(
+                    owner,
+                    id,
+                    update.url.as_deref(),
+                    update.events.as_deref(),
+                    update.enabled,
+                )
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn delete<'a>(&'a self, owner: &'a str, id: i64) -> BoxFuture<'a, Result<bool, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .delete_webhook_subscription(owner, id)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn targets(&self, event: WebhookEvent) -> BoxFuture<'_, Result<Vec<WebhookTarget>, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .list_webhook_targets(event)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn record_delivery(
+        &self,
+        id: i64,
+        delivered: bool,
+        max_failures: u32,
+    ) -> BoxFuture<'_, Result<bool, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .record_webhook_delivery(id, delivered, max_failures)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Published streams for the streams API
+impl StreamSource for LazyStore {
+    fn list<'a>(&'a self, query: &'a StreamQuery) -> BoxFuture<'a, Result<StreamPage, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store.list_streams(query).await.map_err(|e| e.to_string())
+        })
+    }
+
+    fn search<'a>(
+        &'a self,
+        terms: &'a str,
+        query: &'a StreamQuery,
+    ) -> BoxFuture<'a, Result<Vec<SearchMatch>, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .search_streams(terms, query)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn stream<'a>(
+        &'a self,
+        video_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<StreamDetail>, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .get_published_stream(video_id)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn transcript<'a>(
+        &'a self,
+        video_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<TranscribeResponse>, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            let transcripts = store
+                .get_stream_transcripts(&[video_id])
+                .await
+                .map_err(|e| e.to_string())?;
+            transcripts
+                .get(video_id)
+                .map(|transcript| serde_json::from_str(transcript).map_err(|e| e.to_string()))
+                .transpose()
+        })
+    }
+}
+
+/// Entities of published streams for the GraphQL API
+#[cfg(feature = "graphql")]
+impl EntitySource for LazyStore {
+    fn entities<'a>(&'a self, video_id: &'a str) -> BoxFuture<'a, Result<StreamEntities, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .get_stream_entities(video_id)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn mentioning<'a>(
+        &'a self,
+        kind: EntityKind,
+        name: &'a str,
+        query: &'a StreamQuery,
+    ) -> BoxFuture<'a, Result<StreamPage, String>> {
+        Box::pin(async move {
+            let store = self.get().await.map_err(|e| e.to_string())?;
+            store
+                .list_streams_mentioning(kind, name, query)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{admin::AdminApi, health::ReadinessCheck, server::Server};
+
+    /// A store whose URL can't be parsed, so every connection fails without a database
+    fn unreachable() -> LazyStore {
+        LazyStore::new("not a database url")
+    }
+
+    #[tokio::test]
+    async fn test_requests_fail_while_unreachable() {
+        let store = unreachable();
+        assert!(store.get().await.is_err());
+        // the failed connection isn't kept, so the next request connects again
+        assert!(store.get().await.is_err());
+
+        assert!((store.api_key_lookup())("hash".into()).await.is_err());
+        assert!((store.broadcast_lists())("alerts".into(), None)
+            .await
+            .is_err());
+        let unsubscribe = Subscription::Unsubscribe {
+            token: "token".into(),
+        };
+        assert!((store.subscriptions())(unsubscribe).await.is_err());
+        assert!(store.targets(WebhookEvent::NewSummary).await.is_err());
+        assert!(StreamSource::list(&store, &StreamQuery::default())
+            .await
+            .is_err());
+        assert!(store.transcript("dQw4w9WgXcQ").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_health_is_served_while_unreachable() {
+        let store = unreachable();
+        let ready: ReadinessCheck = Arc::new(|| Box::pin(async { Ok(()) }));
+        let trigger = Arc::new(|_| Ok(()));
+        let admin = AdminApi::new(trigger)
+            .with_api_keys(store.api_key_lookup())
+            .with_stream_source(Arc::new(store.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::new("1.2.3", ready)
+                .with_admin(admin)
+                .serve(listener),
+        );
+
+        let healthz = reqwest::get(format!("http://{addr}/healthz"))
+            .await
+            .unwrap();
+        assert_eq!(healthz.status(), 200);
+
+        let streams = reqwest::Client::new()
+            .get(format!("http://{addr}/api/streams"))
+            .bearer_auth("key")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(streams.status(), 503);
+    }
+}
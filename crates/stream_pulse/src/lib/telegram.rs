@@ -0,0 +1,375 @@
+//! # Telegram
+//!
+//! Delivers summaries on Telegram, where much of the site's audience already follows
+//! the news. [`TelegramNotifier`] is a [`ProcessorHook`] posting each stored stream's
+//! TL;DR and a link to its page to a channel, and [`TelegramBot`] answers commands sent
+//! to the bot from the datastore:
+//!
+//! - `/latest` lists the most recently summarized sittings
+//! - `/search <term>` lists the sittings whose title or summary matches `term`
+//!
+//! Both go through the [Bot API](https://core.telegram.org/bots/api) with the bot's
+//! token. The bot has to be an admin of the channel to post in it.
+
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::json;
+use stream_datastore::{ListedStream, Stream, StreamQuery, StreamReader};
+
+use crate::processor::hooks::ProcessorHook;
+
+const API_URL: &str = "https://api.telegram.org";
+
+/// How long `getUpdates` waits for a message before answering with none
+const POLL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Wait after failing to poll for updates, e.g. while Telegram is unreachable
+const POLL_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Characters of a TL;DR posted, well within Telegram's 4096 a message
+const MAX_TLDR_CHARS: usize = 3000;
+
+/// Sittings listed in answer to a command
+const LISTED_STREAMS: u32 = 5;
+
+const HELP: &str = "Summaries of Kenya's Parliament sittings.\n\n\
+    /latest - the most recently summarized sittings\n\
+    /search <term> - sittings mentioning a bill, MP or topic, e.g. /search Finance Bill";
+
+/// Requests to the Bot API as one bot
+#[derive(Clone)]
+struct BotApi {
+    client: reqwest::Client,
+    token: String,
+}
+
+impl std::fmt::Debug for BotApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BotApi").finish_non_exhaustive()
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<IncomingMessage>,
+}
+
+#[derive(Deserialize)]
+struct IncomingMessage {
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+impl BotApi {
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        body: serde_json::Value,
+        timeout: Duration,
+    ) -> anyhow::Result<T> {
+        let response = self
+            .client
+            .post(format!("{API_URL}/bot{}/{method}", self.token))
+            .timeout(timeout)
+            .json(&body)
+            .send()
+            .await?
+            .json::<ApiResponse<T>>()
+            .await?;
+        match response {
+            ApiResponse {
+                ok: true,
+                result: Some(result),
+                ..
+            } => Ok(result),
+            ApiResponse { description, .. } => anyhow::bail!(
+                "Telegram {method} failed: {}",
+                description.unwrap_or_default()
+            ),
+        }
+    }
+
+    /// Sends `text`, formatted as HTML, to the chat `chat_id`: a channel's `@username`
+    /// or a chat's numeric ID
+    async fn send_message(&self, chat_id: serde_json::Value, text: &str) -> anyhow::Result<()> {
+        let body = json!({ "chat_id": chat_id, "text": text, "parse_mode": "HTML" });
+        self.call::<serde_json::Value>("sendMessage", body, Duration::from_secs(10))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Posts each stored stream that was summarized to a channel. A failed post is logged
+/// and not retried.
+#[derive(Debug, Clone)]
+pub struct TelegramNotifier {
+    api: BotApi,
+    /// The channel's `@username`, or its numeric ID, e.g. "-1001234567890"
+    channel: String,
+    site_url: String,
+}
+
+impl TelegramNotifier {
+    pub fn new(token: impl Into<String>, channel: impl Into<String>) -> Self {
+        Self {
+            api: BotApi {
+                client: reqwest::Client::default(),
+                token: token.into(),
+            },
+            channel: channel.into(),
+            site_url: "https://bungebits.ke".into(),
+        }
+    }
+
+    /// Links streams to their pages on `site_url` rather than on bungebits.ke
+    pub fn with_site_url(mut self, site_url: impl Into<String>) -> Self {
+        self.site_url = site_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.api.client = client;
+        self
+    }
+
+    /// Posts `stream`'s TL;DR, or does nothing if it has no summary
+    pub async fn notify(&self, stream: &Stream) -> anyhow::Result<()> {
+        let Some(message) = stream_message(stream, &self.site_url) else {
+            return Ok(());
+        };
+        self.api
+            .send_message(self.channel.as_str().into(), &message)
+            .await
+    }
+}
+
+impl ProcessorHook for TelegramNotifier {
+    fn on_stream_stored<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.notify(stream))
+    }
+}
+
+/// Answers commands sent to the bot, polling Telegram for them
+#[derive(Debug, Clone)]
+pub struct TelegramBot {
+    api: BotApi,
+    site_url: String,
+}
+
+impl TelegramBot {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            api: BotApi {
+                client: reqwest::Client::default(),
+                token: token.into(),
+            },
+            site_url: "https://bungebits.ke".into(),
+        }
+    }
+
+    /// Links streams to their pages on `site_url` rather than on bungebits.ke
+    pub fn with_site_url(mut self, site_url: impl Into<String>) -> Self {
+        self.site_url = site_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Answers commands from `store` until the task is dropped. Failing to poll or to
+    /// answer is logged, and polling carries on.
+    pub async fn run(&self, store: &impl StreamReader) {
+        let mut offset = 0;
+        loop {
+            let body = json!({
+                "offset": offset,
+                "timeout": POLL_TIMEOUT.as_secs(),
+                "allowed_updates": ["message"],
+            });
+            let updates = match self
+                .api
+                .call::<Vec<Update>>("getUpdates", body, POLL_TIMEOUT + Duration::from_secs(10))
+                .await
+            {
+                Ok(updates) => updates,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to poll Telegram for commands");
+                    tokio::time::sleep(POLL_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            for update in updates {
+                offset = offset.max(update.update_id + 1);
+                let Some((chat, text)) = update
+                    .message
+                    .and_then(|message| Some((message.chat.id, message.text?)))
+                else {
+                    continue;
+                };
+                let Some(command) = Command::parse(&text) else {
+                    continue;
+                };
+                let answer = self.answer(store, command).await;
+                if let Err(e) = self.api.send_message(chat.into(), &answer).await {
+                    tracing::warn!(error = %e, chat, "Failed to answer Telegram command");
+                }
+            }
+        }
+    }
+
+    async fn answer(&self, store: &impl StreamReader, command: Command<'_>) -> String {
+        let query = StreamQuery {
+            per_page: LISTED_STREAMS,
+            ..Default::default()
+        };
+        let listed = match command {
+            Command::Help => return HELP.into(),
+            Command::Latest => store.list_streams(&query).await.map(|page| page.streams),
+            Command::Search("") => {
+                return "Send /search followed by what to look for, e.g. /search Finance Bill"
+                    .into()
+            }
+            Command::Search(terms) => store
+                .search_streams(terms, &query)
+                .await
+                .map(|matches| matches.into_iter().map(|m| m.stream).collect()),
+        };
+
+        match listed {
+            Ok(streams) if streams.is_empty() => match command {
+                Command::Search(terms) => {
+                    format!("No sittings mention \"{}\"", escape_html(terms))
+                }
+                _ => "No sittings have been summarized yet".into(),
+            },
+            Ok(streams) => list_message(&streams, &self.site_url),
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to list streams for Telegram");
+                "Summaries are unavailable right now, try again later".into()
+            }
+        }
+    }
+}
+
+/// A command sent to the bot, without the bot's `@username` if it was addressed by it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command<'a> {
+    Help,
+    Latest,
+    /// Search terms, trimmed
+    Search(&'a str),
+}
+
+impl<'a> Command<'a> {
+    /// `None` if `text` isn't a command
+    fn parse(text: &'a str) -> Option<Self> {
+        let text = text.trim().strip_prefix('/')?;
+        let (command, terms) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let command = command.split('@').next().unwrap_or_default();
+        match command.to_ascii_lowercase().as_str() {
+            "latest" => Some(Command::Latest),
+            "search" => Some(Command::Search(terms.trim())),
+            _ => Some(Command::Help),
+        }
+    }
+}
+
+/// The post announcing `stream`, `None` if it has no summary
+fn stream_message(stream: &Stream, site_url: &str) -> Option<String> {
+    let summary = stream
+        .summary_tldr
+        .as_deref()
+        .or(stream.summary_md.as_deref())?;
+    let summary = match summary.char_indices().nth(MAX_TLDR_CHARS) {
+        Some((end, _)) => format!("{}…", &summary[..end]),
+        None => summary.to_string(),
+    };
+    let date = stream
+        .published_at()
+        .map(|at| format!("{}\n", at.format("%-d %B %Y")))
+        .unwrap_or_default();
+
+    Some(format!(
+        "<b>{}</b>\n{date}\n{}\n\n<a href=\"{site_url}/summaries/{}\">Read the summary</a>",
+        escape_html(&stream.title),
+        escape_html(summary.trim()),
+        stream.video_id
+    ))
+}
+
+/// Lists `streams`, each linked to its page
+fn list_message(streams: &[ListedStream], site_url: &str) -> String {
+    streams
+        .iter()
+        .map(|stream| {
+            format!(
+                "• <a href=\"{site_url}/summaries/{}\">{}</a>, {}",
+                stream.video_id,
+                escape_html(&stream.title),
+                stream.stream_timestamp.format("%-d %B %Y")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escapes the characters Telegram's HTML formatting reserves
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_are_parsed() {
+        assert_eq!(Command::parse("/latest"), Some(Command::Latest));
+        assert_eq!(
+            Command::parse("/latest@bungebits_bot"),
+            Some(Command::Latest)
+        );
+        assert_eq!(
+            Command::parse("/search  Finance Bill "),
+            Some(Command::Search("Finance Bill"))
+        );
+        assert_eq!(Command::parse("/start"), Some(Command::Help));
+        assert_eq!(Command::parse("hello"), None);
+    }
+
+    #[test]
+    fn test_streams_are_posted_with_their_tldr_and_link() {
+        let mut stream = Stream {
+            video_id: "dQw4w9WgXcQ".into(),
+            title: "Senate Plenary | Tuesday 22nd July 2025".into(),
+            published_at_exact: Some("2025-07-22T14:30:00Z".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(stream_message(&stream, "https://bungebits.ke"), None);
+
+        stream.summary_tldr = Some("The Senate passed the Finance Bill <2025>.".into());
+        assert_eq!(
+            stream_message(&stream, "https://bungebits.ke").unwrap(),
+            "<b>Senate Plenary | Tuesday 22nd July 2025</b>\n22 July 2025\n\n\
+             The Senate passed the Finance Bill &lt;2025&gt;.\n\n\
+             <a href=\"https://bungebits.ke/summaries/dQw4w9WgXcQ\">Read the summary</a>"
+        );
+    }
+}
@@ -27,6 +27,33 @@ pub struct VideoRenderer {
     pub length_text: Option<AccessibilityText>,
     #[serde(rename = "upcomingEventData")]
     pub upcoming_event_data: Option<UpcomingEventData>,
+    /// The first lines of the description
+    #[serde(rename = "descriptionSnippet")]
+    pub description_snippet: Option<TextRuns>,
+    #[serde(rename = "thumbnailOverlays", default)]
+    pub thumbnail_overlays: Vec<ThumbnailOverlay>,
+}
+
+impl VideoRenderer {
+    /// Whether the video was streamed live, going by its "Streamed 2 days ago" publish
+    /// text. Uploads only say "2 days ago"
+    pub fn is_live_recording(&self) -> bool {
+        self.published_time_text
+            .as_ref()
+            .and_then(|text| text.simple_text.as_deref())
+            .is_some_and(|text| text.starts_with("Streamed"))
+    }
+
+    /// Whether the video is being streamed now, going by the "LIVE" label
+    /// overlaid on its thumbnail in place of a duration
+    pub fn is_live_now(&self) -> bool {
+        self.thumbnail_overlays.iter().any(|overlay| {
+            overlay
+                .time_status
+                .as_ref()
+                .is_some_and(|status| status.style.as_deref() == Some("LIVE"))
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -68,6 +95,23 @@ pub struct Thumbnail {
     pub thumbnails: Vec<ThumbnailItem>,
 }
 
+impl Thumbnail {
+    /// URL of the largest thumbnail, without the query it is cropped and signed with.
+    /// The bare URL serves the full image, and doesn't expire.
+    pub fn best_url(&self) -> Option<String> {
+        self.thumbnails
+            .iter()
+            .max_by_key(|thumbnail| thumbnail.width * thumbnail.height)
+            .map(|thumbnail| {
+                let url = thumbnail
+                    .url
+                    .split_once('?')
+                    .map_or(thumbnail.url.as_str(), |(url, _)| url);
+                url.to_string()
+            })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ThumbnailItem {
     pub url: String,
@@ -75,6 +119,18 @@ pub struct ThumbnailItem {
     pub height: i32,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThumbnailOverlay {
+    #[serde(rename = "thumbnailOverlayTimeStatusRenderer")]
+    pub time_status: Option<ThumbnailOverlayTimeStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThumbnailOverlayTimeStatus {
+    /// `DEFAULT` for durations, `LIVE` for streams being broadcast
+    pub style: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpcomingEventData {
     #[serde(rename = "isReminderSet")]
@@ -0,0 +1,751 @@
+//! # Webhook subscriptions
+//!
+//! Lets third parties register their own callback URLs for events, rather than the one
+//! [`WebhookNotifier`](crate::WebhookNotifier) URL set by operators. Subscriptions are
+//! managed through the [admin endpoints](crate::admin) by any API key, each key only
+//! seeing and changing the subscriptions it registered:
+//!
+//! - `GET /api/webhooks` answers with the key's subscriptions, as JSON
+//! - `POST /api/webhooks` registers one, e.g.
+//!   `{"url": "https://example.com/hooks", "events": ["new_summary"]}`, answering 201
+//!   with it and the `secret` its deliveries are signed with. The secret isn't shown
+//!   again.
+//! - `GET /api/webhooks/{id}` answers with the subscription
+//! - `PATCH /api/webhooks/{id}` changes any of its `url`, `events` and `enabled`, e.g.
+//!   `{"enabled": true}` to re-enable it after it was disabled
+//! - `DELETE /api/webhooks/{id}` deletes it, answering 204
+//!
+//! [`SubscribedWebhooks`] is a [`ProcessorHook`] delivering the events of each stored
+//! stream to the subscriptions to them:
+//!
+//! - `new_summary`, with the payload [`WebhookNotifier`](crate::WebhookNotifier) posts
+//! - `new_vote_record`, when the stream's summary records votes, with the votes
+//!
+//! Deliveries are signed like [`WebhookNotifier`](crate::WebhookNotifier)'s, in the
+//! [`SIGNATURE_HEADER`], and name their event in the [`EVENT_HEADER`]. A subscription
+//! whose deliveries fail [`MAX_CONSECUTIVE_FAILURES`] times in a row is disabled.
+//!
+//! URLs have to be HTTPS, and not name a loopback, private, link-local or otherwise
+//! non-global address, so that subscriptions can't reach services beside this one.
+//! Deliveries are sent through a client of their own, which refuses to connect to hosts
+//! resolving to such addresses, follows no redirects and ignores proxy settings.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, StreamExt};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use stream_datastore::{ApiKey, Stream, Vote, WebhookEvent, WebhookSubscription, WebhookTarget};
+
+use crate::{
+    processor::{
+        hooks::ProcessorHook,
+        webhook::{signature, StreamSummarized, SIGNATURE_HEADER},
+    },
+    server::{Request, Response},
+};
+
+/// Header naming the event a delivery is of, e.g. "new_summary"
+pub const EVENT_HEADER: &str = "X-Bunge-Event";
+
+/// Deliveries to a subscription failing in a row before it is disabled
+pub const MAX_CONSECUTIVE_FAILURES: u32 = 10;
+
+/// Deliveries sent at once, so that a stream's deliveries hold up the run for about one
+/// delivery's [`TIMEOUT`] rather than one per subscription
+const MAX_CONCURRENT_DELIVERIES: usize = 16;
+
+/// Subscriptions a key may register
+const MAX_SUBSCRIPTIONS_PER_KEY: usize = 10;
+
+/// Prefix of the secrets deliveries are signed with, to tell them apart in secret
+/// scanners
+const SECRET_PREFIX: &str = "whsec_";
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where webhook subscriptions are kept, e.g. the datastore, failing with why it
+/// couldn't be reached. Subscriptions are changed on behalf of `owner`, the name of the
+/// API key the request was authorized by.
+pub trait WebhookStore: Send + Sync {
+    fn create<'a>(
+        &'a self,
+        owner: &'a str,
+        url: &'a str,
+        secret: &'a str,
+        events: &'a [WebhookEvent],
+    ) -> BoxFuture<'a, Result<WebhookSubscription, String>>;
+
+    fn list<'a>(
+        &'a self,
+        owner: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<WebhookSubscription>, String>>;
+
+    /// `None` if `owner` has no subscription `id`
+    fn update<'a>(
+        &'a self,
+        owner: &'a str,
+        id: i64,
+        update: &'a WebhookUpdate,
+    ) -> BoxFuture<'a, Result<Option<WebhookSubscription>, String>>;
+
+    /// Whether `owner` had a subscription `id`
+    fn delete<'a>(&'a self, owner: &'a str, id: i64) -> BoxFuture<'a, Result<bool, String>>;
+
+    /// The enabled subscriptions to `event`
+    fn targets(&self, event: WebhookEvent) -> BoxFuture<'_, Result<Vec<WebhookTarget>, String>>;
+
+    /// Records whether a delivery to subscription `id` succeeded, returning whether the
+    /// failures it made disabled the subscription
+    fn record_delivery(
+        &self,
+        id: i64,
+        delivered: bool,
+        max_failures: u32,
+    ) -> BoxFuture<'_, Result<bool, String>>;
+}
+
+/// A subscription to register, from the body of `POST /api/webhooks`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewWebhook {
+    url: String,
+    events: Vec<WebhookEvent>,
+}
+
+/// Changes to a subscription, from the body of `PATCH /api/webhooks/{id}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookUpdate {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    /// Enabling a subscription forgets the failures that disabled it
+    pub enabled: Option<bool>,
+}
+
+/// A registered subscription, with the secret only shown when it is registered
+#[derive(Serialize)]
+struct Registered<'a> {
+    #[serde(flatten)]
+    subscription: &'a WebhookSubscription,
+    secret: &'a str,
+}
+
+/// Payload of a `new_vote_record` delivery
+#[derive(Debug, Serialize, PartialEq)]
+struct VotesRecorded<'a> {
+    /// Always "new_vote_record"
+    event: &'a str,
+    video_id: &'a str,
+    title: &'a str,
+    url: String,
+    votes: &'a [Vote],
+}
+
+pub(crate) async fn route(store: &dyn WebhookStore, key: &ApiKey, request: &Request) -> Response {
+    let owner = key.name.as_str();
+    let id = match request.path.strip_prefix("/api/webhooks") {
+        Some("" | "/") => None,
+        Some(id) => match id.strip_prefix('/').and_then(|id| id.parse::<i64>().ok()) {
+            Some(id) => Some(id),
+            None => return ("404 Not Found", "text/plain", "not found".into()),
+        },
+        None => return ("404 Not Found", "text/plain", "not found".into()),
+    };
+
+    let answer = match (request.method.as_str(), id) {
+        ("GET", None) => store.list(owner).await.map(|subscriptions| {
+            (
+                "200 OK",
+                "application/json",
+                serde_json::to_string(&subscriptions).unwrap_or_default(),
+            )
+        }),
+        ("POST", None) => {
+            let new = match serde_json::from_slice::<NewWebhook>(&request.body) {
+                Ok(new) => new,
+                Err(e) => return ("400 Bad Request", "text/plain", e.to_string()),
+            };
+            if let Err(reason) = validate(Some(&new.url), Some(&new.events)) {
+                return ("400 Bad Request", "text/plain", reason);
+            }
+            register(store, owner, &new).await
+        }
+        ("GET", Some(id)) => store.list(owner).await.map(|subscriptions| {
+            match subscriptions.iter().find(|s| s.id == id) {
+                Some(subscription) => (
+                    "200 OK",
+                    "application/json",
+                    serde_json::to_string(subscription).unwrap_or_default(),
+                ),
+                None => ("404 Not Found", "text/plain", "not found".into()),
+            }
+        }),
+        ("PATCH", Some(id)) => {
+            let update = match serde_json::from_slice::<WebhookUpdate>(&request.body) {
+                Ok(update) => update,
+                Err(e) => return ("400 Bad Request", "text/plain", e.to_string()),
+            };
+            if let Err(reason) = validate(update.url.as_deref(), update.events.as_deref()) {
+                return ("400 Bad Request", "text/plain", reason);
+            }
+            let update = WebhookUpdate {
+                events: update.events.as_deref().map(unique),
+                ..update
+            };
+            store.update(owner, id, &update).await.map(|updated| {
+                tracing::info!(owner, id, ?update, "Webhook subscription updated");
+                match updated {
+                    Some(subscription) => (
+                        "200 OK",
+                        "application/json",
+                        serde_json::to_string(&subscription).unwrap_or_default(),
+                    ),
+                    None => ("404 Not Found", "text/plain", "not found".into()),
+                }
+            })
+        }
+        ("DELETE", Some(id)) => store.delete(owner, id).await.map(|deleted| {
+            if deleted {
+                tracing::info!(owner, id, "Webhook subscription deleted");
+                ("204 No Content", "text/plain", String::new())
+            } else {
+                ("404 Not Found", "text/plain", "not found".into())
+            }
+        }),
+        _ => {
+            return (
+                "405 Method Not Allowed",
+                "text/plain",
+                "method not allowed".into(),
+            )
+        }
+    };
+
+    answer.unwrap_or_else(|e| {
+        tracing::error!(error = %e, owner, "Failed to manage webhook subscriptions");
+        (
+            "503 Service Unavailable",
+            "text/plain",
+            "webhook subscriptions unavailable".into(),
+        )
+    })
+}
+
+async fn register(
+    store: &dyn WebhookStore,
+    owner: &str,
+    new: &NewWebhook,
+) -> Result<Response, String> {
+    if store.list(owner).await?.len() >= MAX_SUBSCRIPTIONS_PER_KEY {
+        return Ok((
+            "409 Conflict",
+            "text/plain",
+            format!("at most {MAX_SUBSCRIPTIONS_PER_KEY} subscriptions may be registered"),
+        ));
+    }
+    let secret = generate_secret();
+    let subscription = store
+        .create(owner, &new.url, &secret, &unique(&new.events))
+        .await?;
+    tracing::info!(
+        owner,
+        id = subscription.id,
+        url = subscription.url,
+        "Webhook subscription registered"
+    );
+    Ok((
+        "201 Created",
+        "application/json",
+        serde_json::to_string(&Registered {
+            subscription: &subscription,
+            secret: &secret,
+        })
+        .unwrap_or_default(),
+    ))
+}
+
+/// Checks what is given of a subscription, failing with why it can't be registered
+fn validate(url: Option<&str>, events: Option<&[WebhookEvent]>) -> Result<(), String> {
+    if events.is_some_and(|events| events.is_empty()) {
+        return Err("at least one event is required".into());
+    }
+    let Some(url) = url else {
+        return Ok(());
+    };
+    let url = reqwest::Url::parse(url).map_err(|e| format!("invalid url: {e}"))?;
+    if url.scheme() != "https" {
+        return Err("url must be https".into());
+    }
+    let host = url.host_str().unwrap_or_default();
+    let internal = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(ip) => !is_global(ip),
+        Err(_) => host == "localhost" || host.ends_with(".localhost") || host.ends_with(".local"),
+    };
+    if host.is_empty() || internal {
+        return Err("url must be publicly reachable".into());
+    }
+    Ok(())
+}
+
+/// Whether `ip` is reachable on the internet, rather than only on this host or network,
+/// or reserved
+fn is_global(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_global_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_global_v4(ip),
+            None => is_global_v6(ip),
+        },
+    }
+}
+
+fn is_global_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "this network", shared address space, IETF protocol assignments, benchmarking
+        // and reserved
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (b & 0xfe) == 18)
+        || a >= 240)
+}
+
+fn is_global_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local, link-local and documentation addresses
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        // IPv4-compatible and NAT64 addresses, checked as the IPv4 address they embed
+        || (segments[..6] == [0; 6] && !is_global_v4(embedded_v4(ip)))
+        || (segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] && !is_global_v4(embedded_v4(ip))))
+}
+
+/// The IPv4 address in the last 32 bits of `ip`
+fn embedded_v4(ip: Ipv6Addr) -> Ipv4Addr {
+    let [.., a, b, c, d] = ip.octets();
+    Ipv4Addr::new(a, b, c, d)
+}
+
+/// Resolves hosts with the system resolver, leaving out non-global addresses so that a
+/// host resolving to one at delivery time is refused, whatever it resolved to when
+/// registered
+struct GlobalResolver;
+
+impl reqwest::dns::Resolve for GlobalResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_global(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+            if addrs.is_empty() {
+                return Err(
+                    format!("{} doesn't resolve to a public address", name.as_str()).into(),
+                );
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// The client deliveries are sent through, kept apart from the run's so that they
+/// can't be redirected or proxied to internal addresses
+fn delivery_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .dns_resolver(Arc::new(GlobalResolver))
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+        .timeout(TIMEOUT)
+        .build()
+        .expect("the TLS backend initializes")
+}
+
+/// `events` without repeats, in the order first given
+fn unique(events: &[WebhookEvent]) -> Vec<WebhookEvent> {
+    let mut unique = Vec::with_capacity(events.len());
+    for event in events {
+        if !unique.contains(event) {
+            unique.push(*event);
+        }
+    }
+    unique
+}
+
+/// A new random secret to sign a subscription's deliveries with
+fn generate_secret() -> String {
+    let random = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect::<String>();
+    format!("{SECRET_PREFIX}{random}")
+}
+
+/// Delivers each stored stream's events to the subscriptions to them, up to
+/// [`MAX_CONCURRENT_DELIVERIES`] at once. A failed delivery is logged and counted against
+/// the subscription, and not retried.
+#[derive(Clone)]
+pub struct SubscribedWebhooks {
+    client: reqwest::Client,
+    store: Arc<dyn WebhookStore>,
+}
+
+impl std::fmt::Debug for SubscribedWebhooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubscribedWebhooks").finish_non_exhaustive()
+    }
+}
+
+impl SubscribedWebhooks {
+    pub fn new(store: Arc<dyn WebhookStore>) -> Self {
+        Self {
+            client: delivery_client(),
+            store,
+        }
+    }
+
+    /// Delivers `stream`'s events, failing if the subscriptions couldn't be listed or a
+    /// delivery failed
+    pub async fn notify(&self, stream: &Stream) -> anyhow::Result<()> {
+        let mut deliveries = Vec::new();
+        for (event, body) in payloads(stream)? {
+            let targets = self
+                .store
+                .targets(event)
+                .await
+                .map_err(anyhow::Error::msg)?;
+            let body = Arc::new(body);
+            deliveries.extend(
+                targets
+                    .into_iter()
+                    .map(|target| (target, event, body.clone())),
+            );
+        }
+
+        let failed = futures::stream::iter(deliveries)
+            .map(|(target, event, body)| async move {
+                let delivered = self.deliver(&target, event, &body).await;
+                if let Err(e) = &delivered {
+                    tracing::warn!(
+                        error = %e,
+                        subscription = target.subscription_id,
+                        video_id = stream.video_id,
+                        %event,
+                        "Failed to deliver webhook"
+                    );
+                }
+                self.record(&target, delivered.is_ok()).await;
+                delivered.is_err()
+            })
+            .buffer_unordered(MAX_CONCURRENT_DELIVERIES)
+            .filter(|failed| std::future::ready(*failed))
+            .count()
+            .await;
+        if failed > 0 {
+            anyhow::bail!("{failed} webhook deliveries for {} failed", stream.video_id);
+        }
+        Ok(())
+    }
+
+    /// Counts the delivery for or against `target`, disabling it after too many failures
+    async fn record(&self, target: &WebhookTarget, delivered: bool) {
+        match self
+            .store
+            .record_delivery(target.subscription_id, delivered, MAX_CONSECUTIVE_FAILURES)
+            .await
+        {
+            Ok(true) => tracing::warn!(
+                subscription = target.subscription_id,
+                "Webhook subscription disabled after {MAX_CONSECUTIVE_FAILURES} failed deliveries"
+            ),
+            Ok(false) => {}
+            Err(e) => tracing::error!(
+                error = %e,
+                subscription = target.subscription_id,
+                "Failed to record webhook delivery"
+            ),
+        }
+    }
+
+    async fn deliver(
+        &self,
+        target: &WebhookTarget,
+        event: WebhookEvent,
+        body: &[u8],
+    ) -> anyhow::Result<()> {
+        // subscriptions registered before the checks tightened are checked again
+        validate(Some(&target.url), None).map_err(anyhow::Error::msg)?;
+        let response = self
+            .client
+            .post(&target.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature(&target.secret, body))
+            .header(EVENT_HEADER, event.as_str())
+            .body(body.to_vec())
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Webhook responded with {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+impl ProcessorHook for SubscribedWebhooks {
+    fn on_stream_stored<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.notify(stream))
+    }
+}
+
+/// The events `stream` makes, and the body each is delivered with
+fn payloads(stream: &Stream) -> serde_json::Result<Vec<(WebhookEvent, Vec<u8>)>> {
+    let summarized = StreamSummarized {
+        event: WebhookEvent::NewSummary.as_str(),
+        ..StreamSummarized::from(stream)
+    };
+    let mut payloads = vec![(WebhookEvent::NewSummary, serde_json::to_vec(&summarized)?)];
+
+    let votes = stream
+        .structured_summary
+        .as_ref()
+        .map(|summary| summary.votes.as_slice())
+        .unwrap_or_default();
+    if !votes.is_empty() {
+        let recorded = VotesRecorded {
+            event: WebhookEvent::NewVoteRecord.as_str(),
+            video_id: &stream.video_id,
+            title: &stream.title,
+            url: stream.url(),
+            votes,
+        };
+        payloads.push((WebhookEvent::NewVoteRecord, serde_json::to_vec(&recorded)?));
+    }
+    Ok(payloads)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use reqwest::dns::Resolve;
+    use stream_datastore::{ApiKeyRole, Json, StructuredSummary};
+
+    use super::*;
+
+    /// Subscriptions kept in memory
+    #[derive(Default)]
+    struct Subscriptions(Mutex<Vec<WebhookSubscription>>);
+
+    impl WebhookStore for Subscriptions {
+        fn create<'a>(
+            &'a self,
+            owner: &'a str,
+            url: &'a str,
+            _secret: &'a str,
+            events: &'a [WebhookEvent],
+        ) -> BoxFuture<'a, Result<WebhookSubscription, String>> {
+            let mut subscriptions = self.0.lock().unwrap();
+            let subscription = WebhookSubscription {
+                id: subscriptions.len() as i64 + 1,
+                owner: owner.into(),
+                url: url.into(),
+                events: events.to_vec(),
+                enabled: true,
+                consecutive_failures: 0,
+            };
+            subscriptions.push(subscription.clone());
+            Box::pin(async { Ok(subscription) })
+        }
+
+        fn list<'a>(
+            &'a self,
+            owner: &'a str,
+        ) -> BoxFuture<'a, Result<Vec<WebhookSubscription>, String>> {
+            let mut subscriptions = self.0.lock().unwrap().clone();
+            subscriptions.retain(|s| s.owner == owner);
+            Box::pin(async { Ok(subscriptions) })
+        }
+
+        fn update<'a>(
+            &'a self,
+            owner: &'a str,
+            id: i64,
+            update: &'a WebhookUpdate,
+        ) -> BoxFuture<'a, Result<Option<WebhookSubscription>, String>> {
+            let mut subscriptions = self.0.lock().unwrap();
+            let updated = subscriptions
+                .iter_mut()
+                .find(|s| s.owner == owner && s.id == id)
+                .map(|s| {
+                    s.enabled = update.enabled.unwrap_or(s.enabled);
+                    s.clone()
+                });
+            Box::pin(async { Ok(updated) })
+        }
+
+        fn delete<'a>(&'a self, owner: &'a str, id: i64) -> BoxFuture<'a, Result<bool, String>> {
+            let mut subscriptions = self.0.lock().unwrap();
+            let before = subscriptions.len();
+            subscriptions.retain(|s| s.owner != owner || s.id != id);
+            let deleted = subscriptions.len() < before;
+            Box::pin(async move { Ok(deleted) })
+        }
+
+        fn targets(
+            &self,
+            _event: WebhookEvent,
+        ) -> BoxFuture<'_, Result<Vec<WebhookTarget>, String>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn record_delivery(
+            &self,
+            _id: i64,
+            _delivered: bool,
+            _max_failures: u32,
+        ) -> BoxFuture<'_, Result<bool, String>> {
+            Box::pin(async { Ok(false) })
+        }
+    }
+
+    fn key(name: &str) -> ApiKey {
+        ApiKey {
+            name: name.into(),
+            role: ApiKeyRole::Public,
+            requests_per_minute: None,
+        }
+    }
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.into(),
+            path: path.into(),
+            query: String::new(),
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+            peer: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keys_manage_their_own_subscriptions() {
+        let store = Subscriptions::default();
+        let (partner, other) = (key("partner"), key("other"));
+
+        let invalid = request(
+            "POST",
+            "/api/webhooks",
+            r#"{"url": "https://10.0.0.1/hooks", "events": ["new_summary"]}"#,
+        );
+        assert_eq!(route(&store, &partner, &invalid).await.0, "400 Bad Request");
+        let unknown_event = request(
+            "POST",
+            "/api/webhooks",
+            r#"{"url": "https://example.com/hooks", "events": ["new_bill"]}"#,
+        );
+        assert_eq!(
+            route(&store, &partner, &unknown_event).await.0,
+            "400 Bad Request"
+        );
+
+        let create = request(
+            "POST",
+            "/api/webhooks",
+            r#"{"url": "https://example.com/hooks", "events": ["new_summary", "new_vote_record"]}"#,
+        );
+        let (status, _, body) = route(&store, &partner, &create).await;
+        assert_eq!(status, "201 Created");
+        let created: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(created["id"], 1);
+        assert!(created["secret"]
+            .as_str()
+            .unwrap()
+            .starts_with(SECRET_PREFIX));
+
+        let get = request("GET", "/api/webhooks/1", "");
+        assert_eq!(route(&store, &partner, &get).await.0, "200 OK");
+        assert_eq!(route(&store, &other, &get).await.0, "404 Not Found");
+        let disable = request("PATCH", "/api/webhooks/1", r#"{"enabled": false}"#);
+        let (status, _, body) = route(&store, &partner, &disable).await;
+        assert_eq!(status, "200 OK");
+        assert!(body.contains(r#""enabled":false"#));
+
+        let delete = request("DELETE", "/api/webhooks/1", "");
+        assert_eq!(route(&store, &other, &delete).await.0, "404 Not Found");
+        assert_eq!(route(&store, &partner, &delete).await.0, "204 No Content");
+        assert!(store.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_streams_with_votes_make_vote_record_events() {
+        let mut stream = Stream {
+            video_id: "dQw4w9WgXcQ".into(),
+            title: "National Assembly | Tuesday 22nd July 2025".into(),
+            ..Default::default()
+        };
+        let events = |stream: &Stream| {
+            payloads(stream)
+                .unwrap()
+                .into_iter()
+                .map(|(event, _)| event)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(events(&stream), vec![WebhookEvent::NewSummary]);
+
+        stream.structured_summary = Some(Json(StructuredSummary {
+            votes: vec![Vote {
+                subject: "Finance Bill, 2025".into(),
+                result: "passed".into(),
+                ayes: Some(195),
+                noes: Some(106),
+                abstentions: None,
+            }],
+            ..Default::default()
+        }));
+        let payloads = payloads(&stream).unwrap();
+        assert_eq!(
+            payloads.iter().map(|(event, _)| *event).collect::<Vec<_>>(),
+            vec![WebhookEvent::NewSummary, WebhookEvent::NewVoteRecord]
+        );
+        let summary: serde_json::Value = serde_json::from_slice(&payloads[0].1).unwrap();
+        assert_eq!(summary["event"], "new_summary");
+        let votes: serde_json::Value = serde_json::from_slice(&payloads[1].1).unwrap();
+        assert_eq!(votes["votes"][0]["ayes"], 195);
+    }
+
+    #[tokio::test]
+    async fn test_deliveries_only_reach_public_addresses() {
+        for internal in [
+            "https://127.0.0.1/hooks",
+            "https://100.64.0.1/hooks",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[::ffff:10.0.0.1]/hooks",
+            "https://[64:ff9b::c0a8:1]/hooks",
+            "https://[fd00::1]/hooks",
+            "https://metadata.localhost/hooks",
+        ] {
+            assert!(validate(Some(internal), None).is_err(), "{internal}");
+        }
+        assert!(validate(Some("https://93.184.215.14/hooks"), None).is_ok());
+        assert!(validate(Some("https://[2606:4700::1111]/hooks"), None).is_ok());
+
+        let name = "localhost".parse().unwrap();
+        assert!(GlobalResolver.resolve(name).await.is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! # WhatsApp
+//!
+//! Sends each stored stream's summary over the
+//! [WhatsApp Business Cloud API](https://developers.facebook.com/docs/whatsapp/cloud-api)
+//! to the numbers on broadcast lists that opted in. WhatsApp only allows messages to
+//! start a conversation from an approved template, so the summary fills the template's
+//! body parameters: `{{1}}` the stream's title, `{{2}}` its TL;DR and `{{3}}` a link to
+//! its page.
+//!
+//! The lists are managed through the [admin endpoints](crate::admin):
+//!
+//! - `GET /admin/whatsapp/{list}` answers with the list's numbers, as JSON
+//! - `PUT /admin/whatsapp/{list}/{phone_number}` adds the number, or updates it, as
+//!   opted in, or as opted out with `{"opted_in": false}`
+//! - `DELETE /admin/whatsapp/{list}/{phone_number}` removes it
+//!
+//! Changes answer with the list's numbers after the change. Numbers are given with their
+//! country code, e.g. `+254712345678`, and stored as digits.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::json;
+use stream_datastore::{Stream, WhatsAppSubscriber};
+
+use crate::{
+    processor::hooks::ProcessorHook,
+    server::{Request, Response},
+};
+
+const API_URL: &str = "https://graph.facebook.com/v21.0";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Characters of a TL;DR sent, keeping the template's body within WhatsApp's 1024
+const MAX_TLDR_CHARS: usize = 700;
+
+/// A change to a broadcast list requested through the admin endpoints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListChange {
+    OptIn { phone_number: String },
+    OptOut { phone_number: String },
+    Remove { phone_number: String },
+}
+
+/// Applies a change to the named list, if one is given, and returns the list's numbers
+pub type BroadcastLists = Arc<
+    dyn Fn(
+            String,
+            Option<ListChange>,
+        ) -> BoxFuture<'static, Result<Vec<WhatsAppSubscriber>, String>>
+        + Send
+        + Sync,
+>;
+
+/// Sends each stored stream that was summarized to the opted in numbers on `lists`, a
+/// number on several lists once. A failed message is logged and not retried.
+#[derive(Clone)]
+pub struct WhatsAppNotifier {
+    client: reqwest::Client,
+    access_token: String,
+    /// ID of the business phone number messages are sent from
+    phone_number_id: String,
+    template: String,
+    language: String,
+    lists: Vec<String>,
+    subscribers: BroadcastLists,
+    site_url: String,
+}
+
+impl std::fmt::Debug for WhatsAppNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WhatsAppNotifier")
+            .field("phone_number_id", &self.phone_number_id)
+            .field("template", &self.template)
+            .field("lists", &self.lists)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WhatsAppNotifier {
+    /// Sends the approved template `template`, in English, from the business number
+    /// `phone_number_id` to the numbers `subscribers` lists for each of `lists`
+    pub fn new(
+        access_token: impl Into<String>,
+        phone_number_id: impl Into<String>,
+        template: impl Into<String>,
+        lists: Vec<String>,
+        subscribers: BroadcastLists,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            access_token: access_token.into(),
+            phone_number_id: phone_number_id.into(),
+            template: template.into(),
+            language: "en".into(),
+            lists,
+            subscribers,
+            site_url: "https://bungebits.ke".into(),
+        }
+    }
+
+    /// Sends the template's translation into `language`, e.g. "sw" for Swahili
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    /// Links streams to their pages on `site_url` rather than on bungebits.ke
+    pub fn with_site_url(mut self, site_url: impl Into<String>) -> Self {
+        self.site_url = site_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Sends `stream`'s summary to each opted in number, or does nothing if it has no
+    /// summary. Fails with how many messages failed, once each number was tried.
+    pub async fn notify(&self, stream: &Stream) -> anyhow::Result<()> {
+        let Some(message) =
+            template_message(stream, &self.template, &self.language, &self.site_url)
+        else {
+            return Ok(());
+        };
+
+        let mut recipients = HashSet::new();
+        for list in &self.lists {
+            let subscribers = (self.subscribers)(list.clone(), None)
+                .await
+                .map_err(anyhow::Error::msg)?;
+            recipients.extend(
+                subscribers
+                    .into_iter()
+                    .filter(|s| s.opted_in)
+                    .map(|s| s.phone_number),
+            );
+        }
+
+        let mut failed = 0;
+        for to in &recipients {
+            let mut message = message.clone();
+            message["to"] = to.as_str().into();
+            if let Err(e) = self.send(&message).await {
+                tracing::warn!(error = %e, video_id = stream.video_id, "Failed to send WhatsApp message");
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            anyhow::bail!(
+                "{failed} of {} WhatsApp messages for {} failed",
+                recipients.len(),
+                stream.video_id
+            );
+        }
+        Ok(())
+    }
+
+    async fn send(&self, message: &serde_json::Value) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .post(format!("{API_URL}/{}/messages", self.phone_number_id))
+            .timeout(TIMEOUT)
+            .bearer_auth(&self.access_token)
+            .json(message)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            anyhow::bail!(
+                "WhatsApp responded with {status}: {}",
+                response.text().await.unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl ProcessorHook for WhatsAppNotifier {
+    fn on_stream_stored<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.notify(stream))
+    }
+}
+
+/// The template message sending `stream`'s summary, without its recipient, `None` if
+/// it has no summary
+fn template_message(
+    stream: &Stream,
+    template: &str,
+    language: &str,
+    site_url: &str,
+) -> Option<serde_json::Value> {
+    let summary = stream
+        .summary_tldr
+        .as_deref()
+        .or(stream.summary_md.as_deref())?;
+    let mut summary = parameter(summary);
+    if let Some((end, _)) = summary.char_indices().nth(MAX_TLDR_CHARS) {
+        summary.truncate(end);
+        summary.push('…');
+    }
+    let parameters = [
+        parameter(&stream.title),
+        summary,
+        format!("{site_url}/summaries/{}", stream.video_id),
+    ];
+
+    Some(json!({
+        "messaging_product": "whatsapp",
+        "type": "template",
+        "template": {
+            "name": template,
+            "language": { "code": language },
+            "components": [{
+                "type": "body",
+                "parameters": parameters
+                    .iter()
+                    .map(|text| json!({ "type": "text", "text": text }))
+                    .collect::<Vec<_>>(),
+            }],
+        },
+    }))
+}
+
+/// `text` as a template parameter, which may not hold newlines, tabs or runs of spaces
+fn parameter(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Answers the admin endpoints under `/admin/whatsapp/`, for a request already
+/// authorized as admin
+pub(crate) async fn route(lists: &BroadcastLists, request: &Request) -> Response {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct OptIn {
+        opted_in: bool,
+    }
+
+    let path = request.path.trim_start_matches("/admin/whatsapp/");
+    let (list, phone_number) = match path.split_once('/') {
+        Some((list, phone_number)) => (list, Some(phone_number)),
+        None => (path, None),
+    };
+    if !is_list_name(list) {
+        return ("404 Not Found", "text/plain", "not found".into());
+    }
+    let phone_number = match phone_number.map(normalize_phone_number) {
+        Some(Some(phone_number)) => Some(phone_number),
+        Some(None) => {
+            return (
+                "400 Bad Request",
+                "text/plain",
+                "invalid phone number".into(),
+            )
+        }
+        None => None,
+    };
+
+    let change = match (request.method.as_str(), phone_number) {
+        ("GET", None) => None,
+        ("PUT", Some(phone_number)) => {
+            let opted_in = request.body.is_empty()
+                || match serde_json::from_slice::<OptIn>(&request.body) {
+                    Ok(body) => body.opted_in,
+                    Err(e) => return ("400 Bad Request", "text/plain", e.to_string()),
+                };
+            Some(if opted_in {
+                ListChange::OptIn { phone_number }
+            } else {
+                ListChange::OptOut { phone_number }
+            })
+        }
+        ("DELETE", Some(phone_number)) => Some(ListChange::Remove { phone_number }),
+        _ => {
+            return (
+                "405 Method Not Allowed",
+                "text/plain",
+                "method not allowed".into(),
+            )
+        }
+    };
+
+    if let Some(change) = &change {
+        tracing::info!(list, ?change, "WhatsApp broadcast list changed");
+    }
+    match lists(list.to_string(), change).await {
+        Ok(subscribers) => (
+            "200 OK",
+            "application/json",
+            serde_json::to_string(&subscribers).unwrap_or_default(),
+        ),
+        Err(e) => {
+            tracing::error!(error = %e, list, "Failed to change WhatsApp broadcast list");
+            (
+                "503 Service Unavailable",
+                "text/plain",
+                "broadcast lists unavailable".into(),
+            )
+        }
+    }
+}
+
+/// Lowercase letters, digits, `-` and `_`, at most 64 of them
+fn is_list_name(list: &str) -> bool {
+    (1..=64).contains(&list.len())
+        && list
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+}
+
+/// The digits of an international phone number, e.g. "254712345678" for
+/// "+254712345678", `None` if it isn't one
+fn normalize_phone_number(phone_number: &str) -> Option<String> {
+    let digits = phone_number.strip_prefix('+').unwrap_or(phone_number);
+    ((8..=15).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| digits.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_summaries_fill_the_template_parameters() {
+        let mut stream = Stream {
+            video_id: "dQw4w9WgXcQ".into(),
+            title: "Senate Plenary |  Tuesday 22nd July 2025".into(),
+            ..Default::default()
+        };
+        let message = |stream: &Stream| {
+            template_message(stream, "session_summary", "en", "https://bungebits.ke")
+        };
+        assert_eq!(message(&stream), None);
+
+        stream.summary_tldr = Some("The Senate passed\nthe Finance Bill.".into());
+        let message = message(&stream).unwrap();
+        assert_eq!(message["template"]["name"], "session_summary");
+        let parameters = &message["template"]["components"][0]["parameters"];
+        assert_eq!(
+            parameters[0]["text"],
+            "Senate Plenary | Tuesday 22nd July 2025"
+        );
+        assert_eq!(parameters[1]["text"], "The Senate passed the Finance Bill.");
+        assert_eq!(
+            parameters[2]["text"],
+            "https://bungebits.ke/summaries/dQw4w9WgXcQ"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lists_are_changed_through_the_endpoints() {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let lists: BroadcastLists = {
+            let changes = changes.clone();
+            Arc::new(move |list, change| {
+                changes.lock().unwrap().push((list, change));
+                Box::pin(async { Ok(Vec::new()) })
+            })
+        };
+        let request = |method: &str, path: &str, body: &str| Request {
+            method: method.into(),
+            path: path.into(),
+            query: String::new(),
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+            peer: None,
+        };
+
+        let put = route(
+            &lists,
+            &request("PUT", "/admin/whatsapp/summaries/+254712345678", ""),
+        )
+        .await;
+        assert_eq!(put.0, "200 OK");
+        let opt_out = request(
+            "PUT",
+            "/admin/whatsapp/summaries/254712345678",
+            r#"{"opted_in": false}"#,
+        );
+        assert_eq!(route(&lists, &opt_out).await.0, "200 OK");
+        let invalid = request("DELETE", "/admin/whatsapp/summaries/0712-345-678", "");
+        assert_eq!(route(&lists, &invalid).await.0, "400 Bad Request");
+        let get = route(&lists, &request("GET", "/admin/whatsapp/summaries", "")).await;
+        assert_eq!(get, ("200 OK", "application/json", "[]".to_string()));
+
+        let phone_number = "254712345678".to_string();
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![
+                (
+                    "summaries".to_string(),
+                    Some(ListChange::OptIn {
+                        phone_number: phone_number.clone()
+                    })
+                ),
+                (
+                    "summaries".to_string(),
+                    Some(ListChange::OptOut { phone_number })
+                ),
+                ("summaries".to_string(), None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! # X
+//!
+//! A [`ProcessorHook`] posting each stored stream to X as a thread through the
+//! [X API v2](https://docs.x.com/x-api/posts/creation-of-a-post): the stream's title and
+//! TL;DR, then its key points, the bills, votes and motions of its structured summary,
+//! and last the link to the stream on YouTube. Text longer than a post is split
+//! between words into several.
+//!
+//! Posts are made as the account whose access token is given, signed with OAuth 1.0a.
+//! In [preview](XNotifier::preview) mode the thread is logged instead of posted, to
+//! check what would be posted before connecting an account.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use serde_json::json;
+use sha1::Sha1;
+use stream_datastore::{Stream, StreamCategory};
+
+use crate::processor::hooks::ProcessorHook;
+
+const POSTS_URL: &str = "https://api.x.com/2/tweets";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Characters a post holds. X counts some characters, e.g. emoji, as two, which
+/// summaries rarely have.
+const MAX_POST_CHARS: usize = 280;
+
+/// Posts in a thread, the link included, so that a long summary doesn't flood timelines
+const MAX_THREAD_POSTS: usize = 8;
+
+/// Key points posted from a structured summary
+const MAX_KEY_POINTS: usize = 4;
+
+/// Keys of the app and of the account it posts as
+#[derive(Clone)]
+pub struct XCredentials {
+    pub consumer_key: String,
+    pub consumer_secret: String,
+    pub access_token: String,
+    pub access_token_secret: String,
+}
+
+impl std::fmt::Debug for XCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XCredentials")
+            .field("consumer_key", &self.consumer_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Posts each stored stream that was summarized as a thread. A thread that fails
+/// partway is logged and not retried, leaving the posts made.
+#[derive(Debug, Clone)]
+pub struct XNotifier {
+    client: reqwest::Client,
+    /// `None` to log threads instead of posting them
+    credentials: Option<XCredentials>,
+    /// Categories of the streams posted, all of them when empty
+    categories: Vec<StreamCategory>,
+}
+
+#[derive(Deserialize)]
+struct Created {
+    data: CreatedPost,
+}
+
+#[derive(Deserialize)]
+struct CreatedPost {
+    id: String,
+}
+
+impl XNotifier {
+    pub fn new(credentials: XCredentials) -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            credentials: Some(credentials),
+            categories: Vec::new(),
+        }
+    }
+
+    /// Logs the threads that would be posted instead of posting them
+    pub fn preview() -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            credentials: None,
+            categories: Vec::new(),
+        }
+    }
+
+    /// Only posts streams in `categories`, e.g. the National Assembly's and the Senate's
+    /// sittings without their committees'
+    pub fn with_categories(mut self, categories: Vec<StreamCategory>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Posts `stream`'s thread, or does nothing if it has no summary or isn't in one of
+    /// the categories posted
+    pub async fn notify(&self, stream: &Stream) -> anyhow::Result<()> {
+        if !self.categories.is_empty() && !self.categories.contains(&stream.category()) {
+            return Ok(());
+        }
+        let Some(thread) = thread(stream) else {
+            return Ok(());
+        };
+
+        let Some(credentials) = &self.credentials else {
+            for (i, text) in thread.iter().enumerate() {
+                tracing::info!(video_id = %stream.video_id, post = i + 1, %text, "Would post to X");
+            }
+            return Ok(());
+        };
+
+        let mut reply_to: Option<String> = None;
+        for text in &thread {
+            let mut body = json!({ "text": text });
+            if let Some(id) = &reply_to {
+                body["reply"] = json!({ "in_reply_to_tweet_id": id });
+            }
+            let response = self
+                .client
+                .post(POSTS_URL)
+                .timeout(TIMEOUT)
+                .header(
+                    reqwest::header::AUTHORIZATION,
+                    authorization(credentials, "POST", POSTS_URL),
+                )
+                .json(&body)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                anyhow::bail!(
+                    "X responded with {} for {}: {}",
+                    response.status(),
+                    stream.video_id,
+                    response.text().await.unwrap_or_default()
+                );
+            }
+            reply_to = Some(response.json::<Created>().await?.data.id);
+        }
+        Ok(())
+    }
+}
+
+impl ProcessorHook for XNotifier {
+    fn on_stream_stored<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.notify(stream))
+    }
+}
+
+/// The posts of `stream`'s thread, `None` if it has no TL;DR
+fn thread(stream: &Stream) -> Option<Vec<String>> {
+    let tldr = stream.summary_tldr.as_deref()?;
+    let mut sections = vec![format!("{}: {}", stream.title, tldr)];
+    if let Some(summary) = &stream.structured_summary {
+        let bills = summary
+            .bills
+            .iter()
+            .map(|bill| format!("{}: {}", bill.name, bill.summary));
+        let votes = summary
+            .votes
+            .iter()
+            .map(|vote| format!("{}: {}", vote.subject, vote.result));
+        let motions = summary.motions.iter().map(|motion| match &motion.outcome {
+            Some(outcome) => format!("{}: {outcome}", motion.title),
+            None => motion.title.clone(),
+        });
+        sections.extend(
+            bills
+                .chain(votes)
+                .chain(motions)
+                .take(MAX_KEY_POINTS)
+                .map(|point| format!("• {point}")),
+        );
+    }
+
+    let mut posts = sections
+        .iter()
+        .flat_map(|section| split_post(section, MAX_POST_CHARS))
+        .take(MAX_THREAD_POSTS - 1)
+        .collect::<Vec<_>>();
+    posts.push(format!("Watch the sitting: {}", stream.url()));
+    Some(posts)
+}
+
+/// `text` split between words into posts of at most `limit` characters, words longer
+/// than a post split where they overflow it
+fn split_post(text: &str, limit: usize) -> Vec<String> {
+    let mut posts = Vec::new();
+    let mut post = String::new();
+    let mut len = 0;
+    for word in text.split_whitespace() {
+        let mut word = word;
+        loop {
+            let word_len = word.chars().count();
+            let needed = if len == 0 {
+                word_len
+            } else {
+                len + 1 + word_len
+            };
+            if needed <= limit {
+                if len > 0 {
+                    post.push(' ');
+                }
+                post.push_str(word);
+                len = needed;
+                break;
+            }
+            if len > 0 {
+                posts.push(std::mem::take(&mut post));
+                len = 0;
+                continue;
+            }
+            let end = word
+                .char_indices()
+                .nth(limit)
+                .map_or(word.len(), |(end, _)| end);
+            posts.push(word[..end].to_string());
+            word = &word[end..];
+            if word.is_empty() {
+                break;
+            }
+        }
+    }
+    if !post.is_empty() {
+        posts.push(post);
+    }
+    posts
+}
+
+/// The OAuth 1.0a `Authorization` header of a request with a JSON body, which isn't
+/// signed
+fn authorization(credentials: &XCredentials, method: &str, url: &str) -> String {
+    let nonce = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect::<String>();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .to_string();
+    let mut params = vec![
+        ("oauth_consumer_key", credentials.consumer_key.clone()),
+        ("oauth_nonce", nonce),
+        ("oauth_signature_method", "HMAC-SHA1".to_string()),
+        ("oauth_timestamp", timestamp),
+        ("oauth_token", credentials.access_token.clone()),
+        ("oauth_version", "1.0".to_string()),
+    ];
+    let signature = signature(
+        method,
+        url,
+        &params,
+        &credentials.consumer_secret,
+        &credentials.access_token_secret,
+    );
+    params.push(("oauth_signature", signature));
+
+    let params = params
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", encode(key), encode(value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("OAuth {params}")
+}
+
+/// Base64 HMAC-SHA1 of the request's signature base string
+fn signature(
+    method: &str,
+    url: &str,
+    params: &[(&str, String)],
+    consumer_secret: &str,
+    token_secret: &str,
+) -> String {
+    let mut encoded = params
+        .iter()
+        .map(|(key, value)| (encode(key), encode(value)))
+        .collect::<Vec<_>>();
+    encoded.sort();
+    let params = encoded
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+    let base = format!("{method}&{}&{}", encode(url), encode(&params));
+
+    let key = format!("{}&{}", encode(consumer_secret), encode(token_secret));
+    let mut mac =
+        Hmac::<Sha1>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(base.as_bytes());
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+/// Percent-encodes all but RFC 3986's unreserved characters, as OAuth 1.0a requires
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_datastore::{BillDiscussed, Json, StructuredSummary};
+
+    use super::*;
+
+    #[test]
+    fn test_signature_matches_the_oauth_example() {
+        // https://developer.x.com/en/docs/authentication/oauth-1-0a/creating-a-signature
+        let params = [
+            ("include_entities", "true".to_string()),
+            (
+                "status",
+                "Hello Ladies + Gentlemen, a signed OAuth request!".to_string(),
+            ),
+            ("oauth_consumer_key", "xvz1evFS4wEEPTGEFPHBog".to_string()),
+            (
+                "oauth_nonce",
+                "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg".to_string(),
+            ),
+            ("oauth_signature_method", "HMAC-SHA1".to_string()),
+            ("oauth_timestamp", "1318622958".to_string()),
+            (
+                "oauth_token",
+                "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb".to_string(),
+            ),
+            ("oauth_version", "1.0".to_string()),
+        ];
+        assert_eq!(
+            signature(
+                "POST",
+                "https://api.twitter.com/1.1/statuses/update.json",
+                &params,
+                "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw",
+                "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE",
+            ),
+            "hCtSmYh+iHYCEqBWrE7C7hYmtUk="
+        );
+    }
+
+    #[test]
+    fn test_summaries_are_threaded_within_the_post_limit() {
+        let stream = Stream {
+            video_id: "dQw4w9WgXcQ".into(),
+            title: "Senate Plenary".into(),
+            summary_tldr: Some("The Senate debated the Finance Bill. ".repeat(12)),
+            structured_summary: Some(Json(StructuredSummary {
+                bills: vec![BillDiscussed {
+                    name: "Finance Bill".into(),
+                    number: None,
+                    stage: None,
+                    summary: "Passed its second reading".into(),
+                }],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let thread = thread(&stream).unwrap();
+
+        assert_eq!(thread.len(), 4);
+        assert!(thread[0].starts_with("Senate Plenary: The Senate debated"));
+        assert!(thread
+            .iter()
+            .all(|post| post.chars().count() <= MAX_POST_CHARS));
+        assert_eq!(thread[2], "• Finance Bill: Passed its second reading");
+        assert_eq!(
+            thread[3],
+            "Watch the sitting: https://www.youtube.com/watch?v=dQw4w9WgXcQ"
+        );
+        assert_eq!(split_post(&"a".repeat(300), 280).len(), 2);
+    }
+}
@@ -0,0 +1,526 @@
+//! # YouTube Data API scraper
+//!
+//! Lists the channel's past streams through the official
+//! [YouTube Data API v3](https://developers.google.com/youtube/v3/docs) instead of the
+//! `ytInitialData` embedded in the channel page, which breaks whenever YouTube changes
+//! its markup. Streams listed this way carry exact RFC 3339 publish dates.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize};
+use stream_datastore::{Stream, StreamCategory, StreamStatus};
+
+use crate::{
+    parser::{ParseFilters, YtHtmlDocument},
+    yt::{format_duration, format_views, scrape_each, Channel, ChannelScraper},
+};
+
+const DEFAULT_BASE_URL: &str = "https://www.googleapis.com/youtube/v3";
+/// The most items the Data API returns per page
+const MAX_RESULTS: usize = 50;
+
+/// Lists the channel's uploads playlist, then looks up the videos in it
+/// to keep live and completed live streams only. Costs 3 quota units per channel per run.
+#[derive(Debug, Clone)]
+pub struct ApiChannelScraper {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    channels: Vec<Channel>,
+    max_results: usize,
+    filters: ParseFilters,
+}
+
+impl ApiChannelScraper {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            channels: vec![Channel::new(Self::CHANNEL_URL)],
+            max_results: MAX_RESULTS,
+            filters: ParseFilters::default(),
+        }
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Channels to list streams from, instead of the Parliament of Kenya channel.
+    /// Channels are looked up by the handle in their URL, e.g. `@ParliamentofKenyaChannel`.
+    pub fn with_channels(mut self, channels: impl IntoIterator<Item = Channel>) -> Self {
+        let channels = channels.into_iter().collect::<Vec<_>>();
+        if !channels.is_empty() {
+            self.channels = channels;
+        }
+        self
+    }
+
+    /// Number of most recent uploads to look through, at most 50
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results.clamp(1, MAX_RESULTS);
+        self
+    }
+
+    /// Which listed streams to keep, as the html scraper does. See [`ParseFilters`]
+    pub fn with_filters(mut self, filters: ParseFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        query: &[(&str, &str)],
+    ) -> anyhow::Result<T> {
+        let response = self
+            .client
+            .get(format!("{}/{endpoint}", self.base_url))
+            .query(query)
+            .query(&[("key", self.api_key.as_str())])
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .json::<ApiErrorResponse>()
+                .await
+                .map(|e| e.error.message)
+                .unwrap_or_else(|_| status.to_string());
+            anyhow::bail!("YouTube Data API {endpoint} request failed ({status}): {message}");
+        }
+
+        Ok(response.json().await?)
+    }
+
+    async fn uploads_playlist_id(&self, channel: &Channel) -> anyhow::Result<String> {
+        let handle = channel
+            .handle()
+            .ok_or_else(|| anyhow::anyhow!("No channel handle in {}", channel.url))?;
+        let channels = self
+            .get::<ListResponse<ApiChannel>>(
+                "channels",
+                &[("part", "contentDetails"), ("forHandle", handle)],
+            )
+            .await?;
+
+        channels
+            .items
+            .into_iter()
+            .next()
+            .map(|channel| channel.content_details.related_playlists.uploads)
+            .ok_or_else(|| anyhow::anyhow!("No channel found for {handle}"))
+    }
+
+    /// Lists the completed live streams among the channel's most recent uploads
+    async fn scrape_channel_streams(&self, channel: &Channel) -> anyhow::Result<Vec<Stream>> {
+        let playlist_id = self.uploads_playlist_id(channel).await?;
+        let max_results = self.max_results.to_string();
+
+        let playlist_items = self
+            .get::<ListResponse<PlaylistItem>>(
+                "playlistItems",
+                &[
+                    ("part", "contentDetails"),
+                    ("playlistId", &playlist_id),
+                    ("maxResults", &max_results),
+                ],
+            )
+            .await?;
+        if playlist_items.items.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let video_ids = playlist_items
+            .items
+            .iter()
+            .map(|item| item.content_details.video_id.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        let videos = self
+            .get::<ListResponse<Video>>(
+                "videos",
+                &[
+                    (
+                        "part",
+                        "snippet,contentDetails,statistics,liveStreamingDetails",
+                    ),
+                    ("id", &video_ids),
+                ],
+            )
+            .await?;
+
+        Ok(videos
+            .items
+            .into_iter()
+            .filter_map(Video::into_stream)
+            .filter(|stream| self.filters.accepts(stream))
+            .collect())
+    }
+}
+
+impl ChannelScraper for ApiChannelScraper {
+    const CHANNEL_URL: &str = "https://www.youtube.com/@ParliamentofKenyaChannel/streams";
+
+    type Error = anyhow::Error;
+
+    fn channel_urls(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .map(|channel| channel.url.clone())
+            .collect()
+    }
+
+    async fn scrape_channel(&self) -> anyhow::Result<YtHtmlDocument> {
+        anyhow::bail!(
+            "ApiChannelScraper lists streams from the Data API and fetches no html document"
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        scrape_each(&self.channels, |channel| {
+            self.scrape_channel_streams(channel)
+        })
+        .await
+    }
+
+    /// Looks the video up like listed ones. Costs 1 quota unit.
+    async fn fetch_stream(&self, video_id: &str) -> anyhow::Result<Stream> {
+        let videos = self
+            .get::<ListResponse<Video>>(
+                "videos",
+                &[
+                    (
+                        "part",
+                        "snippet,contentDetails,statistics,liveStreamingDetails",
+                    ),
+                    ("id", video_id),
+                ],
+            )
+            .await?;
+        let video = videos
+            .items
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No video found for {video_id}"))?;
+        video
+            .into_stream()
+            .ok_or_else(|| anyhow::anyhow!("{video_id} was not streamed live"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    error: ApiError,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListResponse<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiChannel {
+    content_details: ChannelContentDetails,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelContentDetails {
+    related_playlists: RelatedPlaylists,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelatedPlaylists {
+    uploads: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistItem {
+    content_details: PlaylistItemContentDetails,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistItemContentDetails {
+    video_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Video {
+    id: String,
+    snippet: VideoSnippet,
+    content_details: VideoContentDetails,
+    #[serde(default)]
+    statistics: Option<VideoStatistics>,
+    #[serde(default)]
+    live_streaming_details: Option<LiveStreamingDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VideoSnippet {
+    title: String,
+    #[serde(default)]
+    description: String,
+    published_at: String,
+    /// `live` or `upcoming` until the broadcast has ended, `none` afterwards
+    live_broadcast_content: String,
+    /// Keyed by size name, e.g. `high` or `maxres`
+    #[serde(default)]
+    thumbnails: HashMap<String, ApiThumbnail>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiThumbnail {
+    url: String,
+    #[serde(default)]
+    width: u32,
+    #[serde(default)]
+    height: u32,
+}
+
+impl VideoSnippet {
+    /// URL of the largest thumbnail
+    fn thumbnail_url(&self) -> Option<String> {
+        self.thumbnails
+            .values()
+            .max_by_key(|thumbnail| thumbnail.width * thumbnail.height)
+            .map(|thumbnail| thumbnail.url.clone())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VideoContentDetails {
+    /// ISO 8601 duration, e.g. `PT4H37M8S`
+    duration: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VideoStatistics {
+    /// Hidden when the channel disables view counts
+    view_count: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LiveStreamingDetails {
+    scheduled_start_time: Option<String>,
+    actual_start_time: Option<String>,
+    actual_end_time: Option<String>,
+}
+
+impl Video {
+    /// Builds a `Stream` from a scheduled, live or completed live stream, and skips
+    /// ordinary uploads
+    fn into_stream(self) -> Option<Stream> {
+        let live = self.live_streaming_details?;
+        let upcoming = match self.snippet.live_broadcast_content.as_str() {
+            "live" => Some((StreamStatus::Live, live.actual_start_time.clone())),
+            // upcoming streams with no scheduled start have nothing to show
+            "upcoming" => Some((
+                StreamStatus::Scheduled,
+                Some(live.scheduled_start_time.clone()?),
+            )),
+            _ => None,
+        };
+        if let Some((status, start)) = upcoming {
+            let mut stream = Stream {
+                video_id: self.id,
+                category: StreamCategory::from_title(&self.snippet.title),
+                thumbnail_url: self.snippet.thumbnail_url(),
+                title: self.snippet.title,
+                is_live_recording: true,
+                published_at_exact: start
+                    .and_then(|start| DateTime::parse_from_rfc3339(&start).ok())
+                    .map(|date| date.with_timezone(&Utc)),
+                status,
+                ..Default::default()
+            };
+            stream.set_description(self.snippet.description);
+            return Some(stream);
+        }
+        if self.snippet.live_broadcast_content != "none" || live.actual_end_time.is_none() {
+            return None;
+        }
+
+        let duration_secs = parse_iso8601_duration(&self.content_details.duration)?;
+
+        let view_count = self
+            .statistics
+            .and_then(|s| s.view_count)
+            .map(|count| format_views(&count))
+            .unwrap_or_default();
+
+        let thumbnail_url = self.snippet.thumbnail_url();
+        let streamed_date = live.actual_start_time.unwrap_or(self.snippet.published_at);
+        let mut stream = Stream {
+            video_id: self.id,
+            category: StreamCategory::from_title(&self.snippet.title),
+            thumbnail_url,
+            title: self.snippet.title,
+            view_count,
+            published_at_exact: DateTime::parse_from_rfc3339(&streamed_date)
+                .ok()
+                .map(|date| date.with_timezone(&Utc)),
+            streamed_date,
+            duration: format_duration(duration_secs),
+            is_live_recording: true,
+            ..Default::default()
+        };
+        stream.set_description(self.snippet.description);
+        Some(stream)
+    }
+}
+
+/// Seconds in an ISO 8601 duration such as `PT4H37M8S` or `P1DT2H`
+fn parse_iso8601_duration(duration: &str) -> Option<u64> {
+    let mut rest = duration.strip_prefix('P')?;
+    let mut secs = 0;
+    let mut in_time = false;
+
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = time;
+            continue;
+        }
+
+        let end = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount = rest[..end].parse::<u64>().ok()?;
+        let unit = rest[end..].chars().next()?;
+        let multiplier = match (unit, in_time) {
+            ('W', false) => 604_800,
+            ('D', false) => 86_400,
+            ('H', true) => 3_600,
+            ('M', true) => 60,
+            ('S', true) => 1,
+            _ => return None,
+        };
+        secs += amount * multiplier;
+        rest = &rest[end + unit.len_utf8()..];
+    }
+
+    Some(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn video(live_broadcast_content: &str, duration: &str, ended: bool) -> Video {
+        serde_json::from_value(json!({
+            "id": "abc123",
+            "snippet": {
+                "title": "National Assembly | Afternoon Sitting",
+                "description": "0:00 Prayers\n12:40 Statements\n1:05:10 Bills",
+                "publishedAt": "2025-03-04T11:58:02Z",
+                "liveBroadcastContent": live_broadcast_content,
+                "thumbnails": {
+                    "default": { "url": "https://i.ytimg.com/vi/abc123/default.jpg", "width": 120, "height": 90 },
+                    "maxres": { "url": "https://i.ytimg.com/vi/abc123/maxresdefault.jpg", "width": 1280, "height": 720 },
+                    "high": { "url": "https://i.ytimg.com/vi/abc123/hqdefault.jpg", "width": 480, "height": 360 },
+                },
+            },
+            "contentDetails": { "duration": duration },
+            "statistics": { "viewCount": "1203882" },
+            "liveStreamingDetails": {
+                "actualStartTime": "2025-03-04T12:00:14Z",
+                "actualEndTime": ended.then_some("2025-03-04T16:37:22Z"),
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_upcoming_stream_into_stream() {
+        let mut upcoming = video("upcoming", "P0D", false);
+        upcoming
+            .live_streaming_details
+            .as_mut()
+            .unwrap()
+            .scheduled_start_time = Some("2025-03-05T11:30:00Z".into());
+
+        let stream = upcoming.into_stream().unwrap();
+        assert_eq!(stream.status, StreamStatus::Scheduled);
+        assert_eq!(
+            stream.published_at_exact.unwrap().to_rfc3339(),
+            "2025-03-05T11:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_completed_stream_into_stream() {
+        let stream = video("none", "PT4H37M8S", true).into_stream().unwrap();
+
+        assert_eq!(stream.video_id, "abc123");
+        assert_eq!(stream.title, "National Assembly | Afternoon Sitting");
+        assert_eq!(stream.view_count, "1,203,882 views");
+        assert_eq!(stream.streamed_date, "2025-03-04T12:00:14Z");
+        assert_eq!(stream.duration, "4:37:08");
+        assert_eq!(stream.chapters.unwrap().len(), 3);
+        assert_eq!(
+            stream.thumbnail_url.as_deref(),
+            Some("https://i.ytimg.com/vi/abc123/maxresdefault.jpg")
+        );
+        assert_eq!(
+            stream.published_at_exact.unwrap().to_rfc3339(),
+            "2025-03-04T12:00:14+00:00"
+        );
+    }
+
+    #[test]
+    fn test_live_stream_into_stream() {
+        let stream = video("live", "P0D", false).into_stream().unwrap();
+
+        assert_eq!(stream.status, StreamStatus::Live);
+        assert_eq!(
+            stream.published_at_exact.unwrap().to_rfc3339(),
+            "2025-03-04T12:00:14+00:00"
+        );
+        assert!(stream.duration.is_empty());
+    }
+
+    #[test]
+    fn test_unscheduled_short_and_ordinary_videos_are_skipped() {
+        assert!(video("upcoming", "P0D", false).into_stream().is_none());
+        let short = video("none", "PT9M59S", true).into_stream().unwrap();
+        assert!(!ParseFilters::default().accepts(&short));
+
+        let mut upload = video("none", "PT1H", true);
+        upload.live_streaming_details = None;
+        assert!(upload.into_stream().is_none());
+    }
+
+    #[test]
+    fn test_parse_iso8601_duration() {
+        assert_eq!(parse_iso8601_duration("PT4H37M8S"), Some(16_628));
+        assert_eq!(parse_iso8601_duration("PT12M26S"), Some(746));
+        assert_eq!(parse_iso8601_duration("P1DT2H"), Some(93_600));
+        assert_eq!(parse_iso8601_duration("P0D"), Some(0));
+        assert_eq!(parse_iso8601_duration("4:37:08"), None);
+        assert_eq!(format_duration(746), "12:26");
+    }
+}
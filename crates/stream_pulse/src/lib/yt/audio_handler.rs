@@ -1,14 +1,34 @@
-use std::ops::Deref;
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
-use ytdlp_bindings::{AudioProcessor, YtDlp};
+use ytdlp_bindings::{AudioProcessor, YtDlp, YtDlpError};
 
-use crate::yt::AudioHandler;
+use crate::{
+    processor::cleanup::CleanupConfig,
+    yt::{pacing::Pacer, AudioHandler},
+};
 
-pub struct YtDlpWrapper(YtDlp);
+pub struct YtDlpWrapper {
+    yt_dlp: YtDlp,
+    pacer: Option<Pacer>,
+}
 
 impl YtDlpWrapper {
     pub fn new(yt_dlp: YtDlp) -> Self {
-        YtDlpWrapper(yt_dlp)
+        YtDlpWrapper {
+            yt_dlp,
+            pacer: None,
+        }
+    }
+
+    /// Start downloads in turns of `pacer`, each its download gap after the one before,
+    /// and stop starting them while it is paused. Failed downloads count towards pausing
+    /// it, as bot checks fail every download.
+    pub fn with_pacer(mut self, pacer: Pacer) -> Self {
+        self.pacer = Some(pacer);
+        self
     }
 }
 
@@ -16,7 +36,7 @@ impl Deref for YtDlpWrapper {
     type Target = YtDlp;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.yt_dlp
     }
 }
 
@@ -36,10 +56,16 @@ impl AudioHandler for YtDlpWrapper {
 
         // download audio if needed
         if !audio_mp3_path.exists() {
-            if let Err(e) = self
+            if let Some(pacer) = &self.pacer {
+                pacer.wait_for_download_blocking()?;
+            }
+            let downloaded = self
                 .download_audio(&stream_url, "mp3", &audio_output_template)
-                .inspect_err(|e| tracing::error!(error = ?e, "Failed to download audio"))
-            {
+                .inspect_err(|e| tracing::error!(error = ?e, "Failed to download audio"));
+            if let Some(pacer) = &self.pacer {
+                pacer.record_download(downloaded.is_ok());
+            }
+            if let Err(e) = downloaded {
                 anyhow::bail!("Failed to download audio: {:?}", e);
             }
 
@@ -58,24 +84,55 @@ impl AudioHandler for YtDlpWrapper {
     fn clean_up(
         &self,
         stream: &stream_datastore::Stream,
-        audio_dl_path: &std::path::Path,
-    ) -> anyhow::Result<std::path::PathBuf> {
-        // intermediate cleaned file paths
+        audio_path: &Path,
+        cleanup: &CleanupConfig,
+    ) -> anyhow::Result<PathBuf> {
+        // intermediate cleaned file paths, next to the download
         let base_name = &stream.video_id;
-        let audio_mp3_path = audio_dl_path.join(format!("{base_name}.mp3"));
+        let audio_dir = audio_path.parent().unwrap_or(Path::new("."));
+        let copy = |pass: &str| audio_dir.join(format!("{base_name}_{pass}.mp3"));
 
-        let denoised_path = audio_dl_path.join(format!("{base_name}_denoised.mp3"));
-        let normalized_path = audio_dl_path.join(format!("{base_name}_normalized.mp3"));
-        let trimmed_path = audio_dl_path.join(format!("{base_name}_trimmed.mp3"));
-
-        // perform cleanup if final trimmed audio does not exist
-        if !trimmed_path.exists() {
-            self.denoise_audio(audio_mp3_path, &denoised_path)
-                .and_then(|_| self.normalize_volume(&denoised_path, &normalized_path))
-                .and_then(|_| self.trim_silence(&normalized_path, &trimmed_path))?;
-        } else {
-            tracing::debug!("Cleaned audio already exists at {:?}", trimmed_path);
+        let mut cleaned = audio_path.to_path_buf();
+        if cleanup.denoise {
+            cleaned = clean_up_pass(&cleaned, copy("denoised"), |input, output| {
+                self.denoise_audio(input, output)
+            })?;
+        }
+        if cleanup.normalize {
+            cleaned = clean_up_pass(&cleaned, copy("normalized"), |input, output| {
+                self.normalize_volume(input, output)
+            })?;
         }
-        Ok(trimmed_path)
+        if cleanup.trim_silence {
+            cleaned = clean_up_pass(&cleaned, copy("trimmed"), |input, output| {
+                self.trim_silence(input, output)
+            })?;
+        }
+        Ok(cleaned)
+    }
+
+    fn check_dependencies(&self) -> anyhow::Result<()> {
+        self.yt_dlp
+            .check_dependencies()
+            .map_err(|e| anyhow::anyhow!("Audio can't be downloaded: {e}"))
+    }
+}
+
+/// Runs `pass` over `input` into `output`, unless an earlier run already did. The pass
+/// writes to a partial file moved to `output` once it finishes, so that a pass cut short
+/// is run again instead of its truncated output being reused.
+fn clean_up_pass(
+    input: &Path,
+    output: PathBuf,
+    pass: impl FnOnce(&Path, &Path) -> Result<(), YtDlpError>,
+) -> anyhow::Result<PathBuf> {
+    if output.exists() {
+        tracing::debug!("Cleaned audio already exists at {:?}", output);
+        return Ok(output);
     }
+    // ffmpeg picks the output format by the extension, so it is kept
+    let partial = output.with_extension("part.mp3");
+    pass(input, &partial)?;
+    std::fs::rename(&partial, &output)?;
+    Ok(output)
 }
@@ -0,0 +1,194 @@
+//! # Browser scraper
+//!
+//! Renders the channel page in headless Chromium and reads `ytInitialData` from the
+//! page's own javascript, for when the html served to plain requests doesn't carry it,
+//! e.g. consent interstitials and experiment buckets that load the grid from script.
+//! Far slower than fetching the page, so it is meant as the secondary of a
+//! [`FallbackScraper`](crate::yt::fallback::FallbackScraper) behind the html scraper.
+
+use std::{path::PathBuf, time::Duration};
+
+use chromiumoxide::{browser::BrowserConfig, Browser, Page};
+use futures::StreamExt;
+use serde_json::Value;
+use stream_datastore::Stream;
+
+use crate::{
+    parser::{parse_streams_page, ParseFilters, YtHtmlDocument},
+    yt::{scrape_each, Channel, ChannelScraper},
+};
+
+/// Clicks the first button of the consent form, "Reject all"
+const REJECT_CONSENT_JS: &str = r#"
+    const button = document.querySelector('form[action*="consent.youtube.com"] button');
+    if (button) button.click();
+    !!button
+"#;
+const INITIAL_DATA_JS: &str = "JSON.stringify(window.ytInitialData ?? null)";
+
+pub struct BrowserScraper {
+    channels: Vec<Channel>,
+    filters: ParseFilters,
+    executable: Option<PathBuf>,
+    timeout: Duration,
+}
+
+impl Default for BrowserScraper {
+    fn default() -> Self {
+        Self {
+            channels: vec![Channel::new(Self::CHANNEL_URL)],
+            filters: ParseFilters::default(),
+            executable: None,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl BrowserScraper {
+    /// Channels to list streams from, instead of the Parliament of Kenya channel.
+    /// Streams are listed in channel order.
+    pub fn with_channels(mut self, channels: impl IntoIterator<Item = Channel>) -> Self {
+        let channels = channels.into_iter().collect::<Vec<_>>();
+        if !channels.is_empty() {
+            self.channels = channels;
+        }
+        self
+    }
+
+    /// Which listed streams to keep, see [`ParseFilters`]
+    pub fn with_filters(mut self, filters: ParseFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Path of the Chromium or Chrome binary to launch, instead of the first found
+    /// on the `PATH`
+    pub fn with_executable(mut self, executable: impl Into<PathBuf>) -> Self {
+        self.executable = Some(executable.into());
+        self
+    }
+
+    /// How long to wait for a page to render, 30 seconds by default
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Launches a headless browser, runs `f` with it, and closes it again
+    async fn with_browser<T, F, Fut>(&self, f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(Browser) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<(Browser, T)>>,
+    {
+        let mut config = BrowserConfig::builder().request_timeout(self.timeout);
+        if let Some(executable) = &self.executable {
+            config = config.chrome_executable(executable);
+        }
+        let config = config.build().map_err(anyhow::Error::msg)?;
+
+        let (browser, mut handler) = Browser::launch(config).await?;
+        let events = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = f(browser).await;
+        let result = match result {
+            Ok((mut browser, value)) => {
+                if let Err(e) = browser.close().await {
+                    tracing::warn!(error = %e, "Failed to close browser");
+                }
+                Ok(value)
+            }
+            Err(e) => Err(e),
+        };
+        events.abort();
+
+        result
+    }
+
+    /// Opens `url`, getting past a consent interstitial if one is served
+    async fn open(&self, browser: &Browser, url: &str) -> anyhow::Result<Page> {
+        let page = tokio::time::timeout(self.timeout, browser.new_page(url))
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out rendering {url}"))??;
+
+        let on_consent_page = page
+            .url()
+            .await?
+            .is_some_and(|url| url.contains("consent.youtube.com"));
+        if on_consent_page {
+            let clicked = page
+                .evaluate(REJECT_CONSENT_JS)
+                .await?
+                .into_value::<bool>()?;
+            if !clicked {
+                anyhow::bail!("Found no consent button to dismiss rendering {url}");
+            }
+            page.wait_for_navigation().await?;
+        }
+
+        Ok(page)
+    }
+
+    /// Lists the streams on the first page of `channel`'s grid as rendered
+    async fn scrape_channel_streams(
+        &self,
+        browser: &Browser,
+        channel: &Channel,
+    ) -> anyhow::Result<Vec<Stream>> {
+        let page = self.open(browser, &channel.url).await?;
+        let json = page
+            .evaluate(INITIAL_DATA_JS)
+            .await?
+            .into_value::<String>()?;
+        let _ = page.close().await;
+
+        let json = serde_json::from_str::<Value>(&json)?;
+        if json.is_null() {
+            anyhow::bail!("Rendered {} without ytInitialData", channel.url);
+        }
+
+        Ok(parse_streams_page(&json, &self.filters)?.streams)
+    }
+}
+
+impl ChannelScraper for BrowserScraper {
+    const CHANNEL_URL: &str = "https://www.youtube.com/@ParliamentofKenyaChannel/streams";
+
+    type Error = anyhow::Error;
+
+    fn channel_urls(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .map(|channel| channel.url.clone())
+            .collect()
+    }
+
+    /// Renders the first channel's page, returning the html of the rendered document
+    async fn scrape_channel(&self) -> anyhow::Result<YtHtmlDocument> {
+        let url = self.channels[0].url.clone();
+        self.with_browser(|browser| async move {
+            let page = self.open(&browser, &url).await?;
+            let html = page.content().await?;
+            let _ = page.close().await;
+            Ok((browser, YtHtmlDocument::from(html)))
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        self.with_browser(|browser| async move {
+            let streams = scrape_each(&self.channels, |channel| {
+                self.scrape_channel_streams(&browser, channel)
+            })
+            .await?;
+            Ok((browser, streams))
+        })
+        .await
+    }
+}
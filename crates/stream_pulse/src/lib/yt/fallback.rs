@@ -0,0 +1,116 @@
+//! # Fallback
+//!
+//! A [`ChannelScraper`] combinator that lists streams with a secondary scraper when the
+//! primary fails, e.g. an [`RssChannelScraper`](crate::yt::rss_scraper::RssChannelScraper)
+//! for when YouTube's markup changes break `ytInitialData` extraction.
+
+use stream_datastore::Stream;
+
+use crate::{
+    parser::YtHtmlDocument,
+    yt::{ChannelScraper, VideoDetails},
+};
+
+/// Lists streams with `A`, falling back to `B` when `A` fails
+#[derive(Debug, Clone)]
+pub struct FallbackScraper<A, B> {
+    primary: A,
+    secondary: B,
+}
+
+impl<A, B> FallbackScraper<A, B> {
+    pub fn new(primary: A, secondary: B) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+impl<A, B> ChannelScraper for FallbackScraper<A, B>
+where
+    A: ChannelScraper,
+    B: ChannelScraper,
+{
+    const CHANNEL_URL: &str = A::CHANNEL_URL;
+
+    type Error = anyhow::Error;
+
+    fn channel_urls(&self) -> Vec<String> {
+        self.primary.channel_urls()
+    }
+
+    async fn scrape_channel(&self) -> anyhow::Result<YtHtmlDocument> {
+        self.primary.scrape_channel().await
+    }
+
+    async fn resolve_details(&self, video_id: &str) -> anyhow::Result<VideoDetails> {
+        self.primary.resolve_details(video_id).await
+    }
+
+    async fn fetch_stream(&self, video_id: &str) -> anyhow::Result<Stream> {
+        self.primary.fetch_stream(video_id).await
+    }
+
+    fn listing_processed(&self) {
+        self.primary.listing_processed();
+        self.secondary.listing_processed();
+    }
+
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        let primary = match self.primary.scrape_streams().await {
+            Ok(streams) => return Ok(streams),
+            Err(e) => e,
+        };
+
+        tracing::warn!(
+            error = ?primary,
+            primary = ?self.primary.channel_urls(),
+            secondary = ?self.secondary.channel_urls(),
+            "Primary channel scraper failed, falling back"
+        );
+
+        self.secondary.scrape_streams().await.map_err(|secondary| {
+            anyhow::anyhow!(
+                "Both channel scrapers failed. Primary: {primary:?}, secondary: {secondary:?}"
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StaticScraper(Option<&'static str>);
+
+    impl ChannelScraper for StaticScraper {
+        const CHANNEL_URL: &str = "https://youtube.com/static";
+
+        type Error = anyhow::Error;
+
+        async fn scrape_channel(&self) -> anyhow::Result<YtHtmlDocument> {
+            anyhow::bail!("no document")
+        }
+
+        async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+            match self.0 {
+                Some(video_id) => Ok(vec![Stream {
+                    video_id: video_id.into(),
+                    ..Default::default()
+                }]),
+                None => anyhow::bail!("structure might have changed"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_when_primary_fails() {
+        let scraper = FallbackScraper::new(StaticScraper(Some("a")), StaticScraper(Some("b")));
+        assert_eq!(scraper.scrape_streams().await.unwrap()[0].video_id, "a");
+
+        let scraper = FallbackScraper::new(StaticScraper(None), StaticScraper(Some("b")));
+        assert_eq!(scraper.scrape_streams().await.unwrap()[0].video_id, "b");
+
+        let scraper = FallbackScraper::new(StaticScraper(None), StaticScraper(None));
+        let err = scraper.scrape_streams().await.unwrap_err().to_string();
+        assert!(err.contains("Both channel scrapers failed"), "{err}");
+    }
+}
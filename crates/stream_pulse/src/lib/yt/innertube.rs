@@ -0,0 +1,377 @@
+//! # Innertube scraper
+//!
+//! Lists a channel's streams through `youtubei/v1`, the internal API YouTube's web client
+//! loads pages from, instead of extracting `ytInitialData` from the channel page's html
+//! with a regex. Responses carry the same renderers, so they are parsed the same way,
+//! and continuations are the API's own paging.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use stream_datastore::{Stream, StreamCategory, StreamStatus};
+
+use crate::{
+    parser::{parse_continuation, parse_streams_page, ParseFilters, YtHtmlDocument},
+    yt::{
+        format_duration, format_views, pacing::Pacer, scrape_each, Channel, ChannelScraper,
+        VideoDetails,
+    },
+};
+
+const BASE_URL: &str = "https://www.youtube.com/youtubei/v1";
+/// Web client version requests are sent as, unless configured
+pub(crate) const WEB_CLIENT_VERSION: &str = "2.20260213.01.00";
+/// `params` of a browse request for a channel's streams tab
+const STREAMS_TAB_PARAMS: &str = "EgdzdHJlYW1z8gYECgJ6AA%3D%3D";
+
+pub struct InnertubeScraper {
+    client: reqwest::Client,
+    client_version: String,
+    channels: Vec<Channel>,
+    max_pages: usize,
+    filters: ParseFilters,
+    pacer: Option<Pacer>,
+}
+
+impl Default for InnertubeScraper {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            client_version: WEB_CLIENT_VERSION.to_string(),
+            channels: vec![Channel::new(Self::CHANNEL_URL)],
+            max_pages: 1,
+            filters: ParseFilters::default(),
+            pacer: None,
+        }
+    }
+}
+
+impl InnertubeScraper {
+    /// Channels to list streams from, instead of the Parliament of Kenya channel.
+    /// Streams are listed in channel order.
+    pub fn with_channels(mut self, channels: impl IntoIterator<Item = Channel>) -> Self {
+        let channels = channels.into_iter().collect::<Vec<_>>();
+        if !channels.is_empty() {
+            self.channels = channels;
+        }
+        self
+    }
+
+    /// Pages of the streams tab to fetch, 1 by default. See [`Scraper::with_max_pages`].
+    ///
+    /// [`Scraper::with_max_pages`]: crate::yt::scraper::Scraper::with_max_pages
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Web client version to send requests as. Versions are accepted for months after
+    /// they are superseded, so this only needs bumping if requests start failing.
+    pub fn with_client_version(mut self, client_version: impl Into<String>) -> Self {
+        self.client_version = client_version.into();
+        self
+    }
+
+    /// Which listed streams to keep, see [`ParseFilters`]
+    pub fn with_filters(mut self, filters: ParseFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// See [`Scraper::with_pacer`]
+    ///
+    /// [`Scraper::with_pacer`]: crate::yt::scraper::Scraper::with_pacer
+    pub fn with_pacer(mut self, pacer: Pacer) -> Self {
+        self.pacer = Some(pacer);
+        self
+    }
+
+    /// Lists the streams of `channel`, following continuations up to `max_pages`
+    async fn scrape_channel_streams(&self, channel: &Channel) -> anyhow::Result<Vec<Stream>> {
+        let resolved = self
+            .post("navigation/resolve_url", json!({ "url": channel.url }))
+            .await?;
+        let browse_id = browse_id(&resolved)
+            .ok_or_else(|| anyhow::anyhow!("Failed to resolve a browse id for {}", channel.url))?;
+
+        let json = self
+            .post(
+                "browse",
+                json!({ "browseId": browse_id, "params": STREAMS_TAB_PARAMS }),
+            )
+            .await?;
+        let page = parse_streams_page(&json, &self.filters)?;
+        let mut streams = page.streams;
+        let mut continuation = page.continuation;
+
+        for page_number in 2..=self.max_pages {
+            let Some(token) = continuation.take() else {
+                break;
+            };
+
+            // pages already fetched are still worth processing, so a failed page ends the walk
+            let page = self
+                .post("browse", json!({ "continuation": token }))
+                .await
+                .and_then(|json| Ok(parse_continuation(&json, &self.filters)?));
+            match page {
+                Ok(page) => {
+                    streams.extend(page.streams);
+                    continuation = page.continuation;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, page_number, "Failed to fetch next page of streams");
+                    break;
+                }
+            }
+        }
+
+        Ok(streams)
+    }
+
+    async fn post(&self, endpoint: &str, body: Value) -> anyhow::Result<Value> {
+        let request = post(&self.client, endpoint, &self.client_version, body);
+        match &self.pacer {
+            Some(pacer) => pacer.run(request).await,
+            None => request.await,
+        }
+    }
+}
+
+impl ChannelScraper for InnertubeScraper {
+    const CHANNEL_URL: &str = "https://www.youtube.com/@ParliamentofKenyaChannel";
+
+    type Error = anyhow::Error;
+
+    fn channel_urls(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .map(|channel| channel.url.clone())
+            .collect()
+    }
+
+    async fn scrape_channel(&self) -> anyhow::Result<YtHtmlDocument> {
+        anyhow::bail!(
+            "InnertubeScraper lists streams through youtubei and fetches no html document"
+        )
+    }
+
+    #[tracing::instrument(skip(self), fields(max_pages = self.max_pages))]
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        scrape_each(&self.channels, |channel| {
+            self.scrape_channel_streams(channel)
+        })
+        .await
+    }
+
+    /// Reads the live broadcast details or publish date, and the full description,
+    /// from the video's player response
+    async fn resolve_details(&self, video_id: &str) -> anyhow::Result<VideoDetails> {
+        let json = self.post("player", json!({ "videoId": video_id })).await?;
+        Ok(video_details(&json))
+    }
+
+    async fn fetch_stream(&self, video_id: &str) -> anyhow::Result<Stream> {
+        let json = self.post("player", json!({ "videoId": video_id })).await?;
+        player_stream(&json).ok_or_else(|| anyhow::anyhow!("No video found for {video_id}"))
+    }
+}
+
+/// POSTs `body` to `endpoint` of the API, e.g. "browse", with the context of the
+/// public web client at `client_version`
+pub(crate) async fn post(
+    client: &reqwest::Client,
+    endpoint: &str,
+    client_version: &str,
+    mut body: Value,
+) -> anyhow::Result<Value> {
+    body["context"] = json!({
+        "client": {
+            "clientName": "WEB",
+            "clientVersion": client_version,
+            "hl": "en",
+        }
+    });
+
+    let response = client
+        .post(format!("{BASE_URL}/{endpoint}?prettyPrint=false"))
+        .header("Accept-Language", "en-US,en;q=0.9")
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// The channel id `resolve_url` resolved a channel url to
+fn browse_id(json: &Value) -> Option<&str> {
+    json["endpoint"]["browseEndpoint"]["browseId"].as_str()
+}
+
+/// Reads the exact start and description from a `player` response
+fn video_details(json: &Value) -> VideoDetails {
+    let microformat = &json["microformat"]["playerMicroformatRenderer"];
+    let published_at = [
+        &microformat["liveBroadcastDetails"]["startTimestamp"],
+        &microformat["publishDate"],
+    ]
+    .into_iter()
+    .filter_map(|date| date.as_str())
+    .find_map(|date| DateTime::parse_from_rfc3339(date).ok())
+    .map(|date| date.with_timezone(&Utc));
+
+    VideoDetails {
+        published_at,
+        description: json["videoDetails"]["shortDescription"]
+            .as_str()
+            .filter(|description| !description.trim().is_empty())
+            .map(str::to_string),
+    }
+}
+
+/// Reads the stream from a `player` response, which watch pages embed as well
+pub(crate) fn player_stream(json: &Value) -> Option<Stream> {
+    let video = &json["videoDetails"];
+    let video_id = video["videoId"].as_str()?;
+    let title = video["title"].as_str()?;
+    let broadcast = &json["microformat"]["playerMicroformatRenderer"]["liveBroadcastDetails"];
+    let status = match (
+        video["isUpcoming"].as_bool(),
+        broadcast["isLiveNow"].as_bool(),
+    ) {
+        (Some(true), _) => StreamStatus::Scheduled,
+        (_, Some(true)) => StreamStatus::Live,
+        _ => StreamStatus::Archived,
+    };
+    let details = video_details(json);
+
+    let mut stream = Stream {
+        video_id: video_id.to_string(),
+        title: title.to_string(),
+        category: StreamCategory::from_title(title),
+        view_count: video["viewCount"]
+            .as_str()
+            .map(format_views)
+            .unwrap_or_default(),
+        streamed_date: details
+            .published_at
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_default(),
+        duration: video["lengthSeconds"]
+            .as_str()
+            .and_then(|secs| secs.parse().ok())
+            .map(format_duration)
+            .unwrap_or_default(),
+        published_at_exact: details.published_at,
+        thumbnail_url: video["thumbnail"]["thumbnails"]
+            .as_array()
+            .and_then(|thumbnails| {
+                thumbnails.iter().max_by_key(|thumbnail| {
+                    thumbnail["width"].as_u64().unwrap_or_default()
+                        * thumbnail["height"].as_u64().unwrap_or_default()
+                })
+            })
+            .and_then(|thumbnail| thumbnail["url"].as_str())
+            .map(str::to_string),
+        is_live_recording: video["isLiveContent"].as_bool().unwrap_or_default(),
+        status,
+        ..Default::default()
+    };
+    if let Some(description) = details.description {
+        stream.set_description(description);
+    }
+    Some(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browse_id() {
+        let json = json!({
+            "endpoint": {
+                "browseEndpoint": {
+                    "browseId": "UCXuseB7juWB7DIgTJcwtHFQ",
+                    "canonicalBaseUrl": "/@ParliamentofKenyaChannel"
+                }
+            }
+        });
+        assert_eq!(browse_id(&json), Some("UCXuseB7juWB7DIgTJcwtHFQ"));
+        assert_eq!(browse_id(&json!({})), None);
+    }
+
+    #[test]
+    fn test_video_details() {
+        let json = json!({
+            "videoDetails": { "shortDescription": "0:00 Prayers" },
+            "microformat": {
+                "playerMicroformatRenderer": {
+                    "publishDate": "2025-03-04T03:58:02-08:00",
+                    "liveBroadcastDetails": {
+                        "isLiveNow": false,
+                        "startTimestamp": "2025-03-04T04:00:14-08:00"
+                    }
+                }
+            }
+        });
+
+        let details = video_details(&json);
+        assert_eq!(
+            details.published_at.unwrap().to_rfc3339(),
+            "2025-03-04T12:00:14+00:00"
+        );
+        assert_eq!(details.description.as_deref(), Some("0:00 Prayers"));
+        assert_eq!(video_details(&json!({})), VideoDetails::default());
+    }
+
+    #[test]
+    fn test_player_stream() {
+        let json = json!({
+            "videoDetails": {
+                "videoId": "abc123",
+                "title": "Senate Plenary | Tuesday 4th March 2025",
+                "lengthSeconds": "16628",
+                "isLiveContent": true,
+                "viewCount": "3882",
+                "shortDescription": "0:00 Prayers",
+                "thumbnail": {
+                    "thumbnails": [
+                        { "url": "https://i.ytimg.com/vi/abc123/default.jpg", "width": 120, "height": 90 },
+                        { "url": "https://i.ytimg.com/vi/abc123/maxresdefault.jpg", "width": 1280, "height": 720 }
+                    ]
+                }
+            },
+            "microformat": {
+                "playerMicroformatRenderer": {
+                    "liveBroadcastDetails": {
+                        "isLiveNow": false,
+                        "startTimestamp": "2025-03-04T04:00:14-08:00"
+                    }
+                }
+            }
+        });
+
+        let stream = player_stream(&json).unwrap();
+        assert_eq!(stream.video_id, "abc123");
+        assert_eq!(stream.category, Some(StreamCategory::Senate));
+        assert_eq!(stream.duration, "4:37:08");
+        assert_eq!(stream.view_count, "3,882 views");
+        assert_eq!(
+            stream.thumbnail_url.as_deref(),
+            Some("https://i.ytimg.com/vi/abc123/maxresdefault.jpg")
+        );
+        assert!(stream.is_live_recording);
+        assert_eq!(stream.status, StreamStatus::Archived);
+        assert!(stream.published_at_exact.is_some());
+        assert!(player_stream(&json!({})).is_none());
+    }
+}
@@ -1,28 +1,381 @@
+pub mod api_scraper;
 pub mod audio_handler;
+#[cfg(feature = "browser")]
+pub mod browser;
+pub mod fallback;
+pub mod innertube;
+pub mod pacing;
+pub mod rss_scraper;
 pub mod scraper;
+pub mod timedtext;
 
 use std::{
     fmt::Debug,
     future::Future,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
-use stream_datastore::Stream;
+use chrono::{DateTime, Utc};
+use stream_datastore::{Stream, StreamCategory};
 
-use crate::parser::YtHtmlDocument;
+#[cfg(feature = "browser")]
+use crate::yt::browser::BrowserScraper;
+use crate::{
+    parser::{parse_streams, ParseFilters, YtHtmlDocument},
+    processor::cleanup::CleanupConfig,
+    yt::{
+        api_scraper::ApiChannelScraper, fallback::FallbackScraper, innertube::InnertubeScraper,
+        rss_scraper::RssChannelScraper, scraper::Scraper,
+    },
+};
 
 pub trait AudioHandler {
     const BASE_URL: &str;
 
     fn download(&self, stream: &Stream, audio_dl_path: &Path) -> anyhow::Result<PathBuf>;
 
-    fn clean_up(&self, stream: &Stream, audio_dl_path: &Path) -> anyhow::Result<PathBuf>;
+    /// Runs the passes `cleanup` enables over the audio downloaded to `audio_path`,
+    /// returning the path of the cleaned copy
+    fn clean_up(
+        &self,
+        stream: &Stream,
+        audio_path: &Path,
+        cleanup: &CleanupConfig,
+    ) -> anyhow::Result<PathBuf>;
+
+    /// Fails if the tools audio is downloaded and cleaned with can't be run. Defaults to
+    /// nothing, for handlers that need none
+    fn check_dependencies(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// A YouTube channel to list streams from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Channel {
+    /// The channel's streams tab, e.g. `https://www.youtube.com/@ParliamentofKenyaChannel/streams`
+    pub url: String,
+    /// Category given to every stream listed on the channel, instead of
+    /// inferring it from the stream's title
+    pub category: Option<StreamCategory>,
+}
+
+impl Channel {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            category: None,
+        }
+    }
+
+    pub fn with_category(mut self, category: StreamCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// The channel's handle, e.g. `@ParliamentofKenyaChannel`, if its URL has one
+    pub fn handle(&self) -> Option<&str> {
+        self.url.split('/').find(|segment| segment.starts_with('@'))
+    }
+
+    /// Gives `streams` the channel's category, if it has one
+    fn tag(&self, streams: &mut [Stream]) {
+        if let Some(category) = self.category {
+            for stream in streams {
+                stream.category = Some(category);
+            }
+        }
+    }
+}
+
+/// Lists the streams of every channel with `scrape`, tagging each with its
+/// channel. A channel that fails is skipped, unless every channel fails.
+pub(crate) async fn scrape_each<'a, F, Fut>(
+    channels: &'a [Channel],
+    mut scrape: F,
+) -> anyhow::Result<Vec<Stream>>
+where
+    F: FnMut(&'a Channel) -> Fut,
+    Fut: Future<Output = anyhow::Result<Vec<Stream>>>,
+{
+    let mut streams = Vec::new();
+    let mut failures = 0;
+    let mut last_error = None;
+
+    for channel in channels {
+        match scrape(channel).await {
+            Ok(mut channel_streams) => {
+                channel.tag(&mut channel_streams);
+                streams.append(&mut channel_streams);
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, channel = %channel.url, "Failed to scrape channel");
+                failures += 1;
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if failures == channels.len() => Err(e),
+        _ => Ok(streams),
+    }
+}
+
+/// `3882` as `3,882 views`, matching the channel page's view counts
+pub(crate) fn format_views(count: &str) -> String {
+    let mut grouped = String::with_capacity(count.len() + count.len() / 3);
+    for (i, c) in count.chars().enumerate() {
+        if i > 0 && (count.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{grouped} views")
+}
+
+/// Formats seconds the way the channel page shows durations, e.g. `4:37:08` or `12:26`
+pub(crate) fn format_duration(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    match hours {
+        0 => format!("{minutes}:{seconds:02}"),
+        _ => format!("{hours}:{minutes:02}:{seconds:02}"),
+    }
+}
+
+/// Parses `<url>` or `<category>=<url>`, e.g. `senate=https://www.youtube.com/@SenateKE/streams`
+impl FromStr for Channel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.split_once('=') {
+            Some((category, url)) if !category.contains('/') => {
+                Ok(Channel::new(url.trim()).with_category(category.parse()?))
+            }
+            _ if s.starts_with("http") => Ok(Channel::new(s)),
+            _ => Err(format!("Unsupported channel: {s}")),
+        }
+    }
 }
 
 pub trait ChannelScraper {
+    /// The channel listed when none are configured, the Parliament of Kenya's
     const CHANNEL_URL: &str;
 
     type Error: Debug;
 
+    /// URLs of the channels streams are listed from. Defaults to [`Self::CHANNEL_URL`]
+    fn channel_urls(&self) -> Vec<String> {
+        vec![Self::CHANNEL_URL.to_string()]
+    }
+
     fn scrape_channel(&self) -> impl Future<Output = anyhow::Result<YtHtmlDocument>>;
+
+    /// Lists the channel's past streams. Defaults to parsing the `ytInitialData`
+    /// script data from the document returned by [`ChannelScraper::scrape_channel`],
+    /// with the default [`ParseFilters`]
+    fn scrape_streams(&self) -> impl Future<Output = anyhow::Result<Vec<Stream>>> {
+        async move {
+            let doc = self.scrape_channel().await?;
+            let json = doc.to_json::<serde_json::Value>()?;
+            Ok(parse_streams(&json, &ParseFilters::default())?)
+        }
+    }
+
+    /// Resolves the details of the video with `video_id` its listing lacks. Defaults to
+    /// none, leaving streams dated by the approximate "time ago" text they were listed
+    /// with, and described by their description snippet
+    fn resolve_details(
+        &self,
+        _video_id: &str,
+    ) -> impl Future<Output = anyhow::Result<VideoDetails>> {
+        async { Ok(VideoDetails::default()) }
+    }
+
+    /// Looks up the video with `video_id` as a stream, to process it without listing
+    /// its channel. Defaults to an error, for scrapers that can only list channels
+    fn fetch_stream(&self, video_id: &str) -> impl Future<Output = anyhow::Result<Stream>> {
+        async move { anyhow::bail!("Looking up {video_id} on its own is not supported") }
+    }
+
+    /// Called after a run has processed every stream the scraper listed, so that
+    /// scrapers caching listings can skip them while they are unchanged. Defaults
+    /// to nothing
+    fn listing_processed(&self) {}
+}
+
+/// Details of a video from its watch page, which the channel page doesn't list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoDetails {
+    /// Exact start of the stream
+    pub published_at: Option<DateTime<Utc>>,
+    /// The full description, where listings only carry a snippet of it
+    pub description: Option<String>,
+}
+
+/// Where the processor lists streams from, chosen at runtime
+pub enum ChannelSource {
+    /// The `ytInitialData` embedded in the channel page
+    Html(Scraper),
+    /// YouTube's internal `youtubei` API
+    Innertube(InnertubeScraper),
+    /// The YouTube Data API
+    Api(ApiChannelScraper),
+    /// The channel page, falling back to the channel's feed when extraction fails
+    HtmlWithRssFallback(FallbackScraper<Scraper, RssChannelScraper>),
+    /// The `youtubei` API, falling back to the channel's feed when it fails
+    InnertubeWithRssFallback(FallbackScraper<InnertubeScraper, RssChannelScraper>),
+    /// The channel page, falling back to rendering it in a headless browser when
+    /// extraction fails
+    #[cfg(feature = "browser")]
+    HtmlWithBrowserFallback(FallbackScraper<Scraper, BrowserScraper>),
+    /// The channel page, then a headless browser, then the channel's feed
+    #[cfg(feature = "browser")]
+    HtmlWithBrowserAndRssFallback(
+        FallbackScraper<FallbackScraper<Scraper, BrowserScraper>, RssChannelScraper>,
+    ),
+}
+
+impl ChannelScraper for ChannelSource {
+    const CHANNEL_URL: &str = Scraper::CHANNEL_URL;
+
+    type Error = anyhow::Error;
+
+    fn channel_urls(&self) -> Vec<String> {
+        match self {
+            ChannelSource::Html(scraper) => scraper.channel_urls(),
+            ChannelSource::Innertube(scraper) => scraper.channel_urls(),
+            ChannelSource::Api(scraper) => scraper.channel_urls(),
+            ChannelSource::HtmlWithRssFallback(scraper) => scraper.channel_urls(),
+            ChannelSource::InnertubeWithRssFallback(scraper) => scraper.channel_urls(),
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserFallback(scraper) => scraper.channel_urls(),
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserAndRssFallback(scraper) => scraper.channel_urls(),
+        }
+    }
+
+    async fn scrape_channel(&self) -> anyhow::Result<YtHtmlDocument> {
+        match self {
+            ChannelSource::Html(scraper) => scraper.scrape_channel().await,
+            ChannelSource::Innertube(scraper) => scraper.scrape_channel().await,
+            ChannelSource::Api(scraper) => scraper.scrape_channel().await,
+            ChannelSource::HtmlWithRssFallback(scraper) => scraper.scrape_channel().await,
+            ChannelSource::InnertubeWithRssFallback(scraper) => scraper.scrape_channel().await,
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserFallback(scraper) => scraper.scrape_channel().await,
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserAndRssFallback(scraper) => scraper.scrape_channel().await,
+        }
+    }
+
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        match self {
+            ChannelSource::Html(scraper) => scraper.scrape_streams().await,
+            ChannelSource::Innertube(scraper) => scraper.scrape_streams().await,
+            ChannelSource::Api(scraper) => scraper.scrape_streams().await,
+            ChannelSource::HtmlWithRssFallback(scraper) => scraper.scrape_streams().await,
+            ChannelSource::InnertubeWithRssFallback(scraper) => scraper.scrape_streams().await,
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserFallback(scraper) => scraper.scrape_streams().await,
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserAndRssFallback(scraper) => scraper.scrape_streams().await,
+        }
+    }
+
+    async fn resolve_details(&self, video_id: &str) -> anyhow::Result<VideoDetails> {
+        match self {
+            ChannelSource::Html(scraper) => scraper.resolve_details(video_id).await,
+            ChannelSource::Innertube(scraper) => scraper.resolve_details(video_id).await,
+            ChannelSource::Api(scraper) => scraper.resolve_details(video_id).await,
+            ChannelSource::HtmlWithRssFallback(scraper) => scraper.resolve_details(video_id).await,
+            ChannelSource::InnertubeWithRssFallback(scraper) => {
+                scraper.resolve_details(video_id).await
+            }
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserFallback(scraper) => {
+                scraper.resolve_details(video_id).await
+            }
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserAndRssFallback(scraper) => {
+                scraper.resolve_details(video_id).await
+            }
+        }
+    }
+
+    async fn fetch_stream(&self, video_id: &str) -> anyhow::Result<Stream> {
+        match self {
+            ChannelSource::Html(scraper) => scraper.fetch_stream(video_id).await,
+            ChannelSource::Innertube(scraper) => scraper.fetch_stream(video_id).await,
+            ChannelSource::Api(scraper) => scraper.fetch_stream(video_id).await,
+            ChannelSource::HtmlWithRssFallback(scraper) => scraper.fetch_stream(video_id).await,
+            ChannelSource::InnertubeWithRssFallback(scraper) => {
+                scraper.fetch_stream(video_id).await
+            }
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserFallback(scraper) => scraper.fetch_stream(video_id).await,
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserAndRssFallback(scraper) => {
+                scraper.fetch_stream(video_id).await
+            }
+        }
+    }
+
+    fn listing_processed(&self) {
+        match self {
+            ChannelSource::Html(scraper) => scraper.listing_processed(),
+            ChannelSource::Innertube(scraper) => scraper.listing_processed(),
+            ChannelSource::Api(scraper) => scraper.listing_processed(),
+            ChannelSource::HtmlWithRssFallback(scraper) => scraper.listing_processed(),
+            ChannelSource::InnertubeWithRssFallback(scraper) => scraper.listing_processed(),
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserFallback(scraper) => scraper.listing_processed(),
+            #[cfg(feature = "browser")]
+            ChannelSource::HtmlWithBrowserAndRssFallback(scraper) => scraper.listing_processed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_from_str() {
+        let channel = "https://www.youtube.com/@ParliamentofKenyaChannel/streams"
+            .parse::<Channel>()
+            .unwrap();
+        assert_eq!(channel.category, None);
+        assert_eq!(channel.handle(), Some("@ParliamentofKenyaChannel"));
+
+        let channel = "senate=https://www.youtube.com/@SenateKE/streams"
+            .parse::<Channel>()
+            .unwrap();
+        assert_eq!(channel.url, "https://www.youtube.com/@SenateKE/streams");
+        assert_eq!(channel.category, Some(StreamCategory::Senate));
+
+        assert!("house=https://www.youtube.com/@SenateKE/streams"
+            .parse::<Channel>()
+            .is_err());
+        assert!("@SenateKE".parse::<Channel>().is_err());
+    }
+
+    #[test]
+    fn test_channel_urls() {
+        assert_eq!(Scraper::default().channel_urls(), [Scraper::CHANNEL_URL]);
+
+        let scraper = Scraper::default().with_channels([
+            Channel::new("https://www.youtube.com/@EALAOfficial/streams"),
+            Channel::new("https://www.youtube.com/@NairobiCountyAssembly/streams"),
+        ]);
+        assert_eq!(
+            scraper.channel_urls(),
+            [
+                "https://www.youtube.com/@EALAOfficial/streams",
+                "https://www.youtube.com/@NairobiCountyAssembly/streams"
+            ]
+        );
+    }
 }
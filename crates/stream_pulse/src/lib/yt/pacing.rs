@@ -0,0 +1,253 @@
+//! # Pacing
+//!
+//! Spaces out requests to YouTube by a jittered interval, and stops sending them for a
+//! while after repeated failures. Bursts of requests, e.g. from backfill runs, get the
+//! host's IP flagged as a bot, after which every yt-dlp download fails with "Sign in to
+//! confirm you're not a bot" until the flag wears off; requests sent meanwhile only
+//! prolong it. Downloads of multi-hour sittings back to back get it flagged too, so
+//! each download is also kept a gap from the one before it.
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PacingConfig {
+    /// Least time between the starts of two requests
+    pub min_interval: Duration,
+    /// Up to this much is added to each interval, at random
+    pub jitter: Duration,
+    /// Consecutive failed requests after which requests are paused
+    pub failure_threshold: u32,
+    /// How long requests are paused for
+    pub cooldown: Duration,
+    /// Least time from the end of a download to the start of the next, or between their
+    /// starts while downloads run concurrently
+    pub download_gap: Duration,
+    /// Up to this much is added to each download gap, at random
+    pub download_jitter: Duration,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_secs(1),
+            jitter: Duration::from_secs(2),
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(15 * 60),
+            download_gap: Duration::ZERO,
+            download_jitter: Duration::ZERO,
+        }
+    }
+}
+
+/// Returned instead of sending a request while requests are paused
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "YouTube requests are paused after {failures} consecutive failures, for another {retry_in:?}"
+)]
+pub struct CircuitOpen {
+    pub failures: u32,
+    pub retry_in: Duration,
+}
+
+/// A cheaply cloneable pacer. Clones share the same schedule and failure count, so one
+/// pacer should be shared by everything that talks to YouTube.
+#[derive(Debug, Clone, Default)]
+pub struct Pacer {
+    config: PacingConfig,
+    state: Arc<Mutex<PacerState>>,
+}
+
+#[derive(Debug, Default)]
+struct PacerState {
+    next_request: Option<Instant>,
+    /// When the last download finished, or started if it is still running
+    last_download: Option<Instant>,
+    failures: u32,
+    paused_until: Option<Instant>,
+}
+
+impl Pacer {
+    pub fn new(config: PacingConfig) -> Self {
+        Self {
+            config,
+            state: Arc::default(),
+        }
+    }
+
+    /// Waits for the next request's turn
+    pub async fn wait(&self) -> Result<(), CircuitOpen> {
+        tokio::time::sleep(self.reserve(Instant::now())?).await;
+        Ok(())
+    }
+
+    /// Blocks the thread until the next request's turn, for callers outside the runtime
+    pub fn wait_blocking(&self) -> Result<(), CircuitOpen> {
+        std::thread::sleep(self.reserve(Instant::now())?);
+        Ok(())
+    }
+
+    /// Blocks the thread until the next download's turn, which is the next request's
+    /// turn once the download gap since the last download has passed
+    pub fn wait_for_download_blocking(&self) -> Result<(), CircuitOpen> {
+        std::thread::sleep(self.reserve_download(Instant::now())?);
+        Ok(())
+    }
+
+    /// Counts the outcome of a download started after [`Pacer::wait_for_download_blocking`],
+    /// spacing the next download out from its end
+    pub fn record_download(&self, succeeded: bool) {
+        self.state.lock().unwrap().last_download = Some(Instant::now());
+        self.record(succeeded);
+    }
+
+    /// Sends `request` in its turn, counting whether it failed
+    pub async fn run<T>(
+        &self,
+        request: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        self.wait().await?;
+        let result = request.await;
+        self.record(result.is_ok());
+        result
+    }
+
+    /// Counts the outcome of a request sent after [`Pacer::wait`]. Once
+    /// `failure_threshold` requests in a row have failed, requests are paused.
+    pub fn record(&self, succeeded: bool) {
+        let mut state = self.state.lock().unwrap();
+        if succeeded {
+            state.failures = 0;
+            return;
+        }
+
+        state.failures += 1;
+        if state.failures >= self.config.failure_threshold {
+            tracing::warn!(
+                failures = state.failures,
+                cooldown = ?self.config.cooldown,
+                "YouTube requests keep failing, pausing them"
+            );
+            state.paused_until = Some(Instant::now() + self.config.cooldown);
+        }
+    }
+
+    /// Books the next request's turn, returning how long until it comes
+    fn reserve(&self, now: Instant) -> Result<Duration, CircuitOpen> {
+        let mut state = self.state.lock().unwrap();
+        self.reserve_turn(&mut state, now).map(|turn| turn - now)
+    }
+
+    /// Books the next download's turn, returning how long until it comes
+    fn reserve_download(&self, now: Instant) -> Result<Duration, CircuitOpen> {
+        let mut state = self.state.lock().unwrap();
+        let turn = self.reserve_turn(&mut state, now)?;
+        let turn = match state.last_download {
+            Some(last) => {
+                let gap = self.config.download_gap + jitter(self.config.download_jitter);
+                turn.max(last + gap)
+            }
+            None => turn,
+        };
+        state.last_download = Some(turn);
+        Ok(turn - now)
+    }
+
+    fn reserve_turn(&self, state: &mut PacerState, now: Instant) -> Result<Instant, CircuitOpen> {
+        if let Some(paused_until) = state.paused_until {
+            if now < paused_until {
+                return Err(CircuitOpen {
+                    failures: state.failures,
+                    retry_in: paused_until - now,
+                });
+            }
+            // one request is let through to probe, and pauses requests again if it fails
+            state.paused_until = None;
+            state.failures = self.config.failure_threshold.saturating_sub(1);
+        }
+
+        let turn = state.next_request.map_or(now, |next| next.max(now));
+        state.next_request = Some(turn + self.config.min_interval + jitter(self.config.jitter));
+        Ok(turn)
+    }
+}
+
+/// A random duration up to `max`
+fn jitter(max: Duration) -> Duration {
+    let millis = max.as_millis() as u64;
+    if millis == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(rand::thread_rng().gen_range(0..=millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pacer(failure_threshold: u32) -> Pacer {
+        Pacer::new(PacingConfig {
+            min_interval: Duration::from_secs(10),
+            jitter: Duration::ZERO,
+            failure_threshold,
+            cooldown: Duration::from_secs(60),
+            download_gap: Duration::from_secs(30),
+            download_jitter: Duration::ZERO,
+        })
+    }
+
+    #[test]
+    fn test_requests_are_spaced_out() {
+        let pacer = pacer(5);
+        let now = Instant::now();
+
+        assert_eq!(pacer.reserve(now).unwrap(), Duration::ZERO);
+        assert_eq!(pacer.reserve(now).unwrap(), Duration::from_secs(10));
+        assert_eq!(pacer.reserve(now).unwrap(), Duration::from_secs(20));
+        assert_eq!(
+            pacer.reserve(now + Duration::from_secs(60)).unwrap(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_downloads_are_kept_a_gap_apart() {
+        let pacer = pacer(5);
+        let now = Instant::now();
+
+        assert_eq!(pacer.reserve_download(now).unwrap(), Duration::ZERO);
+        // while the first download runs, the next starts a gap after it started
+        assert_eq!(
+            pacer.reserve_download(now).unwrap(),
+            Duration::from_secs(30)
+        );
+
+        pacer.record_download(true);
+        let wait = pacer.reserve_download(Instant::now()).unwrap();
+        assert!(wait > Duration::from_secs(29) && wait <= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_repeated_failures_pause_requests() {
+        let pacer = pacer(2);
+        pacer.record(false);
+        pacer.record(true);
+        pacer.record(false);
+        assert!(pacer.reserve(Instant::now()).is_ok());
+
+        pacer.record(false);
+        let paused = pacer.reserve(Instant::now()).unwrap_err();
+        assert_eq!(paused.failures, 2);
+
+        // after the cooldown a single failure pauses requests again
+        let later = Instant::now() + Duration::from_secs(61);
+        assert!(pacer.reserve(later).is_ok());
+        pacer.record(false);
+        assert!(pacer.reserve(Instant::now()).is_err());
+    }
+}
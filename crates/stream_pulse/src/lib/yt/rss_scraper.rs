@@ -0,0 +1,211 @@
+//! # Rss scraper
+//!
+//! Lists a channel's latest uploads from the Atom feed YouTube publishes for every channel
+//! at `feeds/videos.xml?channel_id=`. The feed's format has been stable for years, which
+//! makes it a dependable fallback for when `ytInitialData` extraction breaks. It only
+//! carries the 15 most recent uploads, and has no durations, so streams listed this way
+//! are not filtered by length.
+
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use stream_datastore::{Stream, StreamCategory};
+
+use crate::{
+    parser::YtHtmlDocument,
+    yt::{format_views, scrape_each, Channel, ChannelScraper},
+};
+
+static ENTRY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<entry>(.*?)</entry>").unwrap());
+static VIDEO_ID_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<yt:videoId>([^<]+)</yt:videoId>").unwrap());
+static TITLE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<title>([^<]*)</title>").unwrap());
+static PUBLISHED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<published>([^<]+)</published>").unwrap());
+static DESCRIPTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<media:description>(.*?)</media:description>").unwrap());
+static THUMBNAIL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<media:thumbnail url="([^"]+)""#).unwrap());
+static VIEWS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<media:statistics views="(\d+)""#).unwrap());
+
+pub struct RssChannelScraper {
+    client: reqwest::Client,
+    channels: Vec<Channel>,
+}
+
+impl Default for RssChannelScraper {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            channels: vec![Channel::new(Self::CHANNEL_URL)],
+        }
+    }
+}
+
+impl RssChannelScraper {
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Feeds to list streams from, instead of the Parliament of Kenya channel's.
+    /// Each channel's URL is its feed URL.
+    pub fn with_channels(mut self, channels: impl IntoIterator<Item = Channel>) -> Self {
+        let channels = channels.into_iter().collect::<Vec<_>>();
+        if !channels.is_empty() {
+            self.channels = channels;
+        }
+        self
+    }
+
+    async fn scrape_feed(&self, channel: &Channel) -> anyhow::Result<Vec<Stream>> {
+        let feed = self
+            .client
+            .get(&channel.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Ok(parse_feed(&feed))
+    }
+}
+
+impl ChannelScraper for RssChannelScraper {
+    const CHANNEL_URL: &str =
+        "https://www.youtube.com/feeds/videos.xml?channel_id=UCXuseB7juWB7DIgTJcwtHFQ";
+
+    type Error = anyhow::Error;
+
+    fn channel_urls(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .map(|channel| channel.url.clone())
+            .collect()
+    }
+
+    async fn scrape_channel(&self) -> anyhow::Result<YtHtmlDocument> {
+        anyhow::bail!(
+            "RssChannelScraper lists streams from channel feeds and fetches no html document"
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        scrape_each(&self.channels, |channel| self.scrape_feed(channel)).await
+    }
+}
+
+/// Parses the feed's entries, skipping upcoming streams, which have no views yet
+fn parse_feed(feed: &str) -> Vec<Stream> {
+    ENTRY_RE
+        .captures_iter(feed)
+        .filter_map(|entry| {
+            let entry = entry.get(1)?.as_str();
+            let capture = |re: &Regex| {
+                re.captures(entry)
+                    .and_then(|cap| cap.get(1))
+                    .map(|m| m.as_str())
+            };
+
+            let views = capture(&VIEWS_RE)?;
+            if views == "0" {
+                return None;
+            }
+
+            let published = capture(&PUBLISHED_RE)?;
+            let title = unescape(capture(&TITLE_RE)?);
+            let mut stream = Stream {
+                video_id: capture(&VIDEO_ID_RE)?.to_string(),
+                category: StreamCategory::from_title(&title),
+                title,
+                view_count: format_views(views),
+                streamed_date: published.to_string(),
+                thumbnail_url: capture(&THUMBNAIL_RE).map(unescape),
+                // the feed doesn't say which uploads were streamed
+                is_live_recording: true,
+                published_at_exact: DateTime::parse_from_rfc3339(published)
+                    .ok()
+                    .map(|date| date.with_timezone(&Utc)),
+                ..Default::default()
+            };
+            if let Some(description) = capture(&DESCRIPTION_RE) {
+                stream.set_description(unescape(description));
+            }
+            Some(stream)
+        })
+        .collect()
+}
+
+/// Decodes the entities XML escapes text with
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns:media="http://search.yahoo.com/mrss/" xmlns="http://www.w3.org/2005/Atom">
+ <title>Parliament of Kenya Channel</title>
+ <entry>
+  <yt:videoId>abc123</yt:videoId>
+  <title>Senate | Debate on the Finance Bill &amp; Appropriations</title>
+  <published>2025-03-04T12:00:14+00:00</published>
+  <media:group>
+   <media:title>Senate | Debate on the Finance Bill &amp; Appropriations</media:title>
+   <media:thumbnail url="https://i4.ytimg.com/vi/abc123/hqdefault.jpg" width="480" height="360"/>
+   <media:description>Order Paper &amp; Hansard: parliament.go.ke</media:description>
+   <media:community>
+    <media:statistics views="3882"/>
+   </media:community>
+  </media:group>
+ </entry>
+ <entry>
+  <yt:videoId>def456</yt:videoId>
+  <title>National Assembly | Morning Sitting</title>
+  <published>2025-03-05T06:00:00+00:00</published>
+  <media:group>
+   <media:community>
+    <media:statistics views="0"/>
+   </media:community>
+  </media:group>
+ </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_feed() {
+        let streams = parse_feed(FEED);
+
+        assert_eq!(streams.len(), 1, "upcoming stream should be skipped");
+        assert_eq!(streams[0].video_id, "abc123");
+        assert_eq!(
+            streams[0].title,
+            "Senate | Debate on the Finance Bill & Appropriations"
+        );
+        assert_eq!(streams[0].view_count, "3,882 views");
+        assert_eq!(
+            streams[0].thumbnail_url.as_deref(),
+            Some("https://i4.ytimg.com/vi/abc123/hqdefault.jpg")
+        );
+        assert_eq!(
+            streams[0].description.as_deref(),
+            Some("Order Paper & Hansard: parliament.go.ke")
+        );
+        assert_eq!(
+            streams[0].published_at_exact.unwrap().to_rfc3339(),
+            "2025-03-04T12:00:14+00:00"
+        );
+    }
+}
@@ -1,15 +1,371 @@
-use std::ops::Deref;
+use std::{
+    collections::HashMap,
+    future::Future,
+    ops::Deref,
+    sync::{Arc, LazyLock, Mutex},
+};
 
-use crate::yt::ChannelScraper;
+use rand::seq::SliceRandom;
+use regex::Regex;
+use reqwest::{
+    header::{ACCEPT_LANGUAGE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT},
+    StatusCode,
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use stream_datastore::Stream;
 
-#[derive(Default)]
-pub struct Scraper(reqwest::Client);
+use crate::{
+    parser::{parse_continuation, parse_streams_page, ParseFilters, YtHtmlDocument},
+    yt::{
+        innertube,
+        pacing::{CircuitOpen, Pacer},
+        scrape_each, Channel, ChannelScraper, VideoDetails,
+    },
+    RetryPolicy,
+};
+
+/// Browsers requests are sent as, one picked per request. Pages are parsed by their
+/// english text, e.g. "views" and "ago", so only english locales are rotated through.
+const USER_AGENTS: [&str; 3] = [
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.3 Safari/605.1.15",
+    "Mozilla/5.0 (X11; Linux x86_64; rv:135.0) Gecko/20100101 Firefox/135.0",
+];
+const ACCEPT_LANGUAGES: [&str; 3] = ["en-US,en;q=0.9", "en-GB,en;q=0.9", "en-US,en;q=0.8"];
+
+/// The parts of a channel page that say which streams it lists and which of them are
+/// live, leaving out view counts and "time ago" text that change on every fetch
+static LISTING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""(?:videoId|style)":"[^"]*""#).unwrap());
+
+/// Channel page listings seen by earlier runs, shared across runs so that a channel
+/// page that hasn't changed since every stream on it was processed can be skipped.
+/// YouTube rarely sends `ETag` or `Last-Modified` headers for channel pages, so
+/// listings are also compared by a hash of the videos on them.
+#[derive(Debug, Clone, Default)]
+pub struct PageCache(Arc<Mutex<PageCacheEntries>>);
+
+#[derive(Debug, Default)]
+struct PageCacheEntries {
+    /// Listings whose streams have all been processed, by page url
+    processed: HashMap<String, CachedListing>,
+    /// Listings fetched since the last processed run
+    pending: HashMap<String, CachedListing>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CachedListing {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    hash: [u8; 32],
+}
+
+impl PageCache {
+    fn processed(&self, url: &str) -> Option<CachedListing> {
+        self.0.lock().unwrap().processed.get(url).cloned()
+    }
+
+    fn fetched(&self, url: &str, listing: CachedListing) {
+        self.0
+            .lock()
+            .unwrap()
+            .pending
+            .insert(url.to_string(), listing);
+    }
+
+    /// Records the listings fetched since the last call as processed
+    fn mark_processed(&self) {
+        let mut entries = self.0.lock().unwrap();
+        let pending = std::mem::take(&mut entries.pending);
+        entries.processed.extend(pending);
+    }
+}
+
+/// A fetched page, with the validators it was served with
+struct FetchedPage {
+    doc: YtHtmlDocument,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub struct Scraper {
+    client: reqwest::Client,
+    channels: Vec<Channel>,
+    max_pages: usize,
+    filters: ParseFilters,
+    retry_policy: RetryPolicy,
+    page_cache: Option<PageCache>,
+    pacer: Option<Pacer>,
+}
+
+impl Default for Scraper {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            channels: vec![Channel::new(Self::CHANNEL_URL)],
+            max_pages: 1,
+            filters: ParseFilters::default(),
+            retry_policy: RetryPolicy::default(),
+            page_cache: None,
+            pacer: None,
+        }
+    }
+}
 
 impl Deref for Scraper {
     type Target = reqwest::Client;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.client
+    }
+}
+
+impl Scraper {
+    /// Channels to list streams from, instead of the Parliament of Kenya channel.
+    /// Streams are listed in channel order.
+    pub fn with_channels(mut self, channels: impl IntoIterator<Item = Channel>) -> Self {
+        let channels = channels.into_iter().collect::<Vec<_>>();
+        if !channels.is_empty() {
+            self.channels = channels;
+        }
+        self
+    }
+
+    /// Pages of the /streams tab to walk by following continuation tokens, 1 by default.
+    /// The first page holds about 30 streams, so backfill runs can set this high to
+    /// walk the entire channel history.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Which listed streams to keep, see [`ParseFilters`]
+    pub fn with_filters(mut self, filters: ParseFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    /// for its timeout settings. Replaced by [`Scraper::with_proxy`]
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Sends requests through `proxy`, e.g. `socks5://127.0.0.1:9050`, for when
+    /// YouTube throttles the host's own IP
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> reqwest::Result<Self> {
+        self.client = reqwest::Client::builder().proxy(proxy).build()?;
+        Ok(self)
+    }
+
+    /// How failed requests, including ones answered with a consent wall instead of
+    /// the page, are retried. 3 retries by default
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Skip channel pages that haven't changed since every stream on them was
+    /// processed, listing no streams from them. `page_cache` should outlive the
+    /// scraper, e.g. be shared by the scrapers of every scheduled run.
+    pub fn with_page_cache(mut self, page_cache: PageCache) -> Self {
+        self.page_cache = Some(page_cache);
+        self
+    }
+
+    /// Send requests in turns of `pacer`, which should be shared with everything else
+    /// that talks to YouTube, and stop sending them while it is paused
+    pub fn with_pacer(mut self, pacer: Pacer) -> Self {
+        self.pacer = Some(pacer);
+        self
+    }
+
+    /// Lists the streams of `channel`, following continuation tokens up to `max_pages`
+    async fn scrape_channel_streams(&self, channel: &Channel) -> anyhow::Result<Vec<Stream>> {
+        let Some(doc) = self.fetch_listing(&channel.url).await? else {
+            tracing::info!(channel = %channel.url, "Channel page unchanged, skipping");
+            return Ok(Vec::new());
+        };
+        let page = parse_streams_page(&doc.to_json::<Value>()?, &self.filters)?;
+        let mut streams = page.streams;
+        let mut continuation = page.continuation;
+
+        if self.max_pages == 1 {
+            return Ok(streams);
+        }
+        let Some(client_version) = doc.client_version() else {
+            tracing::warn!("No client version found on the channel page, skipping further pages");
+            return Ok(streams);
+        };
+
+        for page_number in 2..=self.max_pages {
+            let Some(token) = continuation.take() else {
+                break;
+            };
+
+            // pages already fetched are still worth processing, so a failed page ends the walk
+            let page = self
+                .browse(&token, client_version)
+                .await
+                .and_then(|json| Ok(parse_continuation(&json, &self.filters)?));
+            match page {
+                Ok(page) => {
+                    streams.extend(page.streams);
+                    continuation = page.continuation;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, page_number, "Failed to fetch next page of streams");
+                    break;
+                }
+            }
+        }
+
+        Ok(streams)
+    }
+
+    /// Fetches the channel page at `url`, or `None` if the page cache has it as
+    /// processed and it is unchanged
+    async fn fetch_listing(&self, url: &str) -> anyhow::Result<Option<YtHtmlDocument>> {
+        let Some(page_cache) = &self.page_cache else {
+            return self.fetch_document(url).await.map(Some);
+        };
+
+        let processed = page_cache.processed(url);
+        let Some(page) = self.fetch(url, processed.as_ref()).await? else {
+            return Ok(None);
+        };
+        let listing = CachedListing {
+            hash: listing_hash(&page.doc),
+            etag: page.etag,
+            last_modified: page.last_modified,
+        };
+        if processed.is_some_and(|processed| processed.hash == listing.hash) {
+            return Ok(None);
+        }
+        page_cache.fetched(url, listing);
+
+        Ok(Some(page.doc))
+    }
+
+    async fn fetch_document(&self, url: &str) -> anyhow::Result<YtHtmlDocument> {
+        let page = self.fetch(url, None).await?;
+        page.map(|page| page.doc)
+            .ok_or_else(|| anyhow::anyhow!("Unconditional fetch of {url} was not modified"))
+    }
+
+    /// Fetches the page at `url`, or `None` if it is unchanged since it was served
+    /// as `cached`
+    async fn fetch(
+        &self,
+        url: &str,
+        cached: Option<&CachedListing>,
+    ) -> anyhow::Result<Option<FetchedPage>> {
+        self.retry(|| {
+            self.paced(async move {
+                let (user_agent, accept_language) = {
+                    let mut rng = rand::thread_rng();
+                    (
+                        *USER_AGENTS.choose(&mut rng).unwrap(),
+                        *ACCEPT_LANGUAGES.choose(&mut rng).unwrap(),
+                    )
+                };
+                let mut request = self
+                    .get(url)
+                    .header(USER_AGENT, user_agent)
+                    .header(ACCEPT_LANGUAGE, accept_language);
+                if let Some(etag) = cached.and_then(|cached| cached.etag.as_deref()) {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) =
+                    cached.and_then(|cached| cached.last_modified.as_deref())
+                {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+                let response = request.send().await?;
+                if response.status() == StatusCode::NOT_MODIFIED {
+                    return Ok(None);
+                }
+                let response = response.error_for_status()?;
+
+                // served instead of the page in the EU, and to some datacenter IPs
+                if response
+                    .url()
+                    .host_str()
+                    .is_some_and(|host| host.starts_with("consent."))
+                {
+                    anyhow::bail!("Redirected to a consent page fetching {url}");
+                }
+                let header = |name: reqwest::header::HeaderName| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string)
+                };
+                let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+                let doc = YtHtmlDocument::from(response.text().await?);
+                if is_consent_wall(&doc) {
+                    anyhow::bail!("Served a consent page fetching {url}");
+                }
+
+                Ok(Some(FetchedPage {
+                    doc,
+                    etag,
+                    last_modified,
+                }))
+            })
+        })
+        .await
+    }
+
+    /// Fetches the page following `continuation` from the browse endpoint,
+    /// the way the channel page does when scrolled to the bottom
+    async fn browse(&self, continuation: &str, client_version: &str) -> anyhow::Result<Value> {
+        self.retry(|| {
+            let body = json!({ "continuation": continuation });
+            self.paced(innertube::post(
+                &self.client,
+                "browse",
+                client_version,
+                body,
+            ))
+        })
+        .await
+    }
+
+    /// Sends `request` in the pacer's turn, if the scraper has one
+    async fn paced<T>(
+        &self,
+        request: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        match &self.pacer {
+            Some(pacer) => pacer.run(request).await,
+            None => request.await,
+        }
+    }
+
+    /// Runs `attempt` until it succeeds or the retry policy's retries run out
+    async fn retry<T, Fut>(&self, mut attempt: impl FnMut() -> Fut) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                // retrying would only prolong the pause
+                Err(e) if e.is::<CircuitOpen>() => return Err(e),
+                Err(e) if retries < self.retry_policy.max_retries => {
+                    let delay = self.retry_policy.backoff(retries);
+                    retries += 1;
+                    tracing::warn!(error = %e, retries, ?delay, "YouTube request failed, retrying");
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 }
 
@@ -18,15 +374,121 @@ impl ChannelScraper for Scraper {
 
     type Error = anyhow::Error;
 
-    async fn scrape_channel(&self) -> Result<crate::parser::YtHtmlDocument, Self::Error> {
-        let yt_html_document = self
-            .get(Self::CHANNEL_URL)
-            .header("Accept-Language", "en-US,en;q=0.9")
-            .send()
-            .await?
-            .text()
+    fn channel_urls(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .map(|channel| channel.url.clone())
+            .collect()
+    }
+
+    /// Fetches the first channel's page
+    async fn scrape_channel(&self) -> Result<YtHtmlDocument, Self::Error> {
+        self.fetch_document(&self.channels[0].url).await
+    }
+
+    #[tracing::instrument(skip(self), fields(max_pages = self.max_pages))]
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        scrape_each(&self.channels, |channel| {
+            self.scrape_channel_streams(channel)
+        })
+        .await
+    }
+
+    /// Reads the live broadcast details or publish date, and the full description,
+    /// from the video's watch page
+    async fn resolve_details(&self, video_id: &str) -> anyhow::Result<VideoDetails> {
+        let doc = self
+            .fetch_document(&format!("https://www.youtube.com/watch?v={video_id}"))
             .await?;
+        Ok(VideoDetails {
+            published_at: doc.published_at(),
+            description: doc.description(),
+        })
+    }
+
+    async fn fetch_stream(&self, video_id: &str) -> anyhow::Result<Stream> {
+        let doc = self
+            .fetch_document(&format!("https://www.youtube.com/watch?v={video_id}"))
+            .await?;
+        innertube::player_stream(&doc.player_response()?)
+            .ok_or_else(|| anyhow::anyhow!("No video found for {video_id}"))
+    }
+
+    fn listing_processed(&self) {
+        if let Some(page_cache) = &self.page_cache {
+            page_cache.mark_processed();
+        }
+    }
+}
+
+/// Hashes the videos listed on a channel page and their live badges
+fn listing_hash(doc: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for listing in LISTING_RE.find_iter(doc) {
+        hasher.update(listing.as_str());
+    }
+    hasher.finalize().into()
+}
+
+/// Whether YouTube asked for cookie consent instead of serving the page
+fn is_consent_wall(doc: &YtHtmlDocument) -> bool {
+    !doc.contains("ytInitialData")
+        && !doc.contains("ytInitialPlayerResponse")
+        && doc.contains("consent.youtube.com")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_consent_wall() {
+        let consent = YtHtmlDocument::new(
+            r#"<form action="https://consent.youtube.com/save" method="POST"></form>"#.into(),
+        );
+        assert!(is_consent_wall(&consent));
+
+        let page = YtHtmlDocument::new(include_str!("../../../tests/fixtures/yt.html").into());
+        assert!(!is_consent_wall(&page));
+    }
+
+    #[test]
+    fn test_listing_hash() {
+        let page = include_str!("../../../tests/fixtures/yt.html");
+        let video_id = LISTING_RE
+            .find_iter(page)
+            .find(|listing| listing.as_str().starts_with(r#""videoId""#))
+            .unwrap()
+            .as_str();
+
+        assert_eq!(listing_hash(page), listing_hash(page));
+        assert_eq!(
+            listing_hash(page),
+            listing_hash(&page.replacen(" views", " views ", 1))
+        );
+        assert_ne!(
+            listing_hash(page),
+            listing_hash(&page.replacen(video_id, r#""videoId":"newStream01""#, 1))
+        );
+        assert_ne!(
+            listing_hash(page),
+            listing_hash(&page.replacen(r#""style":"LIVE""#, r#""style":"DEFAULT""#, 1))
+        );
+    }
+
+    #[test]
+    fn test_page_cache_records_listings_once_processed() {
+        let page_cache = PageCache::default();
+        let listing = CachedListing {
+            etag: None,
+            last_modified: None,
+            hash: [0; 32],
+        };
+
+        page_cache.fetched(Scraper::CHANNEL_URL, listing.clone());
+        assert_eq!(page_cache.processed(Scraper::CHANNEL_URL), None);
 
-        Ok(yt_html_document.into())
+        page_cache.mark_processed();
+        assert_eq!(page_cache.processed(Scraper::CHANNEL_URL), Some(listing));
     }
 }
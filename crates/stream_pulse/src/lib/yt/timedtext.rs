@@ -0,0 +1,255 @@
+//! # Timedtext captions
+//!
+//! Reads the captions YouTube generates for most videos with speech recognition, from
+//! the `timedtext` tracks listed in the video's player response, as a transcript. They
+//! are rougher than a Whisper transcription, and have no punctuation, but are often good
+//! enough to summarize a sitting from, and cost nothing to fetch.
+
+use std::{fmt::Debug, future::Future};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    yt::innertube::{self, WEB_CLIENT_VERSION},
+    TranscribeResponse, TranscribeSegment,
+};
+
+/// Transcribes a video from captions published with it, instead of its audio
+pub trait CaptionSource {
+    type Error: Debug;
+
+    /// The transcript of the video with `video_id`, or `None` if it has no captions
+    fn fetch_captions(
+        &self,
+        video_id: &str,
+    ) -> impl Future<Output = Result<Option<TranscribeResponse>, Self::Error>> + Send;
+}
+
+/// Placeholder for processors built without a [`CaptionSource`]. It has no values,
+/// so it can never actually be called.
+#[derive(Debug, Clone, Copy)]
+pub enum NoCaptionSource {}
+
+impl CaptionSource for NoCaptionSource {
+    type Error = std::convert::Infallible;
+
+    async fn fetch_captions(
+        &self,
+        _video_id: &str,
+    ) -> Result<Option<TranscribeResponse>, Self::Error> {
+        match *self {}
+    }
+}
+
+/// Fetches a video's caption tracks through `youtubei/v1/player`. Tracks uploaded by the
+/// channel are preferred to generated ones in the same language.
+#[derive(Debug, Clone)]
+pub struct CaptionTranscriber {
+    client: reqwest::Client,
+    client_version: String,
+    languages: Vec<String>,
+}
+
+impl Default for CaptionTranscriber {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::default(),
+            client_version: WEB_CLIENT_VERSION.to_string(),
+            languages: vec!["en".to_string()],
+        }
+    }
+}
+
+impl CaptionTranscriber {
+    /// Language codes of the tracks to read, most preferred first, instead of `en`.
+    /// Videos with captions in none of them are transcribed from their audio.
+    pub fn with_languages(
+        mut self,
+        languages: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let languages = languages.into_iter().map(Into::into).collect::<Vec<_>>();
+        if !languages.is_empty() {
+            self.languages = languages;
+        }
+        self
+    }
+
+    /// Sends requests through `client`, e.g. one shared with the rest of the run
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// See [`InnertubeScraper::with_client_version`]
+    ///
+    /// [`InnertubeScraper::with_client_version`]: crate::yt::innertube::InnertubeScraper::with_client_version
+    pub fn with_client_version(mut self, client_version: impl Into<String>) -> Self {
+        self.client_version = client_version.into();
+        self
+    }
+}
+
+impl CaptionSource for CaptionTranscriber {
+    type Error = anyhow::Error;
+
+    #[tracing::instrument(skip(self))]
+    async fn fetch_captions(&self, video_id: &str) -> anyhow::Result<Option<TranscribeResponse>> {
+        let player = innertube::post(
+            &self.client,
+            "player",
+            &self.client_version,
+            json!({ "videoId": video_id }),
+        )
+        .await?;
+        let Some(base_url) = caption_track(&player, &self.languages) else {
+            return Ok(None);
+        };
+
+        let timedtext = self
+            .client
+            .get(base_url)
+            .query(&[("fmt", "json3")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TimedText>()
+            .await?;
+
+        Ok(timedtext.into_transcript())
+    }
+}
+
+/// URL of the preferred track among those listed in a `player` response
+fn caption_track<'a>(player: &'a Value, languages: &[String]) -> Option<&'a str> {
+    let tracks =
+        player["captions"]["playerCaptionsTracklistRenderer"]["captionTracks"].as_array()?;
+
+    languages.iter().find_map(|language| {
+        let mut in_language = tracks
+            .iter()
+            .filter(|track| track["languageCode"].as_str() == Some(language.as_str()));
+        in_language
+            .clone()
+            .find(|track| track["kind"].as_str() != Some("asr"))
+            .or_else(|| in_language.next())
+            .and_then(|track| track["baseUrl"].as_str())
+    })
+}
+
+/// A caption track in the `json3` format
+#[derive(Debug, Deserialize)]
+struct TimedText {
+    #[serde(default)]
+    events: Vec<TimedTextEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimedTextEvent {
+    t_start_ms: u64,
+    #[serde(default)]
+    d_duration_ms: u64,
+    #[serde(default)]
+    segs: Vec<TimedTextSeg>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimedTextSeg {
+    #[serde(default)]
+    utf8: String,
+}
+
+impl TimedText {
+    /// Joins the words of each caption into a segment. Events that only break the
+    /// line of the caption on screen carry no words, and are skipped.
+    fn into_transcript(self) -> Option<TranscribeResponse> {
+        let segments = self
+            .events
+            .into_iter()
+            .filter_map(|event| {
+                let text = event
+                    .segs
+                    .into_iter()
+                    .map(|seg| seg.utf8)
+                    .collect::<String>();
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                (!text.is_empty()).then(|| TranscribeSegment {
+                    start: event.t_start_ms as f64 / 1000.0,
+                    end: (event.t_start_ms + event.d_duration_ms) as f64 / 1000.0,
+                    text,
+                    ..Default::default()
+                })
+            })
+            .collect::<Vec<_>>();
+        if segments.is_empty() {
+            return None;
+        }
+
+        Some(TranscribeResponse {
+            duration: segments.iter().map(|s| s.end).fold(0.0, f64::max),
+            text: segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            segments: Some(segments),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caption_track() {
+        let player = json!({
+            "captions": { "playerCaptionsTracklistRenderer": { "captionTracks": [
+                { "baseUrl": "https://www.youtube.com/api/timedtext?v=abc&lang=en&kind=asr", "languageCode": "en", "kind": "asr" },
+                { "baseUrl": "https://www.youtube.com/api/timedtext?v=abc&lang=sw", "languageCode": "sw" },
+                { "baseUrl": "https://www.youtube.com/api/timedtext?v=abc&lang=en", "languageCode": "en" },
+            ] } }
+        });
+        let languages = |codes: &[&str]| codes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            caption_track(&player, &languages(&["en"])),
+            Some("https://www.youtube.com/api/timedtext?v=abc&lang=en")
+        );
+        assert_eq!(
+            caption_track(&player, &languages(&["fr", "sw"])),
+            Some("https://www.youtube.com/api/timedtext?v=abc&lang=sw")
+        );
+        assert_eq!(caption_track(&player, &languages(&["fr"])), None);
+        assert_eq!(caption_track(&json!({}), &languages(&["en"])), None);
+    }
+
+    #[test]
+    fn test_timedtext_into_transcript() {
+        let timedtext = serde_json::from_value::<TimedText>(json!({
+            "events": [
+                { "tStartMs": 0, "dDurationMs": 3_000_000, "id": 1, "wpWinPosId": 1 },
+                { "tStartMs": 1200, "dDurationMs": 4000, "segs": [
+                    { "utf8": "order" }, { "utf8": " order", "tOffsetMs": 400 }, { "utf8": " honourable\nmembers", "tOffsetMs": 900 }
+                ] },
+                { "tStartMs": 5200, "dDurationMs": 10, "aAppend": 1, "segs": [{ "utf8": "\n" }] },
+                { "tStartMs": 5210, "dDurationMs": 3500, "segs": [{ "utf8": "the house is called to order" }] },
+            ]
+        }))
+        .unwrap();
+
+        let transcript = timedtext.into_transcript().unwrap();
+        assert_eq!(
+            transcript.text,
+            "order order honourable members the house is called to order"
+        );
+        assert_eq!(transcript.duration, 8.71);
+        let segments = transcript.segments.unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!((segments[0].start, segments[0].end), (1.2, 5.2));
+
+        let empty = serde_json::from_value::<TimedText>(json!({ "events": [] })).unwrap();
+        assert!(empty.into_transcript().is_none());
+    }
+}
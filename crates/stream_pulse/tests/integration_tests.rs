@@ -1,11 +1,28 @@
 mod mocks;
 
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+#[cfg(feature = "pgvector")]
+use mocks::embedder::MockEmbedder;
 use mocks::{
-    audio_handler::MockAudioHandler, channel_scraper::MockChannelScraper, datastore::MockDataStore,
+    audio_handler::MockAudioHandler, caption_source::MockCaptionSource,
+    category_classifier::MockCategoryClassifier, channel_scraper::MockChannelScraper,
+    datastore::MockDataStore, division_extractor::MockDivisionExtractor,
+    entity_extractor::MockEntityExtractor, order_paper_source::MockOrderPaperSource,
     summarizer::MockSummarizer, transcriber::MockTranscriber,
 };
-use std::collections::HashSet;
-use stream_pulse::{AudioInput, LiveStreamProcessorBuilder};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+use stream_datastore::{
+    PipelineStage, StoredStream, Stream, StreamCategory, StreamState, StreamStatus,
+};
+use stream_pulse::{
+    yt::ChannelScraper, AudioInput, BuilderError, CaptionDestination, CaptionFormat, CleanupConfig,
+    LiveStreamProcessorBuilder, ProcessorEvent, ProcessorEvents, ProcessorHook, ReuploadPolicy,
+    RunReport, StreamRetryPolicy,
+};
 
 fn build_processor(
     store: MockDataStore,
@@ -47,6 +64,7 @@ async fn test_happy_path_processes_max_streams() {
     let inserted = store.inserted.clone();
     let transcriber_calls = transcriber.calls.clone();
     let summarizer_calls = summarizer.calls.clone();
+    let summarizer_contexts = summarizer.contexts.clone();
     let audio_calls = audio_handler.calls.clone();
 
     let processor = build_processor(
@@ -105,6 +123,188 @@ async fn test_happy_path_processes_max_streams() {
             Some("## Summary\nKey points discussed in parliament.")
         );
     }
+
+    // Each stream's metadata should be passed to the summarizer
+    let summarizer_contexts = summarizer_contexts.lock().unwrap();
+    let summarized_titles = summarizer_contexts
+        .iter()
+        .map(|context| context.title.as_str())
+        .collect::<Vec<_>>();
+    let inserted_titles = inserted
+        .iter()
+        .map(|stream| stream.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(summarized_titles, inserted_titles);
+}
+
+#[tokio::test]
+async fn test_run_reports_what_it_did() {
+    let processor = build_processor(
+        MockDataStore::default(),
+        MockTranscriber::new("transcript"),
+        MockSummarizer::new("summary"),
+        MockAudioHandler::default(),
+        MockChannelScraper::from_fixture(),
+        2,
+    );
+    let report = processor.run().await.unwrap();
+
+    assert_eq!(report.processed.len(), 2);
+    assert_eq!(report.filtered, report.discovered - 2);
+    assert_eq!(report.failed, None);
+    assert!(report.errors.is_empty());
+    let stages = report.stage_durations.keys().copied().collect::<Vec<_>>();
+    assert_eq!(
+        stages,
+        ["clean", "download", "store", "summarize", "transcribe"]
+    );
+
+    let processor = build_processor(
+        MockDataStore::default(),
+        MockTranscriber::new("transcript"),
+        MockSummarizer::failing_after(1, "GPT-4 rate limit"),
+        MockAudioHandler::default(),
+        MockChannelScraper::from_fixture(),
+        2,
+    );
+    let err = processor.run().await.unwrap_err();
+    let report = err.downcast_ref::<RunReport>().unwrap();
+    assert_eq!(report.processed.len(), 1);
+    assert!(report.failed.is_some());
+    assert!(report.errors[0].contains("GPT-4 rate limit"));
+}
+
+#[tokio::test]
+async fn test_exact_publish_dates_are_resolved() {
+    let published_at = "2025-03-04T12:00:14Z".parse::<DateTime<Utc>>().unwrap();
+
+    let store = MockDataStore::default();
+    let transcriber = MockTranscriber::new("transcript");
+    let summarizer = MockSummarizer::new("summary");
+    let audio_handler = MockAudioHandler::default();
+    let scraper = MockChannelScraper::from_fixture().with_published_at(published_at);
+    let inserted = store.inserted.clone();
+    let summarizer_contexts = summarizer.contexts.clone();
+
+    let processor = build_processor(store, transcriber, summarizer, audio_handler, scraper, 2);
+    processor.run().await.expect("Processor should succeed");
+
+    let inserted = inserted.lock().unwrap();
+    assert_eq!(inserted.len(), 2);
+    assert!(inserted
+        .iter()
+        .all(|stream| stream.published_at_exact == Some(published_at)));
+    assert!(summarizer_contexts
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|context| context.streamed_at == Some(published_at)));
+}
+
+#[tokio::test]
+async fn test_live_streams_are_recorded_not_processed() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let live = store.live.clone();
+
+    let processor = build_processor(
+        store,
+        MockTranscriber::new("transcript"),
+        MockSummarizer::new("summary"),
+        MockAudioHandler::default(),
+        MockChannelScraper::from_fixture(),
+        2,
+    );
+    processor.run().await.expect("Processor should succeed");
+
+    let live = live.lock().unwrap();
+    assert_eq!(live.len(), 2);
+    assert!(live.iter().all(|s| s.summary_md.is_none()));
+    assert!(inserted
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|s| live.iter().all(|l| l.video_id != s.video_id)));
+}
+
+#[tokio::test]
+async fn test_full_descriptions_are_resolved() {
+    let store = MockDataStore::default();
+    let summarizer = MockSummarizer::new("summary");
+    let scraper = MockChannelScraper::from_fixture()
+        .with_description("Order Paper\n0:00 Prayers\n4:30 Statements\n1:02:15 The Finance Bill");
+    let inserted = store.inserted.clone();
+    let summarizer_contexts = summarizer.contexts.clone();
+
+    let processor = build_processor(
+        store,
+        MockTranscriber::new("transcript"),
+        summarizer,
+        MockAudioHandler::default(),
+        scraper,
+        1,
+    );
+    processor.run().await.expect("Processor should succeed");
+
+    let inserted = inserted.lock().unwrap();
+    assert_eq!(inserted.len(), 1);
+    assert!(inserted[0]
+        .description
+        .as_deref()
+        .is_some_and(|d| d.starts_with("Order Paper")));
+    assert_eq!(inserted[0].chapters.as_ref().unwrap().len(), 3);
+    assert!(summarizer_contexts
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|context| context.chapters.len() == 3));
+}
+
+#[tokio::test]
+async fn test_ambiguous_titles_are_classified() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let classifier = MockCategoryClassifier::new(StreamCategory::Senate);
+    let titles = classifier.titles.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture().with_title("Special Sitting"))
+        .category_classifier(classifier)
+        .max_streams(2)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    assert_eq!(titles.lock().unwrap().len(), 2);
+    assert!(inserted
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|stream| stream.category() == StreamCategory::Senate));
+}
+
+#[tokio::test]
+async fn test_titles_naming_a_house_are_not_classified() {
+    let classifier = MockCategoryClassifier::new(StreamCategory::Committee);
+    let titles = classifier.titles.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(MockDataStore::default())
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .category_classifier(classifier)
+        .max_streams(2)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    assert!(titles.lock().unwrap().is_empty());
 }
 
 // ─── Chunking ────────────────────────────────────────────────────────────────
@@ -142,6 +342,316 @@ async fn test_chunked_audio_input_when_chunking_enabled() {
     }
 }
 
+#[tokio::test]
+async fn test_audio_is_transcribed_as_downloaded_without_cleanup() {
+    let transcriber = MockTranscriber::new("transcript");
+    let transcriber_calls = transcriber.calls.clone();
+    let audio_handler = MockAudioHandler::default();
+    let cleaned = audio_handler.cleaned.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(MockDataStore::default())
+        .transcriber(transcriber)
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(audio_handler)
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(1)
+        .with_cleanup(CleanupConfig::none())
+        .build();
+    processor.run().await.unwrap();
+
+    assert!(cleaned.lock().unwrap().is_empty());
+    assert_eq!(transcriber_calls.lock().unwrap().len(), 1);
+}
+
+// ─── Entity and division extraction ──────────────────────────────────────────
+
+#[tokio::test]
+async fn test_extracted_entities_are_stored_per_stream() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let entities = store.entities.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .entity_extractor(MockEntityExtractor::new("Moses Wetang'ula"))
+        .max_streams(2)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    let inserted = inserted.lock().unwrap();
+    let entities = entities.lock().unwrap();
+    assert_eq!(entities.len(), 2);
+    for ((video_id, entities), stream) in entities.iter().zip(inserted.iter()) {
+        assert_eq!(video_id, &stream.video_id);
+        assert_eq!(entities.members[0].name, "Moses Wetang'ula");
+    }
+}
+
+#[tokio::test]
+async fn test_entity_extraction_failure_does_not_fail_stream() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let entities = store.entities.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .entity_extractor(MockEntityExtractor::failing("model unavailable"))
+        .max_streams(1)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    assert_eq!(inserted.lock().unwrap().len(), 1);
+    assert!(entities.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_extracted_divisions_are_stored_per_stream() {
+    let store = MockDataStore::default();
+    let divisions = store.divisions.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .division_extractor(MockDivisionExtractor::new(
+            "That the Finance Bill be now read a Second Time",
+        ))
+        .max_streams(2)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    let divisions = divisions.lock().unwrap();
+    assert_eq!(divisions.len(), 2);
+    assert!(divisions.iter().all(|(_, d)| d[0].ayes == Some(195)));
+}
+
+// ─── Order papers ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_order_papers_are_summarized_and_stored_with_streams() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let summarizer = MockSummarizer::new("summary");
+    let contexts = summarizer.contexts.clone();
+    let order_paper_source = MockOrderPaperSource::new(&["PRAYERS", "THE FINANCE BILL, 2025"]);
+    let looked_up = order_paper_source.calls.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(summarizer)
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .order_paper_source(order_paper_source)
+        .max_streams(2)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    assert_eq!(looked_up.lock().unwrap().len(), 2);
+    let inserted = inserted.lock().unwrap();
+    assert_eq!(inserted.len(), 2);
+    for stream in inserted.iter() {
+        let order_paper = stream.order_paper.as_ref().unwrap();
+        assert!(order_paper
+            .url
+            .ends_with(&format!("{}.pdf", stream.video_id)));
+    }
+    assert!(contexts
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|context| context.agenda == ["PRAYERS", "THE FINANCE BILL, 2025"]));
+}
+
+// ─── Embeddings ──────────────────────────────────────────────────────────────
+
+#[cfg(feature = "pgvector")]
+#[tokio::test]
+async fn test_summary_and_transcript_embeddings_are_stored() {
+    let store = MockDataStore::default();
+    let embeddings = store.embeddings.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .embedder(MockEmbedder::new())
+        .max_streams(1)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    let embeddings = embeddings.lock().unwrap();
+    assert_eq!(embeddings.len(), 1);
+    let (_, embeddings) = &embeddings[0];
+    assert_eq!(embeddings.summary, vec!["summary".len() as f32]);
+    assert_eq!(embeddings.chunks.len(), 1);
+    assert_eq!(embeddings.chunks[0].content, "transcript");
+}
+
+#[cfg(feature = "pgvector")]
+#[tokio::test]
+async fn test_embedding_failure_does_not_fail_stream() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let embeddings = store.embeddings.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .embedder(MockEmbedder::failing("model unavailable"))
+        .max_streams(1)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    assert_eq!(inserted.lock().unwrap().len(), 1);
+    assert!(embeddings.lock().unwrap().is_empty());
+}
+
+// ─── Timestamp links ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_cited_timestamps_are_linked_to_the_stream() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new(
+            "- The Speaker took the chair [00:01:05]",
+        ))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .with_timestamp_links(true)
+        .max_streams(1)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    let inserted = inserted.lock().unwrap();
+    let stream = &inserted[0];
+    assert_eq!(
+        stream.summary_md.as_deref(),
+        Some(
+            format!(
+                "- The Speaker took the chair [00:01:05](https://youtu.be/{}?t=65)",
+                stream.video_id
+            )
+            .as_str()
+        )
+    );
+}
+
+// ─── Captions ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_captions_are_stored_per_stream() {
+    let store = MockDataStore::default();
+    let captions = store.captions.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::segmented("Order, order."))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .with_captions(
+            [CaptionFormat::Srt, CaptionFormat::WebVtt],
+            CaptionDestination::DataStore,
+        )
+        .max_streams(1)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    let captions = captions.lock().unwrap();
+    assert_eq!(captions.len(), 2);
+    assert_eq!(captions[0].1, "srt");
+    assert_eq!(
+        captions[0].2,
+        "1\n00:00:00,000 --> 00:02:00,000\nOrder, order.\n\n"
+    );
+    assert!(captions[1].2.starts_with("WEBVTT\n\n"));
+}
+
+#[tokio::test]
+async fn test_captioned_streams_skip_audio_transcription() {
+    let audio_handler = MockAudioHandler::default();
+    let downloads = audio_handler.calls.clone();
+    let transcriber = MockTranscriber::new("whisper transcript");
+    let transcriptions = transcriber.calls.clone();
+    let summarizer = MockSummarizer::new("summary");
+    let summarized = summarizer.calls.clone();
+    let caption_source = MockCaptionSource::new("caption transcript");
+    let fetched = caption_source.calls.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(MockDataStore::default())
+        .transcriber(transcriber)
+        .summarizer(summarizer)
+        .audio_handler(audio_handler)
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .caption_source(caption_source)
+        .max_streams(2)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    assert_eq!(fetched.lock().unwrap().len(), 2);
+    assert!(downloads.lock().unwrap().is_empty());
+    assert!(transcriptions.lock().unwrap().is_empty());
+    let summarized = summarized.lock().unwrap();
+    assert!(!summarized.is_empty());
+    assert!(summarized
+        .iter()
+        .all(|transcript| transcript == "caption transcript"));
+}
+
+#[tokio::test]
+async fn test_uncaptioned_streams_are_transcribed_from_audio() {
+    let audio_handler = MockAudioHandler::default();
+    let downloads = audio_handler.calls.clone();
+    let transcriber = MockTranscriber::new("whisper transcript");
+    let transcriptions = transcriber.calls.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(MockDataStore::default())
+        .transcriber(transcriber)
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(audio_handler)
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .caption_source(MockCaptionSource::uncaptioned())
+        .max_streams(2)
+        .build();
+
+    processor.run().await.expect("Pipeline should succeed");
+
+    assert_eq!(downloads.lock().unwrap().len(), 2);
+    assert_eq!(transcriptions.lock().unwrap().len(), 2);
+}
+
 // ─── Filtering ───────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -231,6 +741,551 @@ async fn test_max_streams_limits_processing() {
     assert_eq!(inserted.len(), 2, "Should respect max_streams limit of 2");
 }
 
+#[tokio::test]
+async fn test_tracked_streams_are_processed_first() {
+    let probe_store = MockDataStore::default();
+    let probe_inserted = probe_store.inserted.clone();
+    let processor = build_processor(
+        probe_store,
+        MockTranscriber::new("transcript"),
+        MockSummarizer::new("summary"),
+        MockAudioHandler::default(),
+        MockChannelScraper::from_fixture(),
+        30,
+    );
+    processor.run().await.expect("Probe run should succeed");
+    // streams are otherwise processed oldest first
+    let newest = probe_inserted
+        .lock()
+        .unwrap()
+        .last()
+        .unwrap()
+        .video_id
+        .clone();
+
+    let store = MockDataStore {
+        tracked_ids: HashSet::from([newest.clone()]),
+        ..Default::default()
+    };
+    let inserted = store.inserted.clone();
+    let processor = build_processor(
+        store,
+        MockTranscriber::new("transcript"),
+        MockSummarizer::new("summary"),
+        MockAudioHandler::default(),
+        MockChannelScraper::from_fixture(),
+        1,
+    );
+    processor.run().await.expect("Pipeline should succeed");
+
+    let inserted = inserted.lock().unwrap();
+    assert_eq!(inserted.len(), 1);
+    assert_eq!(inserted[0].video_id, newest);
+}
+
+#[tokio::test]
+async fn test_uploads_are_skipped_when_live_recordings_only() {
+    let build = |scraper: MockChannelScraper| {
+        let store = MockDataStore::default();
+        let inserted = store.inserted.clone();
+        let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+            .store(store)
+            .transcriber(MockTranscriber::new("transcript"))
+            .summarizer(MockSummarizer::new("summary"))
+            .audio_handler(MockAudioHandler::default())
+            .channel_scraper(scraper)
+            .with_live_recordings_only(true)
+            .max_streams(2)
+            .build();
+        (processor, inserted)
+    };
+
+    let (processor, inserted) = build(MockChannelScraper::from_fixture().with_uploads());
+    processor.run().await.expect("Pipeline should succeed");
+    assert!(inserted.lock().unwrap().is_empty());
+
+    let (processor, inserted) = build(MockChannelScraper::from_fixture());
+    processor.run().await.expect("Pipeline should succeed");
+    assert_eq!(inserted.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_listing_is_processed_only_without_a_backlog() {
+    let run = |max_streams| async move {
+        let scraper = MockChannelScraper::from_fixture();
+        let listings_processed = scraper.listings_processed.clone();
+        let processor = build_processor(
+            MockDataStore::default(),
+            MockTranscriber::new("transcript"),
+            MockSummarizer::new("summary"),
+            MockAudioHandler::default(),
+            scraper,
+            max_streams,
+        );
+        processor.run().await.expect("Pipeline should succeed");
+        let listings_processed = *listings_processed.lock().unwrap();
+        listings_processed
+    };
+
+    assert_eq!(
+        run(2).await,
+        0,
+        "Streams beyond the limit are left unprocessed"
+    );
+    assert_eq!(run(100).await, 1);
+}
+
+#[tokio::test]
+async fn test_explicit_video_ids_are_processed_even_when_stored() {
+    let listed = MockChannelScraper::from_fixture()
+        .scrape_streams()
+        .await
+        .unwrap();
+    let video_id = listed
+        .iter()
+        .rfind(|s| s.status == StreamStatus::Archived)
+        .unwrap()
+        .video_id
+        .clone();
+
+    let store = MockDataStore {
+        existing_ids: HashSet::from([video_id.clone()]),
+        ..Default::default()
+    };
+    let inserted = store.inserted.clone();
+    let scraper = MockChannelScraper::from_fixture();
+    let listings_processed = scraper.listings_processed.clone();
+
+    let processor = build_processor(
+        store,
+        MockTranscriber::new("transcript"),
+        MockSummarizer::new("summary"),
+        MockAudioHandler::default(),
+        scraper,
+        1,
+    );
+    processor
+        .process_video_ids(&[&video_id, &video_id])
+        .await
+        .unwrap();
+
+    let inserted = inserted.lock().unwrap();
+    assert_eq!(inserted.len(), 1);
+    assert_eq!(inserted[0].video_id, video_id);
+    assert!(inserted[0].summary_md.is_some());
+    // the channels weren't listed
+    assert_eq!(*listings_processed.lock().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_unknown_video_ids_fail_the_run() {
+    let processor = build_processor(
+        MockDataStore::default(),
+        MockTranscriber::new("transcript"),
+        MockSummarizer::new("summary"),
+        MockAudioHandler::default(),
+        MockChannelScraper::from_fixture(),
+        1,
+    );
+    let err = processor.process_video_ids(&["unknown"]).await.unwrap_err();
+    assert!(format!("{err:?}").contains("Failed to look up video unknown"));
+}
+
+#[tokio::test]
+async fn test_discovered_streams_are_not_processed() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let audio_handler = MockAudioHandler::default();
+    let downloads = audio_handler.calls.clone();
+    let scraper = MockChannelScraper::from_fixture();
+    let listings_processed = scraper.listings_processed.clone();
+
+    let processor = build_processor(
+        store,
+        MockTranscriber::new("transcript"),
+        MockSummarizer::new("summary"),
+        audio_handler,
+        scraper,
+        100,
+    );
+    let streams = processor.discover_streams().await.unwrap();
+
+    assert!(!streams.is_empty());
+    assert!(streams.iter().all(|s| s.status == StreamStatus::Archived));
+    assert!(inserted.lock().unwrap().is_empty());
+    assert!(downloads.lock().unwrap().is_empty());
+    assert_eq!(*listings_processed.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_failed_streams_are_retried_after_their_backoff() {
+    let failed = Arc::new(Mutex::new(Vec::new()));
+    let inserted = Arc::new(Mutex::new(Vec::new()));
+    let build = |summarizer: MockSummarizer| {
+        LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+            .store(MockDataStore {
+                failed: failed.clone(),
+                inserted: inserted.clone(),
+                ..Default::default()
+            })
+            .transcriber(MockTranscriber::new("transcript"))
+            .summarizer(summarizer)
+            .audio_handler(MockAudioHandler::default())
+            .channel_scraper(MockChannelScraper::from_fixture())
+            .max_streams(1)
+            .with_retries(Some(StreamRetryPolicy {
+                max_attempts: 3,
+                backoff: std::time::Duration::from_secs(60 * 60),
+            }))
+            .build()
+    };
+
+    assert!(build(MockSummarizer::failing("GPT-4 rate limit"))
+        .run()
+        .await
+        .is_err());
+    let failed_id = {
+        let failed = failed.lock().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, 1);
+        assert!(failed[0].last_error.contains("GPT-4 rate limit"));
+        failed[0].video_id.clone()
+    };
+
+    // the failed stream waits out its backoff while the next one is processed
+    build(MockSummarizer::new("summary")).run().await.unwrap();
+    assert_eq!(inserted.lock().unwrap().len(), 1);
+    assert_ne!(inserted.lock().unwrap()[0].video_id, failed_id);
+
+    failed.lock().unwrap()[0].last_failed_at -= chrono::TimeDelta::hours(2);
+    build(MockSummarizer::new("summary")).run().await.unwrap();
+    assert_eq!(inserted.lock().unwrap()[1].video_id, failed_id);
+    assert!(failed.lock().unwrap().is_empty());
+}
+
+/// A store holding an earlier upload of each of the fixture's streams, as "orig-{id}"
+async fn store_with_originals() -> MockDataStore {
+    let listed = MockChannelScraper::from_fixture()
+        .scrape_streams()
+        .await
+        .unwrap();
+    let stored = listed
+        .iter()
+        .map(|s| StoredStream {
+            video_id: format!("orig-{}", s.video_id),
+            title: s.title.clone(),
+            duration: s.duration.clone(),
+            stream_timestamp: s.published_at().unwrap_or_else(Utc::now)
+                - chrono::TimeDelta::hours(1),
+        })
+        .collect();
+    MockDataStore {
+        stored,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_reuploads_are_linked_to_their_stored_stream() {
+    let store = store_with_originals().await;
+    let inserted = store.inserted.clone();
+    let aliases = store.aliases.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(3)
+        .with_reupload_detection(Some(ReuploadPolicy::Link))
+        .build();
+    processor.run().await.unwrap();
+
+    assert!(inserted.lock().unwrap().is_empty());
+    let aliases = aliases.lock().unwrap();
+    assert!(!aliases.is_empty());
+    assert!(aliases
+        .iter()
+        .all(|(video_id, original)| *original == format!("orig-{video_id}")));
+}
+
+#[tokio::test]
+async fn test_reuploads_replace_their_stored_stream() {
+    let store = store_with_originals().await;
+    let inserted = store.inserted.clone();
+    let replaced = store.replaced.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(1)
+        .with_reupload_detection(Some(ReuploadPolicy::Replace))
+        .build();
+    processor.run().await.unwrap();
+
+    let video_id = inserted.lock().unwrap()[0].video_id.clone();
+    assert_eq!(
+        *replaced.lock().unwrap(),
+        vec![(format!("orig-{video_id}"), video_id)]
+    );
+}
+
+#[tokio::test]
+async fn test_sources_are_processed_in_turn_within_their_quotas() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let senate = MockChannelScraper::from_fixture()
+        .with_title("Special Sitting")
+        .with_video_id_prefix("senate-");
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(2)
+        .with_source(senate, Some(StreamCategory::Senate), 1)
+        .build();
+    processor.run().await.unwrap();
+
+    let inserted = inserted.lock().unwrap();
+    let from_senate = inserted
+        .iter()
+        .map(|s| s.video_id.starts_with("senate-"))
+        .collect::<Vec<_>>();
+    assert_eq!(from_senate, [false, true, false]);
+    assert_eq!(inserted[1].category, Some(StreamCategory::Senate));
+}
+
+// ─── Progress events ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_progress_events_are_sent_per_stream() {
+    let events = ProcessorEvents::new();
+    let mut receiver = events.subscribe();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(MockDataStore::default())
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(1)
+        .with_events(events)
+        .build();
+    processor.run().await.unwrap();
+
+    let received = std::iter::from_fn(|| receiver.try_recv().ok()).collect::<Vec<_>>();
+    assert!(matches!(
+        received.as_slice(),
+        [
+            ProcessorEvent::StreamStarted { .. },
+            ProcessorEvent::Summarized { .. },
+            ProcessorEvent::Inserted { .. },
+        ]
+    ));
+}
+
+#[tokio::test]
+async fn test_failed_stream_is_reported() {
+    let events = ProcessorEvents::new();
+    let mut receiver = events.subscribe();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(MockDataStore::default())
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::failing("GPT-4 rate limit"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(1)
+        .with_events(events)
+        .build();
+    assert!(processor.run().await.is_err());
+
+    let received = std::iter::from_fn(|| receiver.try_recv().ok()).collect::<Vec<_>>();
+    let Some(ProcessorEvent::Failed { video_id, error }) = received.last() else {
+        panic!("Expected a failure event, got {received:?}");
+    };
+    assert!(video_id.is_some());
+    assert!(error.contains("GPT-4 rate limit"));
+}
+
+// ─── Hooks ───────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Default)]
+struct RecordingHook {
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl ProcessorHook for RecordingHook {
+    fn on_stream_discovered<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        self.record(format!("discovered {}", stream.video_id));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn after_summarize<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        let summary = stream.summary_md.as_deref().unwrap_or_default();
+        self.record(format!("summarized {}: {summary}", stream.video_id));
+        Box::pin(async { anyhow::bail!("Failed to upload summary") })
+    }
+
+    fn on_stream_stored<'a>(&'a self, stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        self.record(format!("stored {}", stream.video_id));
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl RecordingHook {
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+#[tokio::test]
+async fn test_hooks_are_called_at_each_stage() {
+    let hook = RecordingHook::default();
+    let calls = hook.calls.clone();
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(1)
+        .with_hook(hook)
+        .build();
+    processor.run().await.unwrap();
+
+    let video_id = inserted.lock().unwrap()[0].video_id.clone();
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            format!("discovered {video_id}"),
+            format!("summarized {video_id}: summary"),
+            format!("stored {video_id}"),
+        ]
+    );
+}
+
+// ─── Shutdown ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_shutdown_leaves_streams_for_the_next_run() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let audio_handler = MockAudioHandler::default();
+    let downloads = audio_handler.calls.clone();
+    let shutdown = tokio_util::sync::CancellationToken::new();
+    shutdown.cancel();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(audio_handler)
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(2)
+        .with_persistence_check(true)
+        .with_shutdown(shutdown)
+        .build();
+
+    processor.run().await.unwrap();
+    assert!(downloads.lock().unwrap().is_empty());
+    assert!(inserted.lock().unwrap().is_empty());
+}
+
+/// Cancels the run as the first stream starts transcribing
+struct CancellingHook(tokio_util::sync::CancellationToken);
+
+impl ProcessorHook for CancellingHook {
+    fn before_transcribe<'a>(&'a self, _stream: &'a Stream) -> BoxFuture<'a, anyhow::Result<()>> {
+        self.0.cancel();
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn test_cancelled_run_returns_a_partial_report() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let summarizer = MockSummarizer::new("summary");
+    let summarized = summarizer.calls.clone();
+    let cancellation = tokio_util::sync::CancellationToken::new();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(summarizer)
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(2)
+        .with_hook(CancellingHook(cancellation.clone()))
+        .with_cancellation(cancellation)
+        .build();
+
+    let report = processor.run().await.unwrap();
+    assert!(report.cancelled);
+    assert!(report.processed.is_empty());
+    assert!(summarized.lock().unwrap().is_empty());
+    assert!(inserted.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_spent_run_duration_leaves_streams_for_the_next_run() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let scraper = MockChannelScraper::from_fixture();
+    let listings_processed = scraper.listings_processed.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(scraper)
+        .max_streams(100)
+        .with_max_run_duration(Some(std::time::Duration::ZERO))
+        .build();
+    processor.run().await.unwrap();
+
+    assert!(inserted.lock().unwrap().is_empty());
+    assert_eq!(*listings_processed.lock().unwrap(), 0);
+}
+
+// ─── Recorded provider responses ─────────────────────────────────────────────
+
+#[cfg(feature = "cassette")]
+#[tokio::test]
+async fn test_openai_embeddings_replay_from_cassette() {
+    use mocks::ffmpeg::MockFfmpeg;
+    use stream_pulse::{openai::OpenAIClient, Cassette, Embedder};
+
+    let cassette = Cassette::replay(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/cassettes/openai_embeddings.json"
+    ))
+    .expect("cassette fixture should load");
+    let client = OpenAIClient::new("test-key", MockFfmpeg).with_cassette(cassette);
+
+    let inputs = vec![
+        "The Finance Bill, second reading.".to_string(),
+        "The House is adjourned.".to_string(),
+    ];
+    let embeddings = client.embed(&inputs).await.expect("Recorded response");
+    assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+
+    // the single recorded interaction has been played
+    assert!(client.embed(&inputs).await.is_err());
+}
+
 // ─── Edge cases ──────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -313,6 +1368,52 @@ async fn test_all_streams_exist_in_db_returns_ok() {
 
 // ─── Error propagation ──────────────────────────────────────────────────────
 
+fn mock_builder(
+    workdir: &str,
+) -> LiveStreamProcessorBuilder<
+    MockDataStore,
+    MockTranscriber,
+    MockSummarizer,
+    MockAudioHandler,
+    MockChannelScraper,
+> {
+    LiveStreamProcessorBuilder::new(workdir)
+        .store(MockDataStore::default())
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+}
+
+#[test]
+fn test_invalid_settings_fail_to_build() {
+    let workdir = "/tmp/stream-pulse-test";
+    assert!(mock_builder(workdir).with_chunking(900).try_build().is_ok());
+
+    let under_a_file = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml/workdir");
+    assert!(matches!(
+        mock_builder(under_a_file).try_build(),
+        Err(BuilderError::UnwritableDir { .. })
+    ));
+    assert!(matches!(
+        mock_builder(workdir).max_streams(0).try_build(),
+        Err(BuilderError::NoStreams)
+    ));
+    assert!(matches!(
+        mock_builder(workdir).with_chunking(0).try_build(),
+        Err(BuilderError::ChunkDuration(0))
+    ));
+    assert!(matches!(
+        mock_builder(workdir)
+            .with_retries(Some(StreamRetryPolicy {
+                max_attempts: 0,
+                ..Default::default()
+            }))
+            .try_build(),
+        Err(BuilderError::NoRetryAttempts)
+    ));
+}
+
 #[tokio::test]
 async fn test_scraper_failure_propagates_error() {
     let store = MockDataStore::default();
@@ -359,6 +1460,163 @@ async fn test_summarization_failure_propagates_error() {
     assert!(result.is_err(), "Should propagate summarization error");
 }
 
+#[tokio::test]
+async fn test_streams_summarized_before_a_failure_are_kept() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+
+    let processor = build_processor(
+        store,
+        MockTranscriber::new("transcript"),
+        MockSummarizer::failing_after(2, "Out of memory"),
+        MockAudioHandler::default(),
+        MockChannelScraper::from_fixture(),
+        3,
+    );
+    let result = processor.run().await;
+    assert!(result.is_err(), "Should propagate the third stream's error");
+
+    assert_eq!(inserted.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_persistence_check_fails_run_on_lost_inserts() {
+    let store = MockDataStore {
+        discard_inserts: true,
+        ..Default::default()
+    };
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(2)
+        .with_persistence_check(true)
+        .build();
+
+    let err = processor.run().await.unwrap_err();
+    assert!(format!("{err:?}").contains("missing from the store"));
+}
+
+#[tokio::test]
+async fn test_checkpoints_resume_streams_after_a_failed_run() {
+    let workdir = std::env::temp_dir().join("stream-pulse-checkpoints-test");
+    let _ = std::fs::remove_dir_all(&workdir);
+    let build = |store: MockDataStore,
+                 transcriber: MockTranscriber,
+                 summarizer: MockSummarizer,
+                 audio_handler: MockAudioHandler| {
+        LiveStreamProcessorBuilder::new(&workdir)
+            .store(store)
+            .transcriber(transcriber)
+            .summarizer(summarizer)
+            .audio_handler(audio_handler)
+            .channel_scraper(MockChannelScraper::from_fixture())
+            .max_streams(1)
+            .with_checkpoints(true)
+            .build()
+    };
+
+    let processor = build(
+        MockDataStore::default(),
+        MockTranscriber::new("transcript"),
+        MockSummarizer::failing("GPT-4 rate limit"),
+        MockAudioHandler::default(),
+    );
+    assert!(processor.run().await.is_err());
+
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+    let transcriber = MockTranscriber::new("transcript");
+    let transcriptions = transcriber.calls.clone();
+    let audio_handler = MockAudioHandler::default();
+    let downloads = audio_handler.calls.clone();
+    let processor = build(
+        store,
+        transcriber,
+        MockSummarizer::new("summary"),
+        audio_handler,
+    );
+    processor.run().await.unwrap();
+
+    assert_eq!(inserted.lock().unwrap().len(), 1);
+    assert!(downloads.lock().unwrap().is_empty());
+    assert!(transcriptions.lock().unwrap().is_empty());
+    // the checkpoint is removed once the stream is stored
+    let checkpoints = workdir.join("checkpoints");
+    assert!(!checkpoints.exists() || std::fs::read_dir(&checkpoints).unwrap().next().is_none());
+    let _ = std::fs::remove_dir_all(&workdir);
+}
+
+#[tokio::test]
+async fn test_stored_transcripts_survive_a_failed_summary() {
+    let store = MockDataStore::default();
+    let transcripts = store.transcripts.clone();
+    let build = |transcriber: MockTranscriber, summarizer: MockSummarizer| {
+        LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+            .store(store.clone())
+            .transcriber(transcriber)
+            .summarizer(summarizer)
+            .audio_handler(MockAudioHandler::default())
+            .channel_scraper(MockChannelScraper::from_fixture())
+            .max_streams(1)
+            .with_transcript_persistence(true)
+            .build()
+    };
+
+    let processor = build(
+        MockTranscriber::new("transcript"),
+        MockSummarizer::failing("GPT-4 rate limit"),
+    );
+    assert!(processor.run().await.is_err());
+    assert_eq!(transcripts.lock().unwrap().len(), 1);
+
+    let transcriber = MockTranscriber::new("transcript");
+    let transcriptions = transcriber.calls.clone();
+    let processor = build(transcriber, MockSummarizer::new("summary"));
+    processor.run().await.unwrap();
+
+    assert_eq!(store.inserted.lock().unwrap().len(), 1);
+    assert!(transcriptions.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_streams_record_the_stage_they_failed_in() {
+    let store = MockDataStore::default();
+    let states = store.states.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::failing("GPT-4 rate limit"))
+        .audio_handler(MockAudioHandler::default())
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(1)
+        .with_state_tracking(true)
+        .build();
+    assert!(processor.run().await.is_err());
+
+    let states = states
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, state)| *state)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        states,
+        [
+            StreamState::Discovered,
+            StreamState::Cleaned,
+            StreamState::Transcribed,
+            StreamState::Failed {
+                stage: PipelineStage::Summarize
+            },
+        ]
+    );
+}
+
 #[tokio::test]
 async fn test_db_insert_failure_propagates_error() {
     let store = MockDataStore::failing("Connection refused");
@@ -384,3 +1642,27 @@ async fn test_audio_download_failure_propagates_error() {
     let result = processor.run().await;
     assert!(result.is_err(), "Should propagate audio download error");
 }
+
+#[tokio::test]
+async fn test_streams_downloaded_before_a_failed_download_are_processed() {
+    let store = MockDataStore::default();
+    let inserted = store.inserted.clone();
+
+    let processor = LiveStreamProcessorBuilder::new("/tmp/stream-pulse-test")
+        .store(store)
+        .transcriber(MockTranscriber::new("transcript"))
+        .summarizer(MockSummarizer::new("summary"))
+        .audio_handler(MockAudioHandler::failing_after(2, "yt-dlp download failed"))
+        .channel_scraper(MockChannelScraper::from_fixture())
+        .max_streams(3)
+        .download_concurrency(1)
+        .build();
+    let result = processor.run().await;
+    assert!(
+        result.is_err(),
+        "Should propagate the third download's error"
+    );
+
+    // earlier streams are transcribed while the later ones download
+    assert_eq!(inserted.lock().unwrap().len(), 2);
+}
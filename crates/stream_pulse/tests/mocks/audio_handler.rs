@@ -3,19 +3,25 @@ use std::{
     sync::{Arc, Mutex},
 };
 use stream_datastore::Stream;
-use stream_pulse::yt::AudioHandler;
+use stream_pulse::{yt::AudioHandler, CleanupConfig};
 
 #[derive(Clone)]
 pub struct MockAudioHandler {
     pub calls: Arc<Mutex<Vec<String>>>,
+    /// Video IDs of the streams whose audio was cleaned up
+    pub cleaned: Arc<Mutex<Vec<String>>>,
     pub fail_with: Option<String>,
+    /// Downloads that succeed before the rest fail with `fail_with`
+    pub fail_after: usize,
 }
 
 impl Default for MockAudioHandler {
     fn default() -> Self {
         Self {
             calls: Arc::new(Mutex::new(Vec::new())),
+            cleaned: Arc::new(Mutex::new(Vec::new())),
             fail_with: None,
+            fail_after: 0,
         }
     }
 }
@@ -24,7 +30,16 @@ impl MockAudioHandler {
     pub fn failing(msg: &str) -> Self {
         Self {
             calls: Arc::new(Mutex::new(Vec::new())),
+            cleaned: Arc::new(Mutex::new(Vec::new())),
             fail_with: Some(msg.to_string()),
+            fail_after: 0,
+        }
+    }
+
+    pub fn failing_after(downloads: usize, msg: &str) -> Self {
+        Self {
+            fail_after: downloads,
+            ..Self::failing(msg)
         }
     }
 }
@@ -33,14 +48,23 @@ impl AudioHandler for MockAudioHandler {
     const BASE_URL: &'static str = "https://youtube.com";
 
     fn download(&self, stream: &Stream, _audio_dl_path: &Path) -> anyhow::Result<PathBuf> {
+        let mut calls = self.calls.lock().unwrap();
         if let Some(ref msg) = self.fail_with {
-            return Err(anyhow::anyhow!("{}", msg));
+            if calls.len() >= self.fail_after {
+                return Err(anyhow::anyhow!("{}", msg));
+            }
         }
-        self.calls.lock().unwrap().push(stream.video_id.clone());
+        calls.push(stream.video_id.clone());
         Ok(PathBuf::from(format!("/tmp/mock/{}.mp3", stream.video_id)))
     }
 
-    fn clean_up(&self, _stream: &Stream, audio_dl_path: &Path) -> anyhow::Result<PathBuf> {
-        Ok(audio_dl_path.to_path_buf())
+    fn clean_up(
+        &self,
+        stream: &Stream,
+        audio_path: &Path,
+        _cleanup: &CleanupConfig,
+    ) -> anyhow::Result<PathBuf> {
+        self.cleaned.lock().unwrap().push(stream.video_id.clone());
+        Ok(audio_path.to_path_buf())
     }
 }
@@ -0,0 +1,39 @@
+use std::sync::{Arc, Mutex};
+
+use stream_pulse::{yt::timedtext::CaptionSource, TranscribeResponse};
+
+/// Captions every video with `text`, or none when `text` is `None`
+#[derive(Clone)]
+pub struct MockCaptionSource {
+    pub text: Option<String>,
+    pub calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockCaptionSource {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: Some(text.to_string()),
+            calls: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn uncaptioned() -> Self {
+        Self {
+            text: None,
+            calls: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl CaptionSource for MockCaptionSource {
+    type Error = anyhow::Error;
+
+    async fn fetch_captions(&self, video_id: &str) -> anyhow::Result<Option<TranscribeResponse>> {
+        self.calls.lock().unwrap().push(video_id.to_string());
+        Ok(self.text.clone().map(|text| TranscribeResponse {
+            duration: 60.0,
+            text,
+            segments: None,
+        }))
+    }
+}
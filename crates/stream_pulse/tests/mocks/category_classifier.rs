@@ -0,0 +1,29 @@
+use std::sync::{Arc, Mutex};
+
+use stream_datastore::StreamCategory;
+use stream_pulse::CategoryClassifier;
+
+#[derive(Clone)]
+pub struct MockCategoryClassifier {
+    pub category: StreamCategory,
+    pub titles: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockCategoryClassifier {
+    pub fn new(category: StreamCategory) -> Self {
+        Self {
+            category,
+            titles: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl CategoryClassifier for MockCategoryClassifier {
+    const CLASSIFICATION_MODEL: &'static str = "mock-gpt-mini";
+    type Error = anyhow::Error;
+
+    async fn classify_title(&self, title: &str) -> Result<StreamCategory, Self::Error> {
+        self.titles.lock().unwrap().push(title.to_string());
+        Ok(self.category)
+    }
+}
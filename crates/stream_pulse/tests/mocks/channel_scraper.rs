@@ -1,9 +1,22 @@
-use stream_pulse::{parser::YtHtmlDocument, yt::ChannelScraper};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use stream_datastore::{Stream, StreamCategory};
+use stream_pulse::{
+    parser::{parse_streams, ParseFilters, YtHtmlDocument},
+    yt::{ChannelScraper, VideoDetails},
+};
 
 #[derive(Clone)]
 pub struct MockChannelScraper {
     pub html: String,
     pub fail_with: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub uploads: bool,
+    pub video_id_prefix: String,
+    pub listings_processed: Arc<Mutex<usize>>,
 }
 
 impl MockChannelScraper {
@@ -11,9 +24,44 @@ impl MockChannelScraper {
         Self {
             html,
             fail_with: None,
+            published_at: None,
+            title: None,
+            description: None,
+            uploads: false,
+            video_id_prefix: String::new(),
+            listings_processed: Arc::default(),
         }
     }
 
+    pub fn with_published_at(mut self, published_at: DateTime<Utc>) -> Self {
+        self.published_at = Some(published_at);
+        self
+    }
+
+    /// Resolves every stream's full description to `description`
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Lists every fixture stream under `title` instead of its own
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Lists every fixture stream as an uploaded video rather than a live recording
+    pub fn with_uploads(mut self) -> Self {
+        self.uploads = true;
+        self
+    }
+
+    /// Lists every fixture stream with its video ID prefixed, e.g. as another channel's
+    pub fn with_video_id_prefix(mut self, prefix: &str) -> Self {
+        self.video_id_prefix = prefix.to_string();
+        self
+    }
+
     pub fn from_fixture() -> Self {
         Self::new(include_str!("../fixtures/yt.html").to_string())
     }
@@ -22,6 +70,12 @@ impl MockChannelScraper {
         Self {
             html: String::new(),
             fail_with: Some(msg.to_string()),
+            published_at: None,
+            title: None,
+            description: None,
+            uploads: false,
+            video_id_prefix: String::new(),
+            listings_processed: Arc::default(),
         }
     }
 }
@@ -36,4 +90,46 @@ impl ChannelScraper for MockChannelScraper {
         }
         Ok(YtHtmlDocument::new(self.html.clone()))
     }
+
+    async fn scrape_streams(&self) -> anyhow::Result<Vec<Stream>> {
+        let doc = self.scrape_channel().await?;
+        let mut streams = parse_streams(
+            &doc.to_json::<serde_json::Value>()?,
+            &ParseFilters::default(),
+        )?;
+        if let Some(title) = &self.title {
+            for stream in &mut streams {
+                stream.title = title.clone();
+                stream.category = StreamCategory::from_title(title);
+            }
+        }
+        if self.uploads {
+            for stream in &mut streams {
+                stream.is_live_recording = false;
+            }
+        }
+        for stream in &mut streams {
+            stream.video_id.insert_str(0, &self.video_id_prefix);
+        }
+        Ok(streams)
+    }
+
+    async fn resolve_details(&self, _video_id: &str) -> anyhow::Result<VideoDetails> {
+        Ok(VideoDetails {
+            published_at: self.published_at,
+            description: self.description.clone(),
+        })
+    }
+
+    async fn fetch_stream(&self, video_id: &str) -> anyhow::Result<Stream> {
+        self.scrape_streams()
+            .await?
+            .into_iter()
+            .find(|stream| stream.video_id == video_id)
+            .ok_or_else(|| anyhow::anyhow!("No video found for {video_id}"))
+    }
+
+    fn listing_processed(&self) {
+        *self.listings_processed.lock().unwrap() += 1;
+    }
 }
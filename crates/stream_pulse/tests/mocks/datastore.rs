@@ -1,22 +1,68 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
-use stream_datastore::{DataStore, Stream};
+use stream_datastore::{
+    DataStore, DataStoreError, Division, FailedStream, FailedStreamStore, ReuploadStore,
+    StoredStream, Stream, StreamEntities, StreamState, StreamStateStore, TranscriptStore,
+};
+#[cfg(feature = "pgvector")]
+use stream_datastore::{EmbeddingStore, StreamEmbeddings};
+
+/// What was stored, by video ID
+pub type ByVideo<T> = Arc<Mutex<Vec<(String, T)>>>;
 
 #[derive(Clone)]
 pub struct MockDataStore {
     pub existing_ids: HashSet<String>,
+    /// IDs recorded while scheduled or live
+    pub tracked_ids: HashSet<String>,
     pub inserted: Arc<Mutex<Vec<Stream>>>,
+    pub live: Arc<Mutex<Vec<Stream>>>,
+    pub scheduled: Arc<Mutex<Vec<Stream>>>,
+    pub entities: ByVideo<StreamEntities>,
+    pub divisions: ByVideo<Vec<Division>>,
+    #[cfg(feature = "pgvector")]
+    pub embeddings: ByVideo<StreamEmbeddings>,
+    /// `(video_id, format, content)`
+    pub captions: Arc<Mutex<Vec<(String, String, String)>>>,
+    pub failed: Arc<Mutex<Vec<FailedStream>>>,
+    /// Serialized transcripts by video ID
+    pub transcripts: Arc<Mutex<HashMap<String, String>>>,
+    /// Streams stored on earlier runs, for matching re-uploads against
+    pub stored: Vec<StoredStream>,
+    /// `(video_id, canonical_video_id)`
+    pub aliases: Arc<Mutex<Vec<(String, String)>>>,
+    /// `(video_id, replacement_video_id)`
+    pub replaced: Arc<Mutex<Vec<(String, String)>>>,
+    /// `(video_id, state)` in the order they were recorded
+    pub states: Arc<Mutex<Vec<(String, StreamState)>>>,
     pub fail_with: Option<String>,
+    /// Accepts inserts without storing them, like a write lost on the way to the database
+    pub discard_inserts: bool,
 }
 
 impl Default for MockDataStore {
     fn default() -> Self {
         Self {
             existing_ids: HashSet::new(),
+            tracked_ids: HashSet::new(),
             inserted: Arc::new(Mutex::new(Vec::new())),
+            live: Arc::new(Mutex::new(Vec::new())),
+            scheduled: Arc::new(Mutex::new(Vec::new())),
+            entities: Arc::new(Mutex::new(Vec::new())),
+            divisions: Arc::new(Mutex::new(Vec::new())),
+            #[cfg(feature = "pgvector")]
+            embeddings: Arc::new(Mutex::new(Vec::new())),
+            captions: Arc::new(Mutex::new(Vec::new())),
+            failed: Arc::new(Mutex::new(Vec::new())),
+            transcripts: Arc::new(Mutex::new(HashMap::new())),
+            stored: Vec::new(),
+            aliases: Arc::new(Mutex::new(Vec::new())),
+            replaced: Arc::new(Mutex::new(Vec::new())),
+            states: Arc::new(Mutex::new(Vec::new())),
             fail_with: None,
+            discard_inserts: false,
         }
     }
 }
@@ -34,15 +80,221 @@ impl DataStore for MockDataStore {
     async fn get_existing_stream_ids(
         &self,
         _video_ids: &[&str],
-    ) -> anyhow::Result<HashSet<String>> {
-        Ok(self.existing_ids.clone())
+    ) -> Result<HashSet<String>, DataStoreError> {
+        let inserted = self.inserted.lock().unwrap();
+        Ok(self
+            .existing_ids
+            .iter()
+            .cloned()
+            .chain(inserted.iter().map(|s| s.video_id.clone()))
+            .collect())
     }
 
-    async fn insert_stream(&self, stream: &Stream) -> anyhow::Result<()> {
+    async fn insert_stream(&self, stream: &Stream) -> Result<(), DataStoreError> {
         if let Some(ref msg) = self.fail_with {
-            return Err(anyhow::anyhow!("{}", msg));
+            return Err(DataStoreError::Other(msg.clone().into()));
+        }
+        if !self.discard_inserts {
+            self.inserted.lock().unwrap().push(stream.clone());
         }
-        self.inserted.lock().unwrap().push(stream.clone());
         Ok(())
     }
+
+    async fn insert_live_stream(&self, stream: &Stream) -> Result<(), DataStoreError> {
+        self.live.lock().unwrap().push(stream.clone());
+        Ok(())
+    }
+
+    async fn insert_scheduled_stream(&self, stream: &Stream) -> Result<(), DataStoreError> {
+        self.scheduled.lock().unwrap().push(stream.clone());
+        Ok(())
+    }
+
+    async fn get_tracked_stream_ids(
+        &self,
+        _video_ids: &[&str],
+    ) -> Result<HashSet<String>, DataStoreError> {
+        Ok(self.tracked_ids.clone())
+    }
+
+    async fn insert_stream_entities(
+        &self,
+        video_id: &str,
+        entities: &StreamEntities,
+    ) -> Result<(), DataStoreError> {
+        self.entities
+            .lock()
+            .unwrap()
+            .push((video_id.to_string(), entities.clone()));
+        Ok(())
+    }
+
+    async fn insert_stream_divisions(
+        &self,
+        video_id: &str,
+        divisions: &[Division],
+    ) -> Result<(), DataStoreError> {
+        self.divisions
+            .lock()
+            .unwrap()
+            .push((video_id.to_string(), divisions.to_vec()));
+        Ok(())
+    }
+
+    async fn insert_stream_captions(
+        &self,
+        video_id: &str,
+        format: &str,
+        content: &str,
+    ) -> Result<(), DataStoreError> {
+        self.captions.lock().unwrap().push((
+            video_id.to_string(),
+            format.to_string(),
+            content.to_string(),
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "pgvector")]
+impl EmbeddingStore for MockDataStore {
+    async fn store_stream_embeddings(
+        &self,
+        video_id: &str,
+        embeddings: &StreamEmbeddings,
+    ) -> Result<(), DataStoreError> {
+        self.embeddings
+            .lock()
+            .unwrap()
+            .push((video_id.to_string(), embeddings.clone()));
+        Ok(())
+    }
+}
+
+impl FailedStreamStore for MockDataStore {
+    async fn record_stream_failure(
+        &self,
+        video_id: &str,
+        error: &str,
+    ) -> Result<(), DataStoreError> {
+        let mut failed = self.failed.lock().unwrap();
+        match failed.iter_mut().find(|f| f.video_id == video_id) {
+            Some(failure) => {
+                failure.attempts += 1;
+                failure.last_error = error.to_string();
+                failure.last_failed_at = chrono::Utc::now();
+            }
+            None => failed.push(FailedStream {
+                video_id: video_id.to_string(),
+                attempts: 1,
+                last_error: error.to_string(),
+                last_failed_at: chrono::Utc::now(),
+            }),
+        }
+        Ok(())
+    }
+
+    async fn get_failed_streams(&self) -> Result<Vec<FailedStream>, DataStoreError> {
+        Ok(self.failed.lock().unwrap().clone())
+    }
+
+    async fn clear_stream_failure(&self, video_id: &str) -> Result<(), DataStoreError> {
+        self.failed
+            .lock()
+            .unwrap()
+            .retain(|f| f.video_id != video_id);
+        Ok(())
+    }
+}
+
+impl TranscriptStore for MockDataStore {
+    async fn insert_stream_transcript(
+        &self,
+        video_id: &str,
+        transcript: &str,
+    ) -> Result<(), DataStoreError> {
+        self.transcripts
+            .lock()
+            .unwrap()
+            .insert(video_id.to_string(), transcript.to_string());
+        Ok(())
+    }
+
+    async fn get_stream_transcripts(
+        &self,
+        video_ids: &[&str],
+    ) -> Result<HashMap<String, String>, DataStoreError> {
+        let transcripts = self.transcripts.lock().unwrap();
+        Ok(video_ids
+            .iter()
+            .filter_map(|id| Some((id.to_string(), transcripts.get(*id)?.clone())))
+            .collect())
+    }
+}
+
+impl ReuploadStore for MockDataStore {
+    async fn get_streams_between(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<StoredStream>, DataStoreError> {
+        Ok(self
+            .stored
+            .iter()
+            .filter(|s| (from..=to).contains(&s.stream_timestamp))
+            .cloned()
+            .collect())
+    }
+
+    async fn insert_stream_alias(
+        &self,
+        video_id: &str,
+        canonical_video_id: &str,
+    ) -> Result<(), DataStoreError> {
+        self.aliases
+            .lock()
+            .unwrap()
+            .push((video_id.to_string(), canonical_video_id.to_string()));
+        Ok(())
+    }
+
+    async fn replace_stream(
+        &self,
+        video_id: &str,
+        replacement_video_id: &str,
+    ) -> Result<(), DataStoreError> {
+        self.replaced
+            .lock()
+            .unwrap()
+            .push((video_id.to_string(), replacement_video_id.to_string()));
+        Ok(())
+    }
+}
+
+impl StreamStateStore for MockDataStore {
+    async fn set_stream_state(
+        &self,
+        video_id: &str,
+        state: StreamState,
+    ) -> Result<(), DataStoreError> {
+        self.states
+            .lock()
+            .unwrap()
+            .push((video_id.to_string(), state));
+        Ok(())
+    }
+
+    async fn get_stream_states(
+        &self,
+        video_ids: &[&str],
+    ) -> Result<HashMap<String, StreamState>, DataStoreError> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(video_id, _)| video_ids.contains(&video_id.as_str()))
+            .cloned()
+            .collect())
+    }
 }
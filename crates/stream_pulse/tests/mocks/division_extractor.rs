@@ -0,0 +1,34 @@
+use stream_datastore::{Division, DivisionOutcome};
+use stream_pulse::{DivisionExtractor, SummaryContext};
+
+#[derive(Clone)]
+pub struct MockDivisionExtractor {
+    pub divisions: Vec<Division>,
+}
+
+impl MockDivisionExtractor {
+    pub fn new(motion: &str) -> Self {
+        Self {
+            divisions: vec![Division {
+                motion: motion.to_string(),
+                ayes: Some(195),
+                noes: Some(106),
+                abstentions: None,
+                outcome: DivisionOutcome::Agreed,
+            }],
+        }
+    }
+}
+
+impl DivisionExtractor for MockDivisionExtractor {
+    const DIVISION_EXTRACTION_MODEL: &'static str = "mock-gpt-mini";
+    type Error = anyhow::Error;
+
+    async fn extract_divisions(
+        &self,
+        _transcript: &str,
+        _context: &SummaryContext,
+    ) -> Result<Vec<Division>, Self::Error> {
+        Ok(self.divisions.clone())
+    }
+}
@@ -0,0 +1,33 @@
+use stream_pulse::Embedder;
+
+#[derive(Clone, Default)]
+pub struct MockEmbedder {
+    pub fail_with: Option<String>,
+}
+
+impl MockEmbedder {
+    pub fn new() -> Self {
+        Self { fail_with: None }
+    }
+
+    pub fn failing(msg: &str) -> Self {
+        Self {
+            fail_with: Some(msg.to_string()),
+        }
+    }
+}
+
+impl Embedder for MockEmbedder {
+    const EMBEDDING_MODEL: &'static str = "mock-embedding";
+    type Error = anyhow::Error;
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, Self::Error> {
+        if let Some(ref msg) = self.fail_with {
+            return Err(anyhow::anyhow!("{}", msg));
+        }
+        Ok(inputs
+            .iter()
+            .map(|input| vec![input.len() as f32])
+            .collect())
+    }
+}
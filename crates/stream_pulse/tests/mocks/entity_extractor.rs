@@ -0,0 +1,47 @@
+use stream_datastore::{MemberMention, StreamEntities};
+use stream_pulse::{EntityExtractor, SummaryContext};
+
+#[derive(Clone)]
+pub struct MockEntityExtractor {
+    pub entities: StreamEntities,
+    pub fail_with: Option<String>,
+}
+
+impl MockEntityExtractor {
+    pub fn new(member: &str) -> Self {
+        Self {
+            entities: StreamEntities {
+                members: vec![MemberMention {
+                    name: member.to_string(),
+                    constituency: None,
+                    party: None,
+                }],
+                ..Default::default()
+            },
+            fail_with: None,
+        }
+    }
+
+    pub fn failing(msg: &str) -> Self {
+        Self {
+            entities: StreamEntities::default(),
+            fail_with: Some(msg.to_string()),
+        }
+    }
+}
+
+impl EntityExtractor for MockEntityExtractor {
+    const EXTRACTION_MODEL: &'static str = "mock-gpt-mini";
+    type Error = anyhow::Error;
+
+    async fn extract_entities(
+        &self,
+        _transcript: &str,
+        _context: &SummaryContext,
+    ) -> Result<StreamEntities, Self::Error> {
+        if let Some(ref msg) = self.fail_with {
+            return Err(anyhow::anyhow!("{}", msg));
+        }
+        Ok(self.entities.clone())
+    }
+}
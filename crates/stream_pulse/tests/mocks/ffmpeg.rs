@@ -0,0 +1,45 @@
+use std::path::Path;
+use ytdlp_bindings::{AudioProcessor, YtDlpError};
+
+/// Audio processor for provider clients whose tests never touch audio
+#[derive(Clone, Default)]
+pub struct MockFfmpeg;
+
+impl AudioProcessor for MockFfmpeg {
+    fn split_audio_to_chunks(
+        &self,
+        _file_input_path: impl AsRef<Path>,
+        _segment_time_s: u16,
+        _out_template: impl AsRef<Path>,
+    ) -> Result<(), YtDlpError> {
+        Ok(())
+    }
+
+    fn bit_rate(&self, _input_path: impl AsRef<Path>) -> Result<u64, YtDlpError> {
+        Ok(128_000)
+    }
+
+    fn normalize_volume(
+        &self,
+        _input_path: impl AsRef<Path>,
+        _output_path: impl AsRef<Path>,
+    ) -> Result<(), YtDlpError> {
+        Ok(())
+    }
+
+    fn denoise_audio(
+        &self,
+        _input_path: impl AsRef<Path>,
+        _output_path: impl AsRef<Path>,
+    ) -> Result<(), YtDlpError> {
+        Ok(())
+    }
+
+    fn trim_silence(
+        &self,
+        _input_path: impl AsRef<Path>,
+        _output_path: impl AsRef<Path>,
+    ) -> Result<(), YtDlpError> {
+        Ok(())
+    }
+}
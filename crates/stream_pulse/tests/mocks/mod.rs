@@ -1,5 +1,14 @@
 pub mod audio_handler;
+pub mod caption_source;
+pub mod category_classifier;
 pub mod channel_scraper;
 pub mod datastore;
+pub mod division_extractor;
+#[cfg(feature = "pgvector")]
+pub mod embedder;
+pub mod entity_extractor;
+#[cfg(feature = "cassette")]
+pub mod ffmpeg;
+pub mod order_paper_source;
 pub mod summarizer;
 pub mod transcriber;
@@ -0,0 +1,32 @@
+use std::sync::{Arc, Mutex};
+
+use stream_datastore::{OrderPaper, Stream};
+use stream_pulse::hansard::OrderPaperSource;
+
+/// Finds an order paper with `agenda` for every stream, recording the video IDs looked up
+#[derive(Clone)]
+pub struct MockOrderPaperSource {
+    pub agenda: Vec<String>,
+    pub calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockOrderPaperSource {
+    pub fn new(agenda: &[&str]) -> Self {
+        Self {
+            agenda: agenda.iter().map(|item| item.to_string()).collect(),
+            calls: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl OrderPaperSource for MockOrderPaperSource {
+    type Error = anyhow::Error;
+
+    async fn find_order_paper(&self, stream: &Stream) -> anyhow::Result<Option<OrderPaper>> {
+        self.calls.lock().unwrap().push(stream.video_id.clone());
+        Ok(Some(OrderPaper {
+            url: format!("https://www.parliament.go.ke/{}.pdf", stream.video_id),
+            agenda: self.agenda.clone(),
+        }))
+    }
+}
@@ -1,11 +1,14 @@
 use std::sync::{Arc, Mutex};
-use stream_pulse::{Summarizer, SummaryResponse};
+use stream_pulse::{Summarizer, SummaryContext, SummaryResponse};
 
 #[derive(Clone)]
 pub struct MockSummarizer {
     pub summary: String,
     pub calls: Arc<Mutex<Vec<String>>>,
+    pub contexts: Arc<Mutex<Vec<SummaryContext>>>,
     pub fail_with: Option<String>,
+    /// Calls that succeed before `fail_with` applies
+    pub fail_after: usize,
 }
 
 impl MockSummarizer {
@@ -13,7 +16,9 @@ impl MockSummarizer {
         Self {
             summary: summary.to_string(),
             calls: Arc::new(Mutex::new(Vec::new())),
+            contexts: Arc::new(Mutex::new(Vec::new())),
             fail_with: None,
+            fail_after: 0,
         }
     }
 
@@ -21,7 +26,18 @@ impl MockSummarizer {
         Self {
             summary: String::new(),
             calls: Arc::new(Mutex::new(Vec::new())),
+            contexts: Arc::new(Mutex::new(Vec::new())),
             fail_with: Some(msg.to_string()),
+            fail_after: 0,
+        }
+    }
+
+    /// Summarizes `calls` transcripts, then fails with `msg`
+    pub fn failing_after(calls: usize, msg: &str) -> Self {
+        Self {
+            summary: "summary".to_string(),
+            fail_after: calls,
+            ..Self::failing(msg)
         }
     }
 }
@@ -31,13 +47,27 @@ impl Summarizer for MockSummarizer {
     const SUMMARIZER_MODEL: &'static str = "mock-gpt";
     type Error = anyhow::Error;
 
-    async fn summarize(&self, content: &str) -> Result<SummaryResponse, Self::Error> {
-        self.calls.lock().unwrap().push(content.to_string());
+    async fn summarize(
+        &self,
+        content: &str,
+        context: &SummaryContext,
+    ) -> Result<SummaryResponse, Self::Error> {
+        let calls = {
+            let mut calls = self.calls.lock().unwrap();
+            calls.push(content.to_string());
+            calls.len()
+        };
+        self.contexts.lock().unwrap().push(context.clone());
         if let Some(ref msg) = self.fail_with {
-            return Err(anyhow::anyhow!("{}", msg));
+            if calls > self.fail_after {
+                return Err(anyhow::anyhow!("{}", msg));
+            }
         }
         Ok(SummaryResponse {
             summary: self.summary.clone(),
+            structured: None,
+            tldr: None,
+            verification: None,
         })
     }
 }
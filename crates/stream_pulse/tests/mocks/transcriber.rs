@@ -1,11 +1,13 @@
 use std::sync::{Arc, Mutex};
-use stream_pulse::{AudioInput, TranscribeResponse, Transcriber};
+use stream_pulse::{AudioInput, TranscribeResponse, TranscribeSegment, Transcriber};
 
 #[derive(Clone)]
 pub struct MockTranscriber {
     pub response_text: String,
     pub calls: Arc<Mutex<Vec<AudioInput>>>,
     pub fail_with: Option<String>,
+    /// Return the text as a single segment spanning the audio
+    pub segmented: bool,
 }
 
 impl MockTranscriber {
@@ -14,6 +16,14 @@ impl MockTranscriber {
             response_text: response_text.to_string(),
             calls: Arc::new(Mutex::new(Vec::new())),
             fail_with: None,
+            segmented: false,
+        }
+    }
+
+    pub fn segmented(response_text: &str) -> Self {
+        Self {
+            segmented: true,
+            ..Self::new(response_text)
         }
     }
 
@@ -22,6 +32,7 @@ impl MockTranscriber {
             response_text: String::new(),
             calls: Arc::new(Mutex::new(Vec::new())),
             fail_with: Some(msg.to_string()),
+            segmented: false,
         }
     }
 }
@@ -38,7 +49,14 @@ impl Transcriber for MockTranscriber {
         Ok(TranscribeResponse {
             duration: 120.0,
             text: self.response_text.clone(),
-            segments: None,
+            segments: self.segmented.then(|| {
+                vec![TranscribeSegment {
+                    start: 0.0,
+                    end: 120.0,
+                    text: self.response_text.clone(),
+                    ..Default::default()
+                }]
+            }),
         })
     }
 }
@@ -20,4 +20,13 @@ pub enum YtDlpError {
     InvalidInputPath(String),
     #[error("Unsupported file format: {0}")]
     UnsupportedFormat(String),
+    #[error("Failed to read the bit rate of {path}: {output}")]
+    UnknownBitRate { path: String, output: String },
+    #[error("{0} was killed on cancellation")]
+    Cancelled(String),
+    #[error("{program} was killed after running for longer than {timeout:?}")]
+    TimedOut {
+        program: String,
+        timeout: std::time::Duration,
+    },
 }
@@ -18,6 +18,9 @@ pub trait AudioProcessor {
         out_template: impl AsRef<Path>,
     ) -> Result<(), YtDlpError>;
 
+    /// Overall bit rate of an audio file in bits per second, read with `ffprobe`.
+    fn bit_rate(&self, input_path: impl AsRef<Path>) -> Result<u64, YtDlpError>;
+
     /// Normalize volume using EBU R128 loudness standard.
     fn normalize_volume(
         &self,
@@ -88,6 +91,31 @@ impl AudioProcessor for YtDlp {
         ])
     }
 
+    fn bit_rate(&self, input_path: impl AsRef<Path>) -> Result<u64, YtDlpError> {
+        let input_str = input_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| YtDlpError::InvalidPath(input_path.as_ref().display().to_string()))?;
+
+        let output = self.run_ffprobe(&[
+            "-v",
+            "error",
+            "-show_entries",
+            "format=bit_rate",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+            input_str,
+        ])?;
+        // "N/A" for containers that don't record one
+        output
+            .trim()
+            .parse()
+            .map_err(|_| YtDlpError::UnknownBitRate {
+                path: input_str.to_string(),
+                output: output.trim().to_string(),
+            })
+    }
+
     fn normalize_volume(
         &self,
         input_path: impl AsRef<Path>,
@@ -1,7 +1,10 @@
 use std::fmt::Debug;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::YtDlpError;
 
@@ -16,6 +19,11 @@ include!(concat!(env!("OUT_DIR"), "/generated.rs"));
 pub struct YtDlp {
     pub(crate) binary_path: PathBuf,
     pub(crate) cookies_path: Option<PathBuf>,
+    pub(crate) cancel_flag: Option<Arc<AtomicBool>>,
+    pub(crate) yt_dlp_timeout: Option<Duration>,
+    pub(crate) ffmpeg_timeout: Option<Duration>,
+    /// Bytes per second each `yt-dlp` process may download at
+    pub(crate) rate_limit: Option<u64>,
 }
 
 impl YtDlp {
@@ -46,6 +54,10 @@ impl YtDlp {
         Ok(YtDlp {
             binary_path: Self::resolve_yt_dlp_binary()?,
             cookies_path,
+            cancel_flag: None,
+            yt_dlp_timeout: None,
+            ffmpeg_timeout: None,
+            rate_limit: None,
         })
     }
 
@@ -121,9 +133,42 @@ impl YtDlp {
         YtDlp {
             binary_path: binary_path.into(),
             cookies_path: cookies_path.map(Into::into),
+            cancel_flag: None,
+            yt_dlp_timeout: None,
+            ffmpeg_timeout: None,
+            rate_limit: None,
         }
     }
 
+    /// Kills running `yt-dlp` and `ffmpeg` processes once `flag` is set, failing their
+    /// calls with [`YtDlpError::Cancelled`], e.g. to exit promptly on shutdown.
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+
+    /// Kills `yt-dlp` processes still running after `timeout`, failing their calls with
+    /// [`YtDlpError::TimedOut`]. A timed out download is not retried.
+    pub fn with_yt_dlp_timeout(mut self, timeout: Duration) -> Self {
+        self.yt_dlp_timeout = Some(timeout);
+        self
+    }
+
+    /// Kills `ffmpeg` processes still running after `timeout`, failing their calls with
+    /// [`YtDlpError::TimedOut`]. Applies to each process on its own, e.g. to each of the
+    /// denoise, normalize and trim steps of cleaning a stream's audio.
+    pub fn with_ffmpeg_timeout(mut self, timeout: Duration) -> Self {
+        self.ffmpeg_timeout = Some(timeout);
+        self
+    }
+
+    /// Caps the bandwidth of each `yt-dlp` process at `bytes_per_second`, passed to it as
+    /// `--limit-rate`. Processes running at once each get the whole cap.
+    pub fn with_rate_limit(mut self, bytes_per_second: u64) -> Self {
+        self.rate_limit = Some(bytes_per_second);
+        self
+    }
+
     /// Downloads a single video from the given URL.
     ///
     /// # Arguments
@@ -352,7 +397,7 @@ impl YtDlp {
 
             match result {
                 Ok(()) => return Ok(()),
-                Err(err) if matches!(err, YtDlpError::NonZeroExit { .. }) => {
+                Err(err) if matches!(err, YtDlpError::NonZeroExit { .. }) && !self.cancelled() => {
                     tracing::warn!(
                         ?err,
                         attempts,
@@ -384,9 +429,12 @@ impl YtDlp {
             }
             cmd.arg("--cookies").arg(cookies);
         }
+        if let Some(rate_limit) = self.rate_limit {
+            cmd.arg("--limit-rate").arg(rate_limit.to_string());
+        }
 
         cmd.args(args);
-        let output = cmd.output()?;
+        let output = self.output(&mut cmd, "yt-dlp", self.yt_dlp_timeout)?;
 
         if output.status.success() {
             Ok(())
@@ -416,7 +464,11 @@ impl YtDlp {
         if which::which("ffmpeg").is_err() {
             return Err(YtDlpError::BinaryNotFound("ffmpeg".to_string()));
         }
-        let output = Command::new("ffmpeg").args(args).output()?;
+        let output = self.output(
+            Command::new("ffmpeg").args(args),
+            "ffmpeg",
+            self.ffmpeg_timeout,
+        )?;
 
         if output.status.success() {
             Ok(())
@@ -428,6 +480,131 @@ impl YtDlp {
             })
         }
     }
+
+    /// Runs `ffprobe` with `args`, returning what it printed
+    #[cfg(feature = "audio-processing")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub(crate) fn run_ffprobe(&self, args: &[&str]) -> Result<String, YtDlpError> {
+        if which::which("ffprobe").is_err() {
+            return Err(YtDlpError::BinaryNotFound("ffprobe".to_string()));
+        }
+        let output = self.output(
+            Command::new("ffprobe").args(args),
+            "ffprobe",
+            self.ffmpeg_timeout,
+        )?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into())
+        } else {
+            Err(YtDlpError::NonZeroExit {
+                command: "ffprobe".to_string(),
+                status: output.status.code().unwrap_or(-1),
+                output: String::from_utf8_lossy(&output.stderr).into(),
+            })
+        }
+    }
+
+    /// Checks that the `yt-dlp` and `ffmpeg` binaries can be executed, by asking them for
+    /// their versions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`YtDlpError::BinaryNotFound`] if `ffmpeg` is not on the `PATH`, and the
+    /// error running either binary otherwise.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn check_dependencies(&self) -> Result<(), YtDlpError> {
+        let ffmpeg =
+            which::which("ffmpeg").map_err(|_| YtDlpError::BinaryNotFound("ffmpeg".to_string()))?;
+        for (program, binary, arg) in [
+            ("yt-dlp", self.binary_path.as_path(), "--version"),
+            ("ffmpeg", ffmpeg.as_path(), "-version"),
+        ] {
+            let output = self.output(Command::new(binary).arg(arg), program, None)?;
+            if !output.status.success() {
+                return Err(YtDlpError::NonZeroExit {
+                    command: binary.to_string_lossy().into(),
+                    status: output.status.code().unwrap_or(-1),
+                    output: String::from_utf8_lossy(&output.stderr).into(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    /// Runs `cmd` to completion like [`Command::output`], killing it if the cancel flag
+    /// is set meanwhile or it runs for longer than `timeout`
+    fn output(
+        &self,
+        cmd: &mut Command,
+        program: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Output, YtDlpError> {
+        if self.cancel_flag.is_none() && timeout.is_none() {
+            return Ok(cmd.output()?);
+        }
+        if self.cancelled() {
+            return Err(YtDlpError::Cancelled(program.to_string()));
+        }
+
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        // drained while waiting, so that the process doesn't block on a full pipe
+        let drain = |pipe: Option<Box<dyn Read + Send>>| {
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                if let Some(mut pipe) = pipe {
+                    let _ = pipe.read_to_end(&mut buf);
+                }
+                buf
+            })
+        };
+        let stdout = drain(child.stdout.take().map(|p| Box::new(p) as _));
+        let stderr = drain(child.stderr.take().map(|p| Box::new(p) as _));
+
+        let started = std::time::Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if self.cancelled() {
+                tracing::info!(program, pid = child.id(), "Killing process on cancellation");
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(YtDlpError::Cancelled(program.to_string()));
+            }
+            if let Some(timeout) = timeout.filter(|timeout| started.elapsed() >= *timeout) {
+                tracing::warn!(
+                    program,
+                    pid = child.id(),
+                    ?timeout,
+                    "Killing process on timeout"
+                );
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(YtDlpError::TimedOut {
+                    program: program.to_string(),
+                    timeout,
+                });
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        };
+
+        Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
 }
 
 #[cfg(all(test, feature = "yt-dlp-vendored"))]
@@ -25,7 +25,7 @@ export default function Footer() {
       </a>
 
       <a
-        href="/summaries/rss.xml"
+        href="/feed.xml"
         className="hover:underline inline-flex items-center space-x-1 mt-1"
       >
         <Rss className="w-4 h-4" />
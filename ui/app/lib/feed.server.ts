@@ -0,0 +1,30 @@
+import { PrismaClient } from "@prisma-app/client";
+
+const prisma = new PrismaClient();
+
+/** How many of the most recent summaries the feeds list */
+const FEED_SIZE = 20;
+
+/** The feeds are regenerated at most this often, e.g. by a CDN in front of the site */
+export const FEED_CACHE_CONTROL = "public, max-age=900";
+
+/** What the RSS and Atom feeds share, besides where they are served */
+export const recentSummariesFeed = async () => {
+  const summaries = await prisma.streams.findMany({
+    where: { is_published: true, status: "archived", summary_md: { not: null } },
+    orderBy: { stream_timestamp: "desc" },
+    take: FEED_SIZE,
+  });
+
+  return {
+    title: "Bunge Bits – Parliamentary Summaries",
+    description: "Bite-sized summaries of Kenyan parliamentary proceedings.",
+    baseUrl: "https://bungebits.ke",
+    items: summaries.map((s) => ({
+      title: s.title,
+      slug: `/summaries/${s.video_id}`,
+      date: s.stream_timestamp,
+      description: s.summary_tldr,
+    })),
+  };
+};
@@ -47,24 +47,38 @@ type FeedItem = {
   title: string;
   slug: string;
   date?: Date;
+  /** Plain text, e.g. the stream's TL;DR */
+  description?: string | null;
 };
 
 type FeedMeta = {
   title: string;
   description: string;
   baseUrl: string;
+  /** Where the feed itself is served, e.g. "/feed.xml" */
+  feedPath: string;
   items: FeedItem[];
 };
 
-export const toRssFeed = ({ title, description, baseUrl, items }: FeedMeta) => {
+/** `views` is a BIGINT, which `Response.json` can't serialize. View counts fit in a number */
+export const serializeViews = <T extends { views: bigint | null }>(stream: T) => ({
+  ...stream,
+  views: stream.views === null ? null : Number(stream.views),
+});
+
+export const toRssFeed = ({ title, description, baseUrl, feedPath, items }: FeedMeta) => {
   const postItems = items
-    .map(({ title, slug, date }) => {
+    .map(({ title, slug, date, description }) => {
       const link = `${baseUrl}${slug}`;
       const pubDate = date ? `<pubDate>${new Date(date).toUTCString()}</pubDate>` : "";
+      const summary = description
+        ? `<description>${escapeXml(description)}</description>`
+        : "";
       return `
       <item>
         <title>${escapeXml(title)}</title>
         <link>${link}</link>
+        ${summary}
         ${pubDate}
         <guid>${link}</guid>
       </item>
@@ -77,12 +91,50 @@ export const toRssFeed = ({ title, description, baseUrl, items }: FeedMeta) => {
     <channel>
       <title>${escapeXml(title)}</title>
       <description>${escapeXml(description)}</description>
-      <link>${baseUrl}/summaries/rss.xml</link>
+      <link>${baseUrl}${feedPath}</link>
       ${postItems}
     </channel>
   </rss>`;
 };
 
+export const toAtomFeed = ({
+  title,
+  description,
+  baseUrl,
+  feedPath,
+  items,
+}: FeedMeta) => {
+  const updated = new Date(
+    Math.max(0, ...items.map(({ date }) => (date ? new Date(date).getTime() : 0)))
+  ).toISOString();
+  const entries = items
+    .map(({ title, slug, date, description }) => {
+      const link = `${baseUrl}${slug}`;
+      const summary = description ? `<summary>${escapeXml(description)}</summary>` : "";
+      return `
+    <entry>
+      <title>${escapeXml(title)}</title>
+      <link href="${link}" />
+      <id>${link}</id>
+      <updated>${date ? new Date(date).toISOString() : updated}</updated>
+      ${summary}
+    </entry>
+  `;
+    })
+    .join("");
+
+  return `<?xml version="1.0" encoding="UTF-8" ?>
+  <feed xmlns="http://www.w3.org/2005/Atom">
+    <title>${escapeXml(title)}</title>
+    <subtitle>${escapeXml(description)}</subtitle>
+    <link href="${baseUrl}${feedPath}" rel="self" />
+    <link href="${baseUrl}/summaries" />
+    <id>${baseUrl}${feedPath}</id>
+    <updated>${updated}</updated>
+    ${entries}
+  </feed>`;
+};
+
 function escapeXml(str: string): string {
   return str.replace(
     /[<>&'"]/g,
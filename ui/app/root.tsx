@@ -21,7 +21,13 @@ export const links: LinksFunction = () => [
     rel: "alternate",
     type: "application/rss+xml",
     title: "Bunge Bits RSS Feed",
-    href: "/summaries/rss.xml",
+    href: "/feed.xml",
+  },
+  {
+    rel: "alternate",
+    type: "application/atom+xml",
+    title: "Bunge Bits Atom Feed",
+    href: "/atom.xml",
   },
 ];
 
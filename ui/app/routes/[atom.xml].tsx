@@ -0,0 +1,14 @@
+import { FEED_CACHE_CONTROL, recentSummariesFeed } from "~/lib/feed.server";
+import { toAtomFeed } from "~/lib/utils";
+
+export const loader = async () => {
+  const feed = toAtomFeed({ ...(await recentSummariesFeed()), feedPath: "/atom.xml" });
+
+  return new Response(feed, {
+    status: 200,
+    headers: {
+      "Content-Type": "application/atom+xml",
+      "Cache-Control": FEED_CACHE_CONTROL,
+    },
+  });
+};
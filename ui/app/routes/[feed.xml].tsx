@@ -0,0 +1,14 @@
+import { FEED_CACHE_CONTROL, recentSummariesFeed } from "~/lib/feed.server";
+import { toRssFeed } from "~/lib/utils";
+
+export const loader = async () => {
+  const feed = toRssFeed({ ...(await recentSummariesFeed()), feedPath: "/feed.xml" });
+
+  return new Response(feed, {
+    status: 200,
+    headers: {
+      "Content-Type": "application/rss+xml",
+      "Cache-Control": FEED_CACHE_CONTROL,
+    },
+  });
+};
@@ -1,7 +1,7 @@
 import { PrismaClient } from "@prisma-app/client";
 import { HeadersFunction, LoaderFunctionArgs, MetaFunction } from "@remix-run/node";
 import { Await, Link, useLoaderData, useLocation } from "@remix-run/react";
-import { ArrowLeft, Calendar, Clock } from "lucide-react";
+import { ArrowLeft, Calendar, Clock, FileText } from "lucide-react";
 import { Suspense } from "react";
 import ReactMarkdown from "react-markdown";
 
@@ -10,7 +10,7 @@ import { Badge } from "~/components/ui/badge";
 import { Button } from "~/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "~/components/ui/card";
 import { highlightText } from "~/lib/text-highlight";
-import { formatDate, formatDuration, titleCase } from "~/lib/utils";
+import { formatDate, formatDuration, serializeViews, titleCase } from "~/lib/utils";
 
 const prisma = new PrismaClient();
 
@@ -30,7 +30,7 @@ export async function loader({ params }: LoaderFunctionArgs) {
       throw new Response("Not Found", { status: 404 });
     }
 
-    return Response.json({ stream });
+    return Response.json({ stream: serializeViews(stream) });
   } catch (err) {
     console.error("DB fetch failed:", err);
     throw new Response("Internal Server Error", { status: 500 });
@@ -60,6 +60,9 @@ export const meta: MetaFunction<typeof loader> = ({ data }) => {
         data.stream.summary_md?.slice(0, 150).replace(/\n/g, " ") ||
         "Session summary for a legislative stream.",
     },
+    ...(data.stream.thumbnail_url
+      ? [{ property: "og:image", content: data.stream.thumbnail_url }]
+      : []),
   ];
 };
 
@@ -67,6 +70,7 @@ export default function StreamSummary() {
   const { stream } = useLoaderData<typeof loader>();
   const rawMarkdown = stream.summary_md || "";
   const cleanedMarkdown = rawMarkdown.replace(/\\n/g, "\n");
+  const orderPaper = stream.order_paper as { url: string; agenda: string[] } | null;
 
   const location = useLocation();
   const backSearch = location.search || "";
@@ -103,6 +107,17 @@ export default function StreamSummary() {
                       <Clock className="w-4 h-4 mr-2" />
                       {formatDuration(stream.duration)}
                     </div>
+                    {orderPaper?.url && (
+                      <a
+                        href={orderPaper.url}
+                        target="_blank"
+                        rel="noreferrer"
+                        className="flex items-center hover:underline"
+                      >
+                        <FileText className="w-4 h-4 mr-2" />
+                        Order paper
+                      </a>
+                    )}
                   </div>
                 </div>
 
@@ -25,7 +25,7 @@ import {
 import { Input } from "~/components/ui/input";
 import { useDebounce } from "~/lib/hooks";
 import { highlightText } from "~/lib/text-highlight";
-import { formatDate, formatDuration, titleCase } from "~/lib/utils";
+import { formatDate, formatDuration, serializeViews, titleCase } from "~/lib/utils";
 
 const prisma = new PrismaClient();
 const PAGE_SIZE = 9;
@@ -71,16 +71,23 @@ export async function loader({ request }: LoaderFunctionArgs) {
         ),
       ]);
 
-      return Response.json({ streams, total: countResult[0].count, page, query });
+      return Response.json({
+        streams,
+        total: countResult[0].count,
+        live: [],
+        scheduled: [],
+        page,
+        query,
+      });
     } catch (error) {
       console.error("Search error:", error);
       const { streams, total } = await fallbackSearch(query, page);
-      return Response.json({ streams, total, page, query });
+      return Response.json({ streams, total, live: [], scheduled: [], page, query });
     }
   }
 
   // Fallback for no query
-  const [streams, total] = await Promise.all([
+  const [streams, total, live, scheduled] = await Promise.all([
     prisma.streams.findMany({
       where: { is_published: true },
       orderBy: { stream_timestamp: "desc" },
@@ -90,15 +97,35 @@ export async function loader({ request }: LoaderFunctionArgs) {
     prisma.streams.count({
       where: { is_published: true },
     }),
+    // sittings being streamed now, recorded by the pipeline until they can be summarized
+    prisma.streams.findMany({
+      where: { status: "live" },
+      orderBy: { stream_timestamp: "desc" },
+      select: { video_id: true, title: true },
+    }),
+    // sittings announced on the channel, dated by their scheduled start
+    prisma.streams.findMany({
+      where: { status: "scheduled", stream_timestamp: { gte: new Date() } },
+      orderBy: { stream_timestamp: "asc" },
+      select: { video_id: true, title: true, stream_timestamp: true },
+    }),
   ]);
 
-  return Response.json({ streams, total, page, query: null });
+  return Response.json({
+    streams: streams.map(serializeViews),
+    total,
+    live,
+    scheduled,
+    page,
+    query: null,
+  });
 }
 
 async function fallbackSearch(query: string, page: number) {
   const [streams, count] = await Promise.all([
     prisma.streams.findMany({
       where: {
+        status: "archived",
         OR: [
           { title: { contains: query, mode: "insensitive" } },
           { summary_md: { contains: query, mode: "insensitive" } },
@@ -110,6 +137,7 @@ async function fallbackSearch(query: string, page: number) {
     }),
     prisma.streams.count({
       where: {
+        status: "archived",
         OR: [
           { title: { contains: query, mode: "insensitive" } },
           { summary_md: { contains: query, mode: "insensitive" } },
@@ -118,7 +146,7 @@ async function fallbackSearch(query: string, page: number) {
     }),
   ]);
 
-  return { streams, total: count };
+  return { streams: streams.map(serializeViews), total: count };
 }
 
 export const headers: HeadersFunction = () => ({
@@ -126,7 +154,7 @@ export const headers: HeadersFunction = () => ({
 });
 
 export default function Index() {
-  const { streams, total, query } = useLoaderData<typeof loader>();
+  const { streams, total, live, scheduled, query } = useLoaderData<typeof loader>();
   const [searchParams] = useSearchParams();
 
   const page = Number(searchParams.get("page") || 1);
@@ -183,6 +211,9 @@ export default function Index() {
               )}
             </div>
 
+            {live.length > 0 && <LiveSessions sessions={live} />}
+            {scheduled.length > 0 && <ScheduledSessions sessions={scheduled} />}
+
             <div className="grid gap-6 md:grid-cols-2 lg:grid-cols-3">
               {streams.map((stream: streams) => (
                 <StreamSummariesCard
@@ -223,6 +254,79 @@ export function HydrationFallback() {
   return <SummariesSkeleton />;
 }
 
+type LiveSession = Pick<streams, "video_id" | "title">;
+
+function LiveSessions({ sessions }: { sessions: LiveSession[] }) {
+  return (
+    <div className="mb-8 rounded-lg border border-red-200 bg-white/80 p-4 shadow-sm">
+      <div className="mb-2 flex items-center gap-2 text-sm font-semibold text-red-800">
+        <span className="h-2 w-2 animate-pulse rounded-full bg-red-600" />
+        Session in progress
+      </div>
+      <ul className="space-y-1">
+        {sessions.map((session) => (
+          <li key={session.video_id} className="text-sm text-gray-700">
+            <a
+              href={`https://www.youtube.com/watch?v=${session.video_id}`}
+              target="_blank"
+              rel="noreferrer"
+              className="hover:text-red-800 hover:underline"
+            >
+              {titleCase(session.title)}
+            </a>
+          </li>
+        ))}
+      </ul>
+      <p className="mt-2 text-xs text-gray-500">
+        A summary will be published once the sitting ends.
+      </p>
+    </div>
+  );
+}
+
+type ScheduledSession = Pick<streams, "video_id" | "title"> & {
+  stream_timestamp: string;
+};
+
+function ScheduledSessions({ sessions }: { sessions: ScheduledSession[] }) {
+  return (
+    <div className="mb-8 rounded-lg border border-orange-200 bg-white/80 p-4 shadow-sm">
+      <div className="mb-2 flex items-center gap-2 text-sm font-semibold text-gray-800">
+        <Calendar className="h-4 w-4" />
+        Upcoming sessions
+      </div>
+      <ul className="space-y-1">
+        {sessions.map((session) => (
+          <li key={session.video_id} className="text-sm text-gray-700">
+            <a
+              href={`https://www.youtube.com/watch?v=${session.video_id}`}
+              target="_blank"
+              rel="noreferrer"
+              className="hover:text-red-800 hover:underline"
+            >
+              {titleCase(session.title)}
+            </a>{" "}
+            <span className="text-gray-500">
+              scheduled for {formatScheduledStart(session.stream_timestamp)}
+            </span>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
+
+/** Sittings are scheduled in Nairobi time, wherever the page is rendered */
+const formatScheduledStart = (start: string) =>
+  new Date(start).toLocaleString("en-KE", {
+    timeZone: "Africa/Nairobi",
+    weekday: "short",
+    day: "numeric",
+    month: "short",
+    hour: "numeric",
+    minute: "2-digit",
+  });
+
 type StreamSummariesCardProps = {
   stream: streams;
   queryTerms: string;